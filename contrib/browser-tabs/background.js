@@ -0,0 +1,72 @@
+// Shares the open tabs with Crowbar and focuses tabs Crowbar asks for. See
+// src/tab_bridge.rs for the protocol.
+
+const DEFAULT_PORT = 47813;
+// Crowbar only listens while it's open, so keep trying to reach it
+const RETRY_DELAY_MS = 1000;
+
+const sleep = (ms) => new Promise((resolve) => setTimeout(resolve, ms));
+
+const settings = () =>
+  new Promise((resolve) =>
+    chrome.storage.local.get({ token: "", port: DEFAULT_PORT }, resolve)
+  );
+
+const queryTabs = () => new Promise((resolve) => chrome.tabs.query({}, resolve));
+
+async function request(method, path, body) {
+  const { token, port } = await settings();
+  return fetch(`http://127.0.0.1:${port}${path}`, {
+    method,
+    headers: { Authorization: `Bearer ${token}` },
+    body: body === undefined ? undefined : JSON.stringify(body),
+  });
+}
+
+async function sendTabs() {
+  const tabs = await queryTabs();
+  await request(
+    "POST",
+    "/tabs",
+    tabs.map((tab) => ({
+      id: tab.id,
+      window_id: tab.windowId,
+      title: tab.title || "",
+      url: tab.url || "",
+    }))
+  );
+}
+
+function runCommand(command) {
+  if (command.command === "focus") {
+    chrome.tabs.update(command.tab_id, { active: true });
+    chrome.windows.update(command.window_id, { focused: true });
+  }
+}
+
+async function pollCommands() {
+  for (;;) {
+    try {
+      // A new Crowbar instance doesn't know the tabs yet
+      await sendTabs();
+      for (;;) {
+        const response = await request("GET", "/commands");
+        if (!response.ok) {
+          throw new Error(`Crowbar responded with ${response.status}`);
+        }
+        if (response.status === 200) {
+          runCommand(await response.json());
+        }
+      }
+    } catch (e) {
+      await sleep(RETRY_DELAY_MS);
+    }
+  }
+}
+
+const sendTabsQuietly = () => sendTabs().catch(() => {});
+chrome.tabs.onCreated.addListener(sendTabsQuietly);
+chrome.tabs.onRemoved.addListener(sendTabsQuietly);
+chrome.tabs.onUpdated.addListener(sendTabsQuietly);
+
+pollCommands();
@@ -0,0 +1,13 @@
+// Token and port must match browser_bridge_token and browser_bridge_port
+
+const token = document.getElementById("token");
+const port = document.getElementById("port");
+
+chrome.storage.local.get({ token: "", port: 47813 }, (settings) => {
+  token.value = settings.token;
+  port.value = settings.port;
+});
+
+document.getElementById("save").addEventListener("click", () => {
+  chrome.storage.local.set({ token: token.value, port: Number(port.value) });
+});
@@ -1,18 +1,63 @@
 use gpui::{
-    div, prelude::FluentBuilder, uniform_list, white, AnyElement, Context, InteractiveElement,
-    IntoElement, ParentElement, ScrollStrategy, Styled, UniformListScrollHandle, Window,
+    div, img, prelude::FluentBuilder, px, relative, uniform_list, white, AnyElement, ClickEvent,
+    Context, Div, EventEmitter, InteractiveElement, IntoElement, MouseButton, MouseDownEvent,
+    MouseMoveEvent, ParentElement, ScrollStrategy, Stateful, StatefulInteractiveElement, Styled,
+    UniformListScrollHandle, Window,
 };
 
+use crate::actions::action_handler::{ActionId, ActionItem, CommandPreview};
 use crate::actions::registry::ActionRegistry;
-use crate::commands::CommandRegistry;
-use crate::config::Config;
+use crate::cli;
+use crate::commands::{CommandRegistry, SCORES_PREFERENCE, THEME_PREFERENCE, VIEW_PREFERENCE};
+use crate::config::{self, Config, ConfigSource, ResultView};
+use crate::database::Database;
+use crate::events::{self, Event};
+use crate::keybindings::{self, Help};
+use crate::lock;
+use crate::plugins;
+use crate::regex_tester::RegexTester;
+use crate::scheduler;
+use crate::system::{copy_to_clipboard, notifications, read_clipboard};
+use log::warn;
+use std::env;
 use std::sync::Arc;
 
-const ITEMS_TO_SHOW: usize = 30;
+/// Rows the window is sized for while showing the empty state
+const EMPTY_STATE_ROWS: usize = 4;
+
+/// Results run with Ctrl+1 to Ctrl+9
+const QUICK_SELECT_COUNT: usize = 9;
+
+/// Set to show the score overlay regardless of `:debug scores`
+const SCORES_ENV: &str = "CROWBAR_DEBUG_SCORES";
 
 pub enum ItemMode {
     Action,
     Command,
+    Regex,
+}
+
+/// A row of the action list
+enum ListRow {
+    Header(&'static str),
+    Action(usize),
+}
+
+/// Why a query produced no actions
+enum EmptyState {
+    NoHandlers,
+    Scanning,
+    NoMatches,
+}
+
+/// A result or one of its secondary actions was clicked, the window runs it
+/// like on Enter
+pub struct RunSelected;
+
+/// Dry-run preview of the selected action
+struct DryRun {
+    action_name: String,
+    preview: Option<CommandPreview>,
 }
 
 pub struct ActionListView {
@@ -22,12 +67,50 @@ pub struct ActionListView {
     selected_index: usize,
     list_scroll_handle: UniformListScrollHandle,
     mode: ItemMode,
+    /// Clipboard content captured when entering regex mode
+    regex_subject: String,
+    /// Shown instead of the list while previewing the selected action
+    dry_run: Option<DryRun>,
+    /// Id of the action waiting for a second Enter to confirm it
+    confirming: Option<String>,
+    /// Selected entry of the open secondary action menu
+    secondary_menu: Option<usize>,
+    /// Shown below the results after an action failed
+    error: Option<String>,
+    /// Results as a list or as tiles, see `result_view`
+    view: ResultView,
+    grid_columns: usize,
+    /// Each result shows how its relevance came about, for tuning the ranking
+    show_scores: bool,
 }
 
+impl EventEmitter<RunSelected> for ActionListView {}
+
 impl ActionListView {
     pub fn new(cx: &mut Context<Self>) -> ActionListView {
+        let db = Database::new().ok();
+        let preference = |key| {
+            db.as_ref()
+                .and_then(|db: &Database| db.get_preference(key).ok().flatten())
+        };
+        // `:theme` overrides the config, `--theme` has already been applied
+        let theme = preference(THEME_PREFERENCE).filter(|_| cli::args().theme.is_none());
+        if let Some(name) = theme {
+            match cx.global::<Config>().with_theme(&name) {
+                Ok(config) => cx.set_global(config),
+                Err(e) => warn!("Failed to load theme {}: {:#}", name, e),
+            }
+        }
         let actions = ActionRegistry::new(cx);
         let commands = CommandRegistry::new();
+        let config = cx.global::<Config>();
+        // `:view` overrides the config
+        let view = preference(VIEW_PREFERENCE)
+            .and_then(|name| ResultView::from_name(&name))
+            .unwrap_or(config.result_view);
+        let grid_columns = config.grid_columns.max(1);
+        let show_scores = env::var_os(SCORES_ENV).is_some()
+            || preference(SCORES_PREFERENCE).as_deref() == Some("on");
 
         Self {
             actions,
@@ -36,19 +119,39 @@ impl ActionListView {
             selected_index: 0,
             list_scroll_handle: UniformListScrollHandle::new(),
             mode: ItemMode::Action,
+            regex_subject: String::new(),
+            dry_run: None,
+            confirming: None,
+            secondary_menu: None,
+            error: None,
+            view,
+            grid_columns,
+            show_scores,
         }
     }
 
+    fn shows_grid(&self) -> bool {
+        matches!(self.mode, ItemMode::Action) && self.view == ResultView::Grid
+    }
+
     // Get the number of items in the current mode
     fn items_len(&self) -> usize {
         match self.mode {
             ItemMode::Command => self.commands.get_command_list().len(),
             ItemMode::Action => self.actions.get_actions().len(),
+            ItemMode::Regex => 0,
         }
     }
 
     // Navigate with a delta (-1 for up, 1 for down)
     fn navigate(&mut self, delta: isize, cx: &mut Context<Self>) {
+        if let Some(selected) = self.secondary_menu {
+            let entries = self.secondary_actions_len() as isize;
+            self.secondary_menu = Some((selected as isize + delta).rem_euclid(entries) as usize);
+            cx.notify();
+            return;
+        }
+
         let items_len = self.items_len();
 
         if items_len == 0 {
@@ -59,30 +162,125 @@ impl ActionListView {
             // Navigate up
             self.selected_index
                 .checked_sub(delta.abs() as usize)
-                .unwrap_or(items_len.min(ITEMS_TO_SHOW) - 1)
+                .unwrap_or(items_len - 1)
         } else {
             // Navigate down
-            (self.selected_index + delta as usize) % items_len.min(ITEMS_TO_SHOW)
+            (self.selected_index + delta as usize) % items_len
         };
+        self.select(cx);
+    }
 
-        self.list_scroll_handle
-            .scroll_to_item(self.selected_index, ScrollStrategy::Top);
+    /// Move the selection by `rows`, stopping at the first and last item
+    /// instead of wrapping around
+    pub fn navigate_page(&mut self, rows: isize, cx: &mut Context<Self>) {
+        let items_len = self.items_len();
+        if self.secondary_menu.is_some() || items_len == 0 {
+            return;
+        }
+
+        self.selected_index = self
+            .selected_index
+            .saturating_add_signed(rows)
+            .min(items_len - 1);
+        self.select(cx);
+    }
+
+    /// Show the newly selected item, dropping state of the previous one
+    fn select(&mut self, cx: &mut Context<Self>) {
+        self.scroll_to_selected();
+        self.dry_run = None;
+        self.confirming = None;
 
         cx.notify();
     }
 
+    fn scroll_to_selected(&self) {
+        let selected = self.selected_index;
+        let row = match self.mode {
+            ItemMode::Action if self.shows_grid() => selected / self.grid_columns,
+            ItemMode::Action => self
+                .action_rows()
+                .iter()
+                .position(|row| matches!(row, ListRow::Action(index) if *index == selected))
+                .unwrap_or(selected),
+            _ => selected,
+        };
+        self.list_scroll_handle
+            .scroll_to_item(row, ScrollStrategy::Top);
+    }
+
+    /// Actions interleaved with a header wherever a new section starts
+    fn action_rows(&self) -> Vec<ListRow> {
+        let mut rows = Vec::new();
+        let mut current_section = None;
+
+        for (index, item) in self.actions.get_actions().iter().enumerate() {
+            if item.section.is_some() && item.section != current_section {
+                rows.push(ListRow::Header(item.section.unwrap()));
+            }
+            current_section = item.section;
+            rows.push(ListRow::Action(index));
+        }
+
+        rows
+    }
+
+    /// Number of rows to fit the window to, `None` for views that get the
+    /// full window height
+    pub fn fitted_row_count(&self) -> Option<usize> {
+        if !matches!(self.mode, ItemMode::Action) || self.dry_run.is_some() || self.shows_grid() {
+            return None;
+        }
+        if self.filter.is_empty() && self.actions.needs_scan() {
+            return None;
+        }
+
+        // The menu lists the action's name above its secondary actions
+        let menu_rows = self
+            .secondary_menu
+            .map_or(0, |_| self.secondary_actions_len() + 1);
+        match self.action_rows().len() {
+            0 => Some(EMPTY_STATE_ROWS),
+            rows => Some(rows.max(menu_rows)),
+        }
+    }
+
+    /// Up and down move between rows of tiles in the grid view
     pub fn navigate_up(&mut self, cx: &mut Context<Self>) {
-        self.navigate(-1, cx);
+        if self.shows_grid() && self.secondary_menu.is_none() {
+            self.navigate_page(-(self.grid_columns as isize), cx);
+        } else {
+            self.navigate(-1, cx);
+        }
     }
 
     pub fn navigate_down(&mut self, cx: &mut Context<Self>) {
+        if self.shows_grid() && self.secondary_menu.is_none() {
+            self.navigate_page(self.grid_columns as isize, cx);
+        } else {
+            self.navigate(1, cx);
+        }
+    }
+
+    /// The previous tile of the grid, or the previous row of the list
+    pub fn navigate_left(&mut self, cx: &mut Context<Self>) {
+        self.navigate(-1, cx);
+    }
+
+    pub fn navigate_right(&mut self, cx: &mut Context<Self>) {
         self.navigate(1, cx);
     }
 
     pub fn set_filter(&mut self, new_filter: &str, cx: &mut Context<Self>) {
-        // Determine the mode based on the filter
+        // Determine the mode based on the filter. Restricted mode only allows actions.
+        let restricted = cx.global::<Config>().restricted_mode;
         let is_command_mode = new_filter.starts_with(':');
-        self.mode = if is_command_mode {
+        let was_regex_mode = matches!(self.mode, ItemMode::Regex);
+        self.mode = if restricted {
+            ItemMode::Action
+        } else if RegexTester::is_regex_query(new_filter) {
+            ItemMode::Regex
+        } else if is_command_mode {
             ItemMode::Command
         } else {
             ItemMode::Action
@@ -90,35 +288,444 @@ impl ActionListView {
 
         match self.mode {
             ItemMode::Command => {}
+            ItemMode::Regex => {
+                if !was_regex_mode {
+                    self.regex_subject = read_clipboard().unwrap_or_default();
+                }
+            }
             ItemMode::Action => {
                 self.actions.set_filter(new_filter, cx);
+                events::emit(Event::Query { query: new_filter });
+                self.emit_results(new_filter);
             }
         }
 
         // Reset selection
+        self.dry_run = None;
+        self.confirming = None;
+        self.secondary_menu = None;
+        self.error = None;
         self.filter = new_filter.into();
         self.selected_index = 0;
-        self.list_scroll_handle
-            .scroll_to_item(self.selected_index, ScrollStrategy::Top);
+        self.scroll_to_selected();
+    }
+
+    /// Re-run the current filter, e.g. after a handler finished loading data
+    /// in the background. Unlike `set_filter` this keeps the selection.
+    pub fn refresh(&mut self, cx: &mut Context<Self>) {
+        if let ItemMode::Action = self.mode {
+            let filter = self.filter.clone();
+            self.actions.set_filter(&filter, cx);
+            self.emit_results(&filter);
+            self.selected_index = self.selected_index.min(self.items_len().saturating_sub(1));
+            let entries = self.secondary_actions_len();
+            if self
+                .secondary_menu
+                .is_some_and(|selected| selected >= entries)
+            {
+                self.secondary_menu = None;
+            }
+        }
+        cx.notify();
+    }
+
+    fn emit_results(&self, query: &str) {
+        events::emit(Event::Results {
+            query,
+            names: self
+                .actions
+                .get_actions()
+                .iter()
+                .map(|action| action.name.as_str())
+                .collect(),
+        });
     }
 
-    pub fn run_selected_action(&self, cx: &mut Context<Self>) -> bool {
+    /// Collapse the group of the selected result to its best result, or
+    /// expand it again, keeping the group's first result selected
+    pub fn toggle_selected_group(&mut self, cx: &mut Context<Self>) {
+        if !matches!(self.mode, ItemMode::Action) {
+            return;
+        }
+        let Some(group) = self
+            .actions
+            .get_actions()
+            .get(self.selected_index)
+            .and_then(|action| action.section)
+        else {
+            return;
+        };
+
+        self.actions.toggle_group(group);
+        self.refresh(cx);
+        self.selected_index = self
+            .actions
+            .get_actions()
+            .iter()
+            .position(|action| action.section == Some(group))
+            .unwrap_or(0);
+        self.scroll_to_selected();
+    }
+
+    /// Pin the selected application or binary to the top of the
+    /// empty-query results, or unpin it
+    pub fn toggle_selected_pin(&mut self, cx: &mut Context<Self>) {
+        if !matches!(self.mode, ItemMode::Action) {
+            return;
+        }
+        let Some(action) = self.actions.get_actions().get(self.selected_index) else {
+            return;
+        };
+        let ActionId::Dynamic(id) = action.id else {
+            self.show_error(
+                "Only applications and binaries can be pinned".to_string(),
+                cx,
+            );
+            return;
+        };
+        let name = action.name.clone();
+        let db = action.db.clone();
+
+        let result = db
+            .is_action_pinned(id)
+            .and_then(|pinned| db.set_action_pinned(id, !pinned));
+        match result {
+            Ok(()) => self.refresh(cx),
+            Err(e) => self.show_error(format!("Failed to pin {}: {}", name, e), cx),
+        }
+    }
+
+    /// Number of secondary actions of the selected action
+    fn secondary_actions_len(&self) -> usize {
+        self.actions
+            .get_actions()
+            .get(self.selected_index)
+            .map_or(0, |action| action.secondary_actions.len())
+    }
+
+    /// Open the menu of the selected action's secondary actions, returns
+    /// whether the action has any
+    pub fn open_secondary_menu(&mut self, cx: &mut Context<Self>) -> bool {
+        let can_open = matches!(self.mode, ItemMode::Action)
+            && self.dry_run.is_none()
+            && self.secondary_actions_len() > 0;
+        if can_open {
+            self.secondary_menu = Some(0);
+            self.confirming = None;
+            cx.notify();
+        }
+        can_open
+    }
+
+    /// Select result `number`, counting from 1, to run it right away.
+    /// Returns whether there is such a result.
+    pub fn quick_select(&mut self, number: usize, cx: &mut Context<Self>) -> bool {
+        if !matches!(self.mode, ItemMode::Action) || number == 0 || number > self.items_len() {
+            return false;
+        }
+        self.secondary_menu = None;
+        self.selected_index = number - 1;
+        self.select(cx);
+        true
+    }
+
+    /// Number shown next to the result, see `quick_select_hints`
+    fn quick_select_hint(&self, index: usize, config: &Config) -> Option<String> {
+        (config.quick_select_hints && index < QUICK_SELECT_COUNT).then(|| (index + 1).to_string())
+    }
+
+    /// Relevance of the result with its parts, shown with `:debug scores`
+    fn score_breakdown(&self, item: &ActionItem) -> Option<String> {
+        if !self.show_scores {
+            return None;
+        }
+        let total = format!(
+            "{} = {} × {}",
+            item.relevance(),
+            item.relevance,
+            item.relevance_boost
+        );
+        Some(match item.score {
+            Some(score) => format!(
+                "frecency {:.2} · match {} · tokens {:.2} · {}",
+                score.frecency, score.match_quality, score.search_score, total
+            ),
+            None => total,
+        })
+    }
+
+    /// Select the result under the mouse, without scrolling
+    fn hover(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index == self.selected_index || self.secondary_menu.is_some() {
+            return;
+        }
+        self.selected_index = index;
+        self.dry_run = None;
+        self.confirming = None;
+        cx.notify();
+    }
+
+    /// Hovering selects a result, clicking runs it and right-clicking opens
+    /// its secondary actions
+    fn with_mouse_handlers(
+        &self,
+        element: Stateful<Div>,
+        index: usize,
+        cx: &Context<Self>,
+    ) -> Stateful<Div> {
+        element
+            .on_mouse_move(cx.listener(move |this, _: &MouseMoveEvent, _, cx| {
+                this.hover(index, cx);
+            }))
+            .on_click(cx.listener(move |this, _: &ClickEvent, _, cx| {
+                this.secondary_menu = None;
+                this.hover(index, cx);
+                cx.emit(RunSelected);
+            }))
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(move |this, _: &MouseDownEvent, _, cx| {
+                    this.secondary_menu = None;
+                    this.hover(index, cx);
+                    this.open_secondary_menu(cx);
+                }),
+            )
+    }
+
+    /// Close the secondary action menu, returns whether it was open
+    pub fn close_secondary_menu(&mut self, cx: &mut Context<Self>) -> bool {
+        let was_open = self.secondary_menu.take().is_some();
+        cx.notify();
+        was_open
+    }
+
+    /// Show or hide what the selected action would run, without running it
+    pub fn toggle_dry_run(&mut self, cx: &mut Context<Self>) {
+        self.secondary_menu = None;
+        if self.dry_run.take().is_some() || !matches!(self.mode, ItemMode::Action) {
+            cx.notify();
+            return;
+        }
+
+        if let Some(action) = self.actions.get_actions().get(self.selected_index) {
+            self.dry_run = Some(DryRun {
+                action_name: action.name.clone(),
+                preview: action.preview(&self.filter),
+            });
+        }
+        cx.notify();
+    }
+
+    /// Text the selected action inserts into the input, instead of running
+    pub fn selected_completion(&self) -> Option<String> {
+        match self.mode {
+            ItemMode::Action if self.secondary_menu.is_none() => self
+                .actions
+                .get_actions()
+                .get(self.selected_index)
+                .and_then(|action| action.completion.clone()),
+            _ => None,
+        }
+    }
+
+    /// Commands and enabled handlers for the cheat sheet
+    pub fn help(&self, config: &Config) -> Help {
+        let mut commands = if config.restricted_mode {
+            Vec::new()
+        } else {
+            self.commands.get_command_list()
+        };
+        commands.sort();
+        Help {
+            commands,
+            handlers: self.actions.handler_keywords(config),
+        }
+    }
+
+    /// Text Tab inserts into the input for the selected action, its name
+    /// unless the handler completes to something else like a path
+    pub fn selected_tab_completion(&self) -> Option<String> {
+        match self.mode {
+            ItemMode::Action => self
+                .actions
+                .get_actions()
+                .get(self.selected_index)
+                .map(|action| {
+                    action
+                        .tab_completion
+                        .clone()
+                        .unwrap_or_else(|| action.name.clone())
+                }),
+            // `:theme <partial name>` completes to the first matching theme
+            ItemMode::Command => {
+                let partial = theme_argument(&self.filter)?;
+                let theme = config::themes()
+                    .into_iter()
+                    .find(|theme| theme.starts_with(partial))?;
+                Some(format!(":theme {}", theme))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the top action matches the query exactly and outscores the
+    /// runner-up by the configured margin, so it can be run without looking
+    fn has_unambiguous_match(&self, cx: &Context<Self>) -> bool {
+        let margin = cx.global::<Config>().auto_accept_margin as f64;
+        let actions = self.actions.get_actions();
+        let Some(top) = actions.first() else {
+            return false;
+        };
+        let runner_up = actions.get(1).map_or(0, |action| action.relevance());
+
+        matches!(self.mode, ItemMode::Action)
+            && top.name.to_lowercase() == self.filter.trim().to_lowercase()
+            && top.relevance() > 0
+            && top.relevance() as f64 > runner_up as f64 * margin
+    }
+
+    /// Run the top action if the match is unambiguous, see `auto_accept`
+    pub fn auto_accept(&mut self, cx: &mut Context<Self>) -> bool {
+        if !cx.global::<Config>().auto_accept || !self.has_unambiguous_match(cx) {
+            return false;
+        }
+        self.selected_index = 0;
+        self.run_selected_action(cx)
+    }
+
+    /// A space typed after a query with an unambiguous match runs the match,
+    /// see `auto_accept_on_space`
+    pub fn auto_accept_space(&mut self, content: &str, cx: &mut Context<Self>) -> bool {
+        let enabled = cx.global::<Config>().auto_accept_on_space;
+        match content.strip_suffix(' ') {
+            Some(query) if enabled && *self.filter == *query => self.auto_accept(cx),
+            _ => false,
+        }
+    }
+
+    pub fn run_selected_action(&mut self, cx: &mut Context<Self>) -> bool {
         let filter = &self.filter.to_string();
 
         match self.mode {
             ItemMode::Command => {
+                // Scanning takes a while, the results update once it's done
+                if is_rescan(filter) && !lock::is_command_locked("rescan") {
+                    self.actions.rescan(cx);
+                    return true;
+                }
                 let result = self.commands.execute_command(filter);
-                result.success
+                // The new theme is shown right away instead of quitting
+                if let Some(name) = theme_argument(filter) {
+                    if name.is_empty() {
+                        self.show_error(result.message, cx);
+                    } else {
+                        self.switch_theme(name, cx);
+                    }
+                    return false;
+                }
+                // Failed commands keep the window open and say why
+                if !result.success {
+                    self.show_error(result.message, cx);
+                    return false;
+                }
+                true
             }
             ItemMode::Action => {
-                let action = self.actions.get_actions().get(self.selected_index).unwrap();
-                let _ = action.execute(filter);
+                // Nothing to run on the empty state
+                let Some(action) = self.actions.get_actions().get(self.selected_index) else {
+                    return false;
+                };
+                if action.locked || action.is_status {
+                    return false;
+                }
+                if let Some(secondary) = self.secondary_menu {
+                    let name = &action.secondary_actions[secondary].name;
+                    events::emit(Event::Execute {
+                        query: filter,
+                        name: &format!("{}: {}", action.name, name),
+                    });
+                    if let Err(e) = action.execute_secondary(secondary, filter) {
+                        let message = format!("Failed to run {} of {}: {:#}", name, action.name, e);
+                        self.secondary_menu = None;
+                        self.show_error(message, cx);
+                        return false;
+                    }
+                    return true;
+                }
+                let id = action.id.as_str().to_string();
+                if action.needs_confirmation && self.confirming.as_ref() != Some(&id) {
+                    self.confirming = Some(id);
+                    cx.notify();
+                    return false;
+                }
+                events::emit(Event::Execute {
+                    query: filter,
+                    name: &action.name,
+                });
+                if let Err(e) = action.execute(filter) {
+                    let message = format!("Failed to run {}: {:#}", action.name, e);
+                    self.show_error(message, cx);
+                    return false;
+                }
+                if action.stays_open {
+                    self.refresh(cx);
+                    return false;
+                }
                 true
             }
-            _ => false,
+            ItemMode::Regex => {
+                let tester = RegexTester::parse(filter, &self.regex_subject);
+                copy_to_clipboard(tester.pattern()).is_ok()
+            }
+        }
+    }
+
+    /// Apply the colors of a theme file to the open window
+    fn switch_theme(&mut self, name: &str, cx: &mut Context<Self>) {
+        match cx.global::<Config>().with_theme(name) {
+            Ok(config) => {
+                cx.set_global(config);
+                cx.refresh_windows();
+            }
+            Err(e) => self.show_error(format!("Failed to load theme {}: {:#}", name, e), cx),
         }
     }
 
+    /// Keep the window open with the error below the results, and send it as
+    /// a desktop notification with `error_notifications`
+    fn show_error(&mut self, message: String, cx: &mut Context<Self>) {
+        warn!("{}", message);
+        if cx.global::<Config>().error_notifications {
+            if let Err(e) = notifications::send("Crowbar", &message) {
+                warn!("Failed to send notification: {:#}", e);
+            }
+        }
+        self.error = Some(message);
+        cx.notify();
+    }
+
+    /// The last error until the query changes
+    fn render_error(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let message = self.error.clone()?;
+        let theme = cx.global::<Config>();
+
+        Some(
+            div()
+                .absolute()
+                .bottom_2()
+                .left_4()
+                .right_4()
+                .px(px(theme.padding))
+                .py(px(theme.row_padding))
+                .bg(theme.background_color)
+                .border_1()
+                .border_color(theme.border_color)
+                .rounded(px(theme.corner_radius))
+                .child(message)
+                .into_any_element(),
+        )
+    }
+
     // Render a command list
     fn render_command_list(&self, cx: &mut Context<Self>) -> AnyElement {
         let command_items = self.commands.get_command_list();
@@ -131,16 +738,15 @@ impl ActionListView {
             .child(
                 // Command mode indicator
                 div()
-                    .px_4()
-                    .py_2()
-                    .bg(theme.background_color)
+                    .px(px(theme.padding))
+                    .py(px(theme.row_padding))
                     .text_color(theme.text_secondary_color)
                     .child(div().flex().flex_col().child("Available commands"))
                     .child(
                         div().flex().flex_col().children(
                             command_items
                                 .iter()
-                                .map(|command| div().px_4().child(command.clone()))
+                                .map(|command| div().px(px(theme.padding)).child(command.clone()))
                                 .collect::<Vec<_>>(),
                         ),
                     ),
@@ -148,40 +754,502 @@ impl ActionListView {
             .into_any_element()
     }
 
+    /// Render the `:handlers` view: every handler, whether it's enabled and
+    /// why a plugin failed to load or run
+    fn render_handler_list(&self, cx: &mut Context<Self>) -> AnyElement {
+        let theme = cx.global::<Config>();
+        let errors = plugins::errors();
+
+        let rows = self
+            .actions
+            .handler_states()
+            .into_iter()
+            .map(|(id, enabled)| {
+                let error = errors
+                    .iter()
+                    .find(|(plugin_id, _)| *plugin_id == id)
+                    .map(|(_, error)| error.clone());
+                div()
+                    .px(px(theme.padding))
+                    .flex()
+                    .gap(px(theme.gap))
+                    .child(div().flex_grow().child(id))
+                    .when_some(error, |x, error| {
+                        x.child(div().text_color(theme.text_secondary_color).child(error))
+                    })
+                    .child(
+                        div()
+                            .flex_none()
+                            .text_color(theme.text_secondary_color)
+                            .child(if enabled { "enabled" } else { "disabled" }),
+                    )
+            });
+
+        // Plugins whose manifest failed to load never reach the handlers table
+        let loaded = plugins::plugins();
+        let broken = errors
+            .iter()
+            .filter(|(id, _)| !loaded.iter().any(|plugin| plugin.id == *id))
+            .map(|(id, error)| {
+                div()
+                    .px(px(theme.padding))
+                    .flex()
+                    .gap(px(theme.gap))
+                    .child(div().flex_grow().child(id.clone()))
+                    .child(
+                        div()
+                            .text_color(theme.text_secondary_color)
+                            .child(error.clone()),
+                    )
+            });
+
+        div()
+            .size_full()
+            .px(px(theme.padding))
+            .py(px(theme.row_padding))
+            .flex()
+            .flex_col()
+            .child(
+                div()
+                    .text_color(theme.text_secondary_color)
+                    .child("Handlers (:enable <id>, :disable <id>)"),
+            )
+            .children(rows)
+            .children(broken)
+            .into_any_element()
+    }
+
+    /// Render the `:debug` view: how often each scheduled job ran and how long
+    /// it took
+    fn render_debug(&self, cx: &mut Context<Self>) -> AnyElement {
+        let theme = cx.global::<Config>();
+
+        let rows = scheduler::stats().into_iter().map(|(name, stats)| {
+            let average = stats
+                .total_time
+                .checked_div(stats.runs.max(1) as u32)
+                .unwrap_or_default();
+            let last_run = match stats.last_run {
+                Some(last_run) => format!("{}s ago", last_run.elapsed().as_secs()),
+                None => "never".to_string(),
+            };
+            div()
+                .px(px(theme.padding))
+                .flex()
+                .gap(px(theme.gap))
+                .child(div().flex_grow().child(name))
+                .child(
+                    div()
+                        .flex_none()
+                        .text_color(theme.text_secondary_color)
+                        .child(format!(
+                            "every {}s · {} runs · {:.2?} avg · {}",
+                            stats.interval.as_secs(),
+                            stats.runs,
+                            average,
+                            last_run
+                        )),
+                )
+        });
+
+        div()
+            .size_full()
+            .px(px(theme.padding))
+            .py(px(theme.row_padding))
+            .flex()
+            .flex_col()
+            .child(
+                div()
+                    .text_color(theme.text_secondary_color)
+                    .child("Scheduled jobs"),
+            )
+            .children(rows)
+            .into_any_element()
+    }
+
+    /// Render the `:permissions` view: data access granted to each handler
+    fn render_permissions(&self, cx: &mut Context<Self>) -> AnyElement {
+        let theme = cx.global::<Config>();
+
+        let rows = self
+            .actions
+            .data_access_states()
+            .into_iter()
+            .map(|(id, data, access)| {
+                div()
+                    .px(px(theme.padding))
+                    .flex()
+                    .gap(px(theme.gap))
+                    .child(div().flex_none().child(id))
+                    .child(
+                        div()
+                            .flex_grow()
+                            .overflow_hidden()
+                            .text_color(theme.text_secondary_color)
+                            .child(data),
+                    )
+                    .child(
+                        div()
+                            .flex_none()
+                            .when(access.is_none(), |x| {
+                                x.text_color(theme.text_secondary_color)
+                            })
+                            .child(match access {
+                                Some(true) => "allowed",
+                                Some(false) => "denied",
+                                None => "not asked yet",
+                            }),
+                    )
+            });
+
+        div()
+            .size_full()
+            .px(px(theme.padding))
+            .py(px(theme.row_padding))
+            .flex()
+            .flex_col()
+            .child(
+                div()
+                    .text_color(theme.text_secondary_color)
+                    .child("Data access (:permissions allow|deny|revoke <id>)"),
+            )
+            .children(rows)
+            .into_any_element()
+    }
+
+    /// Render the `:settings` view: every option, its value and whether the
+    /// user config, the system config or the defaults set it
+    fn render_settings(&self, cx: &mut Context<Self>) -> AnyElement {
+        let theme = cx.global::<Config>();
+
+        let rows = config::settings().into_iter().map(|(key, value, source)| {
+            div()
+                .px(px(theme.padding))
+                .flex()
+                .gap(px(theme.gap))
+                .child(div().flex_none().child(key))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .text_color(theme.text_secondary_color)
+                        .child(value),
+                )
+                .child(
+                    div()
+                        .flex_none()
+                        .when(source == ConfigSource::Default, |x| {
+                            x.text_color(theme.text_secondary_color)
+                        })
+                        .child(source.name()),
+                )
+        });
+
+        div()
+            .size_full()
+            .px(px(theme.padding))
+            .py(px(theme.row_padding))
+            .flex()
+            .flex_col()
+            .child(
+                div()
+                    .text_color(theme.text_secondary_color)
+                    .child("Settings (theme overrides user overrides system overrides default)"),
+            )
+            .children(rows)
+            .into_any_element()
+    }
+
+    /// Render the themes matching `:theme <partial name>`, Tab completes the
+    /// first one
+    fn render_theme_list(&self, cx: &mut Context<Self>) -> AnyElement {
+        let theme = cx.global::<Config>();
+        let partial = theme_argument(&self.filter).unwrap_or_default();
+        let themes = config::themes();
+        let title = if themes.is_empty() {
+            "No themes in ~/.config/crowbar/themes"
+        } else {
+            "Themes"
+        };
+
+        let rows = themes
+            .iter()
+            .filter(|name| name.starts_with(partial))
+            .map(|name| {
+                let current = theme.theme.as_ref() == Some(name);
+                div()
+                    .px(px(theme.padding))
+                    .flex()
+                    .gap(px(theme.gap))
+                    .child(div().flex_none().child(name.clone()))
+                    .when(current, |row| {
+                        row.child(
+                            div()
+                                .flex_none()
+                                .text_color(theme.text_secondary_color)
+                                .child("current"),
+                        )
+                    })
+            });
+
+        div()
+            .size_full()
+            .px(px(theme.padding))
+            .py(px(theme.row_padding))
+            .flex()
+            .flex_col()
+            .child(div().text_color(theme.text_secondary_color).child(title))
+            .children(rows)
+            .into_any_element()
+    }
+
+    fn render_dry_run(&self, dry_run: &DryRun, cx: &mut Context<Self>) -> AnyElement {
+        let theme = cx.global::<Config>();
+        let label = |text: &'static str| div().text_color(theme.text_secondary_color).child(text);
+
+        let content = match &dry_run.preview {
+            Some(preview) => div()
+                .flex()
+                .flex_col()
+                .gap_1()
+                .when(!preview.details.is_empty(), |x| {
+                    x.child(label("Details")).child(
+                        div()
+                            .pl_4()
+                            .children(preview.details.iter().map(|line| div().child(line.clone()))),
+                    )
+                })
+                .child(label("Command line"))
+                .child(div().pl_4().child(preview.command_line.clone()))
+                .child(label("Working directory"))
+                .child(div().pl_4().child(preview.working_dir.clone()))
+                .child(label("Environment"))
+                .child(
+                    div()
+                        .pl_4()
+                        .child("Inherited from Crowbar")
+                        .children(preview.env.iter().map(|var| div().child(var.clone()))),
+                ),
+            None => div().child(label("This action doesn't run a command")),
+        };
+
+        div()
+            .size_full()
+            .px(px(theme.padding))
+            .py(px(theme.row_padding))
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(format!("Dry run: {}", dry_run.action_name))
+            .child(content)
+            .child(label("Enter runs the action, Shift-Enter goes back"))
+            .into_any_element()
+    }
+
+    fn empty_state(&self) -> EmptyState {
+        if !self.actions.has_handlers() {
+            EmptyState::NoHandlers
+        } else if self.actions.is_scanning() {
+            EmptyState::Scanning
+        } else {
+            EmptyState::NoMatches
+        }
+    }
+
+    /// Explain why there are no results and what could help
+    fn render_empty_state(&self, cx: &mut Context<Self>) -> AnyElement {
+        let theme = cx.global::<Config>();
+        let (title, hints): (String, &[&str]) = match self.empty_state() {
+            EmptyState::NoHandlers => (
+                "No modules are enabled".to_string(),
+                &[
+                    "Enable one with :enable <module>, e.g. :enable executable",
+                    "List all modules with :handlers",
+                ],
+            ),
+            EmptyState::Scanning => (
+                "Still scanning applications and executables".to_string(),
+                &["Results will appear once the scan is done"],
+            ),
+            EmptyState::NoMatches => (
+                format!("No results for \"{}\"", self.filter.trim()),
+                &[
+                    "Check the spelling or try a shorter query",
+                    "Installed something new? Run :rescan",
+                    "More modules can be enabled, see :handlers",
+                ],
+            ),
+        };
+
+        div()
+            .size_full()
+            .px(px(theme.padding))
+            .py(px(theme.row_padding))
+            .flex()
+            .flex_col()
+            .gap_1()
+            .child(title)
+            .children(hints.iter().map(|hint| {
+                div()
+                    .pl_4()
+                    .text_color(theme.text_secondary_color)
+                    .child(*hint)
+            }))
+            .into_any_element()
+    }
+
+    /// Render the secondary actions of the selected action over the list
+    fn render_secondary_menu(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let selected = self.secondary_menu?;
+        let action = self.actions.get_actions().get(self.selected_index)?;
+        let theme = cx.global::<Config>();
+
+        let entries = action
+            .secondary_actions
+            .iter()
+            .enumerate()
+            .map(|(index, secondary)| {
+                div()
+                    .id(index)
+                    .px(px(theme.padding))
+                    .py_1()
+                    .child(secondary.name.clone())
+                    .when(index == selected, |x| x.bg(theme.selected_background_color))
+                    .on_mouse_move(cx.listener(move |this, _: &MouseMoveEvent, _, cx| {
+                        if this.secondary_menu != Some(index) {
+                            this.secondary_menu = Some(index);
+                            cx.notify();
+                        }
+                    }))
+                    .on_click(cx.listener(move |this, _: &ClickEvent, _, cx| {
+                        this.secondary_menu = Some(index);
+                        cx.emit(RunSelected);
+                    }))
+            });
+
+        Some(
+            div()
+                .absolute()
+                .occlude()
+                .top_2()
+                .right_4()
+                .w_64()
+                .py_1()
+                .flex()
+                .flex_col()
+                .bg(theme.background_color)
+                .border_1()
+                .border_color(theme.border_color)
+                .rounded(px(theme.corner_radius))
+                .child(
+                    div()
+                        .px(px(theme.padding))
+                        .py_1()
+                        .overflow_hidden()
+                        .text_color(theme.text_secondary_color)
+                        .child(action.name.clone()),
+                )
+                .children(entries)
+                .into_any_element(),
+        )
+    }
+
     // Render an action list
     fn render_action_list(&self, cx: &mut Context<Self>) -> AnyElement {
-        let items = self.actions.get_actions();
+        let rows = self.action_rows();
 
         if self.filter.is_empty() && self.actions.needs_scan() {
             self.actions.scan(cx);
             loading_screen().into_any_element()
+        } else if rows.is_empty() {
+            self.render_empty_state(cx)
+        } else if self.shows_grid() {
+            self.render_action_grid(cx)
         } else {
             div()
                 .size_full()
+                .relative()
                 .child(
                     uniform_list(
                         cx.entity().clone(),
                         "action-list",
-                        items.len(),
+                        rows.len(),
                         |this, range, _window, cx| {
-                            let items = this
-                                .actions
-                                .get_actions()
+                            let items = this.actions.get_actions();
+                            let theme = cx.global::<Config>();
+
+                            this.action_rows()
                                 .into_iter()
+                                .enumerate()
                                 .skip(range.start)
                                 .take(range.end - range.start)
-                                .enumerate();
-
-                            let theme = cx.global::<Config>();
-
-                            items
-                                .map(|(index, item)| {
-                                    let is_selected = index + range.start == this.selected_index;
-                                    div()
-                                        .id(index + range.start)
-                                        .px_4()
-                                        .py_2()
-                                        .child(item.clone())
+                                .map(|(row, list_row)| {
+                                    let index = match list_row {
+                                        ListRow::Header(section) => {
+                                            let hidden = this.actions.hidden_in_group(section);
+                                            let state = if this.actions.is_group_collapsed(section)
+                                            {
+                                                format!("{} more, collapsed", hidden)
+                                            } else {
+                                                format!("{} more", hidden)
+                                            };
+                                            return div()
+                                                .id(row)
+                                                .px(px(theme.padding))
+                                                .py(px(theme.row_padding))
+                                                .flex()
+                                                .justify_between()
+                                                .text_color(theme.text_secondary_color)
+                                                .child(section)
+                                                .when(hidden > 0, |x| x.child(state));
+                                        }
+                                        ListRow::Action(index) => index,
+                                    };
+                                    let item = &items[index];
+                                    let is_selected = index == this.selected_index;
+                                    let is_confirming = is_selected
+                                        && this.confirming.as_deref() == Some(item.id.as_str());
+                                    let hint = this.quick_select_hint(index, theme);
+                                    let score = this.score_breakdown(item);
+                                    this.with_mouse_handlers(div().id(row), index, cx)
+                                        .px(px(theme.padding))
+                                        .py(px(theme.row_padding))
+                                        .flex()
+                                        .gap(px(theme.gap))
+                                        .child(div().flex_grow().child(item.clone()))
+                                        .when(item.locked, |x| {
+                                            x.child(
+                                                div()
+                                                    .flex_none()
+                                                    .text_color(theme.text_secondary_color)
+                                                    .child("Locked (:unlock)"),
+                                            )
+                                        })
+                                        .when(is_confirming, |x| {
+                                            x.child(
+                                                div()
+                                                    .flex_none()
+                                                    .text_color(theme.text_secondary_color)
+                                                    .child("Press Enter again to confirm"),
+                                            )
+                                        })
+                                        .when_some(score, |x, score| {
+                                            x.child(
+                                                div()
+                                                    .flex_none()
+                                                    .text_sm()
+                                                    .text_color(theme.text_secondary_color)
+                                                    .child(score),
+                                            )
+                                        })
+                                        .when_some(hint, |x, hint| {
+                                            x.child(
+                                                div()
+                                                    .flex_none()
+                                                    .text_color(theme.text_secondary_color)
+                                                    .child(hint),
+                                            )
+                                        })
                                         .when(is_selected, |x| {
                                             x.bg(theme.selected_background_color)
                                         })
@@ -192,9 +1260,110 @@ impl ActionListView {
                     .track_scroll(self.list_scroll_handle.clone())
                     .h_full(),
                 )
+                .when_some(self.render_secondary_menu(cx), |x, menu| x.child(menu))
+                .when_some(self.render_error(cx), |x, error| x.child(error))
                 .into_any_element()
         }
     }
+
+    /// Tiles with the icon above the name, one list row per row of tiles.
+    /// Sections aren't shown, the tiles are ordered by score.
+    fn render_action_grid(&self, cx: &mut Context<Self>) -> AnyElement {
+        let columns = self.grid_columns;
+        let grid_rows = self.items_len().div_ceil(columns);
+
+        div()
+            .size_full()
+            .relative()
+            .child(
+                uniform_list(
+                    cx.entity().clone(),
+                    "action-grid",
+                    grid_rows,
+                    move |this, range, _window, cx| {
+                        let items = this.actions.get_actions();
+                        let theme = cx.global::<Config>();
+
+                        range
+                            .map(|row| {
+                                let tiles = (row * columns..items.len().min((row + 1) * columns))
+                                    .map(|index| {
+                                        let selected = index == this.selected_index;
+                                        let hint = this.quick_select_hint(index, theme);
+                                        let cell = tile(&items[index], selected, hint, theme)
+                                            .id(index)
+                                            .w(relative(1. / columns as f32));
+                                        this.with_mouse_handlers(cell, index, cx)
+                                    });
+                                div().id(row).flex().children(tiles)
+                            })
+                            .collect()
+                    },
+                )
+                .track_scroll(self.list_scroll_handle.clone())
+                .h_full(),
+            )
+            .when_some(self.render_secondary_menu(cx), |x, menu| x.child(menu))
+            .when_some(self.render_error(cx), |x, error| x.child(error))
+            .into_any_element()
+    }
+}
+
+/// Tile of the grid view, the first letter of the name stands in for a
+/// missing icon
+fn tile(item: &ActionItem, selected: bool, hint: Option<String>, theme: &Config) -> gpui::Div {
+    let icon = match &item.icon {
+        Some(path) => img(path.clone()).size_12().into_any_element(),
+        None => div()
+            .size_12()
+            .flex()
+            .items_center()
+            .justify_center()
+            .text_xl()
+            .bg(theme.border_color)
+            .child(
+                item.name
+                    .chars()
+                    .next()
+                    .unwrap_or_default()
+                    .to_uppercase()
+                    .to_string(),
+            )
+            .into_any_element(),
+    };
+
+    div()
+        .relative()
+        .p_2()
+        .flex()
+        .flex_col()
+        .items_center()
+        .gap_2()
+        .when_some(hint, |x, hint| {
+            x.child(
+                div()
+                    .absolute()
+                    .top_1()
+                    .left_2()
+                    .text_xs()
+                    .text_color(theme.text_secondary_color)
+                    .child(hint),
+            )
+        })
+        .child(icon)
+        .child(
+            div()
+                .w_full()
+                .text_sm()
+                .text_center()
+                .overflow_hidden()
+                .whitespace_nowrap()
+                .text_ellipsis()
+                .child(item.name.clone()),
+        )
+        .when(item.locked, |x| x.text_color(theme.text_secondary_color))
+        .when(selected, |x| x.bg(theme.selected_background_color))
+        .rounded(px(theme.corner_radius))
 }
 
 fn loading_screen() -> gpui::Div {
@@ -218,8 +1387,41 @@ fn loading_screen() -> gpui::Div {
 impl gpui::Render for ActionListView {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         div().size_full().child(match self.mode {
+            ItemMode::Command if self.filter.trim() == ":handlers" => self.render_handler_list(cx),
+            ItemMode::Command if self.filter.trim() == ":debug" => self.render_debug(cx),
+            ItemMode::Command if self.filter.trim() == ":settings" => self.render_settings(cx),
+            ItemMode::Command if self.filter.trim() == ":help" => {
+                let config = cx.global::<Config>();
+                keybindings::render_cheat_sheet(config, self.help(config))
+            }
+            ItemMode::Command if self.filter.trim() == ":permissions" => {
+                self.render_permissions(cx)
+            }
+            ItemMode::Command if theme_argument(&self.filter).is_some() => {
+                self.render_theme_list(cx)
+            }
             ItemMode::Command => self.render_command_list(cx),
-            ItemMode::Action => self.render_action_list(cx),
+            ItemMode::Action => match &self.dry_run {
+                Some(dry_run) => self.render_dry_run(dry_run, cx),
+                None => self.render_action_list(cx),
+            },
+            ItemMode::Regex => {
+                RegexTester::parse(&self.filter, &self.regex_subject).render(cx.global::<Config>())
+            }
         })
     }
 }
+
+/// Name typed after `:theme`, possibly partial or empty
+fn theme_argument(filter: &str) -> Option<&str> {
+    let argument = filter.trim_start().strip_prefix(":theme")?;
+    if !argument.is_empty() && !argument.starts_with(' ') {
+        return None;
+    }
+    Some(argument.trim())
+}
+
+/// Whether the filter is the `:rescan` command
+fn is_rescan(filter: &str) -> bool {
+    filter.trim().strip_prefix(':').map(str::trim) == Some("rescan")
+}
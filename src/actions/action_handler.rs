@@ -1,6 +1,9 @@
 use crate::action_list_view::ActionListView;
+use crate::actions::score::Score;
 use crate::database::Database;
 use gpui::{AnyElement, Context, IntoElement, RenderOnce};
+use std::path::PathBuf;
+use std::process::Command;
 use std::sync::Arc;
 use std::usize;
 
@@ -12,6 +15,66 @@ pub trait HandlerFactory {
         db: Arc<Database>,
         cx: &mut Context<ActionListView>,
     ) -> Vec<ActionItem>;
+
+    /// Prefixes and keywords the handler reacts to, listed when the query is `?`
+    fn keywords(&self) -> Vec<Keyword> {
+        Vec::new()
+    }
+
+    /// Sensitive data the handler reads, e.g. "your browser history". The
+    /// user is asked once before the handler runs, see `:permissions`.
+    fn data_access(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Bangs of search engines, e.g. `g` for `!g rust gpui`. A query starting
+    /// with one of them only shows this handler, searching the rest.
+    fn bangs(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Word scoping queries to this handler, e.g. `h` for `h rust`. The
+    /// `[handlers.<id>] keyword` option replaces it.
+    fn keyword(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Query the handler is asked for the terms typed after its keyword
+    fn keyword_query(&self, terms: &str) -> String {
+        terms.to_string()
+    }
+
+    /// Whether the handler takes over the list for the query, hiding the
+    /// results of all other handlers
+    fn claims_query(&self, _query: &str) -> bool {
+        false
+    }
+
+    /// Header the handler's results are listed under with `group_results`.
+    /// Handlers of the same kind, like search engines, share one.
+    fn group(&self) -> &'static str {
+        self.get_id()
+    }
+}
+
+/// Group of all search engines, see `HandlerFactory::group`
+pub const WEB_SEARCH_GROUP: &str = "Web Search";
+
+/// A prefix or keyword that makes a handler show its actions
+#[derive(Debug, Clone)]
+pub struct Keyword {
+    /// Inserted into the input when the keyword is selected
+    pub prefix: String,
+    pub description: String,
+}
+
+impl Keyword {
+    pub fn new(prefix: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            description: description.into(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -34,6 +97,79 @@ impl ActionId {
 pub trait ActionHandler: Send + Sync {
     fn execute(&self, input: &str) -> anyhow::Result<()>;
     fn clone_box(&self) -> Box<dyn ActionHandler>;
+
+    /// Like `execute`, with the database the action was listed from. Handlers
+    /// writing to it override this instead of opening another connection.
+    fn execute_with_db(&self, input: &str, _db: &Database) -> anyhow::Result<()> {
+        self.execute(input)
+    }
+
+    /// Describe the command `execute` would run, for the dry-run preview.
+    /// Handlers that don't spawn processes return `None`.
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        None
+    }
+}
+
+/// The exact command line, working directory and environment of an action
+#[derive(Debug, Clone)]
+pub struct CommandPreview {
+    pub command_line: String,
+    pub working_dir: String,
+    /// Variables set or removed on top of Crowbar's own environment
+    pub env: Vec<String>,
+    /// Lines about the target shown above the command, like a whois summary
+    pub details: Vec<String>,
+}
+
+impl CommandPreview {
+    pub fn from_command(command: &Command) -> Self {
+        let command_line = std::iter::once(command.get_program())
+            .chain(command.get_args())
+            .map(|arg| shell_quote(&arg.to_string_lossy()))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let working_dir = command
+            .get_current_dir()
+            .map(|dir| dir.to_path_buf())
+            .or_else(|| std::env::current_dir().ok())
+            .map(|dir| dir.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let env = command
+            .get_envs()
+            .map(|(key, value)| match value {
+                Some(value) => format!("{}={}", key.to_string_lossy(), value.to_string_lossy()),
+                None => format!("unset {}", key.to_string_lossy()),
+            })
+            .collect();
+
+        Self {
+            command_line,
+            working_dir,
+            env,
+            details: Vec::new(),
+        }
+    }
+
+    pub fn with_details(mut self, details: Vec<String>) -> Self {
+        self.details = details;
+        self
+    }
+}
+
+/// Quote an argument so the command line can be pasted into a shell
+fn shell_quote(arg: &str) -> String {
+    let is_safe = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:@%+,".contains(c));
+    if is_safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
 }
 
 pub trait RenderFn: Send + Sync {
@@ -71,14 +207,49 @@ pub trait ActionDefinition: Send + Sync {
     }
 }
 
+/// Another way to run an action, e.g. "Run in terminal", listed in the menu
+/// Alt-Enter opens for the selected action
+#[derive(Clone)]
+pub struct SecondaryAction {
+    pub name: String,
+    pub handler: Box<dyn ActionHandler>,
+}
+
 #[derive(Clone, IntoElement)]
 pub struct ActionItem {
     pub id: ActionId,
+    pub name: String,
     pub handler: Box<dyn ActionHandler>,
     pub render: Box<dyn RenderFn + Send + Sync>,
     pub relevance: usize,
     pub relevance_boost: usize,
     pub db: Arc<Database>,
+    /// Set when the handler is locked and the session has not been unlocked
+    pub locked: bool,
+    /// Header of the section the item is listed under, if any
+    pub section: Option<&'static str>,
+    /// Enter has to be pressed twice to run the action
+    pub needs_confirmation: bool,
+    /// Enter inserts this text into the input instead of running the action
+    pub completion: Option<String>,
+    /// Tab inserts this text into the input instead of the name
+    pub tab_completion: Option<String>,
+    /// Address the action opens. Rows of different handlers opening the same
+    /// address are merged into one.
+    pub url: Option<String>,
+    /// Running the action keeps the window open and updates the results
+    pub stays_open: bool,
+    /// Row only showing a state, like a search in progress. Enter does
+    /// nothing on it.
+    pub is_status: bool,
+    /// Passed to the handler instead of the query, e.g. the query without
+    /// its bang
+    pub input: Option<String>,
+    pub secondary_actions: Vec<SecondaryAction>,
+    /// Image shown on the tile of the grid view
+    pub icon: Option<PathBuf>,
+    /// Parts of the relevance, shown by the score overlay
+    pub score: Option<Score>,
 }
 
 impl Eq for ActionItem {}
@@ -116,6 +287,7 @@ impl Clone for Box<dyn ActionHandler> {
 impl ActionItem {
     pub fn new<H, R>(
         id: ActionId,
+        name: String,
         handler: H,
         render: R,
         relevance: usize,
@@ -128,20 +300,111 @@ impl ActionItem {
     {
         ActionItem {
             id,
+            name,
             handler: Box::new(handler),
             render: Box::new(render),
             relevance,
             relevance_boost,
             db,
+            locked: false,
+            section: None,
+            needs_confirmation: false,
+            completion: None,
+            tab_completion: None,
+            url: None,
+            stays_open: false,
+            is_status: false,
+            input: None,
+            secondary_actions: Vec::new(),
+            icon: None,
+            score: None,
         }
     }
 
+    pub fn with_section(mut self, section: &'static str) -> Self {
+        self.section = Some(section);
+        self
+    }
+
+    pub fn with_confirmation(mut self, needs_confirmation: bool) -> Self {
+        self.needs_confirmation = needs_confirmation;
+        self
+    }
+
+    pub fn with_completion(mut self, completion: String) -> Self {
+        self.completion = Some(completion);
+        self
+    }
+
+    pub fn with_tab_completion(mut self, completion: String) -> Self {
+        self.tab_completion = Some(completion);
+        self
+    }
+
+    pub fn with_url(mut self, url: String) -> Self {
+        self.url = Some(url);
+        self
+    }
+
+    pub fn with_input(mut self, input: String) -> Self {
+        self.input = Some(input);
+        self
+    }
+
+    pub fn with_icon(mut self, icon: Option<PathBuf>) -> Self {
+        self.icon = icon;
+        self
+    }
+
+    pub fn with_score(mut self, score: Option<Score>) -> Self {
+        self.score = score;
+        self
+    }
+
+    pub fn with_secondary_action<H>(mut self, name: impl Into<String>, handler: H) -> Self
+    where
+        H: ActionHandler + 'static,
+    {
+        self.secondary_actions.push(SecondaryAction {
+            name: name.into(),
+            handler: Box::new(handler),
+        });
+        self
+    }
+
+    pub fn staying_open(mut self) -> Self {
+        self.stays_open = true;
+        self
+    }
+
+    pub fn as_status(mut self) -> Self {
+        self.is_status = true;
+        self
+    }
+
     pub fn relevance(&self) -> usize {
-        return self.relevance * self.relevance_boost;
+        // Fixed relevances are close to the maximum
+        return self.relevance.saturating_mul(self.relevance_boost);
     }
 
     pub fn execute(&self, input: &str) -> anyhow::Result<()> {
         self.db.log_execution(self.id.as_str())?;
-        self.handler.execute(input)
+        self.handler
+            .execute_with_db(self.input.as_deref().unwrap_or(input), &self.db)
+    }
+
+    /// Run one of the secondary actions, counted as a run of the action itself
+    pub fn execute_secondary(&self, index: usize, input: &str) -> anyhow::Result<()> {
+        let Some(secondary) = self.secondary_actions.get(index) else {
+            return Ok(());
+        };
+        self.db.log_execution(self.id.as_str())?;
+        secondary
+            .handler
+            .execute_with_db(self.input.as_deref().unwrap_or(input), &self.db)
+    }
+
+    pub fn preview(&self, input: &str) -> Option<CommandPreview> {
+        self.handler.preview(self.input.as_deref().unwrap_or(input))
     }
 }
@@ -5,3 +5,37 @@ pub const PERPLEXITY_SEARCH: &str = "perplexity";
 pub const URL_OPEN: &str = "url";
 pub const BROWSER_HISTORY: &str = "browser-history";
 pub const EXECUTABLE_HANDLER: &str = "executable";
+pub const FORMATTER: &str = "formatter";
+pub const IP_LOOKUP: &str = "ip-lookup";
+pub const NOTES: &str = "notes";
+pub const TIMESTAMP: &str = "timestamp";
+pub const REMOTE: &str = "remote";
+pub const REMINDERS: &str = "reminders";
+pub const FILE_SEARCH: &str = "file-search";
+pub const PLUGINS: &str = "plugins";
+pub const POWER: &str = "power";
+pub const WIFI: &str = "wifi";
+pub const AUDIO: &str = "audio";
+pub const BITWARDEN: &str = "bitwarden";
+pub const GITHUB: &str = "github";
+pub const DOCKER: &str = "docker";
+pub const LINK_GROUPS: &str = "link-groups";
+pub const BROWSER_TABS: &str = "browser-tabs";
+pub const FLATHUB: &str = "flathub";
+pub const ZOXIDE: &str = "zoxide";
+pub const GIT_REPOS: &str = "git-repos";
+pub const WORLD_CLOCK: &str = "world-clock";
+pub const COLOR: &str = "color";
+pub const ENCODING: &str = "encoding";
+pub const GENERATOR: &str = "generator";
+pub const DOCS: &str = "docs";
+pub const RESOURCES: &str = "resources";
+pub const SHELL: &str = "shell";
+pub const PATHS: &str = "paths";
+pub const OPEN_WITH: &str = "open-with";
+pub const DISPLAYS: &str = "displays";
+pub const UNICODE: &str = "unicode";
+pub const WORKSPACES: &str = "workspaces";
+pub const TEXT_CASE: &str = "text-case";
+pub const NIX: &str = "nix";
+pub const SHORTCUTS: &str = "shortcuts";
@@ -0,0 +1,70 @@
+//! Consent of handlers that read sensitive data. Until the user answers, the
+//! handler doesn't run and two rows ask whether it may read the data. The
+//! answer is stored per handler and can be changed with `:permissions`.
+
+use anyhow::Result;
+use gpui::{div, Element, ParentElement, Rgba, Styled};
+use std::sync::Arc;
+
+use crate::actions::action_handler::{ActionHandler, ActionId, ActionItem};
+use crate::database::Database;
+
+/// Section the consent rows are listed under
+const PERMISSION_SECTION: &str = "Permission needed";
+
+#[derive(Clone)]
+pub struct DataAccessHandler {
+    handler_id: &'static str,
+    allow: bool,
+}
+
+impl ActionHandler for DataAccessHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        Database::new()?.set_data_access(self.handler_id, Some(self.allow))
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+/// Rows asking whether the handler may read `data`
+pub fn prompt(
+    handler_id: &'static str,
+    data: &'static str,
+    db: Arc<Database>,
+    text_secondary_color: Rgba,
+) -> Vec<ActionItem> {
+    [(true, "Allow"), (false, "Don't allow")]
+        .into_iter()
+        .map(|(allow, verb)| {
+            let id = Box::leak(format!("data-access-{}-{}", allow, handler_id).into_boxed_str());
+            let name = format!("{} {} to read {}", verb, handler_id, data);
+            let detail = "Change later with :permissions".to_string();
+            ActionItem::new(
+                ActionId::Builtin(id),
+                name.clone(),
+                DataAccessHandler { handler_id, allow },
+                move || {
+                    div()
+                        .flex()
+                        .gap_4()
+                        .child(div().flex_none().child(name.clone()))
+                        .child(
+                            div()
+                                .flex_grow()
+                                .overflow_hidden()
+                                .child(detail.clone())
+                                .text_color(text_secondary_color),
+                        )
+                        .into_any()
+                },
+                0,
+                1,
+                db.clone(),
+            )
+            .with_section(PERMISSION_SECTION)
+            .staying_open()
+        })
+        .collect()
+}
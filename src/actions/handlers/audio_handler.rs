@@ -0,0 +1,193 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::process::Command;
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::AUDIO;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::audio::{self, AudioDevice, DeviceKind};
+
+/// Queries starting with this word list audio devices
+const AUDIO_PREFIX: &str = "audio";
+
+/// Switches the default audio output and input, mutes and changes the volume
+pub struct AudioHandlerFactory;
+
+impl HandlerFactory for AudioHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        AUDIO
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(
+            format!("{} ", AUDIO_PREFIX),
+            "Switch audio devices, mute and change the volume",
+        )]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(filter) = query.trim_start().strip_prefix(AUDIO_PREFIX) else {
+            return Vec::new();
+        };
+        if !filter.is_empty() && !filter.starts_with(' ') {
+            return Vec::new();
+        }
+        let filter = filter.trim().to_lowercase();
+
+        let config = cx.global::<Config>();
+        let text_secondary_color = config.text_secondary_color;
+        let step = config.audio_volume_step as i32;
+
+        let devices = match audio::devices() {
+            Ok(devices) => devices,
+            Err(e) => {
+                return vec![create_row(
+                    "audio-failed",
+                    "Audio devices unavailable".to_string(),
+                    e.to_string(),
+                    AudioHandler::None,
+                    100,
+                    db,
+                    text_secondary_color,
+                )];
+            }
+        };
+
+        let controls = [
+            ("Volume Up", AudioHandler::ChangeVolume(step)),
+            ("Volume Down", AudioHandler::ChangeVolume(-step)),
+            ("Mute Output", AudioHandler::ToggleMute(DeviceKind::Sink)),
+            ("Mute Input", AudioHandler::ToggleMute(DeviceKind::Source)),
+        ];
+        let mut handlers: Vec<ActionItem> = controls
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (name, _))| name.to_lowercase().contains(&filter))
+            .map(|(index, (name, handler))| {
+                let id = Box::leak(format!("{}-{}", AUDIO, name).into_boxed_str());
+                create_row(
+                    id,
+                    name.to_string(),
+                    "Default device".to_string(),
+                    handler,
+                    200 - index,
+                    db.clone(),
+                    text_secondary_color,
+                )
+            })
+            .collect();
+
+        handlers.extend(
+            devices
+                .into_iter()
+                .filter(|device| device.description.to_lowercase().contains(&filter))
+                .enumerate()
+                .map(|(index, device)| {
+                    let id = Box::leak(format!("{}-{}", AUDIO, device.name).into_boxed_str());
+                    let mut detail = match device.kind {
+                        DeviceKind::Sink => "Output".to_string(),
+                        DeviceKind::Source => "Input".to_string(),
+                    };
+                    let handler = if device.is_default {
+                        detail.push_str(" · default");
+                        AudioHandler::None
+                    } else {
+                        AudioHandler::SetDefault(device.clone())
+                    };
+                    create_row(
+                        id,
+                        device.description,
+                        detail,
+                        handler,
+                        100 - index,
+                        db.clone(),
+                        text_secondary_color,
+                    )
+                }),
+        );
+
+        handlers
+    }
+}
+
+#[derive(Clone)]
+pub enum AudioHandler {
+    SetDefault(AudioDevice),
+    ToggleMute(DeviceKind),
+    /// Change the volume of the default output by this many percent
+    ChangeVolume(i32),
+    /// Informational rows
+    None,
+}
+
+impl AudioHandler {
+    fn command(&self) -> Option<Command> {
+        match self {
+            Self::SetDefault(device) => Some(audio::set_default_command(device)),
+            Self::ToggleMute(kind) => Some(audio::toggle_mute_command(*kind)),
+            Self::ChangeVolume(step) => Some(audio::change_volume_command(*step)),
+            Self::None => None,
+        }
+    }
+}
+
+impl ActionHandler for AudioHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        if let Some(mut command) = self.command() {
+            command.status()?;
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        self.command()
+            .map(|command| CommandPreview::from_command(&command))
+    }
+}
+
+fn create_row(
+    id: &'static str,
+    name: String,
+    detail: String,
+    handler: AudioHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        10,
+        db,
+    )
+}
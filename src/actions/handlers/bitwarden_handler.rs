@@ -0,0 +1,251 @@
+use anyhow::{anyhow, Result};
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::BITWARDEN;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::bitwarden::{self, Field, VaultItem};
+use crate::system::copy_to_clipboard;
+
+/// Queries starting with this word search the vault
+const BITWARDEN_PREFIX: &str = "bw";
+
+/// The vault is listed again when the list is older than this
+const REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// A locked or unavailable vault is checked again after this, e.g. after
+/// `bw unlock`
+const RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+const MAX_ITEMS: usize = 5;
+
+#[derive(Debug, Clone)]
+enum VaultState {
+    Pending,
+    Ready(Vec<VaultItem>),
+    /// No session key is available
+    Locked,
+    Failed(String),
+}
+
+lazy_static::lazy_static! {
+    static ref VAULT_STATE: Mutex<Option<(Instant, VaultState)>> = Mutex::new(None);
+}
+
+/// Searches the Bitwarden vault with `bw <name>` and copies logins
+pub struct BitwardenHandlerFactory;
+
+impl HandlerFactory for BitwardenHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        BITWARDEN
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(
+            format!("{} ", BITWARDEN_PREFIX),
+            "Copy logins from the Bitwarden vault",
+        )]
+    }
+
+    fn data_access(&self) -> Option<&'static str> {
+        Some("the logins in your Bitwarden vault")
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(filter) = query.trim_start().strip_prefix(BITWARDEN_PREFIX) else {
+            return Vec::new();
+        };
+        if !filter.starts_with(' ') {
+            return Vec::new();
+        }
+        let filter = filter.trim().to_lowercase();
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+
+        let info_row = |id, name: &str, detail: String| {
+            vec![create_row(
+                id,
+                name.to_string(),
+                detail,
+                BitwardenHandler::None,
+                100,
+                db.clone(),
+                text_secondary_color,
+            )]
+        };
+
+        let items = match vault_state(cx) {
+            VaultState::Ready(items) => items,
+            VaultState::Pending => {
+                return info_row("bitwarden-loading", "Reading vault...", String::new())
+            }
+            VaultState::Locked => {
+                return info_row(
+                    "bitwarden-locked",
+                    "Bitwarden vault is locked",
+                    "Run bw unlock and set BW_SESSION".to_string(),
+                )
+            }
+            VaultState::Failed(e) => {
+                return info_row("bitwarden-failed", "Bitwarden unavailable", e)
+            }
+        };
+
+        items
+            .into_iter()
+            .filter(|item| item.name.to_lowercase().contains(&filter))
+            .take(MAX_ITEMS)
+            .enumerate()
+            .flat_map(|(index, item)| {
+                let mut fields = vec![Field::Password];
+                if item.username.is_some() {
+                    fields.push(Field::Username);
+                }
+                if item.has_totp {
+                    fields.push(Field::Totp);
+                }
+
+                let db = db.clone();
+                let count = fields.len();
+                fields.into_iter().enumerate().map(move |(offset, field)| {
+                    let id = Box::leak(
+                        format!("{}-{}-{}", BITWARDEN, item.id, field.name()).into_boxed_str(),
+                    );
+                    let detail = match (&item.username, field) {
+                        (Some(username), Field::Password) => {
+                            format!("Copy password · {}", username)
+                        }
+                        (_, Field::Password) => "Copy password".to_string(),
+                        (_, Field::Username) => "Copy username".to_string(),
+                        (_, Field::Totp) => "Copy TOTP code".to_string(),
+                    };
+                    create_row(
+                        id,
+                        item.name.clone(),
+                        detail,
+                        BitwardenHandler::Copy {
+                            id: item.id.clone(),
+                            field,
+                        },
+                        // Keep the fields of an item together, password first
+                        (MAX_ITEMS - index) * 10 + count - offset,
+                        db.clone(),
+                        text_secondary_color,
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub enum BitwardenHandler {
+    Copy {
+        id: String,
+        field: Field,
+    },
+    /// Informational rows
+    None,
+}
+
+impl ActionHandler for BitwardenHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        let BitwardenHandler::Copy { id, field } = self else {
+            return Ok(());
+        };
+
+        let session =
+            bitwarden::session_token().ok_or_else(|| anyhow!("Bitwarden vault is locked"))?;
+        copy_to_clipboard(&bitwarden::get(*field, id, &session)?)
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+fn create_row(
+    id: &'static str,
+    name: String,
+    detail: String,
+    handler: BitwardenHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        10,
+        db,
+    )
+}
+
+/// Current vault items, listing them in the background when missing or outdated
+fn vault_state(cx: &mut Context<ActionListView>) -> VaultState {
+    let mut state = VAULT_STATE.lock().unwrap();
+    if let Some((fetched_at, current)) = state.as_ref() {
+        let max_age = match current {
+            VaultState::Ready(_) => REFRESH_INTERVAL,
+            _ => RETRY_INTERVAL,
+        };
+        if matches!(current, VaultState::Pending) || fetched_at.elapsed() < max_age {
+            return current.clone();
+        }
+    }
+
+    // Keep showing outdated items while listing the vault again
+    let shown = match state.take() {
+        Some((_, ready @ VaultState::Ready(_))) => ready,
+        _ => VaultState::Pending,
+    };
+    *state = Some((Instant::now(), shown.clone()));
+    drop(state);
+
+    cx.spawn(|view, mut cx| async move {
+        let new_state = cx
+            .background_executor()
+            .spawn(async {
+                match bitwarden::session_token() {
+                    Some(session) => match bitwarden::items(&session) {
+                        Ok(items) => VaultState::Ready(items),
+                        Err(e) => VaultState::Failed(e.to_string()),
+                    },
+                    None => VaultState::Locked,
+                }
+            })
+            .await;
+
+        *VAULT_STATE.lock().unwrap() = Some((Instant::now(), new_state));
+        let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+    })
+    .detach();
+
+    shown
+}
@@ -14,8 +14,14 @@ use crate::actions::action_handler::{
     ActionDefinition, ActionHandler, ActionId, ActionItem, HandlerFactory,
 };
 use crate::actions::action_ids::BROWSER_HISTORY;
+use crate::actions::handlers::url_handler::PrivateWindowHandler;
+use crate::actions::highlight::highlighted;
+use crate::actions::loading;
+use crate::actions::score::{self, Score, RELEVANCE_BOOST};
 use crate::config::Config;
 use crate::database::Database;
+use crate::demo;
+use crate::system::browser;
 
 pub struct BrowserHistoryHandlerFactory;
 
@@ -24,6 +30,14 @@ impl HandlerFactory for BrowserHistoryHandlerFactory {
        BROWSER_HISTORY 
     }
 
+    fn group(&self) -> &'static str {
+        "History"
+    }
+
+    fn keyword(&self) -> Option<&'static str> {
+        Some("h")
+    }
+
     fn create_handlers_for_query(
         &self,
         query: &str,
@@ -32,6 +46,10 @@ impl HandlerFactory for BrowserHistoryHandlerFactory {
     ) -> Vec<ActionItem> {
         BrowserHistoryFactory::create_actions_for_query(query, db, cx)
     }
+
+    fn data_access(&self) -> Option<&'static str> {
+        Some("your browser history")
+    }
 }
 
 /// Represents a browser history entry across different browsers
@@ -67,6 +85,12 @@ enum InstallType {
 lazy_static::lazy_static! {
     static ref HISTORY_CACHE: Mutex<Option<Vec<HistoryEntry>>> = Mutex::new(None);
     static ref LAST_CACHE_UPDATE: Mutex<SystemTime> = Mutex::new(UNIX_EPOCH);
+    /// Query typed last and the entries found for the last finished search
+    static ref LATEST_QUERY: Mutex<Option<String>> = Mutex::new(None);
+    static ref LAST_SEARCH: Mutex<Option<(String, Vec<HistoryEntry>)>> = Mutex::new(None);
+    /// Searches copy the browser databases to the same temporary files, so
+    /// only one runs at a time
+    static ref SEARCH_RUNNING: Mutex<()> = Mutex::new(());
 }
 
 // ============================================================================
@@ -79,6 +103,12 @@ pub struct BrowserHistoryHandler {
     entry: Option<HistoryEntry>,
 }
 
+impl Default for BrowserHistoryHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl BrowserHistoryHandler {
     pub fn new() -> Self {
         Self { entry: None }
@@ -193,6 +223,7 @@ impl ActionDefinition for BrowserHistoryHandler {
         // Each entry will create its own handler when filtering
         ActionItem::new(
             self.get_id(),
+            self.get_name(),
             self.clone(),
             move || {
                 div()
@@ -232,6 +263,10 @@ struct HistoryCollector;
 impl HistoryCollector {
     /// Collect history from all browser types
     fn collect_all_browser_histories(search_term: &str) -> Vec<HistoryEntry> {
+        if demo::is_enabled() {
+            return demo::history(search_term);
+        }
+
         let mut entries = Vec::new();
 
         // Define all supported browsers
@@ -712,23 +747,90 @@ impl BrowserHistoryFactory {
         let config = cx.global::<Config>();
         let text_secondary_color = config.text_secondary_color;
 
-        // Use the query parameter to search in the database directly
-        let matching_entries = BrowserHistoryHandler::get_history_entries_for_query(query);
+        // Copying and reading the browser databases takes a while, so the
+        // search runs in the background. Until it's done, entries of the
+        // previous search that still match are shown.
+        let matching_entries = match Self::searched_entries(query) {
+            Some(entries) => entries,
+            None => {
+                Self::search_in_background(query.to_string(), cx);
+                Self::previous_entries(query)
+            }
+        };
 
         info!(
             "Found {} matching browser history entries",
             matching_entries.len()
         );
 
+        let filter = query.trim().to_lowercase();
         matching_entries
             .into_iter()
-            .map(|entry| Self::create_action_from_entry(entry, db.clone(), &config))
+            .map(|entry| Self::create_action_from_entry(entry, &filter, db.clone(), &config))
             .collect()
     }
 
+    /// Entries of the last search, if it was for this query
+    fn searched_entries(query: &str) -> Option<Vec<HistoryEntry>> {
+        match &*LAST_SEARCH.lock().unwrap() {
+            Some((searched, entries)) if searched == query => Some(entries.clone()),
+            _ => None,
+        }
+    }
+
+    /// Entries of the last search matching the query like the SQL queries do
+    fn previous_entries(query: &str) -> Vec<HistoryEntry> {
+        let filter = query.to_lowercase();
+        match &*LAST_SEARCH.lock().unwrap() {
+            Some((_, entries)) => entries
+                .iter()
+                .filter(|entry| {
+                    entry.title.to_lowercase().contains(&filter)
+                        || entry.url.to_lowercase().contains(&filter)
+                })
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Search the history and refresh the list when done. Searches for
+    /// queries typed over in the meantime are skipped.
+    fn search_in_background(query: String, cx: &mut Context<ActionListView>) {
+        let previous = LATEST_QUERY.lock().unwrap().replace(query.clone());
+        if previous.as_ref() == Some(&query) {
+            // Already running
+            return;
+        }
+
+        loading::start(BROWSER_HISTORY);
+        cx.spawn(|view, mut cx| async move {
+            let search = query.clone();
+            let entries = cx
+                .background_executor()
+                .spawn(async move {
+                    let _running = SEARCH_RUNNING.lock().unwrap();
+                    if LATEST_QUERY.lock().unwrap().as_ref() != Some(&search) {
+                        return None;
+                    }
+                    let entries = BrowserHistoryHandler::get_history_entries_for_query(&search);
+                    Some(entries)
+                })
+                .await;
+
+            if let Some(entries) = entries {
+                *LAST_SEARCH.lock().unwrap() = Some((query, entries));
+            }
+            loading::finish(BROWSER_HISTORY);
+            let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+        })
+        .detach();
+    }
+
     /// Create an action item from a history entry
     fn create_action_from_entry(
         entry: HistoryEntry,
+        filter: &str,
         db: Arc<Database>,
         config: &Config,
     ) -> ActionItem {
@@ -740,25 +842,29 @@ impl BrowserHistoryFactory {
         };
         let display_url = entry.url.clone();
         let name = display_title.clone();
+        let match_ranges = score::match_ranges(filter, &name);
         let text_secondary_color = config.text_secondary_color;
+        let highlight_color = config.match_highlight_color;
 
-        // Create a static string ID that lives for the entire program
-        let id_str = Box::leak(
-            format!(
-                "browser-history-{}",
-                entry.url.chars().take(20).collect::<String>()
-            )
-            .into_boxed_str(),
-        );
+        // Create a static string ID that lives for the entire program. The
+        // full URL keeps executions of pages on the same site apart.
+        let id_str = Box::leak(format!("browser-history-{}", entry.url).into_boxed_str());
+        let score = Self::score_entry(&entry, filter, id_str, &db);
+        let relevance = score.relevance();
 
-        ActionItem::new(
+        let item = ActionItem::new(
             ActionId::Builtin(id_str),
+            name.clone(),
             handler,
             move || {
                 div()
                     .flex()
                     .gap_4()
-                    .child(div().flex_none().child(name.clone()))
+                    .child(div().flex_none().child(highlighted(
+                        &name,
+                        &match_ranges,
+                        highlight_color,
+                    )))
                     .child(
                         div()
                             .flex_grow()
@@ -767,9 +873,40 @@ impl BrowserHistoryFactory {
                     )
                     .into_any()
             },
-            50 + entry.visit_count.min(100) as usize,
-            10,
+            relevance,
+            RELEVANCE_BOOST,
             db,
         )
+        .with_url(entry.url.clone())
+        .with_score(Some(score));
+
+        if browser::supports_private_window() {
+            item.with_secondary_action("Open in private window", PrivateWindowHandler(entry.url))
+        } else {
+            item
+        }
+    }
+
+    /// Rank an entry like an executable: pages opened through crowbar gain
+    /// frecency, browser visits add a little on top
+    fn score_entry(entry: &HistoryEntry, filter: &str, id: &str, db: &Database) -> Score {
+        let filter_tokens: Vec<&str> = filter.split_whitespace().collect();
+        let match_quality = score::match_quality(filter, &entry.title)
+            .max(score::match_quality(filter, Self::strip_scheme(&entry.url)));
+        let frecency = db.get_action_frecency(id).unwrap_or(0.0);
+
+        Score::new(
+            frecency,
+            match_quality,
+            score::search_score(&filter_tokens, &entry.title),
+        )
+        .with_external_visits(entry.visit_count)
+    }
+
+    /// e.g. "github.com/..." for "https://www.github.com/...", so typing the
+    /// site name counts as a prefix match
+    fn strip_scheme(url: &str) -> &str {
+        let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+        url.strip_prefix("www.").unwrap_or(url)
     }
 }
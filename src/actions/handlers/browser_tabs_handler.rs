@@ -0,0 +1,150 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use log::warn;
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::BROWSER_TABS;
+use crate::actions::score::{self, Score, RELEVANCE_BOOST};
+use crate::config::Config;
+use crate::database::Database;
+use crate::tab_bridge::{self, Tab};
+
+/// Queries starting with this word list all open tabs
+const TAB_PREFIX: &str = "tab";
+
+/// An open tab ranks like an app launched once, so it comes before the
+/// history entry of the same page
+const OPEN_TAB_FRECENCY: f64 = 1.0;
+
+const MAX_RESULTS: usize = 10;
+
+/// Focuses tabs shared by the companion browser extension
+pub struct BrowserTabsHandlerFactory;
+
+impl HandlerFactory for BrowserTabsHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        BROWSER_TABS
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(
+            format!("{} ", TAB_PREFIX),
+            "Switch to an open browser tab",
+        )]
+    }
+
+    fn data_access(&self) -> Option<&'static str> {
+        Some("your open browser tabs")
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        if !tab_bridge::is_enabled() || query.trim().is_empty() {
+            return Vec::new();
+        }
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+
+        // `tab <filter>` lists every tab, anything else only close matches
+        let query = query.trim_start();
+        let filter = match query.strip_prefix(TAB_PREFIX) {
+            Some(rest) if rest.is_empty() || rest.starts_with(' ') => rest.trim(),
+            _ => query.trim(),
+        }
+        .to_lowercase();
+        let filter_tokens: Vec<&str> = filter.split_whitespace().collect();
+
+        let mut handlers: Vec<ActionItem> = tab_bridge::tabs()
+            .into_iter()
+            .filter(|tab| {
+                tab.title.to_lowercase().contains(&filter)
+                    || tab.url.to_lowercase().contains(&filter)
+            })
+            .map(|tab| {
+                let id = Box::leak(format!("{}-{}", BROWSER_TABS, tab.url).into_boxed_str());
+                let frecency = db.get_action_frecency(id).unwrap_or(0.0);
+                let score = Score::new(
+                    frecency + OPEN_TAB_FRECENCY,
+                    score::match_quality(&filter, &tab.title)
+                        .max(score::match_quality(&filter, &tab.url)),
+                    score::search_score(&filter_tokens, &tab.title),
+                );
+
+                create_row(id, tab, score.relevance(), db.clone(), text_secondary_color)
+                    .with_score(Some(score))
+            })
+            .collect();
+
+        handlers.sort();
+        handlers.truncate(MAX_RESULTS);
+        handlers
+    }
+}
+
+#[derive(Clone)]
+pub struct BrowserTabsHandler {
+    tab: Tab,
+}
+
+impl ActionHandler for BrowserTabsHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        if let Err(e) = tab_bridge::focus(&self.tab) {
+            // The browser went away or the extension stopped, open the page
+            // again instead
+            warn!("Failed to focus tab: {}", e);
+            open::that(&self.tab.url)?;
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+fn create_row(
+    id: &'static str,
+    tab: Tab,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    let name = if tab.title.is_empty() {
+        tab.url.clone()
+    } else {
+        tab.title.clone()
+    };
+    let detail = format!("Open tab · {}", tab.url);
+    let url = tab.url.clone();
+
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        BrowserTabsHandler { tab },
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        RELEVANCE_BOOST,
+        db,
+    )
+    .with_url(url)
+}
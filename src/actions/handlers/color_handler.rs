@@ -0,0 +1,262 @@
+use anyhow::Result;
+use gpui::{div, px, Context, Element, ParentElement, Styled};
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{ActionHandler, ActionId, ActionItem, HandlerFactory};
+use crate::actions::action_ids::COLOR;
+use crate::config::{Color, Config};
+use crate::database::Database;
+use crate::system::copy_to_clipboard;
+
+/// Notations a color can be typed and copied in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Notation {
+    Hex,
+    Rgb,
+    Hsl,
+}
+
+impl Notation {
+    const ALL: [Notation; 3] = [Notation::Hex, Notation::Rgb, Notation::Hsl];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Hex => "Hex",
+            Self::Rgb => "RGB",
+            Self::Hsl => "HSL",
+        }
+    }
+
+    fn format(self, color: Color) -> String {
+        match self {
+            Self::Hex => color.to_hex(),
+            Self::Rgb => format!("rgb({}, {}, {})", color.r, color.g, color.b),
+            Self::Hsl => {
+                let (h, s, l) = to_hsl(color);
+                format!("hsl({}, {}%, {}%)", h, s, l)
+            }
+        }
+    }
+}
+
+/// Converts `#rrggbb`, `rgb()` and `hsl()` colors into the other notations,
+/// with a swatch of the color
+pub struct ColorHandlerFactory;
+
+impl HandlerFactory for ColorHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        COLOR
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some((color, typed)) = parse_color(query) else {
+            return Vec::new();
+        };
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+
+        // The typed notation is already known, the others come first
+        let mut notations = Notation::ALL.to_vec();
+        notations.sort_by_key(|notation| *notation == typed);
+
+        notations
+            .into_iter()
+            .enumerate()
+            .map(|(index, notation)| {
+                let id = match notation {
+                    Notation::Hex => "color-hex",
+                    Notation::Rgb => "color-rgb",
+                    Notation::Hsl => "color-hsl",
+                };
+                ColorHandler {
+                    value: notation.format(color),
+                }
+                .create_action(
+                    ActionId::Builtin(id),
+                    color,
+                    notation.label().to_string(),
+                    100 - index,
+                    db.clone(),
+                    text_secondary_color,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Copies the color in one notation to the clipboard
+#[derive(Clone)]
+pub struct ColorHandler {
+    value: String,
+}
+
+impl ActionHandler for ColorHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        copy_to_clipboard(&self.value)
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+impl ColorHandler {
+    fn create_action(
+        &self,
+        id: ActionId,
+        color: Color,
+        detail: String,
+        relevance: usize,
+        db: Arc<Database>,
+        text_secondary_color: gpui::Rgba,
+    ) -> ActionItem {
+        let name = self.value.clone();
+        let swatch = color.to_rgba();
+
+        ActionItem::new(
+            id,
+            name.clone(),
+            self.clone(),
+            move || {
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_4()
+                    .child(
+                        div()
+                            .flex_none()
+                            .size(px(14.))
+                            .rounded_sm()
+                            .border_1()
+                            .border_color(text_secondary_color)
+                            .bg(swatch),
+                    )
+                    .child(div().flex_none().child(name.clone()))
+                    .child(
+                        div()
+                            .flex_grow()
+                            .child(detail.clone())
+                            .text_color(text_secondary_color),
+                    )
+                    .into_any()
+            },
+            relevance,
+            10,
+            db,
+        )
+    }
+}
+
+/// Parse `#rgb`, `#rrggbb`, `rgb(r, g, b)` or `hsl(h, s%, l%)`, returning
+/// the notation it was typed in
+fn parse_color(query: &str) -> Option<(Color, Notation)> {
+    let query = query.trim().to_lowercase();
+
+    if let Some(hex) = query.strip_prefix('#') {
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let hex = match hex.len() {
+            3 => hex.chars().flat_map(|c| [c, c]).collect(),
+            6 => hex.to_string(),
+            _ => return None,
+        };
+        return Color::from_hex(&hex)
+            .ok()
+            .map(|color| (color, Notation::Hex));
+    }
+
+    if let Some(args) = function_args(&query, "rgb") {
+        let [r, g, b] = args[..] else {
+            return None;
+        };
+        let channel = |value: &str| value.parse::<u8>().ok();
+        return Some((
+            Color::new(channel(r)?, channel(g)?, channel(b)?),
+            Notation::Rgb,
+        ));
+    }
+
+    if let Some(args) = function_args(&query, "hsl") {
+        let [h, s, l] = args[..] else {
+            return None;
+        };
+        let h: f64 = h.strip_suffix("deg").unwrap_or(h).parse().ok()?;
+        let percent = |value: &str| {
+            value
+                .strip_suffix('%')
+                .unwrap_or(value)
+                .parse::<f64>()
+                .ok()
+                .filter(|value| (0.0..=100.0).contains(value))
+        };
+        return Some((from_hsl(h, percent(s)?, percent(l)?), Notation::Hsl));
+    }
+
+    None
+}
+
+/// Arguments of `name(a, b, c)`, separated by commas or spaces as in CSS
+fn function_args<'a>(query: &'a str, name: &str) -> Option<Vec<&'a str>> {
+    let args = query
+        .strip_prefix(name)?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')?;
+    Some(
+        args.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|arg| !arg.is_empty())
+            .collect(),
+    )
+}
+
+/// Hue in degrees, saturation and lightness in percent
+fn to_hsl(color: Color) -> (i64, i64, i64) {
+    let [r, g, b] = [color.r, color.g, color.b].map(|c| c as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let delta = max - min;
+
+    if delta == 0.0 {
+        return (0, 0, (l * 100.0).round() as i64);
+    }
+
+    let s = delta / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+
+    (
+        h.round() as i64 % 360,
+        (s * 100.0).round() as i64,
+        (l * 100.0).round() as i64,
+    )
+}
+
+fn from_hsl(h: f64, s: f64, l: f64) -> Color {
+    let (s, l) = (s / 100.0, l / 100.0);
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = l - c / 2.0;
+    let channel = |value: f64| ((value + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    Color::new(channel(r), channel(g), channel(b))
+}
@@ -0,0 +1,280 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::DISPLAYS;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::displays::{self, Backend, Layout};
+
+/// Queries starting with this word list display profiles and layouts
+const DISPLAY_PREFIX: &str = "display";
+
+/// Profiles and outputs are read again when they're older than this
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone)]
+enum DisplayState {
+    Pending,
+    Ready(Layout),
+    Failed(String),
+}
+
+lazy_static::lazy_static! {
+    static ref DISPLAY_STATE: Mutex<Option<(Instant, DisplayState)>> = Mutex::new(None);
+}
+
+/// Applies autorandr or kanshi profiles and mirrors or extends displays with
+/// `display <name>`
+pub struct DisplayHandlerFactory;
+
+impl HandlerFactory for DisplayHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        DISPLAYS
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(
+            format!("{} ", DISPLAY_PREFIX),
+            "Apply display profiles, mirror or extend displays",
+        )]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(filter) = query.trim_start().strip_prefix(DISPLAY_PREFIX) else {
+            return Vec::new();
+        };
+        if !filter.is_empty() && !filter.starts_with(' ') {
+            return Vec::new();
+        }
+        let filter = filter.trim().to_lowercase();
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+        let backend = Backend::current();
+
+        let layout = match display_state(backend, cx) {
+            DisplayState::Ready(layout) => layout,
+            DisplayState::Pending => {
+                return vec![create_row(
+                    "displays-loading",
+                    "Reading displays...".to_string(),
+                    String::new(),
+                    DisplayHandler::None,
+                    100,
+                    db,
+                    text_secondary_color,
+                )];
+            }
+            DisplayState::Failed(e) => {
+                return vec![create_row(
+                    "displays-failed",
+                    "Displays unavailable".to_string(),
+                    e,
+                    DisplayHandler::None,
+                    100,
+                    db,
+                    text_secondary_color,
+                )];
+            }
+        };
+
+        let mut rows: Vec<(String, String, String, DisplayHandler)> = layout
+            .profiles
+            .into_iter()
+            .map(|profile| {
+                (
+                    format!("profile-{}", profile),
+                    format!("Display profile {}", profile),
+                    backend.profile_tool().to_string(),
+                    DisplayHandler::Profile { backend, profile },
+                )
+            })
+            .collect();
+
+        // Secondary outputs are arranged relative to the primary one
+        if let Some((primary, others)) = layout.outputs.split_first() {
+            for output in others {
+                let mirror = DisplayHandler::Mirror {
+                    backend,
+                    primary: primary.clone(),
+                    output: output.clone(),
+                };
+                if mirror.command().is_some() {
+                    rows.push((
+                        format!("mirror-{}", output),
+                        format!("Mirror {} to {}", primary, output),
+                        "Same picture on both".to_string(),
+                        mirror,
+                    ));
+                }
+                rows.push((
+                    format!("extend-{}", output),
+                    format!("Extend to {}", output),
+                    format!("Right of {}", primary),
+                    DisplayHandler::Extend {
+                        backend,
+                        primary: primary.clone(),
+                        output: output.clone(),
+                    },
+                ));
+            }
+        }
+
+        rows.into_iter()
+            .filter(|(_, name, _, _)| name.to_lowercase().contains(&filter))
+            .enumerate()
+            .map(|(index, (action, name, detail, handler))| {
+                let id = Box::leak(format!("{}-{}", DISPLAYS, action).into_boxed_str());
+                create_row(
+                    id,
+                    name,
+                    detail,
+                    handler,
+                    100 - index.min(100),
+                    db.clone(),
+                    text_secondary_color,
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub enum DisplayHandler {
+    Profile {
+        backend: Backend,
+        profile: String,
+    },
+    Mirror {
+        backend: Backend,
+        primary: String,
+        output: String,
+    },
+    Extend {
+        backend: Backend,
+        primary: String,
+        output: String,
+    },
+    /// Informational rows
+    None,
+}
+
+impl DisplayHandler {
+    fn command(&self) -> Option<Command> {
+        match self {
+            Self::Profile { backend, profile } => {
+                Some(displays::apply_profile_command(*backend, profile))
+            }
+            Self::Mirror {
+                backend,
+                primary,
+                output,
+            } => displays::mirror_command(*backend, primary, output),
+            Self::Extend {
+                backend,
+                primary,
+                output,
+            } => Some(displays::extend_command(*backend, primary, output)),
+            Self::None => None,
+        }
+    }
+}
+
+impl ActionHandler for DisplayHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        if let Some(mut command) = self.command() {
+            command.spawn()?;
+        }
+        // Outputs change with the layout
+        *DISPLAY_STATE.lock().unwrap() = None;
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        self.command()
+            .map(|command| CommandPreview::from_command(&command))
+    }
+}
+
+fn create_row(
+    id: &'static str,
+    name: String,
+    detail: String,
+    handler: DisplayHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        10,
+        db,
+    )
+}
+
+/// Current layout, reading it in the background when missing or outdated
+fn display_state(backend: Backend, cx: &mut Context<ActionListView>) -> DisplayState {
+    let mut state = DISPLAY_STATE.lock().unwrap();
+    if let Some((fetched_at, current)) = state.as_ref() {
+        if matches!(current, DisplayState::Pending) || fetched_at.elapsed() < REFRESH_INTERVAL {
+            return current.clone();
+        }
+    }
+
+    // Keep showing the outdated layout while reading the new one
+    let shown = match state.take() {
+        Some((_, ready @ DisplayState::Ready(_))) => ready,
+        _ => DisplayState::Pending,
+    };
+    *state = Some((Instant::now(), shown.clone()));
+    drop(state);
+
+    cx.spawn(|view, mut cx| async move {
+        let new_state = cx
+            .background_executor()
+            .spawn(async move {
+                match displays::layout(backend) {
+                    Ok(layout) => DisplayState::Ready(layout),
+                    Err(e) => DisplayState::Failed(e.to_string()),
+                }
+            })
+            .await;
+
+        *DISPLAY_STATE.lock().unwrap() = Some((Instant::now(), new_state));
+        let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+    })
+    .detach();
+
+    shown
+}
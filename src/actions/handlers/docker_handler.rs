@@ -0,0 +1,287 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::DOCKER;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::docker::{self, Container, Image};
+use crate::system::terminal;
+
+/// Queries starting with this word list containers and images
+const DOCKER_PREFIX: &str = "docker";
+
+/// Containers and images are listed again when the list is older than this
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+enum DockerState {
+    Pending,
+    Ready {
+        containers: Vec<Container>,
+        images: Vec<Image>,
+    },
+    Failed(String),
+}
+
+lazy_static::lazy_static! {
+    static ref DOCKER_STATE: Mutex<Option<(Instant, DockerState)>> = Mutex::new(None);
+}
+
+/// Starts, stops, removes and opens shells in containers with `docker <name>`
+pub struct DockerHandlerFactory;
+
+impl HandlerFactory for DockerHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        DOCKER
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(
+            format!("{} ", DOCKER_PREFIX),
+            "Manage Docker containers and images",
+        )]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(filter) = query.trim_start().strip_prefix(DOCKER_PREFIX) else {
+            return Vec::new();
+        };
+        if !filter.is_empty() && !filter.starts_with(' ') {
+            return Vec::new();
+        }
+        let filter = filter.trim().to_lowercase();
+        let config = cx.global::<Config>();
+        let text_secondary_color = config.text_secondary_color;
+        let terminal = config.terminal.clone();
+
+        let (containers, images) = match docker_state(cx) {
+            DockerState::Ready { containers, images } => (containers, images),
+            DockerState::Pending => {
+                return vec![create_row(
+                    "docker-loading",
+                    "Reading containers...".to_string(),
+                    String::new(),
+                    DockerHandler::None,
+                    100,
+                    db,
+                    text_secondary_color,
+                )];
+            }
+            DockerState::Failed(e) => {
+                return vec![create_row(
+                    "docker-failed",
+                    "Docker unavailable".to_string(),
+                    e,
+                    DockerHandler::None,
+                    100,
+                    db,
+                    text_secondary_color,
+                )];
+            }
+        };
+
+        let mut handlers = Vec::new();
+        let matching_containers = containers.into_iter().filter(|container| {
+            container.name.to_lowercase().contains(&filter)
+                || container.image.to_lowercase().contains(&filter)
+        });
+        for (index, container) in matching_containers.enumerate() {
+            let detail = format!("{} · {}", container.image, container.status);
+            let actions = if container.running {
+                vec![
+                    ("stop", "Stop", DockerHandler::Stop(container.id.clone())),
+                    (
+                        "shell",
+                        "Shell in",
+                        DockerHandler::Shell {
+                            id: container.id.clone(),
+                            terminal: terminal.clone(),
+                        },
+                    ),
+                ]
+            } else {
+                vec![
+                    ("start", "Start", DockerHandler::Start(container.id.clone())),
+                    (
+                        "remove",
+                        "Remove",
+                        DockerHandler::Remove(container.id.clone()),
+                    ),
+                ]
+            };
+
+            for (offset, (action, verb, handler)) in actions.into_iter().enumerate() {
+                let id =
+                    Box::leak(format!("{}-{}-{}", DOCKER, action, container.name).into_boxed_str());
+                let needs_confirmation = matches!(handler, DockerHandler::Remove(_));
+                handlers.push(
+                    create_row(
+                        id,
+                        format!("{} {}", verb, container.name),
+                        detail.clone(),
+                        handler,
+                        // Keep the actions of a container together
+                        200usize.saturating_sub(index * 2 + offset),
+                        db.clone(),
+                        text_secondary_color,
+                    )
+                    .with_confirmation(needs_confirmation),
+                );
+            }
+        }
+
+        let matching_images = images
+            .into_iter()
+            .filter(|image| image.name.to_lowercase().contains(&filter));
+        for (index, image) in matching_images.enumerate() {
+            let id = Box::leak(format!("{}-remove-image-{}", DOCKER, image.name).into_boxed_str());
+            handlers.push(
+                create_row(
+                    id,
+                    format!("Remove image {}", image.name),
+                    format!("{} MB", image.size / 1_000_000),
+                    DockerHandler::RemoveImage(image.id),
+                    50usize.saturating_sub(index),
+                    db.clone(),
+                    text_secondary_color,
+                )
+                .with_confirmation(true),
+            );
+        }
+
+        handlers
+    }
+}
+
+#[derive(Clone)]
+pub enum DockerHandler {
+    Start(String),
+    Stop(String),
+    Remove(String),
+    RemoveImage(String),
+    Shell {
+        id: String,
+        terminal: Option<String>,
+    },
+    /// Informational rows
+    None,
+}
+
+impl DockerHandler {
+    fn shell_command(id: &str, terminal: Option<&str>) -> Command {
+        terminal::command(terminal, &docker::exec_shell_args(id))
+    }
+}
+
+impl ActionHandler for DockerHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        match self {
+            Self::Start(id) => docker::start(id)?,
+            Self::Stop(id) => docker::stop(id)?,
+            Self::Remove(id) => docker::remove_container(id)?,
+            Self::RemoveImage(id) => docker::remove_image(id)?,
+            Self::Shell { id, terminal } => {
+                Self::shell_command(id, terminal.as_deref()).spawn()?;
+            }
+            Self::None => return Ok(()),
+        }
+        // The list is outdated once a container changes
+        *DOCKER_STATE.lock().unwrap() = None;
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        match self {
+            Self::Shell { id, terminal } => Some(CommandPreview::from_command(
+                &Self::shell_command(id, terminal.as_deref()),
+            )),
+            _ => None,
+        }
+    }
+}
+
+fn create_row(
+    id: &'static str,
+    name: String,
+    detail: String,
+    handler: DockerHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        10,
+        db,
+    )
+}
+
+/// Current containers and images, reading them in the background when
+/// missing or outdated
+fn docker_state(cx: &mut Context<ActionListView>) -> DockerState {
+    let mut state = DOCKER_STATE.lock().unwrap();
+    if let Some((fetched_at, current)) = state.as_ref() {
+        if matches!(current, DockerState::Pending) || fetched_at.elapsed() < REFRESH_INTERVAL {
+            return current.clone();
+        }
+    }
+
+    // Keep showing outdated containers while reading them again
+    let shown = match state.take() {
+        Some((_, ready @ DockerState::Ready { .. })) => ready,
+        _ => DockerState::Pending,
+    };
+    *state = Some((Instant::now(), shown.clone()));
+    drop(state);
+
+    cx.spawn(|view, mut cx| async move {
+        let new_state = cx
+            .background_executor()
+            .spawn(async {
+                match (docker::containers(), docker::images()) {
+                    (Ok(containers), Ok(images)) => DockerState::Ready { containers, images },
+                    (Err(e), _) | (_, Err(e)) => DockerState::Failed(e.to_string()),
+                }
+            })
+            .await;
+
+        *DOCKER_STATE.lock().unwrap() = Some((Instant::now(), new_state));
+        let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+    })
+    .detach();
+
+    shown
+}
@@ -0,0 +1,181 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use log::warn;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::DOCS;
+use crate::common::expand_tilde;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::docsets::{self, DocEntry};
+
+/// Queries starting with this word search documentation
+const DOCS_PREFIX: &str = "doc";
+
+const MAX_ENTRIES: usize = 10;
+
+/// Searches symbols of installed Zeal/Dash docsets with `doc <symbol>`, or
+/// `doc <docset> <symbol>` to search one docset, falling back to devdocs.io
+pub struct DocsHandlerFactory;
+
+impl HandlerFactory for DocsHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        DOCS
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(
+            format!("{} ", DOCS_PREFIX),
+            "Search documentation of installed docsets or devdocs.io",
+        )]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(terms) = query.trim_start().strip_prefix(DOCS_PREFIX) else {
+            return Vec::new();
+        };
+        if !terms.starts_with(' ') {
+            return Vec::new();
+        }
+        let terms = terms.trim();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let config = cx.global::<Config>();
+        let text_secondary_color = config.text_secondary_color;
+        let dirs: Vec<PathBuf> = config
+            .docset_dirs
+            .iter()
+            .map(|dir| expand_tilde(dir))
+            .collect();
+
+        let mut docsets = docsets::installed(&dirs);
+        // `doc rust vec` only searches the Rust docset
+        let mut symbol = terms;
+        if let Some((first, rest)) = terms.split_once(' ') {
+            if let Some(docset) = docsets
+                .iter()
+                .find(|docset| docset.name.eq_ignore_ascii_case(first))
+                .cloned()
+            {
+                docsets = vec![docset];
+                symbol = rest.trim();
+            }
+        }
+
+        let mut entries: Vec<DocEntry> = docsets
+            .iter()
+            .flat_map(|docset| {
+                docsets::search(docset, symbol, MAX_ENTRIES).unwrap_or_else(|e| {
+                    warn!("Failed to search docset {}: {}", docset.name, e);
+                    Vec::new()
+                })
+            })
+            .collect();
+        let lowercase = symbol.to_lowercase();
+        entries.sort_by_key(|entry| {
+            let name = entry.name.to_lowercase();
+            (name != lowercase, !name.starts_with(&lowercase), name.len())
+        });
+
+        let mut handlers: Vec<ActionItem> = entries
+            .into_iter()
+            .take(MAX_ENTRIES)
+            .enumerate()
+            .map(|(index, entry)| {
+                let id = Box::leak(format!("{}-{}", DOCS, entry.url).into_boxed_str());
+                let detail = if entry.kind.is_empty() {
+                    entry.docset.clone()
+                } else {
+                    format!("{} · {}", entry.kind, entry.docset)
+                };
+                create_row(
+                    id,
+                    entry.name,
+                    detail,
+                    DocsHandler { url: entry.url },
+                    100usize.saturating_sub(index),
+                    db.clone(),
+                    text_secondary_color,
+                )
+            })
+            .collect();
+
+        handlers.push(create_row(
+            "docs-devdocs",
+            format!("Search devdocs.io for {}", terms),
+            String::new(),
+            DocsHandler {
+                url: docsets::devdocs_url(terms),
+            },
+            1,
+            db,
+            text_secondary_color,
+        ));
+
+        handlers
+    }
+}
+
+/// Opens a documentation page
+#[derive(Clone)]
+pub struct DocsHandler {
+    url: String,
+}
+
+impl ActionHandler for DocsHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        open::that(&self.url)?;
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+fn create_row(
+    id: &'static str,
+    name: String,
+    detail: String,
+    handler: DocsHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    let url = handler.url.clone();
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        10,
+        db,
+    )
+    .with_url(url)
+}
@@ -1,13 +1,13 @@
 use anyhow;
-use gpui::{div, Context, Element, ParentElement, Styled};
+use gpui::{div, prelude::FluentBuilder, Context, Element, ParentElement, Styled};
 use std::sync::Arc;
 
 use crate::action_list_view::ActionListView;
 use crate::actions::action_handler::{
-    ActionDefinition, ActionHandler, ActionId, ActionItem, HandlerFactory,
+    ActionDefinition, ActionHandler, ActionId, ActionItem, HandlerFactory, WEB_SEARCH_GROUP,
 };
 use crate::actions::action_ids::{self, DUCKDUCKGO_SEARCH};
-use crate::config::Config;
+use crate::config::{Config, UsageColumn};
 use crate::database::Database;
 
 pub struct DuckDuckGoHandlerFactory;
@@ -17,6 +17,14 @@ impl HandlerFactory for DuckDuckGoHandlerFactory {
         DUCKDUCKGO_SEARCH
     }
 
+    fn group(&self) -> &'static str {
+        WEB_SEARCH_GROUP
+    }
+
+    fn bangs(&self) -> Vec<String> {
+        vec!["ddg".to_string()]
+    }
+
     fn create_handlers_for_query(
         &self,
         query: &str,
@@ -50,6 +58,7 @@ impl ActionDefinition for DuckDuckGoHandler {
     fn create_action(&self, db: Arc<Database>, cx: &mut Context<ActionListView>) -> ActionItem {
         let config = cx.global::<Config>();
         let text_secondary_color = config.text_secondary_color;
+        let show_count = config.usage_column != UsageColumn::None;
 
         let (relevance, execution_count) = db
             .get_action_relevance(self.get_id().as_str())
@@ -58,6 +67,7 @@ impl ActionDefinition for DuckDuckGoHandler {
 
         ActionItem::new(
             self.get_id(),
+            self.get_name(),
             self.clone(),
             move || {
                 div()
@@ -70,11 +80,13 @@ impl ActionDefinition for DuckDuckGoHandler {
                             .child("Search Engine")
                             .text_color(text_secondary_color),
                     )
-                    .child(
-                        div()
-                            .child(format!("{}", execution_count))
-                            .text_color(text_secondary_color),
-                    )
+                    .when(show_count, |x| {
+                        x.child(
+                            div()
+                                .child(format!("{}", execution_count))
+                                .text_color(text_secondary_color),
+                        )
+                    })
                     .into_any()
             },
             relevance,
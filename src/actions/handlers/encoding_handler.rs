@@ -0,0 +1,242 @@
+use anyhow::{anyhow, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::ENCODING;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::copy_to_clipboard;
+
+/// Longest result shown in full, longer ones are cut in the list but copied
+/// completely
+const MAX_SHOWN_CHARS: usize = 80;
+
+/// A hash or an encoding applied to the text of a query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transform {
+    Md5,
+    Sha1,
+    Sha256,
+    Sha512,
+    Base64Encode,
+    Base64Decode,
+    UrlEncode,
+    UrlDecode,
+    HexEncode,
+    HexDecode,
+}
+
+impl Transform {
+    /// Split a query like `sha256 foo` or `base64 decode Zm9v` into the
+    /// transform and its text. The text keeps its case and inner spaces.
+    fn from_query(query: &str) -> Option<(Self, &str)> {
+        let query = query.trim_start();
+        let (word, rest) = query.split_once(' ')?;
+
+        let hash = match word.to_lowercase().as_str() {
+            "md5" => Some(Self::Md5),
+            "sha1" => Some(Self::Sha1),
+            "sha256" => Some(Self::Sha256),
+            "sha512" => Some(Self::Sha512),
+            _ => None,
+        };
+        if let Some(hash) = hash {
+            return Some((hash, rest));
+        }
+
+        let (direction, text) = rest.trim_start().split_once(' ')?;
+        let transform = match (
+            word.to_lowercase().as_str(),
+            direction.to_lowercase().as_str(),
+        ) {
+            ("base64", "encode") => Self::Base64Encode,
+            ("base64", "decode") => Self::Base64Decode,
+            ("url", "encode") => Self::UrlEncode,
+            ("url", "decode") => Self::UrlDecode,
+            ("hex", "encode") => Self::HexEncode,
+            ("hex", "decode") => Self::HexDecode,
+            _ => return None,
+        };
+        Some((transform, text))
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Md5 => "MD5",
+            Self::Sha1 => "SHA-1",
+            Self::Sha256 => "SHA-256",
+            Self::Sha512 => "SHA-512",
+            Self::Base64Encode => "Base64 encoded",
+            Self::Base64Decode => "Base64 decoded",
+            Self::UrlEncode => "URL encoded",
+            Self::UrlDecode => "URL decoded",
+            Self::HexEncode => "Hex encoded",
+            Self::HexDecode => "Hex decoded",
+        }
+    }
+
+    fn apply(self, text: &str) -> Result<String> {
+        let bytes = text.as_bytes();
+        match self {
+            Self::Md5 => Ok(to_hex(&Md5::digest(bytes))),
+            Self::Sha1 => Ok(to_hex(&Sha1::digest(bytes))),
+            Self::Sha256 => Ok(to_hex(&Sha256::digest(bytes))),
+            Self::Sha512 => Ok(to_hex(&Sha512::digest(bytes))),
+            Self::Base64Encode => Ok(BASE64.encode(bytes)),
+            Self::Base64Decode => {
+                let decoded = BASE64
+                    .decode(text.trim())
+                    .map_err(|e| anyhow!("Not valid Base64: {}", e))?;
+                String::from_utf8(decoded).map_err(|_| anyhow!("Decoded bytes aren't text"))
+            }
+            Self::UrlEncode => Ok(urlencoding::encode(text).into_owned()),
+            Self::UrlDecode => urlencoding::decode(text)
+                .map(|decoded| decoded.into_owned())
+                .map_err(|_| anyhow!("Decoded bytes aren't text")),
+            Self::HexEncode => Ok(to_hex(bytes)),
+            Self::HexDecode => {
+                let decoded = from_hex(text.trim()).ok_or_else(|| anyhow!("Not valid hex"))?;
+                String::from_utf8(decoded).map_err(|_| anyhow!("Decoded bytes aren't text"))
+            }
+        }
+    }
+}
+
+/// Hashes and encodes text with e.g. `md5 hello`, `sha256 foo` or
+/// `base64 encode bar`, copying the result
+pub struct EncodingHandlerFactory;
+
+impl HandlerFactory for EncodingHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        ENCODING
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![
+            Keyword::new("md5 ", "MD5 hash of the text"),
+            Keyword::new("sha1 ", "SHA-1 hash of the text"),
+            Keyword::new("sha256 ", "SHA-256 hash of the text"),
+            Keyword::new("sha512 ", "SHA-512 hash of the text"),
+            Keyword::new("base64 encode ", "Encode the text as Base64"),
+            Keyword::new("base64 decode ", "Decode Base64"),
+            Keyword::new("url encode ", "Percent-encode the text"),
+            Keyword::new("url decode ", "Decode percent-encoded text"),
+            Keyword::new("hex encode ", "Encode the text as hex"),
+            Keyword::new("hex decode ", "Decode hex"),
+        ]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some((transform, text)) = Transform::from_query(query) else {
+            return Vec::new();
+        };
+        if text.is_empty() {
+            return Vec::new();
+        }
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+
+        let handler = match transform.apply(text) {
+            Ok(value) => EncodingHandler { value: Some(value) },
+            Err(e) => {
+                return vec![EncodingHandler { value: None }.create_action(
+                    e.to_string(),
+                    transform.name().to_string(),
+                    db,
+                    text_secondary_color,
+                )];
+            }
+        };
+
+        let name = handler.value.clone().unwrap_or_default();
+        let name = if name.chars().count() > MAX_SHOWN_CHARS {
+            format!(
+                "{}…",
+                name.chars().take(MAX_SHOWN_CHARS).collect::<String>()
+            )
+        } else {
+            name
+        };
+        vec![handler.create_action(name, transform.name().to_string(), db, text_secondary_color)]
+    }
+}
+
+/// Copies the result to the clipboard, `None` for rows showing an error
+#[derive(Clone)]
+pub struct EncodingHandler {
+    value: Option<String>,
+}
+
+impl ActionHandler for EncodingHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        match &self.value {
+            Some(value) => copy_to_clipboard(value),
+            None => Ok(()),
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+impl EncodingHandler {
+    fn create_action(
+        &self,
+        name: String,
+        detail: String,
+        db: Arc<Database>,
+        text_secondary_color: gpui::Rgba,
+    ) -> ActionItem {
+        ActionItem::new(
+            ActionId::Builtin(ENCODING),
+            name.clone(),
+            self.clone(),
+            move || {
+                div()
+                    .flex()
+                    .gap_4()
+                    .child(div().flex_none().child(name.clone()))
+                    .child(
+                        div()
+                            .flex_grow()
+                            .overflow_hidden()
+                            .child(detail.clone())
+                            .text_color(text_secondary_color),
+                    )
+                    .into_any()
+            },
+            100,
+            10,
+            db,
+        )
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    let hex: String = hex.chars().filter(|c| !c.is_whitespace()).collect();
+    if hex.len() % 2 != 0 || !hex.is_ascii() {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
@@ -1,22 +1,44 @@
 use anyhow::Result;
-use gpui::{div, Context, Element, ParentElement, Styled};
+use gpui::{div, relative, Context, Element, ParentElement, Styled};
 use rusqlite::{self, Row};
+use std::ops::Range;
 use std::path::PathBuf;
+use std::process::Command;
 use std::sync::Arc;
 
 use crate::action_list_view::ActionListView;
 use crate::actions::action_handler::{
-    ActionDefinition, ActionHandler, ActionId, ActionItem, HandlerFactory,
+    ActionDefinition, ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory,
 };
 use crate::actions::action_ids::EXECUTABLE_HANDLER;
-use crate::config::Config;
+use crate::actions::highlight::highlighted;
+use crate::actions::score::{self, Score, RELEVANCE_BOOST};
+use crate::common::time_ago;
+use crate::config::{Config, UsageColumn};
 use crate::database::Database;
+use crate::system::{copy_to_clipboard, icons, terminal};
 
 // Constant values
-const RELEVANCE_BOOST: usize = 30;
-const MAX_RESULTS: usize = 10;
 const TRIGRAM_SIMILARITY_THRESHOLD: f64 = 0.1;
 const FUZZY_MATCH_WEIGHT: f64 = 30.0;
+// Weight of the full-text rank in the search score, whose token part is
+// about 1 for a good match
+const TEXT_RANK_WEIGHT: f64 = 1.0;
+
+// Sections of the empty-query screen
+const PINNED_SECTION: &str = "Pinned";
+const POPULAR_SECTION: &str = "Popular";
+const TIME_OF_DAY_SECTION: &str = "Usually at this time";
+const MAX_TIME_OF_DAY_RESULTS: usize = 3;
+// Keeps time-of-day suggestions above popular actions. Like the pinned
+// relevance it's above `score::FIXED_RELEVANCE`, handler boosts leave it be.
+const TIME_OF_DAY_RELEVANCE: usize = usize::MAX / 64;
+// Keeps pinned actions above everything else
+const PINNED_RELEVANCE: usize = usize::MAX / 32;
+
+// Frecency filling the bar of `usage_column = "frecency"`, about ten runs
+// in the last days
+const FRECENCY_BAR_FULL: f64 = 10.0;
 
 // SQL Queries
 const SQL_POPULAR_ACTIONS: &str = "
@@ -26,7 +48,7 @@ SELECT
     a.action_type,
     p.path as program_path,
     d.exec as desktop_exec,
-    (
+    ((
         -- Base frequency score (number of executions with time decay)
         SELECT COALESCE(
             SUM(
@@ -36,8 +58,16 @@ SELECT
             )
         ), 0)
         FROM action_executions ae
-        WHERE ae.action_id = a.id
-    ) as rank_score
+        WHERE ae.action_id = CAST(a.id AS TEXT)
+    ) + (
+        -- Executions merged into daily counts by the retention
+        SELECT COALESCE(
+            SUM(executions / (1.0 + julianday('now') - julianday(day || 'T12:00:00Z'))),
+            0
+        )
+        FROM action_execution_days ad
+        WHERE ad.action_id = CAST(a.id AS TEXT)
+    )) as rank_score
 FROM actions a
 LEFT JOIN program_items p ON (
     a.action_type = 'program' AND p.id = a.id
@@ -46,17 +76,66 @@ LEFT JOIN desktop_items d ON (
     a.action_type = 'desktop' AND d.id = a.id
 )
 ORDER BY rank_score DESC
-LIMIT 10
+LIMIT ?
 ";
 
-const SQL_DIRECT_MATCH: &str = "
+// Actions pinned with `:pin` or the pin key, in the order they were added
+const SQL_PINNED_ACTIONS: &str = "
 SELECT 
+    a.id,
+    a.name,
+    a.action_type,
+    p.path as program_path,
+    d.exec as desktop_exec
+FROM actions a
+LEFT JOIN program_items p ON (
+    a.action_type = 'program' AND p.id = a.id
+)
+LEFT JOIN desktop_items d ON (
+    a.action_type = 'desktop' AND d.id = a.id
+)
+WHERE a.pinned
+ORDER BY a.id
+";
+
+// Actions mostly run around the current hour of the day during the last 60 days
+const SQL_TIME_OF_DAY_ACTIONS: &str = "
+SELECT 
+    a.id,
+    a.name,
+    a.action_type,
+    p.path as program_path,
+    d.exec as desktop_exec,
+    SUM(
+        CASE WHEN strftime('%H', ae.execution_timestamp) = strftime('%H', 'now') THEN 1 ELSE 0 END
+    ) as hour_count,
+    COUNT(*) as total_count
+FROM action_executions ae
+JOIN actions a ON ae.action_id = a.id
+LEFT JOIN program_items p ON (
+    a.action_type = 'program' AND p.id = a.id
+)
+LEFT JOIN desktop_items d ON (
+    a.action_type = 'desktop' AND d.id = a.id
+)
+WHERE julianday('now') - julianday(ae.execution_timestamp) <= 60
+GROUP BY a.id
+-- Run at least 3 times at this hour, and at least half of all runs
+HAVING hour_count >= 3 AND hour_count * 2 >= total_count
+ORDER BY hour_count * 1.0 / total_count DESC, hour_count DESC
+LIMIT ?
+";
+
+// Actions with words starting with every token, through the full-text index.
+// bm25 weighs matches in the name over the search name and the keywords.
+const SQL_TEXT_MATCH: &str = "
+SELECT
     a.id,
     a.name,
     a.action_type,
     p.path as program_path,
     d.exec as desktop_exec,
-    (
+    ((
         -- Base frequency score (number of executions with time decay)
         SELECT COALESCE(
             SUM(
@@ -66,13 +145,84 @@ SELECT
             )
         ), 0)
         FROM action_executions ae
-        WHERE ae.action_id = a.id
-    ) * (
+        WHERE ae.action_id = CAST(a.id AS TEXT)
+    ) + (
+        -- Executions merged into daily counts by the retention
+        SELECT COALESCE(
+            SUM(executions / (1.0 + julianday('now') - julianday(day || 'T12:00:00Z'))),
+            0
+        )
+        FROM action_execution_days ad
+        WHERE ad.action_id = CAST(a.id AS TEXT)
+    )) * (
         -- Time of day relevance
         1.0 + COALESCE((
             SELECT 0.5 * COUNT(*)
             FROM action_executions ae2
-            WHERE ae2.action_id = a.id
+            WHERE ae2.action_id = CAST(a.id AS TEXT)
+            AND strftime('%H', ae2.execution_timestamp) = strftime('%H', 'now')
+        ), 0)
+    ) as base_score,
+    -- Match quality scoring
+    CASE
+        WHEN a.searchname = ?1 THEN 100.0
+        WHEN a.searchname LIKE ?1 || '%' THEN 50.0
+        ELSE 10.0
+    END as match_quality,
+    a.searchname,
+    -- Lower is better
+    bm25(actions_fts, 10.0, 5.0, 1.0) as text_rank
+FROM actions_fts
+JOIN actions a ON a.id = actions_fts.rowid
+LEFT JOIN program_items p ON (
+    a.action_type = 'program' AND p.id = a.id
+)
+LEFT JOIN desktop_items d ON (
+    a.action_type = 'desktop' AND d.id = a.id
+)
+WHERE actions_fts MATCH ?2
+-- Weighted like the relevance computed afterwards, with the text rank in
+-- place of the token score, so the limit keeps the best results. Actions
+-- never run are ordered by the text rank alone.
+ORDER BY match_quality * (1.0 + base_score) * (1.0 - text_rank / (1.0 - text_rank)) DESC
+LIMIT ?3
+";
+
+// Substrings inside words, like `fox` in Firefox, which the full-text index
+// can't find. Scans every action, so it's only run when the index finds
+// nothing.
+const SQL_SUBSTRING_MATCH: &str = "
+SELECT 
+    a.id,
+    a.name,
+    a.action_type,
+    p.path as program_path,
+    d.exec as desktop_exec,
+    ((
+        -- Base frequency score (number of executions with time decay)
+        SELECT COALESCE(
+            SUM(
+                1.0 / (1.0 + (
+                    (julianday('now') - julianday(execution_timestamp)) * 24.0 * 60.0
+                ) / (24.0 * 60.0)
+            )
+        ), 0)
+        FROM action_executions ae
+        WHERE ae.action_id = CAST(a.id AS TEXT)
+    ) + (
+        -- Executions merged into daily counts by the retention
+        SELECT COALESCE(
+            SUM(executions / (1.0 + julianday('now') - julianday(day || 'T12:00:00Z'))),
+            0
+        )
+        FROM action_execution_days ad
+        WHERE ad.action_id = CAST(a.id AS TEXT)
+    )) * (
+        -- Time of day relevance
+        1.0 + COALESCE((
+            SELECT 0.5 * COUNT(*)
+            FROM action_executions ae2
+            WHERE ae2.action_id = CAST(a.id AS TEXT)
             AND strftime('%H', ae2.execution_timestamp) = strftime('%H', 'now')
         ), 0)
     ) as base_score,
@@ -87,7 +237,8 @@ SELECT
         -- Partial match - lower priority
         ELSE 1.0
     END as match_quality,
-    a.searchname
+    a.searchname,
+    0.0 as text_rank
 FROM actions a
 LEFT JOIN program_items p ON (
     a.action_type = 'program' AND p.id = a.id
@@ -101,7 +252,7 @@ WHERE (
     OR a.name LIKE '%' || ? || '%'
 )
 ORDER BY match_quality DESC, base_score DESC
-LIMIT 10
+LIMIT ?
 ";
 
 const SQL_FUZZY_CANDIDATES: &str = "
@@ -111,7 +262,7 @@ SELECT
     a.action_type,
     p.path as program_path,
     d.exec as desktop_exec,
-    (
+    ((
         SELECT COALESCE(
             SUM(
                 1.0 / (1.0 + (
@@ -120,8 +271,16 @@ SELECT
             )
         ), 0)
         FROM action_executions ae
-        WHERE ae.action_id = a.id
-    ) as base_score,
+        WHERE ae.action_id = CAST(a.id AS TEXT)
+    ) + (
+        -- Executions merged into daily counts by the retention
+        SELECT COALESCE(
+            SUM(executions / (1.0 + julianday('now') - julianday(day || 'T12:00:00Z'))),
+            0
+        )
+        FROM action_execution_days ad
+        WHERE ad.action_id = CAST(a.id AS TEXT)
+    )) as base_score,
     a.searchname
 FROM actions a
 LEFT JOIN program_items p ON (
@@ -142,19 +301,63 @@ impl HandlerFactory for AppHandlerFactory {
         EXECUTABLE_HANDLER
     }
 
+    fn group(&self) -> &'static str {
+        "Applications"
+    }
+
     fn create_handlers_for_query(
         &self,
         query: &str,
         db: Arc<Database>,
         cx: &mut Context<ActionListView>,
     ) -> Vec<ActionItem> {
-        match get_actions_filtered(&db, query) {
+        let limit = cx.global::<Config>().result_limit(EXECUTABLE_HANDLER);
+        let mut items: Vec<ActionItem> = match get_actions_filtered(&db, query, limit) {
             Ok(actions) => actions
                 .into_iter()
                 .map(|action| action.create_action(db.clone(), cx))
                 .collect(),
             Err(_) => Vec::new(),
+        };
+
+        if query.trim().is_empty() {
+            let mut pinned: Vec<ActionItem> = get_pinned_actions(&db)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|action| {
+                    action
+                        .create_action(db.clone(), cx)
+                        .with_section(PINNED_SECTION)
+                })
+                .collect();
+            // Pinned actions aren't repeated as popular ones
+            items = items
+                .into_iter()
+                .filter(|item| !pinned.iter().any(|pin| pin.id == item.id))
+                .map(|item| item.with_section(POPULAR_SECTION))
+                .collect();
+
+            // Only suggest actions that aren't already listed
+            let time_of_day_actions = get_time_of_day_actions(&db).unwrap_or_default();
+            for action in time_of_day_actions {
+                let listed = items
+                    .iter()
+                    .chain(&pinned)
+                    .any(|item| item.id == action.get_id());
+                if !listed {
+                    items.push(
+                        action
+                            .create_action(db.clone(), cx)
+                            .with_section(TIME_OF_DAY_SECTION),
+                    );
+                }
+            }
+
+            pinned.extend(items);
+            items = pinned;
         }
+
+        items
     }
 }
 
@@ -174,25 +377,85 @@ pub struct ExecutableHandler {
     pub name: String,
     pub executable_type: ExecutableType,
     pub relevance: usize,
+    /// Parts of the name matched by the query, see `score::match_ranges`
+    pub match_ranges: Vec<Range<usize>>,
+    /// How `relevance` came about, for searches
+    pub score: Option<Score>,
 }
 
-impl ActionHandler for ExecutableHandler {
-    fn execute(&self, _input: &str) -> Result<()> {
+impl ExecutableHandler {
+    /// The process started by this action
+    fn command(&self) -> Option<Command> {
         match &self.executable_type {
             ExecutableType::Application(command) => {
                 let mut parts = command.split_whitespace();
-                if let Some(program) = parts.next() {
-                    let args: Vec<&str> = parts.collect();
-                    std::process::Command::new(program).args(args).spawn()?;
-                }
-            }
-            ExecutableType::Binary(path) => {
-                std::process::Command::new(path).spawn()?;
+                let mut process = Command::new(parts.next()?);
+                process.args(parts);
+                Some(process)
             }
+            ExecutableType::Binary(path) => Some(Command::new(path)),
+        }
+    }
+}
+
+impl ActionHandler for ExecutableHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        if let Some(mut command) = self.command() {
+            command.spawn()?;
         }
         Ok(())
     }
 
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        self.command()
+            .map(|command| CommandPreview::from_command(&command))
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+/// Secondary actions of applications and binaries
+#[derive(Clone)]
+pub enum ExecutableAction {
+    RunInTerminal {
+        path: PathBuf,
+        terminal: Option<String>,
+    },
+    Copy(String),
+}
+
+impl ExecutableAction {
+    fn command(&self) -> Option<Command> {
+        match self {
+            Self::RunInTerminal { path, terminal } => Some(terminal::command(
+                terminal.as_deref(),
+                &[path.to_string_lossy().into_owned()],
+            )),
+            Self::Copy(_) => None,
+        }
+    }
+}
+
+impl ActionHandler for ExecutableAction {
+    fn execute(&self, _input: &str) -> Result<()> {
+        match self {
+            Self::Copy(text) => copy_to_clipboard(text),
+            Self::RunInTerminal { .. } => {
+                if let Some(mut command) = self.command() {
+                    command.spawn()?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        self.command()
+            .map(|command| CommandPreview::from_command(&command))
+    }
+
     fn clone_box(&self) -> Box<dyn ActionHandler> {
         Box::new(self.clone())
     }
@@ -202,8 +465,31 @@ impl ActionDefinition for ExecutableHandler {
     fn create_action(&self, db: Arc<Database>, cx: &mut Context<ActionListView>) -> ActionItem {
         let config = cx.global::<Config>();
         let text_secondary_color = config.text_secondary_color;
-        let execution_count = db.get_execution_count(self.get_id().as_str()).unwrap_or(0);
+        let highlight_color = config.match_highlight_color;
+        let terminal = config.terminal.clone();
+        let border_color = config.border_color;
+        let id = self.get_id();
+        let (usage, frecency_fill) = match config.usage_column {
+            UsageColumn::Count => {
+                let count = db.get_execution_count(id.as_str()).unwrap_or(0);
+                (Some(count.to_string()), None)
+            }
+            UsageColumn::LastUsed => {
+                let last_used = match db.get_last_execution(id.as_str()).ok().flatten() {
+                    Some(time) => time_ago(chrono::Local::now().fixed_offset() - time),
+                    None => "never".to_string(),
+                };
+                (Some(last_used), None)
+            }
+            UsageColumn::Frecency => {
+                let frecency = db.get_action_frecency(id.as_str()).unwrap_or(0.0);
+                (None, Some((frecency / FRECENCY_BAR_FULL).min(1.0) as f32))
+            }
+            UsageColumn::None => (None, None),
+        };
+        let pinned = db.is_action_pinned(self.id).unwrap_or(false);
         let name = self.get_name();
+        let match_ranges = self.match_ranges.clone();
 
         let (description, detail) = match &self.executable_type {
             ExecutableType::Application(_) => {
@@ -215,31 +501,78 @@ impl ActionDefinition for ExecutableHandler {
             ),
         };
 
-        ActionItem::new(
+        // Binaries have no icons
+        let icon = match &self.executable_type {
+            ExecutableType::Application(command) => db
+                .get_desktop_entry_icon(command)
+                .ok()
+                .flatten()
+                .and_then(|name| icons::lookup(&name)),
+            ExecutableType::Binary(_) => None,
+        };
+
+        let item = ActionItem::new(
             self.get_id(),
+            self.get_name(),
             self.clone(),
             move || {
                 div()
                     .flex()
                     .gap_4()
-                    .child(div().flex_none().child(name.clone()))
+                    .children(
+                        pinned.then(|| div().flex_none().child("★").text_color(highlight_color)),
+                    )
+                    .child(div().flex_none().child(highlighted(
+                        &name,
+                        &match_ranges,
+                        highlight_color,
+                    )))
                     .child(
                         div()
                             .flex_grow()
                             .child(detail.clone())
                             .text_color(text_secondary_color),
                     )
-                    .child(
-                        div()
-                            .child(format!("{}", execution_count))
-                            .text_color(text_secondary_color),
+                    .children(
+                        usage
+                            .clone()
+                            .map(|usage| div().child(usage).text_color(text_secondary_color)),
                     )
+                    .children(frecency_fill.map(|fill| {
+                        div().flex_none().flex().items_center().child(
+                            div()
+                                .w_16()
+                                .h_1()
+                                .bg(border_color)
+                                .child(div().h_full().w(relative(fill)).bg(highlight_color)),
+                        )
+                    }))
                     .into_any()
             },
             self.relevance,
             RELEVANCE_BOOST,
             db,
         )
+        .with_icon(icon)
+        .with_score(self.score);
+
+        match &self.executable_type {
+            ExecutableType::Application(command) => {
+                item.with_secondary_action("Copy command", ExecutableAction::Copy(command.clone()))
+            }
+            ExecutableType::Binary(path) => item
+                .with_secondary_action(
+                    "Run in terminal",
+                    ExecutableAction::RunInTerminal {
+                        path: path.clone(),
+                        terminal,
+                    },
+                )
+                .with_secondary_action(
+                    "Copy path",
+                    ExecutableAction::Copy(path.to_string_lossy().into_owned()),
+                ),
+        }
     }
 
     fn get_id(&self) -> ActionId {
@@ -255,11 +588,15 @@ impl ActionDefinition for ExecutableHandler {
     }
 }
 
-/// Get filtered actions based on the search query
-pub fn get_actions_filtered(db: &Database, filter: &str) -> Result<Vec<Box<dyn ActionDefinition>>> {
+/// Get filtered actions based on the search query, at most `limit`
+pub fn get_actions_filtered(
+    db: &Database,
+    filter: &str,
+    limit: usize,
+) -> Result<Vec<Box<dyn ActionDefinition>>> {
     // Skip empty filter case - just return popular items
     if filter.trim().is_empty() {
-        return get_popular_actions(db);
+        return get_popular_actions(db, limit);
     }
 
     // Process the filter to improve search quality
@@ -269,12 +606,16 @@ pub fn get_actions_filtered(db: &Database, filter: &str) -> Result<Vec<Box<dyn A
     // Generate trigrams for fuzzy matching
     let filter_trigrams = generate_trigrams(&filter);
 
-    // First try direct matching
-    let mut handlers = search_with_direct_match(db, &filter)?;
+    // First try the full-text index, then substrings inside words
+    let mut handlers = search_with_text_match(db, &filter, limit)?;
+    if handlers.is_empty() {
+        handlers = search_with_direct_match(db, &filter, limit)?;
+    }
 
     // If direct matching didn't find enough results, try fuzzy matching
     if handlers.len() < 5 {
-        let fuzzy_matches = search_with_fuzzy_match(db, &filter, &filter_trigrams, &filter_tokens)?;
+        let fuzzy_matches =
+            search_with_fuzzy_match(db, &filter, &filter_trigrams, &filter_tokens, limit)?;
 
         // Add only fuzzy matches that aren't already in the results
         for fuzzy_match in fuzzy_matches {
@@ -298,10 +639,7 @@ pub fn get_actions_filtered(db: &Database, filter: &str) -> Result<Vec<Box<dyn A
         }
     });
 
-    // Limit to MAX_RESULTS
-    if handlers.len() > MAX_RESULTS {
-        handlers.truncate(MAX_RESULTS);
-    }
+    handlers.truncate(limit);
 
     Ok(handlers)
 }
@@ -332,12 +670,54 @@ fn generate_trigrams(text: &str) -> Vec<String> {
         .collect()
 }
 
+/// FTS5 query for words starting with every token of the filter, `None` if
+/// the filter has no letters or digits
+fn text_match_query(filter: &str) -> Option<String> {
+    let tokens: Vec<String> = filter
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(|token| format!("\"{}\"*", token))
+        .collect();
+    (!tokens.is_empty()).then(|| tokens.join(" "))
+}
+
+/// Prefix search through the full-text index
+fn search_with_text_match(
+    db: &Database,
+    filter: &str,
+    limit: usize,
+) -> Result<Vec<Box<dyn ActionDefinition>>> {
+    let Some(query) = text_match_query(filter) else {
+        return Ok(Vec::new());
+    };
+    let reader = db.reader()?;
+    let mut stmt = reader.prepare(SQL_TEXT_MATCH)?;
+
+    let params = rusqlite::params![filter, query, limit];
+    let rows = stmt.query_map(params, |row| {
+        row_to_action_definition(db, row, &filter.split_whitespace().collect::<Vec<&str>>())
+    })?;
+
+    let mut handlers = Vec::new();
+    for row in rows {
+        handlers.push(row?);
+    }
+
+    Ok(handlers)
+}
+
 /// Direct match search using traditional LIKE operators
-fn search_with_direct_match(db: &Database, filter: &str) -> Result<Vec<Box<dyn ActionDefinition>>> {
-    let mut stmt = db.connection().prepare(SQL_DIRECT_MATCH)?;
+fn search_with_direct_match(
+    db: &Database,
+    filter: &str,
+    limit: usize,
+) -> Result<Vec<Box<dyn ActionDefinition>>> {
+    let reader = db.reader()?;
+    let mut stmt = reader.prepare(SQL_SUBSTRING_MATCH)?;
 
-    // Use the filter for all the query parameters
-    let rows = stmt.query_map([&filter, &filter, &filter, &filter, &filter], |row| {
+    // The filter fills every match parameter, followed by the limit
+    let params = rusqlite::params![filter, filter, filter, filter, filter, limit];
+    let rows = stmt.query_map(params, |row| {
         row_to_action_definition(db, row, &filter.split_whitespace().collect::<Vec<&str>>())
     })?;
 
@@ -355,9 +735,11 @@ fn search_with_fuzzy_match(
     filter: &str,
     filter_trigrams: &[String],
     filter_tokens: &[&str],
+    limit: usize,
 ) -> Result<Vec<Box<dyn ActionDefinition>>> {
     // Get all potential candidates
-    let mut stmt = db.connection().prepare(SQL_FUZZY_CANDIDATES)?;
+    let reader = db.reader()?;
+    let mut stmt = reader.prepare(SQL_FUZZY_CANDIDATES)?;
 
     let rows = stmt.query_map([], |row| {
         let id: usize = row.get(0)?;
@@ -402,9 +784,11 @@ fn search_with_fuzzy_match(
         let similarity = calculate_trigram_similarity(filter_trigrams, &name_trigrams);
 
         // Calculate final relevance score
-        let search_score = calculate_search_score(filter_tokens, &searchname);
+        let search_score = score::search_score(filter_tokens, &searchname);
         let fuzzy_score = similarity * FUZZY_MATCH_WEIGHT;
-        let relevance = (base_score * (1.0 + search_score + fuzzy_score)) as usize;
+        let score = Score::new(base_score, 1.0, search_score + fuzzy_score);
+        let relevance = score.relevance();
+        let match_ranges = score::match_ranges(filter, &name);
 
         // Only include results with reasonable similarity
         if similarity > TRIGRAM_SIMILARITY_THRESHOLD {
@@ -416,6 +800,8 @@ fn search_with_fuzzy_match(
                             name,
                             executable_type: ExecutableType::Binary(PathBuf::from(path)),
                             relevance,
+                            match_ranges,
+                            score: Some(score),
                         })
                     } else {
                         continue;
@@ -428,6 +814,8 @@ fn search_with_fuzzy_match(
                             name,
                             executable_type: ExecutableType::Application(exec),
                             relevance,
+                            match_ranges,
+                            score: Some(score),
                         })
                     } else {
                         continue;
@@ -443,10 +831,7 @@ fn search_with_fuzzy_match(
     // Sort by relevance score (higher is better)
     handlers.sort_by(|a, b| b.get_relevance().cmp(&a.get_relevance()));
 
-    // Limit to MAX_RESULTS
-    if handlers.len() > MAX_RESULTS {
-        handlers.truncate(MAX_RESULTS);
-    }
+    handlers.truncate(limit);
 
     Ok(handlers)
 }
@@ -481,10 +866,15 @@ fn row_to_action_definition(
     let base_score: f64 = row.get(5)?;
     let match_quality: f64 = row.get(6)?;
     let searchname: String = row.get(7)?;
+    // bm25 is negative, more so for better matches
+    let text_rank = -row.get::<_, f64>(8)?;
 
     // Calculate final relevance score combining match quality and usage patterns
-    let search_score = calculate_search_score(filter_tokens, &searchname);
-    let relevance = ((base_score * match_quality) * (1.0 + search_score)) as usize;
+    let search_score = score::search_score(filter_tokens, &searchname)
+        + TEXT_RANK_WEIGHT * text_rank / (1.0 + text_rank);
+    let score = Score::new(base_score, match_quality, search_score);
+    let relevance = score.relevance();
+    let match_ranges = score::match_ranges(&filter_tokens.join(" "), &name);
 
     let handler: Box<dyn ActionDefinition> = match action_type.as_str() {
         "program" => {
@@ -495,6 +885,8 @@ fn row_to_action_definition(
                     name,
                     executable_type: ExecutableType::Binary(PathBuf::from(path)),
                     relevance,
+                    match_ranges,
+                    score: Some(score),
                 })
             } else {
                 return Err(rusqlite::Error::InvalidColumnType(
@@ -512,6 +904,8 @@ fn row_to_action_definition(
                     name,
                     executable_type: ExecutableType::Application(exec),
                     relevance,
+                    match_ranges,
+                    score: Some(score),
                 })
             } else {
                 return Err(rusqlite::Error::InvalidColumnType(
@@ -534,10 +928,11 @@ fn row_to_action_definition(
 }
 
 /// Helper method to get popular actions when there's no filter
-fn get_popular_actions(db: &Database) -> Result<Vec<Box<dyn ActionDefinition>>> {
-    let mut stmt = db.connection().prepare(SQL_POPULAR_ACTIONS)?;
+fn get_popular_actions(db: &Database, limit: usize) -> Result<Vec<Box<dyn ActionDefinition>>> {
+    let reader = db.reader()?;
+    let mut stmt = reader.prepare(SQL_POPULAR_ACTIONS)?;
 
-    let rows = stmt.query_map([], |row| {
+    let rows = stmt.query_map([limit], |row| {
         let id: usize = row.get(0)?;
         let action_type: String = row.get(2)?;
         let name: String = row.get(1)?;
@@ -553,6 +948,8 @@ fn get_popular_actions(db: &Database) -> Result<Vec<Box<dyn ActionDefinition>>>
                         name,
                         executable_type: ExecutableType::Binary(PathBuf::from(path)),
                         relevance,
+                        match_ranges: Vec::new(),
+                        score: None,
                     })
                 } else {
                     return Err(rusqlite::Error::InvalidColumnType(
@@ -570,6 +967,8 @@ fn get_popular_actions(db: &Database) -> Result<Vec<Box<dyn ActionDefinition>>>
                         name,
                         executable_type: ExecutableType::Application(exec),
                         relevance,
+                        match_ranges: Vec::new(),
+                        score: None,
                     })
                 } else {
                     return Err(rusqlite::Error::InvalidColumnType(
@@ -599,36 +998,73 @@ fn get_popular_actions(db: &Database) -> Result<Vec<Box<dyn ActionDefinition>>>
     Ok(handlers)
 }
 
-/// Helper to calculate a more sophisticated search score
-fn calculate_search_score(filter_tokens: &[&str], searchname: &str) -> f64 {
-    if filter_tokens.is_empty() {
-        return 0.0;
+/// Actions with a strong affinity to the current hour of the day
+fn get_pinned_actions(db: &Database) -> Result<Vec<Box<dyn ActionDefinition>>> {
+    let reader = db.reader()?;
+    let mut stmt = reader.prepare(SQL_PINNED_ACTIONS)?;
+
+    let rows = stmt.query_map([], |row| {
+        let id: usize = row.get(0)?;
+        let name: String = row.get(1)?;
+        let action_type: String = row.get(2)?;
+        let path: Option<String> = row.get(3)?;
+        let exec: Option<String> = row.get(4)?;
+        Ok((id, name, action_type, path, exec))
+    })?;
+
+    let mut handlers: Vec<Box<dyn ActionDefinition>> = Vec::new();
+    for (index, row) in rows.enumerate() {
+        let (id, name, action_type, path, exec) = row?;
+        let executable_type = match (action_type.as_str(), path, exec) {
+            ("program", Some(path), _) => ExecutableType::Binary(PathBuf::from(path)),
+            ("desktop", _, Some(exec)) => ExecutableType::Application(exec),
+            _ => continue,
+        };
+
+        handlers.push(Box::new(ExecutableHandler {
+            id,
+            name,
+            executable_type,
+            relevance: PINNED_RELEVANCE - index,
+            match_ranges: Vec::new(),
+            score: None,
+        }));
     }
 
-    // Count how many tokens match
-    let searchname = searchname.to_lowercase();
-    let mut matched_tokens = 0.0;
+    Ok(handlers)
+}
 
-    for token in filter_tokens {
-        // Check if token is in the searchname
-        if searchname.contains(token) {
-            matched_tokens += 1.0;
+fn get_time_of_day_actions(db: &Database) -> Result<Vec<Box<dyn ActionDefinition>>> {
+    let reader = db.reader()?;
+    let mut stmt = reader.prepare(SQL_TIME_OF_DAY_ACTIONS)?;
 
-            // Bonus for tokens that are at the start of words
-            if searchname.starts_with(token) {
-                matched_tokens += 0.5;
-            } else {
-                // Check if token is at the start of any word
-                for word in searchname.split_whitespace() {
-                    if word.starts_with(token) {
-                        matched_tokens += 0.3;
-                        break;
-                    }
-                }
-            }
-        }
+    let rows = stmt.query_map([MAX_TIME_OF_DAY_RESULTS], |row| {
+        let id: usize = row.get(0)?;
+        let name: String = row.get(1)?;
+        let action_type: String = row.get(2)?;
+        let path: Option<String> = row.get(3)?;
+        let exec: Option<String> = row.get(4)?;
+        Ok((id, name, action_type, path, exec))
+    })?;
+
+    let mut handlers: Vec<Box<dyn ActionDefinition>> = Vec::new();
+    for (index, row) in rows.enumerate() {
+        let (id, name, action_type, path, exec) = row?;
+        let executable_type = match (action_type.as_str(), path, exec) {
+            ("program", Some(path), _) => ExecutableType::Binary(PathBuf::from(path)),
+            ("desktop", _, Some(exec)) => ExecutableType::Application(exec),
+            _ => continue,
+        };
+
+        handlers.push(Box::new(ExecutableHandler {
+            id,
+            name,
+            executable_type,
+            relevance: TIME_OF_DAY_RELEVANCE - index,
+            match_ranges: Vec::new(),
+            score: None,
+        }));
     }
 
-    // Calculate the final score as a percentage of matched tokens
-    matched_tokens / filter_tokens.len() as f64
+    Ok(handlers)
 }
@@ -0,0 +1,501 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use log::warn;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::FILE_SEARCH;
+use crate::activity;
+use crate::common::expand_tilde;
+use crate::config::Config;
+use crate::database::Database;
+use crate::demo;
+use crate::system::copy_to_clipboard;
+use crate::system::file_finder::{self, FileSearchBackend};
+use crate::system::media::{self, AudioTags};
+
+/// Queries starting with this prefix search for files
+const FILE_PREFIX: &str = "file ";
+
+const MAX_RESULTS: usize = 5;
+
+/// Results of external indexers are cached per query; the cache is dropped
+/// when it grows beyond this many queries
+const MAX_CACHED_QUERIES: usize = 100;
+
+/// Cached results are searched again after this, files come and go
+const CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// External indexers run once typing paused for this long
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Tags of this many audio files are kept
+const MAX_CACHED_TAGS: usize = 500;
+
+/// Running media players are looked up again after this
+const PLAYER_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone)]
+enum SearchState {
+    Pending,
+    Ready(Vec<PathBuf>),
+    Failed(String),
+}
+
+lazy_static::lazy_static! {
+    /// Built-in index, `None` until the first scan finished
+    static ref FILE_INDEX: Mutex<Option<Arc<Vec<PathBuf>>>> = Mutex::new(None);
+    static ref INDEXING: Mutex<bool> = Mutex::new(false);
+    /// Searches of external indexers with the time they finished
+    static ref SEARCH_CACHE: Mutex<HashMap<String, (Instant, SearchState)>> =
+        Mutex::new(HashMap::new());
+    /// The search typed last, earlier ones are dropped while debouncing
+    static ref LATEST_SEARCH: Mutex<Option<String>> = Mutex::new(None);
+    /// Tags of audio files in the results, `None` while they're being read
+    static ref AUDIO_TAGS: Mutex<HashMap<PathBuf, Option<AudioTags>>> = Mutex::new(HashMap::new());
+    /// Player to queue audio files in, `None` before the first lookup
+    static ref QUEUE_PLAYER: Mutex<Option<(Instant, Option<String>)>> = Mutex::new(None);
+}
+
+pub struct FileSearchHandlerFactory;
+
+impl HandlerFactory for FileSearchHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        FILE_SEARCH
+    }
+
+    fn group(&self) -> &'static str {
+        "Files"
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(FILE_PREFIX, "Search files by name")]
+    }
+
+    fn keyword(&self) -> Option<&'static str> {
+        Some("f")
+    }
+
+    /// `f notes` searches like `file notes`
+    fn keyword_query(&self, terms: &str) -> String {
+        format!("{}{}", FILE_PREFIX, terms)
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(terms) = query.strip_prefix(FILE_PREFIX).map(str::trim) else {
+            return Vec::new();
+        };
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let config = cx.global::<Config>();
+        let text_secondary_color = config.text_secondary_color;
+        let roots: Vec<PathBuf> = config
+            .file_search_roots
+            .iter()
+            .map(|root| expand_tilde(root))
+            .collect();
+        let backend = match FileSearchBackend::from_name(&config.file_search_backend) {
+            Ok(backend) => backend,
+            Err(e) => {
+                warn!("{}, falling back to the built-in index", e);
+                FileSearchBackend::Index
+            }
+        };
+
+        let state = match backend {
+            _ if demo::is_enabled() => SearchState::Ready(demo::files(terms, MAX_RESULTS)),
+            FileSearchBackend::Index => search_index(terms, roots, cx),
+            _ => search_with_tool(backend, terms, roots, cx),
+        };
+
+        let files = match state {
+            SearchState::Ready(files) => files,
+            SearchState::Pending => {
+                return vec![create_row(
+                    ActionId::Builtin(FILE_SEARCH),
+                    "Searching files...".to_string(),
+                    terms.to_string(),
+                    FileHandler::Open(PathBuf::new()),
+                    100,
+                    db,
+                    text_secondary_color,
+                )
+                .as_status()];
+            }
+            SearchState::Failed(e) => {
+                return vec![create_row(
+                    ActionId::Builtin(FILE_SEARCH),
+                    "File search failed".to_string(),
+                    e,
+                    FileHandler::Open(PathBuf::new()),
+                    100,
+                    db,
+                    text_secondary_color,
+                )
+                .as_status()];
+            }
+        };
+
+        let mut handlers = Vec::new();
+        for (index, path) in files.into_iter().enumerate() {
+            let mut name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let detail = path.to_string_lossy().into_owned();
+
+            let open_id = Box::leak(format!("{}-open-{}", FILE_SEARCH, detail).into_boxed_str());
+            if media::is_audio_file(&path) {
+                // Show tags instead of the file name once they're read
+                let tags = audio_tags(&path, cx).unwrap_or_default();
+                let summary = tags.summary();
+                if let Some(title) = tags.title {
+                    name = title;
+                }
+                handlers.push(with_file_actions(
+                    create_row(
+                        ActionId::Builtin(open_id),
+                        format!("Play {}", name),
+                        if summary.is_empty() {
+                            detail.clone()
+                        } else {
+                            format!("{} · {}", summary, detail)
+                        },
+                        FileHandler::Open(path.clone()),
+                        100 - index,
+                        db.clone(),
+                        text_secondary_color,
+                    ),
+                    &path,
+                ));
+
+                if let Some(player) = queue_player(cx) {
+                    let queue_id =
+                        Box::leak(format!("{}-queue-{}", FILE_SEARCH, detail).into_boxed_str());
+                    handlers.push(create_row(
+                        ActionId::Builtin(queue_id),
+                        format!("Queue {}", name),
+                        format!("Add to the queue of {}", media::player_name(&player)),
+                        FileHandler::Queue(player, path.clone()),
+                        75 - index,
+                        db.clone(),
+                        text_secondary_color,
+                    ));
+                }
+            } else {
+                // Tab switches to the path navigator, to open the file with
+                // another application
+                handlers.push(
+                    with_file_actions(
+                        create_row(
+                            ActionId::Builtin(open_id),
+                            name.clone(),
+                            detail.clone(),
+                            FileHandler::Open(path.clone()),
+                            100 - index,
+                            db.clone(),
+                            text_secondary_color,
+                        ),
+                        &path,
+                    )
+                    .with_tab_completion(detail.clone()),
+                );
+            }
+
+            let reveal_id =
+                Box::leak(format!("{}-reveal-{}", FILE_SEARCH, detail).into_boxed_str());
+            handlers.push(create_row(
+                ActionId::Builtin(reveal_id),
+                format!("Reveal {}", name),
+                "Show in file manager".to_string(),
+                FileHandler::Reveal(path),
+                50 - index,
+                db.clone(),
+                text_secondary_color,
+            ));
+        }
+
+        handlers
+    }
+}
+
+#[derive(Clone)]
+pub enum FileHandler {
+    /// Open the file with its default application
+    Open(PathBuf),
+    /// Show the file in the file manager
+    Reveal(PathBuf),
+    /// Add an audio file to the track list of an MPRIS player
+    Queue(String, PathBuf),
+    CopyPath(PathBuf),
+}
+
+impl ActionHandler for FileHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        match self {
+            Self::Open(path) => open::that(path)?,
+            Self::Reveal(path) => {
+                // Not every file manager implements the FileManager1 interface,
+                // fall back to opening the parent directory
+                let revealed = reveal_command(path)
+                    .status()
+                    .is_ok_and(|status| status.success());
+                if !revealed {
+                    open::that(path.parent().unwrap_or(path))?;
+                }
+            }
+            Self::Queue(player, path) => {
+                media::add_to_queue_command(player, &file_uri(path)).spawn()?;
+            }
+            Self::CopyPath(path) => copy_to_clipboard(&path.to_string_lossy())?,
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        match self {
+            Self::Reveal(path) => Some(CommandPreview::from_command(&reveal_command(path))),
+            Self::Queue(player, path) => Some(CommandPreview::from_command(
+                &media::add_to_queue_command(player, &file_uri(path)),
+            )),
+            Self::Open(_) | Self::CopyPath(_) => None,
+        }
+    }
+}
+
+/// Secondary actions of the row opening a file
+fn with_file_actions(item: ActionItem, path: &Path) -> ActionItem {
+    item.with_secondary_action(
+        "Open containing folder",
+        FileHandler::Reveal(path.to_path_buf()),
+    )
+    .with_secondary_action("Copy path", FileHandler::CopyPath(path.to_path_buf()))
+}
+
+/// Ask the file manager to select the file through the freedesktop D-Bus API
+fn reveal_command(path: &Path) -> Command {
+    let uri = file_uri(path);
+
+    let mut command = Command::new("dbus-send");
+    command
+        .args([
+            "--session",
+            "--dest=org.freedesktop.FileManager1",
+            "--type=method_call",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+        ])
+        .arg(format!("array:string:{}", uri))
+        .arg("string:");
+    command
+}
+
+fn file_uri(path: &Path) -> String {
+    format!(
+        "file://{}",
+        urlencoding::encode(&path.to_string_lossy()).replace("%2F", "/")
+    )
+}
+
+fn create_row(
+    id: ActionId,
+    name: String,
+    detail: String,
+    handler: FileHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        id,
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        10,
+        db,
+    )
+}
+
+/// Search the built-in index, building it in the background on first use
+fn search_index(terms: &str, roots: Vec<PathBuf>, cx: &mut Context<ActionListView>) -> SearchState {
+    if let Some(index) = FILE_INDEX.lock().unwrap().clone() {
+        let terms: Vec<&str> = terms.split_whitespace().collect();
+        let mut files: Vec<PathBuf> = index
+            .iter()
+            .filter(|path| file_finder::matches_terms(path, &terms))
+            .cloned()
+            .collect();
+        // Prefer shallow paths, which tend to be the files people look for
+        files.sort_by_key(|path| path.components().count());
+        files.truncate(MAX_RESULTS);
+        return SearchState::Ready(files);
+    }
+
+    let mut indexing = INDEXING.lock().unwrap();
+    if !*indexing {
+        *indexing = true;
+        cx.spawn(|view, mut cx| async move {
+            let files = cx
+                .background_executor()
+                .spawn(async move { file_finder::scan_files(&roots) })
+                .await;
+
+            *FILE_INDEX.lock().unwrap() = Some(Arc::new(files));
+            *INDEXING.lock().unwrap() = false;
+            let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+        })
+        .detach();
+    }
+
+    SearchState::Pending
+}
+
+/// Run an external indexer in the background once typing paused, caching
+/// the results per query for `CACHE_TTL`
+fn search_with_tool(
+    backend: FileSearchBackend,
+    terms: &str,
+    roots: Vec<PathBuf>,
+    cx: &mut Context<ActionListView>,
+) -> SearchState {
+    *LATEST_SEARCH.lock().unwrap() = Some(terms.to_string());
+
+    let mut cache = SEARCH_CACHE.lock().unwrap();
+    cache.retain(|_, (searched_at, _)| searched_at.elapsed() < CACHE_TTL);
+    if let Some((_, state)) = cache.get(terms) {
+        return state.clone();
+    }
+    if cache.len() >= MAX_CACHED_QUERIES {
+        cache.clear();
+    }
+    cache.insert(terms.to_string(), (Instant::now(), SearchState::Pending));
+    drop(cache);
+
+    let terms = terms.to_string();
+    cx.spawn(|view, mut cx| async move {
+        cx.background_executor().timer(DEBOUNCE).await;
+        if LATEST_SEARCH.lock().unwrap().as_ref() != Some(&terms) || !activity::is_active() {
+            // Typing went on or the window was hidden, search again if
+            // the query comes back
+            SEARCH_CACHE.lock().unwrap().remove(&terms);
+            return;
+        }
+
+        let query = terms.clone();
+        let state = cx
+            .background_executor()
+            .spawn(async move {
+                match file_finder::search_with_tool(backend, &roots, &query, MAX_RESULTS) {
+                    Ok(files) => SearchState::Ready(files),
+                    Err(e) => SearchState::Failed(e.to_string()),
+                }
+            })
+            .await;
+
+        SEARCH_CACHE
+            .lock()
+            .unwrap()
+            .insert(terms, (Instant::now(), state));
+        let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+    })
+    .detach();
+
+    SearchState::Pending
+}
+
+/// Tags of an audio file, reading them in the background on first use
+fn audio_tags(path: &Path, cx: &mut Context<ActionListView>) -> Option<AudioTags> {
+    let mut tags = AUDIO_TAGS.lock().unwrap();
+    if let Some(cached) = tags.get(path) {
+        return cached.clone();
+    }
+    if tags.len() >= MAX_CACHED_TAGS {
+        tags.clear();
+    }
+    tags.insert(path.to_path_buf(), None);
+    drop(tags);
+
+    let path = path.to_path_buf();
+    cx.spawn(|view, mut cx| async move {
+        let file = path.clone();
+        // Files without readable tags keep showing their file name
+        let read = cx
+            .background_executor()
+            .spawn(async move { media::read_tags(&file).unwrap_or_default() })
+            .await;
+
+        AUDIO_TAGS.lock().unwrap().insert(path, Some(read));
+        let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+    })
+    .detach();
+
+    None
+}
+
+/// Running player that can queue files, looking for one in the background
+/// when the last lookup is outdated
+fn queue_player(cx: &mut Context<ActionListView>) -> Option<String> {
+    let mut state = QUEUE_PLAYER.lock().unwrap();
+    if let Some((checked_at, player)) = state.as_ref() {
+        if checked_at.elapsed() < PLAYER_REFRESH_INTERVAL {
+            return player.clone();
+        }
+    }
+
+    // Keep the last player while looking again
+    let current = state.take().and_then(|(_, player)| player);
+    *state = Some((Instant::now(), current.clone()));
+    drop(state);
+
+    cx.spawn(|view, mut cx| async move {
+        let player = cx
+            .background_executor()
+            .spawn(async { media::queue_player() })
+            .await;
+
+        let changed = {
+            let mut state = QUEUE_PLAYER.lock().unwrap();
+            let changed = state.as_ref().map(|(_, current)| current) != Some(&player);
+            *state = Some((Instant::now(), player));
+            changed
+        };
+        if changed {
+            let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+        }
+    })
+    .detach();
+
+    current
+}
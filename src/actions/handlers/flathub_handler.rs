@@ -0,0 +1,264 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::FLATHUB;
+use crate::activity;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::flathub::{self, FlathubApp};
+use crate::system::terminal;
+
+/// Queries starting with this word search Flathub
+const FLATHUB_PREFIX: &str = "flathub";
+
+/// Shorter search terms aren't sent to Flathub
+const MIN_TERMS_LEN: usize = 2;
+
+/// Searches start once typing paused for this long
+const DEBOUNCE: Duration = Duration::from_millis(400);
+
+#[derive(Debug, Clone)]
+enum SearchState {
+    Pending,
+    Ready {
+        apps: Vec<FlathubApp>,
+        installed: HashSet<String>,
+    },
+    Failed(String),
+}
+
+lazy_static::lazy_static! {
+    /// Searches of this session
+    static ref SEARCHES: Mutex<HashMap<String, SearchState>> = Mutex::new(HashMap::new());
+    /// The search typed last, earlier ones are dropped while debouncing
+    static ref LATEST: Mutex<Option<String>> = Mutex::new(None);
+}
+
+/// Searches Flathub with `flathub <terms>`, installing apps in a terminal and
+/// running the ones already installed
+pub struct FlathubHandlerFactory;
+
+impl HandlerFactory for FlathubHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        FLATHUB
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(
+            format!("{} ", FLATHUB_PREFIX),
+            "Search and install Flatpak apps from Flathub",
+        )]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(terms) = query.trim_start().strip_prefix(FLATHUB_PREFIX) else {
+            return Vec::new();
+        };
+        if !terms.starts_with(' ') {
+            return Vec::new();
+        }
+        let terms = terms.trim().to_lowercase();
+        if terms.len() < MIN_TERMS_LEN {
+            return Vec::new();
+        }
+        let config = cx.global::<Config>();
+        let text_secondary_color = config.text_secondary_color;
+        let terminal = config.terminal.clone();
+
+        let (apps, installed) = match search_state(&terms, cx) {
+            SearchState::Ready { apps, installed } => (apps, installed),
+            SearchState::Pending => {
+                return vec![create_row(
+                    "flathub-status",
+                    "Searching Flathub...".to_string(),
+                    String::new(),
+                    FlathubHandler::None,
+                    10,
+                    db,
+                    text_secondary_color,
+                )];
+            }
+            SearchState::Failed(e) => {
+                return vec![create_row(
+                    "flathub-status",
+                    "Flathub search failed".to_string(),
+                    e,
+                    FlathubHandler::None,
+                    10,
+                    db,
+                    text_secondary_color,
+                )];
+            }
+        };
+
+        apps.into_iter()
+            .enumerate()
+            .map(|(index, app)| {
+                let id = Box::leak(format!("{}-{}", FLATHUB, app.id).into_boxed_str());
+                let (name, detail, handler) = if installed.contains(&app.id) {
+                    (
+                        format!("Run {}", app.name),
+                        format!("Installed · {}", app.summary),
+                        FlathubHandler::Run(app.id),
+                    )
+                } else {
+                    (
+                        format!("Install {}", app.name),
+                        app.summary,
+                        FlathubHandler::Install {
+                            id: app.id,
+                            terminal: terminal.clone(),
+                        },
+                    )
+                };
+                create_row(
+                    id,
+                    name,
+                    detail,
+                    handler,
+                    100usize.saturating_sub(index),
+                    db.clone(),
+                    text_secondary_color,
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub enum FlathubHandler {
+    Install {
+        id: String,
+        terminal: Option<String>,
+    },
+    Run(String),
+    /// Informational rows
+    None,
+}
+
+impl FlathubHandler {
+    fn install_command(id: &str, terminal: Option<&str>) -> Command {
+        terminal::command(terminal, &flathub::install_args(id))
+    }
+}
+
+impl ActionHandler for FlathubHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        match self {
+            Self::Install { id, terminal } => {
+                Self::install_command(id, terminal.as_deref()).spawn()?;
+                // The installed state is outdated once the install finishes
+                SEARCHES.lock().unwrap().clear();
+            }
+            Self::Run(id) => flathub::run(id)?,
+            Self::None => {}
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        match self {
+            Self::Install { id, terminal } => Some(CommandPreview::from_command(
+                &Self::install_command(id, terminal.as_deref()),
+            )),
+            _ => None,
+        }
+    }
+}
+
+fn create_row(
+    id: &'static str,
+    name: String,
+    detail: String,
+    handler: FlathubHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        10,
+        db,
+    )
+}
+
+/// Returns the state of a search, starting it in the background if needed
+fn search_state(terms: &str, cx: &mut Context<ActionListView>) -> SearchState {
+    let key = terms.to_string();
+    let previous = LATEST.lock().unwrap().replace(key.clone());
+
+    let mut searches = SEARCHES.lock().unwrap();
+    if previous.as_ref() != Some(&key) {
+        // Retry failed searches once they're typed again, e.g. when back online
+        searches.retain(|_, state| !matches!(state, SearchState::Failed(_)));
+    }
+    if let Some(state) = searches.get(&key) {
+        return state.clone();
+    }
+    searches.insert(key.clone(), SearchState::Pending);
+    drop(searches);
+
+    cx.spawn(|view, mut cx| async move {
+        cx.background_executor().timer(DEBOUNCE).await;
+        if LATEST.lock().unwrap().as_ref() != Some(&key) || !activity::is_active() {
+            // Typing went on or the window was hidden, search again if
+            // the query comes back
+            SEARCHES.lock().unwrap().remove(&key);
+            return;
+        }
+
+        let terms = key.clone();
+        let state = cx
+            .background_executor()
+            .spawn(async move {
+                match flathub::search(&terms) {
+                    Ok(apps) => SearchState::Ready {
+                        apps,
+                        installed: flathub::installed_apps(),
+                    },
+                    Err(e) => SearchState::Failed(e.to_string()),
+                }
+            })
+            .await;
+
+        SEARCHES.lock().unwrap().insert(key, state);
+        let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+    })
+    .detach();
+
+    SearchState::Pending
+}
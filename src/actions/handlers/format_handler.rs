@@ -0,0 +1,174 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionDefinition, ActionHandler, ActionId, ActionItem, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::{self, FORMATTER};
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::{copy_to_clipboard, read_clipboard};
+
+pub struct FormatHandlerFactory;
+
+impl HandlerFactory for FormatHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        FORMATTER
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![
+            Keyword::new("json", "Format JSON from the clipboard"),
+            Keyword::new("yaml", "Format YAML from the clipboard"),
+            Keyword::new("format", "Format JSON or YAML from the clipboard"),
+        ]
+    }
+
+    fn data_access(&self) -> Option<&'static str> {
+        Some("your clipboard")
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(format) = DataFormat::from_query(query) else {
+            return Vec::new();
+        };
+
+        vec![FormatHandler { format }.create_action(db, cx)]
+    }
+}
+
+/// Structured data formats supported by the formatter
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DataFormat {
+    Json,
+    Yaml,
+    /// Pick JSON or YAML based on the clipboard content
+    Auto,
+}
+
+impl DataFormat {
+    fn from_query(query: &str) -> Option<Self> {
+        let query = query.trim().to_lowercase();
+        let keyword = query
+            .strip_prefix("format")
+            .map(str::trim)
+            .unwrap_or(&query);
+
+        match keyword {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "" if query.starts_with("format") => Some(Self::Auto),
+            _ => None,
+        }
+    }
+
+    fn resolve(self, content: &str) -> Self {
+        match self {
+            Self::Auto => {
+                let trimmed = content.trim_start();
+                if trimmed.starts_with('{') || trimmed.starts_with('[') {
+                    Self::Json
+                } else {
+                    Self::Yaml
+                }
+            }
+            format => format,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::Yaml => "YAML",
+            Self::Auto => "JSON/YAML",
+        }
+    }
+
+    /// Validate and pretty-print the content. Parser errors include the line
+    /// and column of the first problem.
+    fn format(self, content: &str) -> Result<String> {
+        match self.resolve(content) {
+            Self::Json => {
+                let value: serde_json::Value = serde_json::from_str(content)?;
+                Ok(serde_json::to_string_pretty(&value)?)
+            }
+            _ => {
+                let value: serde_yaml::Value = serde_yaml::from_str(content)?;
+                Ok(serde_yaml::to_string(&value)?)
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct FormatHandler {
+    format: DataFormat,
+}
+
+impl ActionHandler for FormatHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        let content = read_clipboard()?;
+        let formatted = self.format.format(&content)?;
+        copy_to_clipboard(&formatted)
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+impl ActionDefinition for FormatHandler {
+    fn create_action(&self, db: Arc<Database>, cx: &mut Context<ActionListView>) -> ActionItem {
+        let config = cx.global::<Config>();
+        let text_secondary_color = config.text_secondary_color;
+        let name = self.get_name();
+
+        // Validate the clipboard up front so errors are visible before running
+        let detail = match read_clipboard() {
+            Ok(content) if content.trim().is_empty() => "Clipboard is empty".to_string(),
+            Ok(content) => match self.format.format(&content) {
+                Ok(_) => format!("Valid {}", self.format.resolve(&content).name()),
+                Err(e) => e.to_string(),
+            },
+            Err(e) => e.to_string(),
+        };
+
+        ActionItem::new(
+            self.get_id(),
+            self.get_name(),
+            self.clone(),
+            move || {
+                div()
+                    .flex()
+                    .gap_4()
+                    .child(div().flex_none().child(name.clone()))
+                    .child(
+                        div()
+                            .flex_grow()
+                            .overflow_hidden()
+                            .child(detail.clone())
+                            .text_color(text_secondary_color),
+                    )
+                    .into_any()
+            },
+            100,
+            10,
+            db,
+        )
+    }
+
+    fn get_id(&self) -> ActionId {
+        ActionId::Builtin(action_ids::FORMATTER)
+    }
+
+    fn get_name(&self) -> String {
+        format!("Format {} from Clipboard", self.format.name())
+    }
+}
@@ -0,0 +1,171 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use rand::rngs::OsRng;
+use rand::Rng;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::GENERATOR;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::copy_to_clipboard;
+
+/// Longer values are more likely a typo than a wish
+const MAX_LENGTH: usize = 256;
+
+const PIN_DIGITS: &str = "0123456789";
+
+/// A random value a query asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Generate {
+    Uuid,
+    Password(Option<usize>),
+    Pin(Option<usize>),
+}
+
+impl Generate {
+    /// `uuid`, `password [length]` or `pin [length]`
+    fn from_query(query: &str) -> Option<Self> {
+        let query = query.trim().to_lowercase();
+        let mut words = query.split_whitespace();
+        let keyword = words.next()?;
+        let length = match words.next() {
+            Some(length) => Some(length.parse::<usize>().ok()?),
+            None => None,
+        };
+        if words.next().is_some() || length.is_some_and(|length| length == 0) {
+            return None;
+        }
+
+        match (keyword, length) {
+            ("uuid", None) => Some(Self::Uuid),
+            ("password", length) => Some(Self::Password(length)),
+            ("pin", length) => Some(Self::Pin(length)),
+            _ => None,
+        }
+    }
+
+    /// The value and a description of it
+    fn generate(self, config: &Config) -> (String, String) {
+        match self {
+            Self::Uuid => (Uuid::new_v4().to_string(), "Random UUID (v4)".to_string()),
+            Self::Password(length) => {
+                let length = length.unwrap_or(config.password_length).min(MAX_LENGTH);
+                (
+                    random_string(&config.password_charset, length),
+                    format!("Random password, {} characters", length),
+                )
+            }
+            Self::Pin(length) => {
+                let length = length.unwrap_or(config.pin_length).min(MAX_LENGTH);
+                (
+                    random_string(PIN_DIGITS, length),
+                    format!("Random PIN, {} digits", length),
+                )
+            }
+        }
+    }
+}
+
+/// Generates UUIDs, passwords and PINs with `uuid`, `password [length]` and
+/// `pin [length]`, copying them on Enter
+pub struct GeneratorHandlerFactory;
+
+impl HandlerFactory for GeneratorHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        GENERATOR
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![
+            Keyword::new("uuid", "Generate a random UUID"),
+            Keyword::new("password", "Generate a password, optionally of a length"),
+            Keyword::new("pin", "Generate a PIN, optionally of a length"),
+        ]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(generate) = Generate::from_query(query) else {
+            return Vec::new();
+        };
+        let config = cx.global::<Config>();
+        let text_secondary_color = config.text_secondary_color;
+        let (value, detail) = generate.generate(config);
+        if value.is_empty() {
+            return Vec::new();
+        }
+
+        vec![GeneratorHandler { value }.create_action(detail, db, text_secondary_color)]
+    }
+}
+
+/// Copies the generated value to the clipboard
+#[derive(Clone)]
+pub struct GeneratorHandler {
+    value: String,
+}
+
+impl ActionHandler for GeneratorHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        copy_to_clipboard(&self.value)
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+impl GeneratorHandler {
+    fn create_action(
+        &self,
+        detail: String,
+        db: Arc<Database>,
+        text_secondary_color: gpui::Rgba,
+    ) -> ActionItem {
+        let name = self.value.clone();
+
+        ActionItem::new(
+            ActionId::Builtin(GENERATOR),
+            name.clone(),
+            self.clone(),
+            move || {
+                div()
+                    .flex()
+                    .gap_4()
+                    .child(div().flex_none().child(name.clone()))
+                    .child(
+                        div()
+                            .flex_grow()
+                            .overflow_hidden()
+                            .child(detail.clone())
+                            .text_color(text_secondary_color),
+                    )
+                    .into_any()
+            },
+            100,
+            10,
+            db,
+        )
+    }
+}
+
+/// `length` characters drawn uniformly from `charset` by the OS's
+/// cryptographically secure generator
+fn random_string(charset: &str, length: usize) -> String {
+    let chars: Vec<char> = charset.chars().collect();
+    if chars.is_empty() {
+        return String::new();
+    }
+    (0..length)
+        .map(|_| chars[OsRng.gen_range(0..chars.len())])
+        .collect()
+}
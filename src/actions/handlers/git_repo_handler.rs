@@ -0,0 +1,304 @@
+use anyhow::{anyhow, Result};
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::GIT_REPOS;
+use crate::actions::score::{self, Score, RELEVANCE_BOOST};
+use crate::common::expand_tilde;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::git_repos;
+use crate::system::terminal;
+
+/// Queries starting with this word list git repositories
+const REPO_PREFIX: &str = "repo";
+
+const MAX_REPOS: usize = 8;
+
+/// Repositories are scanned again in the background when the last scan is
+/// older than this
+const RESCAN_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Frecency of an action that was never run, so repositories rank like an
+/// app launched once
+const NEW_REPO_FRECENCY: f64 = 1.0;
+
+/// Set once a scan was started, the launcher scans at most once per run
+static SCAN_STARTED: AtomicBool = AtomicBool::new(false);
+static SCANNING: AtomicBool = AtomicBool::new(false);
+
+/// Opens git repositories found under `git_repo_roots` in the editor, a
+/// terminal or the browser with `repo <name>`
+pub struct GitRepoHandlerFactory;
+
+impl HandlerFactory for GitRepoHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        GIT_REPOS
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(
+            format!("{} ", REPO_PREFIX),
+            "Open git repositories in the editor, a terminal or the browser",
+        )]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(filter) = query.trim_start().strip_prefix(REPO_PREFIX) else {
+            return Vec::new();
+        };
+        if !filter.starts_with(' ') {
+            return Vec::new();
+        }
+        let filter = filter.trim().to_lowercase();
+        let filter_tokens: Vec<&str> = filter.split_whitespace().collect();
+
+        let config = cx.global::<Config>();
+        let text_secondary_color = config.text_secondary_color;
+        let editor = config.editor.clone();
+        let terminal = config.terminal.clone();
+        let roots: Vec<PathBuf> = config
+            .git_repo_roots
+            .iter()
+            .map(|root| expand_tilde(root))
+            .collect();
+
+        rescan_if_outdated(roots, db.clone(), cx);
+
+        let repos = db.search_git_repos(&filter, MAX_REPOS).unwrap_or_default();
+        if repos.is_empty() && SCANNING.load(Ordering::Relaxed) {
+            return vec![create_row(
+                "git-repos-scanning",
+                "Looking for repositories...".to_string(),
+                String::new(),
+                GitRepoHandler::None,
+                10,
+                db,
+                text_secondary_color,
+            )];
+        }
+
+        let mut handlers = Vec::new();
+        for repo in repos {
+            let path = repo.path.to_string_lossy().to_string();
+            let web_url = repo.remote_url.as_deref().and_then(git_repos::web_url);
+            let mut actions = Vec::new();
+            let edit = GitRepoHandler::Edit {
+                directory: repo.path.clone(),
+                editor: editor.clone(),
+                terminal: terminal.clone(),
+            };
+            // Without an editor the row would only fail
+            if edit.command().is_some() {
+                actions.push(("edit", "Edit", path.clone(), edit));
+            }
+            actions.push((
+                "terminal",
+                "Terminal in",
+                path.clone(),
+                GitRepoHandler::Terminal {
+                    directory: repo.path.clone(),
+                    terminal: terminal.clone(),
+                },
+            ));
+            if let Some(url) = web_url {
+                actions.push(("browse", "Browse", url.clone(), GitRepoHandler::Browse(url)));
+            }
+
+            for (action, verb, detail, handler) in actions {
+                let id = Box::leak(format!("{}-{}-{}", GIT_REPOS, action, path).into_boxed_str());
+                let frecency = db.get_action_frecency(id).unwrap_or(0.0);
+                let score = Score::new(
+                    frecency + NEW_REPO_FRECENCY,
+                    score::match_quality(&filter, &repo.name),
+                    score::search_score(&filter_tokens, &path),
+                );
+
+                let url = match &handler {
+                    GitRepoHandler::Browse(url) => Some(url.clone()),
+                    _ => None,
+                };
+                let row = create_row(
+                    id,
+                    format!("{} {}", verb, repo.name),
+                    detail,
+                    handler,
+                    score.relevance(),
+                    db.clone(),
+                    text_secondary_color,
+                )
+                .with_score(Some(score));
+                handlers.push(match url {
+                    Some(url) => row.with_url(url),
+                    None => row,
+                });
+            }
+        }
+
+        handlers
+    }
+}
+
+#[derive(Clone)]
+pub enum GitRepoHandler {
+    Edit {
+        directory: PathBuf,
+        editor: Option<String>,
+        terminal: Option<String>,
+    },
+    Terminal {
+        directory: PathBuf,
+        terminal: Option<String>,
+    },
+    Browse(String),
+    /// Informational rows
+    None,
+}
+
+impl GitRepoHandler {
+    fn command(&self) -> Option<Command> {
+        match self {
+            Self::Edit {
+                directory,
+                editor,
+                terminal,
+            } => editor_command(editor.as_deref(), terminal.as_deref(), directory),
+            Self::Terminal {
+                directory,
+                terminal,
+            } => Some(terminal::shell_in(terminal.as_deref(), directory)),
+            _ => None,
+        }
+    }
+}
+
+impl ActionHandler for GitRepoHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        match self {
+            Self::Browse(url) => open::that(url)?,
+            Self::None => {}
+            _ => {
+                let mut command = self
+                    .command()
+                    .ok_or_else(|| anyhow!("No editor configured, set editor or $EDITOR"))?;
+                command.spawn()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        self.command()
+            .map(|command| CommandPreview::from_command(&command))
+    }
+}
+
+/// The configured editor, or `$VISUAL`/`$EDITOR` in a terminal. `None` if
+/// no editor is known.
+fn editor_command(editor: Option<&str>, terminal: Option<&str>, dir: &Path) -> Option<Command> {
+    let mut command = match editor {
+        Some(editor) => {
+            let mut parts = editor.split_whitespace();
+            let mut command = Command::new(parts.next()?);
+            command.args(parts).arg(dir);
+            command
+        }
+        None => {
+            let editor = env::var("VISUAL")
+                .or_else(|_| env::var("EDITOR"))
+                .ok()
+                .filter(|editor| !editor.is_empty())?;
+            terminal::command(terminal, &[editor, dir.to_string_lossy().to_string()])
+        }
+    };
+    command.current_dir(dir);
+    Some(command)
+}
+
+fn create_row(
+    id: &'static str,
+    name: String,
+    detail: String,
+    handler: GitRepoHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        RELEVANCE_BOOST,
+        db,
+    )
+}
+
+/// Scan the roots in the background if the stored repositories are older
+/// than `RESCAN_INTERVAL`
+fn rescan_if_outdated(roots: Vec<PathBuf>, db: Arc<Database>, cx: &mut Context<ActionListView>) {
+    if SCAN_STARTED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+
+    let scanned_at = db
+        .git_repos_scanned_at()
+        .ok()
+        .flatten()
+        .and_then(|time| chrono::DateTime::parse_from_rfc3339(&time).ok());
+    let outdated = scanned_at.map_or(true, |time| {
+        (chrono::Local::now().fixed_offset() - time)
+            .to_std()
+            .map_or(true, |age| age > RESCAN_INTERVAL)
+    });
+    if !outdated {
+        return;
+    }
+
+    SCANNING.store(true, Ordering::Relaxed);
+    cx.spawn(|view, mut cx| async move {
+        let repos = cx
+            .background_executor()
+            .spawn(async move { git_repos::scan(&roots) })
+            .await;
+
+        if let Err(e) = db.store_git_repos(&repos) {
+            log::warn!("Failed to store git repositories: {}", e);
+        }
+        SCANNING.store(false, Ordering::Relaxed);
+        let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+    })
+    .detach();
+}
@@ -0,0 +1,254 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::GITHUB;
+use crate::activity;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::github::{self, GithubResult, SearchKind};
+
+/// Queries starting with this word search GitHub
+const GITHUB_PREFIX: &str = "gh";
+
+/// Word after the prefix that searches issues and pull requests instead of
+/// repositories
+const ISSUES_KEYWORD: &str = "issue";
+
+/// Shorter search terms aren't sent to GitHub
+const MIN_TERMS_LEN: usize = 2;
+
+/// Searches start once typing paused for this long, to stay within GitHub's
+/// rate limit
+const DEBOUNCE: Duration = Duration::from_millis(400);
+
+const MAX_RESULTS: usize = 8;
+
+#[derive(Debug, Clone)]
+enum SearchState {
+    Pending,
+    Ready(Vec<GithubResult>),
+    Failed(String),
+}
+
+type SearchKey = (SearchKind, String);
+
+lazy_static::lazy_static! {
+    /// Searches of this session
+    static ref SEARCHES: Mutex<HashMap<SearchKey, SearchState>> = Mutex::new(HashMap::new());
+    /// The search typed last, earlier ones are dropped while debouncing
+    static ref LATEST: Mutex<Option<SearchKey>> = Mutex::new(None);
+}
+
+/// Searches GitHub repositories with `gh <terms>` and issues with
+/// `gh issue <terms>`
+pub struct GithubHandlerFactory;
+
+impl HandlerFactory for GithubHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        GITHUB
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![
+            Keyword::new(format!("{} ", GITHUB_PREFIX), "Search GitHub repositories"),
+            Keyword::new(
+                format!("{} {} ", GITHUB_PREFIX, ISSUES_KEYWORD),
+                "Search GitHub issues and pull requests",
+            ),
+        ]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some((kind, terms)) = parse_query(query) else {
+            return Vec::new();
+        };
+        if terms.len() < MIN_TERMS_LEN {
+            return Vec::new();
+        }
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+
+        let (results, status) = match search_state(kind, &terms, db.clone(), cx) {
+            SearchState::Ready(results) => (results, None),
+            // Show what earlier searches found while waiting or offline
+            SearchState::Pending => (
+                recall(&db, kind, &terms),
+                Some(("Searching GitHub...".to_string(), String::new())),
+            ),
+            SearchState::Failed(e) => (
+                recall(&db, kind, &terms),
+                Some(("GitHub search failed".to_string(), e)),
+            ),
+        };
+
+        let mut handlers: Vec<ActionItem> = results
+            .into_iter()
+            .enumerate()
+            .map(|(index, result)| {
+                let id = Box::leak(format!("{}-{}", GITHUB, result.url).into_boxed_str());
+                create_row(
+                    id,
+                    result.title,
+                    result.detail,
+                    GithubHandler::Open(result.url.clone()),
+                    100 - index,
+                    db.clone(),
+                    text_secondary_color,
+                )
+                .with_url(result.url)
+            })
+            .collect();
+
+        if let Some((name, detail)) = status {
+            handlers.push(create_row(
+                "github-status",
+                name,
+                detail,
+                GithubHandler::None,
+                10,
+                db,
+                text_secondary_color,
+            ));
+        }
+
+        handlers
+    }
+}
+
+#[derive(Clone)]
+pub enum GithubHandler {
+    Open(String),
+    /// Informational rows
+    None,
+}
+
+impl ActionHandler for GithubHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        if let Self::Open(url) = self {
+            open::that(url)?;
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+/// Split `gh [issue] <terms>` into the kind of search and its terms
+fn parse_query(query: &str) -> Option<(SearchKind, String)> {
+    let rest = query.trim_start().strip_prefix(GITHUB_PREFIX)?;
+    if !rest.starts_with(' ') {
+        return None;
+    }
+
+    let rest = rest.trim();
+    Some(match rest.strip_prefix(ISSUES_KEYWORD) {
+        Some(terms) if terms.is_empty() || terms.starts_with(' ') => {
+            (SearchKind::Issues, terms.trim().to_string())
+        }
+        _ => (SearchKind::Repositories, rest.to_string()),
+    })
+}
+
+fn recall(db: &Database, kind: SearchKind, terms: &str) -> Vec<GithubResult> {
+    db.search_github_results(kind, terms, MAX_RESULTS)
+        .unwrap_or_default()
+}
+
+fn create_row(
+    id: &'static str,
+    name: String,
+    detail: String,
+    handler: GithubHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        10,
+        db,
+    )
+}
+
+/// Returns the state of a search, starting it in the background if needed.
+/// Results are remembered in the database for offline recall.
+fn search_state(
+    kind: SearchKind,
+    terms: &str,
+    db: Arc<Database>,
+    cx: &mut Context<ActionListView>,
+) -> SearchState {
+    let key = (kind, terms.to_string());
+    let previous = LATEST.lock().unwrap().replace(key.clone());
+
+    let mut searches = SEARCHES.lock().unwrap();
+    if previous.as_ref() != Some(&key) {
+        // Retry failed searches once they're typed again, e.g. when back online
+        searches.retain(|_, state| !matches!(state, SearchState::Failed(_)));
+    }
+    if let Some(state) = searches.get(&key) {
+        return state.clone();
+    }
+    searches.insert(key.clone(), SearchState::Pending);
+    drop(searches);
+
+    let token = cx.global::<Config>().github_token.clone();
+    cx.spawn(|view, mut cx| async move {
+        cx.background_executor().timer(DEBOUNCE).await;
+        if LATEST.lock().unwrap().as_ref() != Some(&key) || !activity::is_active() {
+            // Typing went on or the window was hidden, search again if
+            // the query comes back
+            SEARCHES.lock().unwrap().remove(&key);
+            return;
+        }
+
+        let (kind, terms) = key.clone();
+        let result = cx
+            .background_executor()
+            .spawn(async move { github::search(kind, &terms, token.as_deref()) })
+            .await;
+
+        let state = match result {
+            Ok(results) => {
+                let _ = db.store_github_results(&results);
+                SearchState::Ready(results)
+            }
+            Err(e) => SearchState::Failed(e.to_string()),
+        };
+        SEARCHES.lock().unwrap().insert(key, state);
+        let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+    })
+    .detach();
+
+    SearchState::Pending
+}
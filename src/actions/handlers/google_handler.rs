@@ -1,13 +1,13 @@
 use anyhow;
-use gpui::{div, Context, Element, ParentElement, Styled};
+use gpui::{div, prelude::FluentBuilder, Context, Element, ParentElement, Styled};
 use std::sync::Arc;
 
 use crate::action_list_view::ActionListView;
 use crate::actions::action_handler::{
-    ActionDefinition, ActionHandler, ActionId, ActionItem, HandlerFactory,
+    ActionDefinition, ActionHandler, ActionId, ActionItem, HandlerFactory, WEB_SEARCH_GROUP,
 };
 use crate::actions::action_ids::{self, GOOGLE_SEARCH};
-use crate::config::Config;
+use crate::config::{Config, UsageColumn};
 use crate::database::Database;
 
 pub struct GoogleHandlerFactory;
@@ -17,6 +17,18 @@ impl HandlerFactory for GoogleHandlerFactory {
         GOOGLE_SEARCH
     }
 
+    fn group(&self) -> &'static str {
+        WEB_SEARCH_GROUP
+    }
+
+    fn bangs(&self) -> Vec<String> {
+        vec!["g".to_string(), "google".to_string()]
+    }
+
+    fn keyword(&self) -> Option<&'static str> {
+        Some("g")
+    }
+
     fn create_handlers_for_query(
         &self,
         query: &str,
@@ -49,12 +61,14 @@ impl ActionDefinition for GoogleHandler {
     fn create_action(&self, db: Arc<Database>, cx: &mut Context<ActionListView>) -> ActionItem {
         let config = cx.global::<Config>();
         let text_secondary_color = config.text_secondary_color;
+        let show_count = config.usage_column != UsageColumn::None;
 
         let (relevance, execution_count) = db.get_action_relevance(self.get_id().as_str()).unwrap();
         let name = self.get_name();
 
         ActionItem::new(
             self.get_id(),
+            self.get_name(),
             self.clone(),
             move || {
                 div()
@@ -67,11 +81,13 @@ impl ActionDefinition for GoogleHandler {
                             .child("Search Engine")
                             .text_color(text_secondary_color),
                     )
-                    .child(
-                        div()
-                            .child(format!("{}", execution_count))
-                            .text_color(text_secondary_color),
-                    )
+                    .when(show_count, |x| {
+                        x.child(
+                            div()
+                                .child(format!("{}", execution_count))
+                                .text_color(text_secondary_color),
+                        )
+                    })
                     .into_any()
             },
             relevance,
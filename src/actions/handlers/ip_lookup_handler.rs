@@ -0,0 +1,393 @@
+use anyhow::{anyhow, Result};
+use gpui::{div, Context, Element, ParentElement, Styled};
+use log::debug;
+use std::collections::HashMap;
+use std::net::{IpAddr, ToSocketAddrs};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::IP_LOOKUP;
+use crate::activity;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::copy_to_clipboard;
+
+/// Queries starting with this prefix look up a domain or IP address. IP
+/// addresses are also looked up without it.
+const IP_PREFIX: &str = "ip ";
+
+/// Lookups start once typing paused for this long
+const DEBOUNCE: Duration = Duration::from_millis(400);
+
+/// Number of lines from the whois response shown in the preview
+const WHOIS_SUMMARY_LINES: usize = 8;
+
+/// Whois keys that carry useful information across registries
+const WHOIS_KEYS: &[&str] = &[
+    "OrgName",
+    "org-name",
+    "Organization",
+    "netname",
+    "NetRange",
+    "CIDR",
+    "Registrar",
+    "Creation Date",
+    "Registry Expiry Date",
+    "country",
+    "Country",
+];
+
+/// State of a lookup for a single IP address or domain
+#[derive(Debug, Clone)]
+enum LookupState {
+    Pending,
+    Ready(LookupInfo),
+    Failed(String),
+}
+
+/// Network information about an IP address or domain
+#[derive(Debug, Clone, Default)]
+struct LookupInfo {
+    asn: Option<String>,
+    location: Option<String>,
+    reverse_dns: Option<String>,
+    /// Most relevant lines of the whois response
+    whois: Vec<String>,
+}
+
+/// Cache of lookups, so each target is only queried once per session
+lazy_static::lazy_static! {
+    static ref LOOKUP_CACHE: Mutex<HashMap<String, LookupState>> = Mutex::new(HashMap::new());
+    /// The target typed last, earlier ones are dropped while debouncing
+    static ref LATEST: Mutex<Option<String>> = Mutex::new(None);
+}
+
+pub struct IpLookupHandlerFactory;
+
+impl HandlerFactory for IpLookupHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        IP_LOOKUP
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(IP_PREFIX, "Look up a domain or IP address")]
+    }
+
+    /// Targets are sent to ipinfo.io and whois servers
+    fn data_access(&self) -> Option<&'static str> {
+        Some("the addresses you look up and send them to ipinfo.io")
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let query = query.trim_start();
+        let target = match query.strip_prefix(IP_PREFIX) {
+            Some(target) if is_lookup_target(target.trim()) => target.trim(),
+            // Text that only looks like a domain, e.g. a file name, isn't
+            // sent anywhere without the prefix
+            None if query.trim().parse::<IpAddr>().is_ok() => query.trim(),
+            _ => return Vec::new(),
+        };
+
+        let state = start_lookup(target, cx);
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+
+        let summary = match &state {
+            LookupState::Pending => "Looking up...".to_string(),
+            LookupState::Failed(e) => e.clone(),
+            LookupState::Ready(info) => info.summary(),
+        };
+
+        let mut handlers = vec![create_row(
+            ActionId::Builtin(IP_LOOKUP),
+            format!("Lookup {}", target),
+            summary,
+            LookupHandler::Open(target.to_string()),
+            100,
+            db.clone(),
+            text_secondary_color,
+        )];
+
+        if let LookupState::Ready(info) = state {
+            let fields = [
+                ("ip-lookup-copy-asn", "Copy ASN", info.asn),
+                ("ip-lookup-copy-location", "Copy Location", info.location),
+                ("ip-lookup-copy-rdns", "Copy Reverse DNS", info.reverse_dns),
+            ];
+
+            for (id, label, value) in fields {
+                if let Some(value) = value {
+                    handlers.push(create_row(
+                        ActionId::Builtin(id),
+                        label.to_string(),
+                        value.clone(),
+                        LookupHandler::Copy(value),
+                        90,
+                        db.clone(),
+                        text_secondary_color,
+                    ));
+                }
+            }
+
+            // The summary itself is shown in the preview of the lookup
+            if !info.whois.is_empty() {
+                handlers.push(create_row(
+                    ActionId::Builtin("ip-lookup-copy-whois"),
+                    "Copy Whois Summary".to_string(),
+                    format!("{} lines", info.whois.len()),
+                    LookupHandler::Copy(info.whois.join("\n")),
+                    90,
+                    db.clone(),
+                    text_secondary_color,
+                ));
+            }
+        }
+
+        handlers
+    }
+}
+
+#[derive(Clone)]
+pub enum LookupHandler {
+    /// Open a whois page for the target in the browser
+    Open(String),
+    /// Copy a single field to the clipboard
+    Copy(String),
+}
+
+impl ActionHandler for LookupHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        match self {
+            Self::Open(target) => open::that(whois_url(target))?,
+            Self::Copy(value) => copy_to_clipboard(value)?,
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    /// The lookup's fields and whois summary, above the command opening the
+    /// whois page
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        let Self::Open(target) = self else {
+            return None;
+        };
+        let command = open::commands(whois_url(target)).into_iter().next()?;
+
+        let details = match LOOKUP_CACHE.lock().unwrap().get(target) {
+            Some(LookupState::Ready(info)) => info.details(),
+            Some(LookupState::Failed(e)) => vec![e.clone()],
+            _ => vec!["Looking up...".to_string()],
+        };
+        Some(CommandPreview::from_command(&command).with_details(details))
+    }
+}
+
+fn whois_url(target: &str) -> String {
+    format!("https://who.is/whois/{}", urlencoding::encode(target))
+}
+
+fn create_row(
+    id: ActionId,
+    name: String,
+    detail: String,
+    handler: LookupHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        id,
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        10,
+        db,
+    )
+}
+
+/// Returns the cached lookup state, starting a background lookup once
+/// typing paused
+fn start_lookup(target: &str, cx: &mut Context<ActionListView>) -> LookupState {
+    *LATEST.lock().unwrap() = Some(target.to_string());
+
+    let mut cache = LOOKUP_CACHE.lock().unwrap();
+    if let Some(state) = cache.get(target) {
+        return state.clone();
+    }
+    cache.insert(target.to_string(), LookupState::Pending);
+    drop(cache);
+
+    let target = target.to_string();
+    cx.spawn(|view, mut cx| async move {
+        cx.background_executor().timer(DEBOUNCE).await;
+        if LATEST.lock().unwrap().as_ref() != Some(&target) || !activity::is_active() {
+            // Typing went on or the window was hidden, look up again if
+            // the target comes back
+            LOOKUP_CACHE.lock().unwrap().remove(&target);
+            return;
+        }
+
+        let lookup_target = target.clone();
+        let state = cx
+            .background_executor()
+            .spawn(async move {
+                match LookupInfo::fetch(&lookup_target) {
+                    Ok(info) => LookupState::Ready(info),
+                    Err(e) => LookupState::Failed(format!("Lookup failed: {}", e)),
+                }
+            })
+            .await;
+
+        LOOKUP_CACHE.lock().unwrap().insert(target, state);
+        let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+    })
+    .detach();
+
+    LookupState::Pending
+}
+
+impl LookupInfo {
+    fn fetch(target: &str) -> Result<Self> {
+        let mut info = Self::fetch_geo(target).unwrap_or_else(|e| {
+            debug!("Geo lookup for {} failed: {}", target, e);
+            Self::default()
+        });
+        info.whois = Self::fetch_whois(target);
+
+        if info.asn.is_none() && info.location.is_none() && info.whois.is_empty() {
+            return Err(anyhow!("no information found"));
+        }
+        Ok(info)
+    }
+
+    /// Query ipinfo.io over HTTPS for ASN, location and reverse DNS.
+    /// Domains are resolved here, it only takes IP addresses.
+    fn fetch_geo(target: &str) -> Result<Self> {
+        let ip = match target.parse::<IpAddr>() {
+            Ok(ip) => ip,
+            Err(_) => (target, 0)
+                .to_socket_addrs()?
+                .next()
+                .ok_or_else(|| anyhow!("{} has no address", target))?
+                .ip(),
+        };
+        let output = Command::new("curl")
+            .args(["-s", "--max-time", "5", "--proto", "=https"])
+            .arg(format!("https://ipinfo.io/{}/json", ip))
+            .output()?;
+
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+        if json["bogon"] == true {
+            return Err(anyhow!("{} is a private address", ip));
+        }
+        if let Some(error) = json["error"].as_object() {
+            return Err(anyhow!(
+                "{}",
+                error["message"].as_str().unwrap_or("unknown error")
+            ));
+        }
+
+        let field = |key: &str| {
+            json[key]
+                .as_str()
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+
+        let location = match (field("city"), field("country")) {
+            (Some(city), Some(country)) => Some(format!("{}, {}", city, country)),
+            (city, country) => city.or(country),
+        };
+
+        Ok(Self {
+            asn: field("org"),
+            location,
+            reverse_dns: field("hostname"),
+            whois: Vec::new(),
+        })
+    }
+
+    /// Run the `whois` tool and keep only the most relevant lines
+    fn fetch_whois(target: &str) -> Vec<String> {
+        let Ok(output) = Command::new("whois").arg("--").arg(target).output() else {
+            return Vec::new();
+        };
+        let text = String::from_utf8_lossy(&output.stdout);
+
+        text.lines()
+            .filter_map(|line| line.trim().split_once(':'))
+            .filter(|(key, value)| WHOIS_KEYS.contains(&key.trim()) && !value.trim().is_empty())
+            .map(|(key, value)| format!("{}: {}", key.trim(), value.trim()))
+            .take(WHOIS_SUMMARY_LINES)
+            .collect()
+    }
+
+    fn summary(&self) -> String {
+        [&self.asn, &self.location, &self.reverse_dns]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+
+    /// Labelled fields followed by the whois summary, one per line
+    fn details(&self) -> Vec<String> {
+        [
+            ("ASN", &self.asn),
+            ("Location", &self.location),
+            ("Reverse DNS", &self.reverse_dns),
+        ]
+        .into_iter()
+        .filter_map(|(label, value)| value.as_ref().map(|value| format!("{}: {}", label, value)))
+        .chain(self.whois.iter().cloned())
+        .collect()
+    }
+}
+
+/// Checks whether the query is an IP address or something that looks like a domain
+fn is_lookup_target(query: &str) -> bool {
+    if query.parse::<IpAddr>().is_ok() {
+        return true;
+    }
+
+    let labels: Vec<&str> = query.split('.').collect();
+    let Some(tld) = labels.last() else {
+        return false;
+    };
+
+    labels.len() >= 2
+        && tld.len() >= 2
+        && tld.chars().all(|c| c.is_ascii_alphabetic())
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
@@ -0,0 +1,164 @@
+use anyhow::{anyhow, Result};
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::sync::Arc;
+use url::Url;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{ActionHandler, ActionId, ActionItem, HandlerFactory};
+use crate::actions::action_ids::LINK_GROUPS;
+use crate::actions::score::{self, Score, RELEVANCE_BOOST};
+use crate::config::{Config, LinkGroup};
+use crate::database::Database;
+
+/// Frecency of a group that was never opened, so it ranks like an app
+/// launched once
+const NEW_GROUP_FRECENCY: f64 = 1.0;
+
+/// Number of hosts listed next to a group
+const MAX_LISTED_HOSTS: usize = 3;
+
+/// Opens all links of a group, e.g. `morning` for mail, calendar and the
+/// standup board
+pub struct LinkGroupHandlerFactory;
+
+impl HandlerFactory for LinkGroupHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        LINK_GROUPS
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let filter = query.trim().to_lowercase();
+        if filter.is_empty() {
+            return Vec::new();
+        }
+        let config = cx.global::<Config>();
+        let text_secondary_color = config.text_secondary_color;
+        let filter_tokens: Vec<&str> = filter.split_whitespace().collect();
+
+        link_groups(&config.link_groups, &db)
+            .into_iter()
+            .filter(|group| group.name.to_lowercase().contains(&filter))
+            .map(|group| {
+                let id = Box::leak(format!("{}-{}", LINK_GROUPS, group.name).into_boxed_str());
+                let frecency = db.get_action_frecency(id).unwrap_or(0.0);
+                let score = Score::new(
+                    frecency + NEW_GROUP_FRECENCY,
+                    score::match_quality(&filter, &group.name),
+                    score::search_score(&filter_tokens, &group.name),
+                );
+
+                create_row(
+                    id,
+                    group,
+                    score.relevance(),
+                    db.clone(),
+                    text_secondary_color,
+                )
+                .with_score(Some(score))
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub struct LinkGroupHandler {
+    urls: Vec<String>,
+}
+
+impl ActionHandler for LinkGroupHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        let failed: Vec<&str> = self
+            .urls
+            .iter()
+            .filter(|url| open::that(url.as_str()).is_err())
+            .map(String::as_str)
+            .collect();
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("Failed to open {}", failed.join(", ")))
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+/// Groups of the config and the ones added with `:group add`. Links added to
+/// a group of the config are appended to it.
+fn link_groups(configured: &[LinkGroup], db: &Database) -> Vec<LinkGroup> {
+    let mut groups = configured.to_vec();
+    for added in db.get_link_groups().unwrap_or_default() {
+        match groups.iter_mut().find(|group| group.name == added.name) {
+            Some(group) => {
+                for url in added.urls {
+                    if !group.urls.contains(&url) {
+                        group.urls.push(url);
+                    }
+                }
+            }
+            None => groups.push(added),
+        }
+    }
+    groups.retain(|group| !group.urls.is_empty());
+    groups
+}
+
+/// e.g. "mail.example.com, calendar.example.com, +2"
+fn describe(urls: &[String]) -> String {
+    let mut hosts: Vec<String> = urls
+        .iter()
+        .take(MAX_LISTED_HOSTS)
+        .map(|url| {
+            Url::parse(url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string))
+                .unwrap_or_else(|| url.clone())
+        })
+        .collect();
+    if urls.len() > MAX_LISTED_HOSTS {
+        hosts.push(format!("+{}", urls.len() - MAX_LISTED_HOSTS));
+    }
+    hosts.join(", ")
+}
+
+fn create_row(
+    id: &'static str,
+    group: LinkGroup,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    let name = format!("Open {} ({} links)", group.name, group.urls.len());
+    let detail = describe(&group.urls);
+
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        LinkGroupHandler { urls: group.urls },
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        RELEVANCE_BOOST,
+        db,
+    )
+}
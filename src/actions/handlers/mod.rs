@@ -1,8 +1,43 @@
+pub mod audio_handler;
+pub mod bitwarden_handler;
 pub mod executable_handler;
+pub mod color_handler;
+pub mod display_handler;
+pub mod docs_handler;
+pub mod encoding_handler;
+pub mod file_search_handler;
 pub mod browser_history_handler;
+pub mod browser_tabs_handler;
+pub mod docker_handler;
 pub mod duckduckgo_handler;
+pub mod flathub_handler;
+pub mod format_handler;
+pub mod generator_handler;
+pub mod git_repo_handler;
+pub mod github_handler;
 pub mod google_handler;
+pub mod ip_lookup_handler;
+pub mod link_group_handler;
+pub mod nix_handler;
+pub mod notes_handler;
+pub mod open_with_handler;
+pub mod path_handler;
 pub mod perplexity_handler;
+pub mod plugin_handler;
+pub mod power_handler;
+pub mod reminders_handler;
+pub mod remote_handler;
+pub mod resources_handler;
+pub mod search_engine_handler;
+pub mod shell_handler;
+pub mod shortcut_handler;
+pub mod text_case_handler;
+pub mod timestamp_handler;
+pub mod unicode_handler;
 pub mod url_handler;
+pub mod wifi_handler;
+pub mod workspace_handler;
+pub mod world_clock_handler;
 pub mod yandex_handler;
 
+pub mod zoxide_handler;
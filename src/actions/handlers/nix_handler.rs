@@ -0,0 +1,276 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::NIX;
+use crate::activity;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::copy_to_clipboard;
+use crate::system::nix::{self, NixPackage};
+use crate::system::terminal;
+
+/// Queries starting with this word search nixpkgs
+const NIX_PREFIX: &str = "nix";
+
+/// Shorter search terms aren't searched, they match most of nixpkgs
+const MIN_TERMS_LEN: usize = 2;
+
+/// Searches start once typing paused for this long
+const DEBOUNCE: Duration = Duration::from_millis(400);
+
+#[derive(Debug, Clone)]
+enum SearchState {
+    Pending,
+    Ready(Vec<NixPackage>),
+    Failed(String),
+}
+
+lazy_static::lazy_static! {
+    /// Searches of this session
+    static ref SEARCHES: Mutex<HashMap<String, SearchState>> = Mutex::new(HashMap::new());
+    /// The search typed last, earlier ones are dropped while debouncing
+    static ref LATEST: Mutex<Option<String>> = Mutex::new(None);
+}
+
+/// Searches nixpkgs with `nix <terms>`, running packages with `nix run` in a
+/// terminal or copying their flake reference
+pub struct NixHandlerFactory;
+
+impl HandlerFactory for NixHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        NIX
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(
+            format!("{} ", NIX_PREFIX),
+            "Search nixpkgs and run packages with nix run",
+        )]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(terms) = query.trim_start().strip_prefix(NIX_PREFIX) else {
+            return Vec::new();
+        };
+        if !terms.starts_with(' ') {
+            return Vec::new();
+        }
+        let terms = terms.trim().to_lowercase();
+        if terms.len() < MIN_TERMS_LEN {
+            return Vec::new();
+        }
+        let config = cx.global::<Config>();
+        let text_secondary_color = config.text_secondary_color;
+        let terminal = config.terminal.clone();
+
+        let packages = match search_state(&terms, cx) {
+            SearchState::Ready(packages) => packages,
+            SearchState::Pending => {
+                return vec![create_row(
+                    "nix-status",
+                    "Searching nixpkgs...".to_string(),
+                    String::new(),
+                    NixHandler::None,
+                    10,
+                    db,
+                    text_secondary_color,
+                )];
+            }
+            SearchState::Failed(e) => {
+                return vec![create_row(
+                    "nix-status",
+                    "nixpkgs search failed".to_string(),
+                    e,
+                    NixHandler::None,
+                    10,
+                    db,
+                    text_secondary_color,
+                )];
+            }
+        };
+        if packages.is_empty() {
+            return vec![create_row(
+                "nix-status",
+                "No packages found".to_string(),
+                terms,
+                NixHandler::None,
+                10,
+                db,
+                text_secondary_color,
+            )];
+        }
+
+        packages
+            .into_iter()
+            .enumerate()
+            .flat_map(|(index, package)| {
+                let relevance = 100usize.saturating_sub(index * 2);
+                let run_id = Box::leak(format!("{}-run-{}", NIX, package.attr).into_boxed_str());
+                let copy_id = Box::leak(format!("{}-copy-{}", NIX, package.attr).into_boxed_str());
+                let detail = match package.version.is_empty() {
+                    true => package.description.clone(),
+                    false => format!("{} · {}", package.version, package.description),
+                };
+                [
+                    create_row(
+                        run_id,
+                        format!("Run {}", package.attr),
+                        detail,
+                        NixHandler::Run {
+                            package: package.clone(),
+                            terminal: terminal.clone(),
+                        },
+                        relevance,
+                        db.clone(),
+                        text_secondary_color,
+                    ),
+                    create_row(
+                        copy_id,
+                        format!("Copy {}", package.flake_ref()),
+                        "Flake reference".to_string(),
+                        NixHandler::CopyFlakeRef(package),
+                        relevance.saturating_sub(1),
+                        db.clone(),
+                        text_secondary_color,
+                    ),
+                ]
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub enum NixHandler {
+    Run {
+        package: NixPackage,
+        terminal: Option<String>,
+    },
+    CopyFlakeRef(NixPackage),
+    /// Informational rows
+    None,
+}
+
+impl NixHandler {
+    fn command(&self) -> Option<Command> {
+        match self {
+            Self::Run { package, terminal } => Some(terminal::command(
+                terminal.as_deref(),
+                &nix::run_args(package),
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl ActionHandler for NixHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        match self {
+            Self::CopyFlakeRef(package) => copy_to_clipboard(&package.flake_ref()),
+            _ => {
+                if let Some(mut command) = self.command() {
+                    command.spawn()?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        self.command()
+            .map(|command| CommandPreview::from_command(&command))
+    }
+}
+
+fn create_row(
+    id: &'static str,
+    name: String,
+    detail: String,
+    handler: NixHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        10,
+        db,
+    )
+}
+
+/// Returns the state of a search, starting it in the background if needed
+fn search_state(terms: &str, cx: &mut Context<ActionListView>) -> SearchState {
+    let key = terms.to_string();
+    let previous = LATEST.lock().unwrap().replace(key.clone());
+
+    let mut searches = SEARCHES.lock().unwrap();
+    if previous.as_ref() != Some(&key) {
+        // Retry failed searches once they're typed again
+        searches.retain(|_, state| !matches!(state, SearchState::Failed(_)));
+    }
+    if let Some(state) = searches.get(&key) {
+        return state.clone();
+    }
+    searches.insert(key.clone(), SearchState::Pending);
+    drop(searches);
+
+    cx.spawn(|view, mut cx| async move {
+        cx.background_executor().timer(DEBOUNCE).await;
+        if LATEST.lock().unwrap().as_ref() != Some(&key) || !activity::is_active() {
+            // Typing went on or the window was hidden, search again if
+            // the query comes back
+            SEARCHES.lock().unwrap().remove(&key);
+            return;
+        }
+
+        let terms = key.clone();
+        let state = cx
+            .background_executor()
+            .spawn(async move {
+                match nix::search(&terms) {
+                    Ok(packages) => SearchState::Ready(packages),
+                    Err(e) => SearchState::Failed(e.to_string()),
+                }
+            })
+            .await;
+
+        SEARCHES.lock().unwrap().insert(key, state);
+        let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+    })
+    .detach();
+
+    SearchState::Pending
+}
@@ -0,0 +1,163 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::NOTES;
+use crate::config::Config;
+use crate::database::{Database, Note};
+use crate::system::copy_to_clipboard;
+
+/// Number of existing notes shown below the append action
+const MAX_NOTE_RESULTS: usize = 5;
+
+/// Maximum length of a note preview in the result list
+const PREVIEW_LENGTH: usize = 60;
+
+pub struct NotesHandlerFactory;
+
+impl HandlerFactory for NotesHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        NOTES
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new("note ", "Append a note, or search notes")]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(text) = parse_note_query(query) else {
+            return Vec::new();
+        };
+
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+        let mut handlers = Vec::new();
+
+        if !text.is_empty() {
+            handlers.push(create_row(
+                ActionId::Builtin(NOTES),
+                "Append Note".to_string(),
+                text.to_string(),
+                NotesHandler::Append(text.to_string()),
+                100,
+                db.clone(),
+                text_secondary_color,
+            ));
+        }
+
+        let notes = db.search_notes(text, MAX_NOTE_RESULTS).unwrap_or_default();
+        for note in notes {
+            handlers.push(create_note_row(note, db.clone(), text_secondary_color));
+        }
+
+        handlers
+    }
+}
+
+#[derive(Clone)]
+pub enum NotesHandler {
+    /// Store a new note
+    Append(String),
+    /// Copy an existing note to the clipboard
+    Copy(String),
+}
+
+impl ActionHandler for NotesHandler {
+    fn execute(&self, input: &str) -> Result<()> {
+        self.execute_with_db(input, &Database::new()?)
+    }
+
+    fn execute_with_db(&self, _input: &str, db: &Database) -> Result<()> {
+        match self {
+            Self::Append(content) => {
+                db.add_note(content)?;
+            }
+            Self::Copy(content) => copy_to_clipboard(content)?,
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+fn create_note_row(note: Note, db: Arc<Database>, text_secondary_color: gpui::Rgba) -> ActionItem {
+    let first_line = note.content.lines().next().unwrap_or_default();
+    let mut preview: String = first_line.chars().take(PREVIEW_LENGTH).collect();
+    if preview.len() < note.content.trim_end().len() {
+        preview.push_str("...");
+    }
+
+    let created_at = chrono::DateTime::parse_from_rfc3339(&note.created_at)
+        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or(note.created_at);
+
+    let id = Box::leak(format!("{}-{}", NOTES, note.id).into_boxed_str());
+
+    create_row(
+        ActionId::Builtin(id),
+        preview,
+        created_at,
+        NotesHandler::Copy(note.content),
+        90,
+        db,
+        text_secondary_color,
+    )
+}
+
+fn create_row(
+    id: ActionId,
+    name: String,
+    detail: String,
+    handler: NotesHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        id,
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        10,
+        db,
+    )
+}
+
+/// Extract the note text from queries like "note buy milk". Returns an empty
+/// string for a bare "note" or "notes" query.
+fn parse_note_query(query: &str) -> Option<&str> {
+    let query = query.trim_start();
+    let lowercase = query.to_lowercase();
+
+    if lowercase == "note" || lowercase == "notes" {
+        return Some("");
+    }
+
+    lowercase
+        .starts_with("note ")
+        .then(|| query["note ".len()..].trim())
+}
@@ -0,0 +1,195 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::OPEN_WITH;
+use crate::common::expand_tilde;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::mime;
+
+/// Queries starting with this list the applications opening the file
+pub const OPEN_WITH_PREFIX: &str = "open with ";
+
+/// Lists the applications whose desktop entry opens the type of a file with
+/// `open with <path>`, the default application first
+pub struct OpenWithHandlerFactory;
+
+impl HandlerFactory for OpenWithHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        OPEN_WITH
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(
+            OPEN_WITH_PREFIX,
+            "Choose the application to open a file with",
+        )]
+    }
+
+    fn claims_query(&self, query: &str) -> bool {
+        query.starts_with(OPEN_WITH_PREFIX)
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(path) = query.strip_prefix(OPEN_WITH_PREFIX) else {
+            return Vec::new();
+        };
+        let path = expand_tilde(path.trim());
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+
+        if !path.exists() {
+            return vec![create_row(
+                "open-with-missing",
+                "No such file".to_string(),
+                path.display().to_string(),
+                OpenWithHandler::None,
+                db,
+                text_secondary_color,
+            )];
+        }
+        let Some(mime_type) = mime::file_type(&path) else {
+            return vec![create_row(
+                "open-with-unknown",
+                "Unknown file type".to_string(),
+                path.display().to_string(),
+                OpenWithHandler::None,
+                db,
+                text_secondary_color,
+            )];
+        };
+
+        let mut applications = db.get_applications_for_type(&mime_type).unwrap_or_default();
+        if applications.is_empty() {
+            return vec![create_row(
+                "open-with-none",
+                "No application opens this file".to_string(),
+                mime_type,
+                OpenWithHandler::None,
+                db,
+                text_secondary_color,
+            )];
+        }
+        let default_app = mime::default_app(&mime_type);
+        if let Some(position) = applications
+            .iter()
+            .position(|app| Some(&app.filename) == default_app.as_ref())
+        {
+            let default = applications.remove(position);
+            applications.insert(0, default);
+        }
+
+        let count = applications.len();
+        applications
+            .into_iter()
+            .enumerate()
+            .map(|(index, app)| {
+                let is_default = Some(&app.filename) == default_app.as_ref();
+                let detail = if is_default {
+                    format!("Default for {}", mime_type)
+                } else {
+                    app.exec.clone()
+                };
+                let mut item = create_row(
+                    Box::leak(format!("{}-{}", OPEN_WITH, app.filename).into_boxed_str()),
+                    format!("Open with {}", app.name),
+                    detail,
+                    OpenWithHandler::Open {
+                        exec: app.exec,
+                        path: path.clone(),
+                    },
+                    db.clone(),
+                    text_secondary_color,
+                );
+                item.relevance = count - index;
+                item
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub enum OpenWithHandler {
+    Open {
+        /// `Exec` of the desktop entry, without field codes
+        exec: String,
+        path: PathBuf,
+    },
+    /// Informational rows
+    None,
+}
+
+impl OpenWithHandler {
+    fn command(&self) -> Option<Command> {
+        match self {
+            Self::Open { exec, path } => {
+                let mut parts = exec.split_whitespace();
+                let mut command = Command::new(parts.next()?);
+                command.args(parts).arg(path);
+                Some(command)
+            }
+            Self::None => None,
+        }
+    }
+}
+
+impl ActionHandler for OpenWithHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        if let Some(mut command) = self.command() {
+            command.spawn()?;
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        self.command()
+            .map(|command| CommandPreview::from_command(&command))
+    }
+}
+
+fn create_row(
+    id: &'static str,
+    name: String,
+    detail: String,
+    handler: OpenWithHandler,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        1,
+        1,
+        db,
+    )
+}
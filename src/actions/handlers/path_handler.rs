@@ -0,0 +1,242 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::PATHS;
+use crate::actions::handlers::open_with_handler::OPEN_WITH_PREFIX;
+use crate::common::expand_tilde;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::{copy_to_clipboard, terminal};
+
+/// Queries starting with one of these browse the filesystem
+const PATH_PREFIXES: &[&str] = &["/", "~/", "./"];
+
+/// Leaves room for the rows of the typed path in the list of ten
+const MAX_ENTRIES: usize = 7;
+
+/// Browses the filesystem when the query is a path, e.g. `~/src/cr`. Tab
+/// completes the selected entry, Enter opens it.
+pub struct PathHandlerFactory;
+
+impl HandlerFactory for PathHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        PATHS
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new("~/", "Browse files and directories")]
+    }
+
+    fn claims_query(&self, query: &str) -> bool {
+        PATH_PREFIXES.iter().any(|prefix| query.starts_with(prefix))
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        if !self.claims_query(query) {
+            return Vec::new();
+        }
+
+        let config = cx.global::<Config>();
+        let text_secondary_color = config.text_secondary_color;
+        let terminal = config.terminal.clone();
+
+        // `~/src/cr` lists the entries of `~/src/` starting with `cr`
+        let (dir_text, partial) = query.split_at(query.rfind('/').map_or(0, |i| i + 1));
+        let dir = absolute(&expand_tilde(dir_text));
+        let path = absolute(&expand_tilde(query));
+
+        let mut handlers: Vec<ActionItem> = entries(&dir, partial)
+            .into_iter()
+            .take(MAX_ENTRIES)
+            .map(|(name, is_dir)| {
+                let entry = dir.join(&name);
+                let name = if is_dir { format!("{}/", name) } else { name };
+                let detail = if is_dir { "Directory" } else { "File" };
+                let completion = format!("{}{}", dir_text, name);
+                create_row(
+                    Box::leak(format!("{}-open-{}", PATHS, entry.display()).into_boxed_str()),
+                    name,
+                    detail.to_string(),
+                    PathHandler::Open(entry),
+                    db.clone(),
+                    text_secondary_color,
+                )
+                .with_tab_completion(completion)
+            })
+            .collect();
+
+        let terminal_dir = if path.is_dir() { path.clone() } else { dir };
+        if terminal_dir.is_dir() {
+            handlers.push(create_row(
+                "paths-terminal",
+                "Open terminal here".to_string(),
+                terminal_dir.display().to_string(),
+                PathHandler::Terminal {
+                    directory: terminal_dir,
+                    terminal,
+                },
+                db.clone(),
+                text_secondary_color,
+            ));
+        }
+        if path.is_file() {
+            handlers.push(
+                create_row(
+                    "paths-open-with",
+                    "Open with...".to_string(),
+                    "Choose the application".to_string(),
+                    PathHandler::None,
+                    db.clone(),
+                    text_secondary_color,
+                )
+                .with_completion(format!("{}{}", OPEN_WITH_PREFIX, query)),
+            );
+        }
+        if path.exists() {
+            handlers.push(create_row(
+                "paths-copy",
+                "Copy path".to_string(),
+                path.display().to_string(),
+                PathHandler::Copy(path),
+                db,
+                text_secondary_color,
+            ));
+        }
+
+        // Keep the listed order, directories first
+        let count = handlers.len();
+        handlers
+            .into_iter()
+            .enumerate()
+            .map(|(index, mut item)| {
+                item.relevance = count - index;
+                item
+            })
+            .collect()
+    }
+}
+
+/// Names of the entries of `dir` starting with `partial`, ignoring case, and
+/// whether they are directories. Hidden entries are only listed when
+/// `partial` starts with a dot.
+fn entries(dir: &Path, partial: &str) -> Vec<(String, bool)> {
+    let partial = partial.to_lowercase();
+    let mut entries: Vec<(String, bool)> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            (name, entry.path().is_dir())
+        })
+        .filter(|(name, _)| partial.starts_with('.') || !name.starts_with('.'))
+        .filter(|(name, _)| name.to_lowercase().starts_with(&partial))
+        .collect();
+    entries.sort_by(|(a, a_is_dir), (b, b_is_dir)| {
+        b_is_dir
+            .cmp(a_is_dir)
+            .then_with(|| a.to_lowercase().cmp(&b.to_lowercase()))
+    });
+    entries
+}
+
+/// `./` paths are relative to the launcher's working directory
+fn absolute(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    env::current_dir()
+        .map(|dir| dir.join(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[derive(Clone)]
+pub enum PathHandler {
+    Open(PathBuf),
+    Terminal {
+        directory: PathBuf,
+        terminal: Option<String>,
+    },
+    Copy(PathBuf),
+    /// Rows completing the query
+    None,
+}
+
+impl ActionHandler for PathHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        match self {
+            Self::Open(path) => open::that(path)?,
+            Self::Terminal {
+                directory,
+                terminal,
+            } => {
+                terminal::shell_in(terminal.as_deref(), directory).spawn()?;
+            }
+            Self::Copy(path) => copy_to_clipboard(&path.to_string_lossy())?,
+            Self::None => {}
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        match self {
+            Self::Terminal {
+                directory,
+                terminal,
+            } => Some(CommandPreview::from_command(&terminal::shell_in(
+                terminal.as_deref(),
+                directory,
+            ))),
+            _ => None,
+        }
+    }
+}
+
+fn create_row(
+    id: &'static str,
+    name: String,
+    detail: String,
+    handler: PathHandler,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        0,
+        1,
+        db,
+    )
+}
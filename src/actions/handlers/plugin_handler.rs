@@ -0,0 +1,235 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::PLUGINS;
+use crate::activity;
+use crate::config::Config;
+use crate::database::{ActionHandlerModel, Database};
+use crate::plugins::{self, Plugin, PluginResult};
+use crate::system::copy_to_clipboard;
+
+/// Plugin id and query of a run
+type RunKey = (String, String);
+
+lazy_static::lazy_static! {
+    /// Plugin runs in progress
+    static ref RUNNING: Mutex<HashSet<RunKey>> = Mutex::new(HashSet::new());
+    /// Outcome of the last finished run. It's shown while its query stays
+    /// typed, also when the plugin doesn't cache results or its script
+    /// failed, so the script isn't run again and again.
+    static ref LAST_RUN: Mutex<Option<(RunKey, Result<Vec<PluginResult>, String>)>> =
+        Mutex::new(None);
+}
+
+/// Sends queries starting with a plugin's prefix to its script
+pub struct PluginHandlerFactory;
+
+impl HandlerFactory for PluginHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        PLUGINS
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        plugins::plugins()
+            .into_iter()
+            .map(|plugin| {
+                Keyword::new(format!("{} ", plugin.manifest.prefix), plugin.manifest.name)
+            })
+            .collect()
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some((plugin, plugin_query)) = plugins::match_query(query.trim_start()) else {
+            return Vec::new();
+        };
+
+        // Plugins share the handlers table with built-in handlers
+        let _ = ActionHandlerModel::insert(db.connection(), &plugin.id);
+        let enabled = ActionHandlerModel::get_active_handlers(db.connection())
+            .unwrap_or_default()
+            .contains(&plugin.id);
+        if !enabled {
+            return Vec::new();
+        }
+
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+
+        let outcome = match plugins::cached_results(&plugin, &plugin_query) {
+            Some(results) => Ok(results),
+            None => match last_run(&plugin, &plugin_query) {
+                Some(outcome) => outcome,
+                None => {
+                    start_run(&plugin, &plugin_query, cx);
+                    return vec![create_row(
+                        &plugin,
+                        0,
+                        PluginResult {
+                            name: format!("Running {}...", plugin.manifest.name),
+                            detail: plugin_query,
+                            command: None,
+                        },
+                        db,
+                        text_secondary_color,
+                    )
+                    .as_status()];
+                }
+            },
+        };
+        let results = match outcome {
+            Ok(results) => results,
+            Err(e) => {
+                return vec![create_row(
+                    &plugin,
+                    0,
+                    PluginResult {
+                        name: format!("{} failed", plugin.manifest.name),
+                        detail: e,
+                        command: None,
+                    },
+                    db,
+                    text_secondary_color,
+                )
+                .as_status()];
+            }
+        };
+
+        results
+            .into_iter()
+            .enumerate()
+            .map(|(index, result)| {
+                create_row(&plugin, index, result, db.clone(), text_secondary_color)
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub struct PluginHandler {
+    dir: PathBuf,
+    name: String,
+    command: Option<String>,
+}
+
+impl PluginHandler {
+    fn command(&self) -> Option<Command> {
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg(self.command.as_ref()?)
+            .current_dir(&self.dir);
+        Some(command)
+    }
+}
+
+impl ActionHandler for PluginHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        match self.command() {
+            Some(mut command) => {
+                command.spawn()?;
+            }
+            // Results without a command are informational, copy them instead
+            None => copy_to_clipboard(&self.name)?,
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        self.command()
+            .map(|command| CommandPreview::from_command(&command))
+    }
+}
+
+fn create_row(
+    plugin: &Plugin,
+    index: usize,
+    result: PluginResult,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    let id = Box::leak(format!("{}-{}", plugin.id, result.name).into_boxed_str());
+    let label = match &plugin.manifest.icon {
+        Some(icon) => format!("{} {}", icon, result.name),
+        None => result.name.clone(),
+    };
+    let detail = result.detail.clone();
+
+    ActionItem::new(
+        ActionId::Builtin(id),
+        result.name.clone(),
+        PluginHandler {
+            dir: plugin.dir.clone(),
+            name: result.name,
+            command: result.command,
+        },
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(label.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        // Keep the order the script printed its results in
+        100usize.saturating_sub(index),
+        20,
+        db,
+    )
+}
+
+/// Outcome of the last run if it was for this query
+fn last_run(plugin: &Plugin, query: &str) -> Option<Result<Vec<PluginResult>, String>> {
+    let last_run = LAST_RUN.lock().unwrap();
+    let (key, outcome) = last_run.as_ref()?;
+    (key.0 == plugin.id && key.1 == query).then(|| outcome.clone())
+}
+
+/// Run the plugin in the background and refresh the list once it's done
+fn start_run(plugin: &Plugin, query: &str, cx: &mut Context<ActionListView>) {
+    // Runs once the window is shown again and the results refresh
+    if !activity::is_active() {
+        return;
+    }
+    let key = (plugin.id.clone(), query.to_string());
+    if !RUNNING.lock().unwrap().insert(key.clone()) {
+        return;
+    }
+
+    let plugin = plugin.clone();
+    let query = query.to_string();
+    cx.spawn(|view, mut cx| async move {
+        // Errors are also recorded by the plugin system and shown in `:handlers`
+        let outcome = cx
+            .background_executor()
+            .spawn(async move { plugins::run(&plugin, &query) })
+            .await
+            .map_err(|e| format!("{:#}", e));
+
+        RUNNING.lock().unwrap().remove(&key);
+        *LAST_RUN.lock().unwrap() = Some((key, outcome));
+        let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+    })
+    .detach();
+}
@@ -0,0 +1,197 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::env;
+use std::process::Command;
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::POWER;
+use crate::config::Config;
+use crate::database::Database;
+
+/// Queries shorter than this don't match, so typing a single letter doesn't
+/// offer to shut down the machine
+const MIN_QUERY_LEN: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerAction {
+    Shutdown,
+    Reboot,
+    Suspend,
+    Hibernate,
+    Lock,
+    Logout,
+}
+
+impl PowerAction {
+    const ALL: [PowerAction; 6] = [
+        Self::Shutdown,
+        Self::Reboot,
+        Self::Suspend,
+        Self::Hibernate,
+        Self::Lock,
+        Self::Logout,
+    ];
+
+    /// Name used in the `power_confirm` config option
+    fn id(self) -> &'static str {
+        match self {
+            Self::Shutdown => "shutdown",
+            Self::Reboot => "reboot",
+            Self::Suspend => "suspend",
+            Self::Hibernate => "hibernate",
+            Self::Lock => "lock",
+            Self::Logout => "logout",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Shutdown => "Shut Down",
+            Self::Reboot => "Reboot",
+            Self::Suspend => "Suspend",
+            Self::Hibernate => "Hibernate",
+            Self::Lock => "Lock Screen",
+            Self::Logout => "Log Out",
+        }
+    }
+
+    fn keywords(self) -> &'static [&'static str] {
+        match self {
+            Self::Shutdown => &["shutdown", "shut down", "power off", "poweroff"],
+            Self::Reboot => &["reboot", "restart"],
+            Self::Suspend => &["suspend", "sleep"],
+            Self::Hibernate => &["hibernate"],
+            Self::Lock => &["lock", "lock screen"],
+            Self::Logout => &["logout", "log out", "sign out"],
+        }
+    }
+
+    fn command(self) -> Command {
+        let (program, args): (&str, Vec<String>) = match self {
+            Self::Shutdown => ("systemctl", vec!["poweroff".into()]),
+            Self::Reboot => ("systemctl", vec!["reboot".into()]),
+            Self::Suspend => ("systemctl", vec!["suspend".into()]),
+            Self::Hibernate => ("systemctl", vec!["hibernate".into()]),
+            Self::Lock => ("loginctl", vec!["lock-session".into()]),
+            // Without a session id, end every session of the user
+            Self::Logout => match env::var("XDG_SESSION_ID") {
+                Ok(session) => ("loginctl", vec!["terminate-session".into(), session]),
+                Err(_) => (
+                    "loginctl",
+                    vec![
+                        "terminate-user".into(),
+                        env::var("USER").unwrap_or_default(),
+                    ],
+                ),
+            },
+        };
+
+        let mut command = Command::new(program);
+        command.args(args);
+        command
+    }
+
+    fn matches(self, query: &str) -> bool {
+        self.keywords()
+            .iter()
+            .any(|keyword| keyword.starts_with(query))
+    }
+}
+
+/// Shutdown, reboot, suspend, hibernate, lock screen and logout
+pub struct PowerHandlerFactory;
+
+impl HandlerFactory for PowerHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        POWER
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        PowerAction::ALL
+            .into_iter()
+            .map(|action| Keyword::new(action.keywords()[0], action.name()))
+            .collect()
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let query = query.trim().to_lowercase();
+        if query.len() < MIN_QUERY_LEN {
+            return Vec::new();
+        }
+
+        let config = cx.global::<Config>();
+        let text_secondary_color = config.text_secondary_color;
+
+        PowerAction::ALL
+            .into_iter()
+            .filter(|action| action.matches(&query))
+            .map(|action| {
+                let needs_confirmation = config.power_confirm.iter().any(|id| id == action.id());
+                create_row(action, db.clone(), text_secondary_color)
+                    .with_confirmation(needs_confirmation)
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub struct PowerHandler {
+    action: PowerAction,
+}
+
+impl ActionHandler for PowerHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        self.action.command().spawn()?;
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        Some(CommandPreview::from_command(&self.action.command()))
+    }
+}
+
+fn create_row(
+    action: PowerAction,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    let id = Box::leak(format!("{}-{}", POWER, action.id()).into_boxed_str());
+    let (relevance, _) = db.get_action_relevance(id).unwrap_or((0, 0));
+    let name = action.name().to_string();
+
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        PowerHandler { action },
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .child("Power")
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        // Power actions are only offered for matching keywords, rank them high
+        relevance + 1000,
+        10,
+        db,
+    )
+}
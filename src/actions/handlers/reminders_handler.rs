@@ -0,0 +1,139 @@
+use anyhow::Result;
+use chrono::Local;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{ActionHandler, ActionId, ActionItem, HandlerFactory};
+use crate::actions::action_ids::REMINDERS;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::{copy_to_clipboard, Calendar};
+
+/// Maximum number of events and todos shown, each
+const MAX_REMINDERS: usize = 3;
+
+/// Keeps reminders above the popular actions on the empty-query screen
+const REMINDER_RELEVANCE: usize = usize::MAX / 2;
+
+const REMINDERS_SECTION: &str = "Today";
+
+/// Shows today's next calendar events and due todos when the query is empty
+pub struct RemindersHandlerFactory;
+
+impl HandlerFactory for RemindersHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        REMINDERS
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let config = cx.global::<Config>();
+        if !query.trim().is_empty() || config.calendar_paths.is_empty() {
+            return Vec::new();
+        }
+
+        let text_secondary_color = config.text_secondary_color;
+        let calendar = Calendar::load(&config.calendar_paths);
+        let today = Local::now().date_naive();
+        let mut rows = Vec::new();
+
+        if config.reminders_show_events {
+            for event in calendar.upcoming_today().into_iter().take(MAX_REMINDERS) {
+                let mut detail = if event.all_day {
+                    "Today".to_string()
+                } else {
+                    format!("Today {}", event.start.format("%H:%M"))
+                };
+                if let Some(location) = &event.location {
+                    detail.push_str(&format!(" · {}", location));
+                }
+                rows.push((event.summary.clone(), detail));
+            }
+        }
+
+        if config.reminders_show_todos {
+            for todo in calendar.due_todos().into_iter().take(MAX_REMINDERS) {
+                let detail = match todo.due {
+                    Some(due) if due.date_naive() < today => {
+                        format!("Overdue since {}", due.format("%Y-%m-%d"))
+                    }
+                    _ => "Due today".to_string(),
+                };
+                rows.push((todo.summary.clone(), detail));
+            }
+        }
+
+        rows.into_iter()
+            .enumerate()
+            .map(|(index, (name, detail))| {
+                let id = Box::leak(format!("{}-{}", REMINDERS, index).into_boxed_str());
+                create_row(
+                    ActionId::Builtin(id),
+                    name,
+                    detail,
+                    REMINDER_RELEVANCE - index,
+                    db.clone(),
+                    text_secondary_color,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Copies the reminder to the clipboard
+#[derive(Clone)]
+pub struct RemindersHandler {
+    text: String,
+}
+
+impl ActionHandler for RemindersHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        copy_to_clipboard(&self.text)
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+fn create_row(
+    id: ActionId,
+    name: String,
+    detail: String,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    let handler = RemindersHandler {
+        text: format!("{} ({})", name, detail),
+    };
+
+    ActionItem::new(
+        id,
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        1,
+        db,
+    )
+    .with_section(REMINDERS_SECTION)
+}
@@ -0,0 +1,205 @@
+use anyhow::{anyhow, Result};
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory,
+};
+use crate::actions::action_ids::REMOTE;
+use crate::config::{Config, RemoteProfile};
+use crate::database::Database;
+use crate::remote::{self, ScanState};
+
+const RELEVANCE_BOOST: usize = 30;
+const MAX_RESULTS: usize = 8;
+
+/// Lists and runs binaries of the active remote profile
+pub struct RemoteHandlerFactory;
+
+impl HandlerFactory for RemoteHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        REMOTE
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(profile) = remote::active_profile(&db) else {
+            return Vec::new();
+        };
+
+        let query = query.trim();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+
+        if !db.has_remote_binaries(&profile.host) {
+            let (name, detail) = match start_scan(&profile, cx) {
+                ScanState::Running => (
+                    format!("Scanning {}...", profile.name),
+                    profile.host.clone(),
+                ),
+                ScanState::Failed(e) => (format!("Scan of {} failed", profile.name), e),
+            };
+
+            return vec![create_row(
+                ActionId::Builtin(REMOTE),
+                name,
+                detail,
+                RemoteHandler::Rescan(profile),
+                100,
+                db,
+                text_secondary_color,
+            )];
+        }
+
+        // The first word selects the binary, the rest is passed as arguments
+        let (program, args) = query.split_once(' ').unwrap_or((query, ""));
+        let binaries = db
+            .search_remote_binaries(&profile.host, program, MAX_RESULTS)
+            .unwrap_or_default();
+
+        let mut handlers: Vec<ActionItem> = binaries
+            .into_iter()
+            .map(|binary| {
+                let relevance = if binary.name == program {
+                    100
+                } else if binary.name.starts_with(program) {
+                    50
+                } else {
+                    10
+                };
+                let command = format!("{} {}", binary.path, args).trim_end().to_string();
+                let id = Box::leak(
+                    format!("{}-{}-{}", REMOTE, profile.name, binary.name).into_boxed_str(),
+                );
+
+                create_row(
+                    ActionId::Builtin(id),
+                    binary.name,
+                    format!("{} on {}", command, profile.name),
+                    RemoteHandler::Run(profile.clone(), command),
+                    relevance,
+                    db.clone(),
+                    text_secondary_color,
+                )
+            })
+            .collect();
+
+        handlers.push(create_row(
+            ActionId::Builtin(REMOTE),
+            format!("Run on {}", profile.name),
+            query.to_string(),
+            RemoteHandler::Run(profile.clone(), query.to_string()),
+            5,
+            db,
+            text_secondary_color,
+        ));
+
+        handlers
+    }
+}
+
+#[derive(Clone)]
+pub enum RemoteHandler {
+    /// Run a command line on the remote machine
+    Run(RemoteProfile, String),
+    /// Drop the cached binary list so the next query scans again
+    Rescan(RemoteProfile),
+}
+
+impl ActionHandler for RemoteHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        match self {
+            Self::Run(profile, command) => remote::run(profile, command),
+            Self::Rescan(profile) => {
+                if let Some(ScanState::Running) = remote::scan_state(&profile.host) {
+                    return Err(anyhow!("Scan of {} is still running", profile.name));
+                }
+                remote::set_scan_state(&profile.host, None);
+                Database::new()?.clear_remote_binaries(&profile.host)
+            }
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        match self {
+            Self::Run(profile, command) => Some(CommandPreview::from_command(
+                &remote::ssh_command(profile, command),
+            )),
+            Self::Rescan(_) => None,
+        }
+    }
+}
+
+fn create_row(
+    id: ActionId,
+    name: String,
+    detail: String,
+    handler: RemoteHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        id,
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        RELEVANCE_BOOST,
+        db,
+    )
+}
+
+/// Returns the state of the profile's scan, starting one in the background if needed
+fn start_scan(profile: &RemoteProfile, cx: &mut Context<ActionListView>) -> ScanState {
+    if let Some(state) = remote::scan_state(&profile.host) {
+        return state;
+    }
+    remote::set_scan_state(&profile.host, Some(ScanState::Running));
+
+    let profile = profile.clone();
+    cx.spawn(|view, mut cx| async move {
+        let host = profile.host.clone();
+        let result = cx
+            .background_executor()
+            .spawn(async move {
+                let binaries = remote::scan(&profile)?;
+                Database::new()?.store_remote_binaries(&profile.host, &binaries)
+            })
+            .await;
+
+        match result {
+            Ok(()) => remote::set_scan_state(&host, None),
+            Err(e) => remote::set_scan_state(&host, Some(ScanState::Failed(e.to_string()))),
+        }
+        let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+    })
+    .detach();
+
+    ScanState::Running
+}
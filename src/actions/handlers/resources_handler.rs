@@ -0,0 +1,230 @@
+use anyhow::{anyhow, Result};
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::RESOURCES;
+use crate::common::expand_tilde;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::{resources, terminal};
+
+/// Queries that show system resources
+const RESOURCES_KEYWORDS: &[&str] = &["sys", "system", "resources"];
+
+/// How often the rows are updated while they're shown
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Set while an update of the rows is scheduled
+static REFRESH_SCHEDULED: AtomicBool = AtomicBool::new(false);
+
+const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+/// Shows CPU, memory, disk and battery usage with `sys`, updated every
+/// second. Enter opens `system_monitor`.
+pub struct ResourcesHandlerFactory;
+
+impl HandlerFactory for ResourcesHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        RESOURCES
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(
+            "sys",
+            "Show CPU, memory, disk and battery usage",
+        )]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let query = query.trim().to_lowercase();
+        if !RESOURCES_KEYWORDS.contains(&query.as_str()) {
+            return Vec::new();
+        }
+
+        let config = cx.global::<Config>();
+        let text_secondary_color = config.text_secondary_color;
+        let handler = ResourcesHandler {
+            system_monitor: config.system_monitor.clone(),
+            terminal: config.terminal.clone(),
+        };
+
+        let mut rows = Vec::new();
+        if let Some(usage) = resources::cpu_usage() {
+            let detail = resources::cpu_load()
+                .map(|(cpus, [one, five, fifteen])| {
+                    format!("{} CPUs · load {:.2} {:.2} {:.2}", cpus, one, five, fifteen)
+                })
+                .unwrap_or_default();
+            rows.push(("resources-cpu", format!("CPU {:.0}%", usage), detail));
+        }
+        if let Some(memory) = resources::memory() {
+            rows.push((
+                "resources-memory",
+                format!(
+                    "Memory {:.1} / {:.1} GiB",
+                    memory.used as f64 / GIB,
+                    memory.total as f64 / GIB
+                ),
+                format!("{:.0}% used", memory.percent()),
+            ));
+        }
+        for (id, path) in [("resources-disk-root", "/"), ("resources-disk-home", "~")] {
+            let path = expand_tilde(path);
+            if id == "resources-disk-home" && same_file_system(Path::new("/"), &path) {
+                continue;
+            }
+            if let Some(disk) = resources::disk(&path) {
+                rows.push((
+                    id,
+                    format!(
+                        "Disk {} {:.0} / {:.0} GiB",
+                        path.display(),
+                        disk.used as f64 / GIB,
+                        disk.total as f64 / GIB
+                    ),
+                    format!("{:.0}% used", disk.percent()),
+                ));
+            }
+        }
+        for battery in resources::batteries() {
+            let id = Box::leak(format!("resources-battery-{}", battery.name).into_boxed_str());
+            rows.push((
+                id,
+                format!("Battery {}%", battery.percentage),
+                format!("{} · {}", battery.name, battery.status),
+            ));
+        }
+
+        schedule_refresh(cx);
+
+        rows.into_iter()
+            .enumerate()
+            .map(|(index, (id, name, detail))| {
+                create_row(
+                    id,
+                    name,
+                    detail,
+                    handler.clone(),
+                    100usize.saturating_sub(index),
+                    db.clone(),
+                    text_secondary_color,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Opens the system monitor
+#[derive(Clone)]
+pub struct ResourcesHandler {
+    system_monitor: Option<String>,
+    terminal: Option<String>,
+}
+
+impl ResourcesHandler {
+    /// `system_monitor`, or `top` in a terminal
+    fn command(&self) -> Result<Command> {
+        match &self.system_monitor {
+            Some(monitor) => {
+                let args = shlex::split(monitor)
+                    .ok_or_else(|| anyhow!("Invalid system_monitor: {}", monitor))?;
+                let (program, args) = args
+                    .split_first()
+                    .ok_or_else(|| anyhow!("system_monitor is empty"))?;
+                let mut command = Command::new(program);
+                command.args(args);
+                Ok(command)
+            }
+            None => Ok(terminal::command(
+                self.terminal.as_deref(),
+                &["top".to_string()],
+            )),
+        }
+    }
+}
+
+impl ActionHandler for ResourcesHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        self.command()?.spawn()?;
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        self.command()
+            .ok()
+            .map(|command| CommandPreview::from_command(&command))
+    }
+}
+
+fn create_row(
+    id: &'static str,
+    name: String,
+    detail: String,
+    handler: ResourcesHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        10,
+        db,
+    )
+}
+
+/// Update the rows in a second. Updating runs the query again, which
+/// schedules the next update as long as the rows are shown.
+fn schedule_refresh(cx: &mut Context<ActionListView>) {
+    if REFRESH_SCHEDULED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    cx.spawn(|view, mut cx| async move {
+        cx.background_executor().timer(REFRESH_INTERVAL).await;
+        REFRESH_SCHEDULED.store(false, Ordering::Relaxed);
+        let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+    })
+    .detach();
+}
+
+/// Home usually is on the root file system, listing it twice isn't useful
+fn same_file_system(a: &Path, b: &Path) -> bool {
+    match (a.metadata(), b.metadata()) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev(),
+        _ => true,
+    }
+}
@@ -0,0 +1,137 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, HandlerFactory, Keyword, WEB_SEARCH_GROUP,
+};
+use crate::config::{Config, SearchEngine};
+use crate::database::Database;
+
+/// A search engine from the config. Every engine is a handler of its own, so
+/// it can be disabled like the built-in ones.
+pub struct SearchEngineHandlerFactory {
+    id: &'static str,
+    engine: SearchEngine,
+}
+
+impl SearchEngineHandlerFactory {
+    pub fn new(engine: SearchEngine) -> Self {
+        Self {
+            id: Box::leak(format!("search-{}", engine.keyword).into_boxed_str()),
+            engine,
+        }
+    }
+}
+
+impl HandlerFactory for SearchEngineHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        self.id
+    }
+
+    fn group(&self) -> &'static str {
+        WEB_SEARCH_GROUP
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(
+            format!("{} ", self.engine.keyword),
+            format!("Search {}", self.engine.name),
+        )]
+    }
+
+    fn bangs(&self) -> Vec<String> {
+        vec![self.engine.keyword.clone()]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let handler = SearchEngineHandler {
+            engine: self.engine.clone(),
+        };
+
+        // `kagi rust gpui` only searches Kagi, with the rest of the query
+        if let Some(terms) = handler.strip_keyword(query) {
+            if terms.is_empty() {
+                return Vec::new();
+            }
+            let name = format!("Search {} for {}", self.engine.name, terms);
+            return vec![handler.create_action(self.id, name, 100, 10, db, cx)];
+        }
+
+        let (relevance, _) = db.get_action_relevance(self.id).unwrap_or_default();
+        let name = format!("{} Search", self.engine.name);
+        vec![handler.create_action(self.id, name, relevance, 1, db, cx)]
+    }
+}
+
+#[derive(Clone)]
+pub struct SearchEngineHandler {
+    engine: SearchEngine,
+}
+
+impl SearchEngineHandler {
+    /// The search terms of a query starting with the engine's keyword
+    fn strip_keyword<'a>(&self, query: &'a str) -> Option<&'a str> {
+        query
+            .trim_start()
+            .strip_prefix(self.engine.keyword.as_str())?
+            .strip_prefix(' ')
+            .map(str::trim)
+    }
+
+    fn create_action(
+        &self,
+        id: &'static str,
+        name: String,
+        relevance: usize,
+        boost: usize,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> ActionItem {
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+        let label = match &self.engine.icon {
+            Some(icon) => format!("{} {}", icon, name),
+            None => name.clone(),
+        };
+
+        ActionItem::new(
+            ActionId::Builtin(id),
+            name,
+            self.clone(),
+            move || {
+                div()
+                    .flex()
+                    .gap_4()
+                    .child(div().flex_none().child(label.clone()))
+                    .child(
+                        div()
+                            .flex_grow()
+                            .child("Search Engine")
+                            .text_color(text_secondary_color),
+                    )
+                    .into_any()
+            },
+            relevance,
+            boost,
+            db,
+        )
+    }
+}
+
+impl ActionHandler for SearchEngineHandler {
+    fn execute(&self, input: &str) -> Result<()> {
+        let terms = self.strip_keyword(input).unwrap_or(input);
+        open::that(self.engine.search_url(terms))?;
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
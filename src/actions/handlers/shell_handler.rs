@@ -0,0 +1,212 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::SHELL;
+use crate::actions::score::{Score, RELEVANCE_BOOST};
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::{notifications, terminal};
+
+/// Queries starting with this run the rest with `sh -c`
+const SHELL_PREFIX: &str = ">";
+
+/// Frecency of a command that was never run, so it ranks like an app
+/// launched once
+const NEW_COMMAND_FRECENCY: f64 = 1.0;
+
+/// Runs shell commands with `> <command>`, in the background or in a terminal
+pub struct ShellHandlerFactory;
+
+impl HandlerFactory for ShellHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        SHELL
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(
+            format!("{} ", SHELL_PREFIX),
+            "Run a shell command",
+        )]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(command) = query.trim_start().strip_prefix(SHELL_PREFIX) else {
+            return Vec::new();
+        };
+        let command = command.trim();
+
+        let config = cx.global::<Config>();
+        let text_secondary_color = config.text_secondary_color;
+        let terminal = config.terminal.clone();
+        let notify_after = config.notify_after_secs.map(Duration::from_secs);
+
+        if command.is_empty() {
+            return vec![create_row(
+                "shell-help",
+                "Type a command to run".to_string(),
+                "sh -c".to_string(),
+                ShellHandler::None,
+                10,
+                db,
+                text_secondary_color,
+            )];
+        }
+
+        let actions = [
+            (
+                "run",
+                format!("Run {}", command),
+                ShellHandler::Run {
+                    command: command.to_string(),
+                    notify_after,
+                },
+            ),
+            (
+                "terminal",
+                format!("Run {} in terminal", command),
+                ShellHandler::Terminal {
+                    command: command.to_string(),
+                    terminal,
+                },
+            ),
+        ];
+
+        actions
+            .into_iter()
+            .enumerate()
+            .map(|(index, (action, name, handler))| {
+                // Every command is an action of its own, so the way a command
+                // is usually run comes first
+                let id = Box::leak(format!("{}-{}-{}", SHELL, action, command).into_boxed_str());
+                let frecency = db.get_action_frecency(id).unwrap_or(0.0);
+                let relevance =
+                    Score::new(frecency + NEW_COMMAND_FRECENCY, 100.0, 0.0).relevance() - index;
+                let detail = handler
+                    .command()
+                    .map(|command| CommandPreview::from_command(&command).command_line)
+                    .unwrap_or_default();
+                create_row(
+                    id,
+                    name,
+                    detail,
+                    handler,
+                    relevance,
+                    db.clone(),
+                    text_secondary_color,
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub enum ShellHandler {
+    Run {
+        command: String,
+        /// Notify when the command finishes after running this long
+        notify_after: Option<Duration>,
+    },
+    Terminal {
+        command: String,
+        terminal: Option<String>,
+    },
+    /// Informational rows
+    None,
+}
+
+impl ShellHandler {
+    /// The command as typed, shown on the row
+    fn command(&self) -> Option<Command> {
+        match self {
+            Self::Run { command, .. } => {
+                let mut shell = Command::new("sh");
+                shell.arg("-c").arg(command);
+                Some(shell)
+            }
+            // The shell keeps the terminal open to read the output
+            Self::Terminal { command, terminal } => Some(terminal::command(
+                terminal.as_deref(),
+                &[
+                    "sh".to_string(),
+                    "-c".to_string(),
+                    format!("{}; exec \"${{SHELL:-sh}}\"", command),
+                ],
+            )),
+            Self::None => None,
+        }
+    }
+
+    /// The command wrapped to notify when it finishes, if it should
+    fn spawned_command(&self) -> Option<Command> {
+        match self {
+            Self::Run {
+                command,
+                notify_after: Some(after),
+            } => Some(notifications::notify_when_done(command, *after)),
+            _ => self.command(),
+        }
+    }
+}
+
+impl ActionHandler for ShellHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        if let Some(mut command) = self.spawned_command() {
+            command.spawn()?;
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        self.spawned_command()
+            .map(|command| CommandPreview::from_command(&command))
+    }
+}
+
+fn create_row(
+    id: &'static str,
+    name: String,
+    detail: String,
+    handler: ShellHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        RELEVANCE_BOOST,
+        db,
+    )
+}
@@ -0,0 +1,202 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::process::Command;
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory,
+};
+use crate::actions::action_ids::SHORTCUTS;
+use crate::actions::score::{self, Score, RELEVANCE_BOOST};
+use crate::config::{Config, Shortcut};
+use crate::database::Database;
+use crate::system::terminal;
+
+/// Frecency of a shortcut that was never run, so it ranks like an app
+/// launched once
+const NEW_SHORTCUT_FRECENCY: f64 = 1.0;
+
+/// Launchers of the `[[shortcuts]]` config, found by their name or keywords
+/// and ranked by how often they're run
+pub struct ShortcutHandlerFactory;
+
+impl HandlerFactory for ShortcutHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        SHORTCUTS
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let filter = query.trim().to_lowercase();
+        if filter.is_empty() {
+            return Vec::new();
+        }
+        let config = cx.global::<Config>();
+        let text_secondary_color = config.text_secondary_color;
+        let terminal = config.terminal.clone();
+        let filter_tokens: Vec<&str> = filter.split_whitespace().collect();
+
+        config
+            .shortcuts
+            .iter()
+            .filter_map(|shortcut| {
+                let handler = ShortcutHandler::new(shortcut, terminal.clone())?;
+                // The best matching of the name and the keywords counts
+                let match_quality = std::iter::once(&shortcut.name)
+                    .chain(&shortcut.keywords)
+                    .map(|text| score::match_quality(&filter, text))
+                    .fold(1.0, f64::max);
+                let searchable = format!("{} {}", shortcut.name, shortcut.keywords.join(" "));
+                let search_score = score::search_score(&filter_tokens, &searchable);
+                if match_quality <= 1.0 && search_score == 0.0 {
+                    return None;
+                }
+
+                let id = Box::leak(format!("{}-{}", SHORTCUTS, shortcut.name).into_boxed_str());
+                let frecency = db.get_action_frecency(id).unwrap_or(0.0);
+                let score = Score::new(
+                    frecency + NEW_SHORTCUT_FRECENCY,
+                    match_quality,
+                    search_score,
+                );
+                Some(
+                    create_row(
+                        id,
+                        shortcut.name.clone(),
+                        handler,
+                        score.relevance(),
+                        db.clone(),
+                        text_secondary_color,
+                    )
+                    .with_score(Some(score)),
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub enum ShortcutHandler {
+    Run(String),
+    Terminal {
+        command: String,
+        terminal: Option<String>,
+    },
+    Url(String),
+}
+
+impl ShortcutHandler {
+    /// `None` for shortcuts with neither a command nor a URL
+    fn new(shortcut: &Shortcut, terminal: Option<String>) -> Option<Self> {
+        match (&shortcut.command, &shortcut.url) {
+            (Some(command), _) if shortcut.terminal => Some(Self::Terminal {
+                command: command.clone(),
+                terminal,
+            }),
+            (Some(command), _) => Some(Self::Run(command.clone())),
+            (None, Some(url)) => Some(Self::Url(url.clone())),
+            (None, None) => None,
+        }
+    }
+
+    fn command(&self) -> Option<Command> {
+        match self {
+            Self::Run(command) => {
+                let mut shell = Command::new("sh");
+                shell.arg("-c").arg(command);
+                Some(shell)
+            }
+            // The shell keeps the terminal open to read the output
+            Self::Terminal { command, terminal } => Some(terminal::command(
+                terminal.as_deref(),
+                &[
+                    "sh".to_string(),
+                    "-c".to_string(),
+                    format!("{}; exec \"${{SHELL:-sh}}\"", command),
+                ],
+            )),
+            Self::Url(_) => None,
+        }
+    }
+
+    /// The command or URL, shown on the row
+    fn detail(&self) -> String {
+        match self {
+            Self::Url(url) => url.clone(),
+            _ => self
+                .command()
+                .map(|command| CommandPreview::from_command(&command).command_line)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl ActionHandler for ShortcutHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        match self {
+            Self::Url(url) => open::that(url.as_str())?,
+            _ => {
+                if let Some(mut command) = self.command() {
+                    command.spawn()?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        self.command()
+            .map(|command| CommandPreview::from_command(&command))
+    }
+}
+
+fn create_row(
+    id: &'static str,
+    name: String,
+    handler: ShortcutHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    let detail = handler.detail();
+    let url = match &handler {
+        ShortcutHandler::Url(url) => Some(url.clone()),
+        _ => None,
+    };
+
+    let item = ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        RELEVANCE_BOOST,
+        db,
+    );
+    match url {
+        Some(url) => item.with_url(url),
+        None => item,
+    }
+}
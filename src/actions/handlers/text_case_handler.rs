@@ -0,0 +1,220 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::TEXT_CASE;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::copy_to_clipboard;
+
+/// A change of case applied to the text of a query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Case {
+    Upper,
+    Lower,
+    Title,
+    Camel,
+    Snake,
+    Slug,
+}
+
+impl Case {
+    const ALL: [Case; 6] = [
+        Case::Upper,
+        Case::Lower,
+        Case::Title,
+        Case::Camel,
+        Case::Snake,
+        Case::Slug,
+    ];
+
+    fn keyword(self) -> &'static str {
+        match self {
+            Self::Upper => "upper",
+            Self::Lower => "lower",
+            Self::Title => "title",
+            Self::Camel => "camel",
+            Self::Snake => "snake",
+            Self::Slug => "slugify",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Upper => "UPPER CASE",
+            Self::Lower => "lower case",
+            Self::Title => "Title Case",
+            Self::Camel => "camelCase",
+            Self::Snake => "snake_case",
+            Self::Slug => "url-slug",
+        }
+    }
+
+    /// Split a query like `snake Hello World` into the case and its text
+    fn from_query(query: &str) -> Option<(Self, &str)> {
+        let (word, text) = query.trim_start().split_once(' ')?;
+        let case = Self::ALL
+            .into_iter()
+            .find(|case| case.keyword().eq_ignore_ascii_case(word))?;
+        Some((case, text.trim()))
+    }
+
+    fn apply(self, text: &str) -> String {
+        match self {
+            Self::Upper => text.to_uppercase(),
+            Self::Lower => text.to_lowercase(),
+            Self::Title => text
+                .split(' ')
+                .map(capitalize)
+                .collect::<Vec<_>>()
+                .join(" "),
+            Self::Camel => words(text)
+                .iter()
+                .enumerate()
+                .map(|(index, word)| match index {
+                    0 => word.to_lowercase(),
+                    _ => capitalize(word),
+                })
+                .collect(),
+            Self::Snake => words(text)
+                .iter()
+                .map(|word| word.to_lowercase())
+                .collect::<Vec<_>>()
+                .join("_"),
+            Self::Slug => words(text)
+                .iter()
+                .map(|word| word.to_lowercase())
+                .collect::<Vec<_>>()
+                .join("-"),
+        }
+    }
+}
+
+/// Changes the case of text with e.g. `snake Hello World` or `slugify My
+/// Post`, copying the result
+pub struct TextCaseHandlerFactory;
+
+impl HandlerFactory for TextCaseHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        TEXT_CASE
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        Case::ALL
+            .into_iter()
+            .map(|case| {
+                Keyword::new(
+                    format!("{} ", case.keyword()),
+                    format!("Convert the text to {}", case.description()),
+                )
+            })
+            .collect()
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some((case, text)) = Case::from_query(query) else {
+            return Vec::new();
+        };
+        let value = case.apply(text);
+        if value.is_empty() {
+            return Vec::new();
+        }
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+        let detail = case.description().to_string();
+        let name = value.clone();
+
+        vec![ActionItem::new(
+            ActionId::Builtin(TEXT_CASE),
+            name.clone(),
+            TextCaseHandler { value },
+            move || {
+                div()
+                    .flex()
+                    .gap_4()
+                    .child(div().flex_none().child(name.clone()))
+                    .child(
+                        div()
+                            .flex_grow()
+                            .overflow_hidden()
+                            .child(detail.clone())
+                            .text_color(text_secondary_color),
+                    )
+                    .into_any()
+            },
+            100,
+            10,
+            db,
+        )]
+    }
+}
+
+/// Copies the converted text to the clipboard
+#[derive(Clone)]
+pub struct TextCaseHandler {
+    value: String,
+}
+
+impl ActionHandler for TextCaseHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        copy_to_clipboard(&self.value)
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+/// First letter upper case, the rest lower case
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// Words of text in any case: `fooBar`, `foo_bar`, `Foo Bar` and `XMLParser`
+/// split into two words each. Other characters than letters and digits only
+/// separate words.
+fn words(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+
+        let previous = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1);
+        let starts_word = c.is_uppercase()
+            && previous.is_some_and(|previous| {
+                previous.is_lowercase()
+                    || (previous.is_uppercase() && next.is_some_and(|next| next.is_lowercase()))
+            });
+        if starts_word && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
@@ -0,0 +1,192 @@
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{ActionHandler, ActionId, ActionItem, HandlerFactory};
+use crate::actions::action_ids::TIMESTAMP;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::copy_to_clipboard;
+
+/// Numbers shorter than this are not treated as timestamps (avoids matching e.g. "2024")
+const MIN_TIMESTAMP_DIGITS: usize = 9;
+
+/// Values above this are interpreted as milliseconds (year 5138 in seconds)
+const MILLISECOND_THRESHOLD: i64 = 100_000_000_000;
+
+/// Suffixes that request a conversion to a Unix timestamp
+const TO_EPOCH_SUFFIXES: &[&str] = &["to epoch", "to unix", "to timestamp"];
+
+/// Date formats accepted in queries, interpreted as local time
+const DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+];
+
+pub struct TimestampHandlerFactory;
+
+impl HandlerFactory for TimestampHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        TIMESTAMP
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some((time, is_millis)) = parse_query(query) else {
+            return Vec::new();
+        };
+
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+        let local = time.with_timezone(&Local);
+
+        let conversions = [
+            (
+                "timestamp-unix-seconds",
+                "Unix timestamp (s)",
+                time.timestamp().to_string(),
+            ),
+            (
+                "timestamp-unix-millis",
+                "Unix timestamp (ms)",
+                time.timestamp_millis().to_string(),
+            ),
+            (
+                "timestamp-iso8601",
+                "ISO 8601",
+                time.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true),
+            ),
+            (
+                "timestamp-local",
+                "Local time",
+                local.format("%Y-%m-%d %H:%M:%S %Z").to_string(),
+            ),
+            (
+                "timestamp-utc",
+                "UTC time",
+                time.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            ),
+        ];
+
+        conversions
+            .into_iter()
+            .enumerate()
+            .map(|(index, (id, label, value))| {
+                let detail = if is_millis && index == 0 {
+                    format!("{} · input detected as milliseconds", label)
+                } else {
+                    label.to_string()
+                };
+
+                TimestampHandler { value }.create_action(
+                    ActionId::Builtin(id),
+                    detail,
+                    100 - index,
+                    db.clone(),
+                    text_secondary_color,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Copies a converted value to the clipboard
+#[derive(Clone)]
+pub struct TimestampHandler {
+    value: String,
+}
+
+impl ActionHandler for TimestampHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        copy_to_clipboard(&self.value)
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+impl TimestampHandler {
+    fn create_action(
+        &self,
+        id: ActionId,
+        detail: String,
+        relevance: usize,
+        db: Arc<Database>,
+        text_secondary_color: gpui::Rgba,
+    ) -> ActionItem {
+        let name = self.value.clone();
+
+        ActionItem::new(
+            id,
+            name.clone(),
+            self.clone(),
+            move || {
+                div()
+                    .flex()
+                    .gap_4()
+                    .child(div().flex_none().child(name.clone()))
+                    .child(
+                        div()
+                            .flex_grow()
+                            .child(detail.clone())
+                            .text_color(text_secondary_color),
+                    )
+                    .into_any()
+            },
+            relevance,
+            10,
+            db,
+        )
+    }
+}
+
+/// Parse a query into a point in time. The flag is set when a numeric input
+/// was interpreted as milliseconds.
+fn parse_query(query: &str) -> Option<(DateTime<Utc>, bool)> {
+    let query = query.trim().to_lowercase();
+    let query = TO_EPOCH_SUFFIXES
+        .iter()
+        .find_map(|suffix| query.strip_suffix(suffix))
+        .unwrap_or(&query)
+        .trim();
+
+    if query == "now" {
+        return Some((Utc::now(), false));
+    }
+
+    let digits = query.strip_prefix('-').unwrap_or(query);
+    if digits.len() >= MIN_TIMESTAMP_DIGITS && digits.chars().all(|c| c.is_ascii_digit()) {
+        let value: i64 = query.parse().ok()?;
+        return if value.abs() >= MILLISECOND_THRESHOLD {
+            Utc.timestamp_millis_opt(value).single().map(|t| (t, true))
+        } else {
+            Utc.timestamp_opt(value, 0).single().map(|t| (t, false))
+        };
+    }
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(query) {
+        return Some((time.with_timezone(&Utc), false));
+    }
+
+    let naive = DATETIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(query, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(query, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|time| (time.with_timezone(&Utc), false))
+}
@@ -0,0 +1,167 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::sync::{Arc, OnceLock};
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::UNICODE;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::copy_to_clipboard;
+
+/// Queries starting with this word search character names
+const CHAR_PREFIX: &str = "char";
+
+const MAX_CHARACTERS: usize = 8;
+
+/// Names of all named characters, built on the first search
+static NAMES: OnceLock<Vec<(char, String)>> = OnceLock::new();
+
+/// Finds characters by name with `char right arrow` or by code point with
+/// `U+2192`, copying the character on Enter
+pub struct UnicodeHandlerFactory;
+
+impl HandlerFactory for UnicodeHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        UNICODE
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(
+            format!("{} ", CHAR_PREFIX),
+            "Search Unicode characters by name",
+        )]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let query = query.trim();
+        let characters = if let Some(character) = parse_code_point(query) {
+            vec![character]
+        } else if let Some(terms) = query.strip_prefix(CHAR_PREFIX) {
+            if !terms.starts_with(' ') || terms.trim().is_empty() {
+                return Vec::new();
+            }
+            search(terms)
+        } else {
+            return Vec::new();
+        };
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+
+        characters
+            .into_iter()
+            .enumerate()
+            .map(|(index, character)| {
+                let code_point = format!("U+{:04X}", character as u32);
+                let name = unicode_names2::name(character)
+                    .map(|name| name.to_string())
+                    .unwrap_or_else(|| code_point.clone());
+                let id = Box::leak(format!("{}-{}", UNICODE, code_point).into_boxed_str());
+                create_row(
+                    id,
+                    character,
+                    name,
+                    code_point,
+                    100 - index,
+                    db.clone(),
+                    text_secondary_color,
+                )
+            })
+            .collect()
+    }
+}
+
+/// `U+2192` or `u+2192`
+fn parse_code_point(query: &str) -> Option<char> {
+    let hex = query
+        .strip_prefix("U+")
+        .or_else(|| query.strip_prefix("u+"))?;
+    if hex.is_empty() || hex.len() > 6 {
+        return None;
+    }
+    char::from_u32(u32::from_str_radix(hex, 16).ok()?)
+}
+
+/// Characters whose name contains all terms, exact and shorter names first
+fn search(terms: &str) -> Vec<char> {
+    let terms = terms.trim().to_uppercase();
+    let tokens: Vec<&str> = terms.split_whitespace().collect();
+    let names = NAMES.get_or_init(|| {
+        (0..=char::MAX as u32)
+            .filter_map(char::from_u32)
+            .filter_map(|character| {
+                unicode_names2::name(character).map(|name| (character, name.to_string()))
+            })
+            .collect()
+    });
+
+    let mut matches: Vec<&(char, String)> = names
+        .iter()
+        .filter(|(_, name)| {
+            tokens
+                .iter()
+                .all(|token| name.split_whitespace().any(|word| word.starts_with(token)))
+        })
+        .collect();
+    matches.sort_by_key(|(_, name)| (*name != terms, name.len()));
+    matches
+        .into_iter()
+        .take(MAX_CHARACTERS)
+        .map(|(character, _)| *character)
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct UnicodeHandler {
+    character: char,
+}
+
+impl ActionHandler for UnicodeHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        copy_to_clipboard(&self.character.to_string())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+fn create_row(
+    id: &'static str,
+    character: char,
+    name: String,
+    code_point: String,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    let label = format!("{}  {}", character, name.to_lowercase());
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name,
+        UnicodeHandler { character },
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(label.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(code_point.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        10,
+        db,
+    )
+}
@@ -1,15 +1,16 @@
 use anyhow;
-use gpui::{div, Context, Element, ParentElement, Styled};
+use gpui::{div, prelude::FluentBuilder, Context, Element, ParentElement, Styled};
 use std::sync::Arc;
 use url::Url;
 
 use crate::action_list_view::ActionListView;
 use crate::actions::action_handler::{
-    ActionDefinition, ActionHandler, ActionId, ActionItem, HandlerFactory,
+    ActionDefinition, ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory,
 };
 use crate::actions::action_ids::{self, URL_OPEN};
-use crate::config::Config;
+use crate::config::{Config, UsageColumn};
 use crate::database::Database;
+use crate::system::browser;
 
 pub struct UrlHandlerFactory;
 
@@ -28,9 +29,16 @@ impl HandlerFactory for UrlHandlerFactory {
             return Vec::new();
         }
 
-        let mut handlers = Vec::new();
-        handlers.push(UrlHandler.create_action(db.clone(), cx));
-        handlers
+        let mut item = UrlHandler
+            .create_action(db.clone(), cx)
+            .with_url(query.to_string());
+        if browser::supports_private_window() {
+            item = item.with_secondary_action(
+                "Open in private window",
+                PrivateWindowHandler(query.to_string()),
+            );
+        }
+        vec![item]
     }
 }
 
@@ -51,12 +59,14 @@ impl ActionDefinition for UrlHandler {
     fn create_action(&self, db: Arc<Database>, cx: &mut Context<ActionListView>) -> ActionItem {
         let config = cx.global::<Config>();
         let text_secondary_color = config.text_secondary_color;
+        let show_count = config.usage_column != UsageColumn::None;
 
         let execution_count = db.get_execution_count(self.get_id().as_str()).unwrap_or(0);
         let name = self.get_name();
 
         ActionItem::new(
             self.get_id(),
+            self.get_name(),
             self.clone(),
             move || {
                 div()
@@ -69,11 +79,13 @@ impl ActionDefinition for UrlHandler {
                             .child("URL Handler")
                             .text_color(text_secondary_color),
                     )
-                    .child(
-                        div()
-                            .child(format!("{}", execution_count))
-                            .text_color(text_secondary_color),
-                    )
+                    .when(show_count, |x| {
+                        x.child(
+                            div()
+                                .child(format!("{}", execution_count))
+                                .text_color(text_secondary_color),
+                        )
+                    })
                     .into_any()
             },
             1,
@@ -90,3 +102,25 @@ impl ActionDefinition for UrlHandler {
         "Open URL".to_string()
     }
 }
+
+/// Opens the URL in a private window of the default browser
+#[derive(Clone)]
+pub struct PrivateWindowHandler(pub String);
+
+impl ActionHandler for PrivateWindowHandler {
+    fn execute(&self, _input: &str) -> anyhow::Result<()> {
+        if let Some(mut command) = browser::private_window_command(&self.0) {
+            command.spawn()?;
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        browser::private_window_command(&self.0)
+            .map(|command| CommandPreview::from_command(&command))
+    }
+}
@@ -0,0 +1,264 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::WIFI;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::wifi::{self, WifiNetwork};
+
+/// Queries starting with this word list Wi-Fi networks
+const WIFI_PREFIX: &str = "wifi";
+
+/// Networks are read again when the list is older than this
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+const MAX_NETWORKS: usize = 8;
+
+#[derive(Debug, Clone)]
+enum WifiState {
+    Pending,
+    Ready {
+        enabled: bool,
+        networks: Vec<WifiNetwork>,
+    },
+    Failed(String),
+}
+
+lazy_static::lazy_static! {
+    static ref WIFI_STATE: Mutex<Option<(Instant, WifiState)>> = Mutex::new(None);
+}
+
+/// Lists visible Wi-Fi networks with `wifi <ssid>`
+pub struct WifiHandlerFactory;
+
+impl HandlerFactory for WifiHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        WIFI
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(
+            format!("{} ", WIFI_PREFIX),
+            "List and connect to Wi-Fi networks",
+        )]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(filter) = query.trim_start().strip_prefix(WIFI_PREFIX) else {
+            return Vec::new();
+        };
+        if !filter.is_empty() && !filter.starts_with(' ') {
+            return Vec::new();
+        }
+        let filter = filter.trim().to_lowercase();
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+
+        let (enabled, networks) = match wifi_state(cx) {
+            WifiState::Ready { enabled, networks } => (enabled, networks),
+            WifiState::Pending => {
+                return vec![create_row(
+                    "wifi-loading",
+                    "Reading Wi-Fi networks...".to_string(),
+                    String::new(),
+                    WifiHandler::None,
+                    100,
+                    db,
+                    text_secondary_color,
+                )];
+            }
+            WifiState::Failed(e) => {
+                return vec![create_row(
+                    "wifi-failed",
+                    "Wi-Fi unavailable".to_string(),
+                    e,
+                    WifiHandler::None,
+                    100,
+                    db,
+                    text_secondary_color,
+                )];
+            }
+        };
+
+        let mut handlers: Vec<ActionItem> = networks
+            .into_iter()
+            .filter(|network| network.ssid.to_lowercase().contains(&filter))
+            .take(MAX_NETWORKS)
+            .enumerate()
+            .map(|(index, network)| {
+                let id = Box::leak(format!("{}-{}", WIFI, network.ssid).into_boxed_str());
+                let mut detail = format!("{}%", network.signal);
+                if !network.security.is_empty() {
+                    detail.push_str(&format!(" · {}", network.security));
+                }
+                let handler = if network.in_use {
+                    detail.push_str(" · connected");
+                    WifiHandler::None
+                } else {
+                    WifiHandler::Connect(network.ssid.clone())
+                };
+                create_row(
+                    id,
+                    network.ssid,
+                    detail,
+                    handler,
+                    100 - index,
+                    db.clone(),
+                    text_secondary_color,
+                )
+            })
+            .collect();
+
+        if filter.is_empty() || "disconnect".starts_with(&filter) {
+            handlers.push(create_row(
+                "wifi-disconnect",
+                "Disconnect Wi-Fi".to_string(),
+                String::new(),
+                WifiHandler::Disconnect,
+                20,
+                db.clone(),
+                text_secondary_color,
+            ));
+        }
+        if filter.is_empty() || "toggle".starts_with(&filter) {
+            let name = if enabled {
+                "Turn Wi-Fi Off"
+            } else {
+                "Turn Wi-Fi On"
+            };
+            handlers.push(create_row(
+                "wifi-toggle",
+                name.to_string(),
+                String::new(),
+                WifiHandler::SetEnabled(!enabled),
+                10,
+                db,
+                text_secondary_color,
+            ));
+        }
+
+        handlers
+    }
+}
+
+#[derive(Clone)]
+pub enum WifiHandler {
+    Connect(String),
+    Disconnect,
+    SetEnabled(bool),
+    /// Informational rows
+    None,
+}
+
+impl WifiHandler {
+    fn command(&self) -> Result<Option<Command>> {
+        Ok(match self {
+            Self::Connect(ssid) => Some(wifi::connect_command(ssid)),
+            Self::Disconnect => Some(wifi::disconnect_command()?),
+            Self::SetEnabled(enabled) => Some(wifi::set_enabled_command(*enabled)),
+            Self::None => None,
+        })
+    }
+}
+
+impl ActionHandler for WifiHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        if let Some(mut command) = self.command()? {
+            command.spawn()?;
+        }
+        // The list is outdated once the connection changes
+        *WIFI_STATE.lock().unwrap() = None;
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        self.command()
+            .ok()
+            .flatten()
+            .map(|command| CommandPreview::from_command(&command))
+    }
+}
+
+fn create_row(
+    id: &'static str,
+    name: String,
+    detail: String,
+    handler: WifiHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        10,
+        db,
+    )
+}
+
+/// Current networks, reading them in the background when missing or outdated
+fn wifi_state(cx: &mut Context<ActionListView>) -> WifiState {
+    let mut state = WIFI_STATE.lock().unwrap();
+    if let Some((fetched_at, current)) = state.as_ref() {
+        if matches!(current, WifiState::Pending) || fetched_at.elapsed() < REFRESH_INTERVAL {
+            return current.clone();
+        }
+    }
+
+    // Keep showing outdated networks while reading new ones
+    let shown = match state.take() {
+        Some((_, ready @ WifiState::Ready { .. })) => ready,
+        _ => WifiState::Pending,
+    };
+    *state = Some((Instant::now(), shown.clone()));
+    drop(state);
+
+    cx.spawn(|view, mut cx| async move {
+        let new_state = cx
+            .background_executor()
+            .spawn(async {
+                match (wifi::is_enabled(), wifi::networks()) {
+                    (Ok(enabled), Ok(networks)) => WifiState::Ready { enabled, networks },
+                    (Err(e), _) | (_, Err(e)) => WifiState::Failed(e.to_string()),
+                }
+            })
+            .await;
+
+        *WIFI_STATE.lock().unwrap() = Some((Instant::now(), new_state));
+        let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+    })
+    .detach();
+
+    shown
+}
@@ -0,0 +1,195 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::WORKSPACES;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::workspaces::{WindowManager, Workspace};
+
+/// Queries starting with this word list workspaces
+const WORKSPACE_PREFIX: &str = "ws";
+
+/// `ws move <name>` moves the focused window instead of switching
+const MOVE_PREFIX: &str = "move";
+
+/// Switches workspaces of i3, sway and Hyprland with `ws <name>`, or moves the
+/// focused window there with `ws move <name>`
+pub struct WorkspaceHandlerFactory;
+
+impl HandlerFactory for WorkspaceHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        WORKSPACES
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![
+            Keyword::new(format!("{} ", WORKSPACE_PREFIX), "Switch workspaces"),
+            Keyword::new(
+                format!("{} {} ", WORKSPACE_PREFIX, MOVE_PREFIX),
+                "Move the focused window to a workspace",
+            ),
+        ]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(filter) = query.trim_start().strip_prefix(WORKSPACE_PREFIX) else {
+            return Vec::new();
+        };
+        if !filter.is_empty() && !filter.starts_with(' ') {
+            return Vec::new();
+        }
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+
+        let Some(window_manager) = WindowManager::detect() else {
+            return vec![create_row(
+                "workspaces-unsupported",
+                "No supported window manager".to_string(),
+                "Workspaces need i3, sway or Hyprland".to_string(),
+                WorkspaceHandler::None,
+                100,
+                db,
+                text_secondary_color,
+            )];
+        };
+        let workspaces = match window_manager.workspaces() {
+            Ok(workspaces) => workspaces,
+            Err(e) => {
+                return vec![create_row(
+                    "workspaces-failed",
+                    "Workspaces unavailable".to_string(),
+                    e.to_string(),
+                    WorkspaceHandler::None,
+                    100,
+                    db,
+                    text_secondary_color,
+                )];
+            }
+        };
+
+        let filter = filter.trim();
+        let (moving, filter) = match filter.strip_prefix(MOVE_PREFIX) {
+            Some(rest) if rest.is_empty() || rest.starts_with(' ') => (true, rest.trim()),
+            _ => (false, filter),
+        };
+
+        let mut targets: Vec<Workspace> = workspaces
+            .into_iter()
+            .filter(|workspace| {
+                workspace
+                    .name
+                    .to_lowercase()
+                    .contains(&filter.to_lowercase())
+            })
+            .collect();
+        // Typing a name that doesn't exist yet creates the workspace
+        if !filter.is_empty() && !targets.iter().any(|workspace| workspace.name == filter) {
+            targets.push(Workspace {
+                name: filter.to_string(),
+                output: String::new(),
+                focused: false,
+            });
+        }
+
+        targets
+            .into_iter()
+            .enumerate()
+            .map(|(index, workspace)| {
+                let (action, name, handler) = if moving {
+                    (
+                        "move",
+                        format!("Move window to workspace {}", workspace.name),
+                        WorkspaceHandler::MoveWindow(window_manager, workspace.name.clone()),
+                    )
+                } else {
+                    (
+                        "switch",
+                        format!("Workspace {}", workspace.name),
+                        WorkspaceHandler::Switch(window_manager, workspace.name.clone()),
+                    )
+                };
+                let detail = match (workspace.focused, workspace.output.is_empty()) {
+                    (true, _) => format!("{} · focused", workspace.output),
+                    (false, true) => format!("New {} workspace", window_manager.name()),
+                    (false, false) => workspace.output.clone(),
+                };
+                let id = Box::leak(
+                    format!("{}-{}-{}", WORKSPACES, action, workspace.name).into_boxed_str(),
+                );
+                create_row(
+                    id,
+                    name,
+                    detail,
+                    handler,
+                    100 - index.min(100),
+                    db.clone(),
+                    text_secondary_color,
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub enum WorkspaceHandler {
+    Switch(WindowManager, String),
+    MoveWindow(WindowManager, String),
+    /// Informational rows
+    None,
+}
+
+impl ActionHandler for WorkspaceHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        match self {
+            Self::Switch(window_manager, workspace) => window_manager.switch_to(workspace),
+            Self::MoveWindow(window_manager, workspace) => window_manager.move_window_to(workspace),
+            Self::None => Ok(()),
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+fn create_row(
+    id: &'static str,
+    name: String,
+    detail: String,
+    handler: WorkspaceHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        10,
+        db,
+    )
+}
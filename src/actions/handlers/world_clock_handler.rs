@@ -0,0 +1,430 @@
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveTime, TimeZone, Utc};
+use chrono_tz::{Tz, TZ_VARIANTS};
+use gpui::{div, Context, Element, ParentElement, Styled};
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{ActionHandler, ActionId, ActionItem, HandlerFactory};
+use crate::actions::action_ids::WORLD_CLOCK;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::copy_to_clipboard;
+
+/// Prefixes of queries for the current time somewhere, e.g. `time in tokyo`
+const TIME_IN_PREFIXES: &[&str] = &["time in ", "time at "];
+
+/// Optional prefix of conversions, e.g. `convert 3pm est to cet`
+const CONVERT_PREFIX: &str = "convert ";
+
+/// Words separating the source of a conversion from its target
+const CONVERT_SEPARATORS: &[&str] = &[" to ", " in "];
+
+/// Names of the local time zone
+const LOCAL_NAMES: &[&str] = &["local", "here"];
+
+/// Common abbreviations, mapped to a zone that observes them so daylight
+/// saving time is applied, e.g. `est` in July gives EDT
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("utc", "UTC"),
+    ("gmt", "Etc/GMT"),
+    ("et", "America/New_York"),
+    ("est", "America/New_York"),
+    ("edt", "America/New_York"),
+    ("ct", "America/Chicago"),
+    ("cst", "America/Chicago"),
+    ("cdt", "America/Chicago"),
+    ("mt", "America/Denver"),
+    ("mst", "America/Denver"),
+    ("mdt", "America/Denver"),
+    ("pt", "America/Los_Angeles"),
+    ("pst", "America/Los_Angeles"),
+    ("pdt", "America/Los_Angeles"),
+    ("akst", "America/Anchorage"),
+    ("hst", "Pacific/Honolulu"),
+    ("bst", "Europe/London"),
+    ("wet", "Europe/Lisbon"),
+    ("cet", "Europe/Berlin"),
+    ("cest", "Europe/Berlin"),
+    ("eet", "Europe/Athens"),
+    ("eest", "Europe/Athens"),
+    ("msk", "Europe/Moscow"),
+    ("ist", "Asia/Kolkata"),
+    ("sgt", "Asia/Singapore"),
+    ("hkt", "Asia/Hong_Kong"),
+    ("jst", "Asia/Tokyo"),
+    ("kst", "Asia/Seoul"),
+    ("aest", "Australia/Sydney"),
+    ("aedt", "Australia/Sydney"),
+    ("acst", "Australia/Adelaide"),
+    ("awst", "Australia/Perth"),
+    ("nzst", "Pacific/Auckland"),
+    ("nzdt", "Pacific/Auckland"),
+];
+
+/// Cities and countries that don't name a zone of the tz database
+const PLACES: &[(&str, &str)] = &[
+    ("nyc", "America/New_York"),
+    ("washington", "America/New_York"),
+    ("boston", "America/New_York"),
+    ("miami", "America/New_York"),
+    ("atlanta", "America/New_York"),
+    ("houston", "America/Chicago"),
+    ("dallas", "America/Chicago"),
+    ("austin", "America/Chicago"),
+    ("san francisco", "America/Los_Angeles"),
+    ("sf", "America/Los_Angeles"),
+    ("seattle", "America/Los_Angeles"),
+    ("portland", "America/Los_Angeles"),
+    ("las vegas", "America/Los_Angeles"),
+    ("montreal", "America/Toronto"),
+    ("ottawa", "America/Toronto"),
+    ("rio", "America/Sao_Paulo"),
+    ("munich", "Europe/Berlin"),
+    ("hamburg", "Europe/Berlin"),
+    ("frankfurt", "Europe/Berlin"),
+    ("cologne", "Europe/Berlin"),
+    ("zurich", "Europe/Zurich"),
+    ("geneva", "Europe/Zurich"),
+    ("barcelona", "Europe/Madrid"),
+    ("milan", "Europe/Rome"),
+    ("edinburgh", "Europe/London"),
+    ("manchester", "Europe/London"),
+    ("st petersburg", "Europe/Moscow"),
+    ("mumbai", "Asia/Kolkata"),
+    ("delhi", "Asia/Kolkata"),
+    ("new delhi", "Asia/Kolkata"),
+    ("bangalore", "Asia/Kolkata"),
+    ("beijing", "Asia/Shanghai"),
+    ("shenzhen", "Asia/Shanghai"),
+    ("hong kong", "Asia/Hong_Kong"),
+    ("osaka", "Asia/Tokyo"),
+    ("kyoto", "Asia/Tokyo"),
+    ("hanoi", "Asia/Bangkok"),
+    ("abu dhabi", "Asia/Dubai"),
+    ("tel aviv", "Asia/Jerusalem"),
+    ("canberra", "Australia/Sydney"),
+    ("wellington", "Pacific/Auckland"),
+    ("germany", "Europe/Berlin"),
+    ("france", "Europe/Paris"),
+    ("spain", "Europe/Madrid"),
+    ("italy", "Europe/Rome"),
+    ("uk", "Europe/London"),
+    ("england", "Europe/London"),
+    ("ireland", "Europe/Dublin"),
+    ("netherlands", "Europe/Amsterdam"),
+    ("poland", "Europe/Warsaw"),
+    ("india", "Asia/Kolkata"),
+    ("china", "Asia/Shanghai"),
+    ("japan", "Asia/Tokyo"),
+    ("korea", "Asia/Seoul"),
+    ("singapore", "Asia/Singapore"),
+    ("brazil", "America/Sao_Paulo"),
+];
+
+/// A time zone a query refers to
+#[derive(Debug, Clone)]
+enum Zone {
+    Local,
+    Named { tz: Tz, label: String },
+}
+
+impl Zone {
+    /// Resolve an abbreviation, city, country or tz database name like
+    /// `Europe/Paris`
+    fn resolve(place: &str) -> Option<Self> {
+        let place = place.trim().to_lowercase();
+        if place.is_empty() {
+            return None;
+        }
+        if LOCAL_NAMES.contains(&place.as_str()) {
+            return Some(Self::Local);
+        }
+
+        let named =
+            |name: &str, label: String| name.parse::<Tz>().ok().map(|tz| Self::Named { tz, label });
+
+        if let Some((_, name)) = ABBREVIATIONS.iter().find(|(abbr, _)| *abbr == place) {
+            return named(name, place.to_uppercase());
+        }
+        if let Some((city, name)) = PLACES.iter().find(|(city, _)| *city == place) {
+            return named(name, title_case(city));
+        }
+
+        let underscored = place.replace(' ', "_");
+        // Full names first, `europe/paris`, then cities, `paris`
+        TZ_VARIANTS
+            .iter()
+            .find(|tz| tz.name().eq_ignore_ascii_case(&underscored))
+            .map(|tz| Self::Named {
+                tz: *tz,
+                label: tz.name().to_string(),
+            })
+            .or_else(|| {
+                TZ_VARIANTS
+                    .iter()
+                    .filter(|tz| tz.name().contains('/'))
+                    .find(|tz| {
+                        tz.name()
+                            .rsplit('/')
+                            .next()
+                            .is_some_and(|city| city.eq_ignore_ascii_case(&underscored))
+                    })
+                    .map(|tz| Self::Named {
+                        tz: *tz,
+                        label: title_case(&place),
+                    })
+            })
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Self::Local => "Local time".to_string(),
+            Self::Named { label, .. } => label.clone(),
+        }
+    }
+
+    /// Name of the zone in the tz database and its UTC offset at `time`
+    fn description(&self, time: DateTime<Utc>) -> String {
+        match self {
+            Self::Local => format!("UTC{}", time.with_timezone(&Local).format("%:z")),
+            Self::Named { tz, .. } => {
+                format!("{}, UTC{}", tz.name(), time.with_timezone(tz).format("%:z"))
+            }
+        }
+    }
+
+    /// `time` in this zone, formatted with chrono's `format`
+    fn format(&self, time: DateTime<Utc>, format: &str) -> String {
+        match self {
+            Self::Local => time.with_timezone(&Local).format(format).to_string(),
+            Self::Named { tz, .. } => time.with_timezone(tz).format(format).to_string(),
+        }
+    }
+
+    /// Today's `time` of this zone. Times skipped by a daylight saving
+    /// change don't exist and give `None`.
+    fn today_at(&self, time: NaiveTime) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Local => Local
+                .from_local_datetime(&Local::now().date_naive().and_time(time))
+                .earliest()
+                .map(|time| time.with_timezone(&Utc)),
+            Self::Named { tz, .. } => tz
+                .from_local_datetime(&Utc::now().with_timezone(tz).date_naive().and_time(time))
+                .earliest()
+                .map(|time| time.with_timezone(&Utc)),
+        }
+    }
+}
+
+/// A parsed query
+enum WorldClockQuery {
+    /// The current time in a zone
+    Now(Zone),
+    /// A time of one zone in another
+    Convert {
+        time: NaiveTime,
+        from: Zone,
+        to: Zone,
+    },
+}
+
+/// Shows the current time around the world with `time in <place>` and
+/// converts times between zones, e.g. `convert 3pm EST to CET`
+pub struct WorldClockHandlerFactory;
+
+impl HandlerFactory for WorldClockHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        WORLD_CLOCK
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(query) = parse_query(query) else {
+            return Vec::new();
+        };
+        let text_secondary_color = cx.global::<Config>().text_secondary_color;
+
+        let (id, value, detail) = match query {
+            WorldClockQuery::Now(zone) => {
+                let now = Utc::now();
+                (
+                    "world-clock-now",
+                    zone.format(now, "%H:%M"),
+                    format!(
+                        "{} · {} · {}",
+                        zone.label(),
+                        zone.format(now, "%a, %-d %b"),
+                        zone.description(now)
+                    ),
+                )
+            }
+            WorldClockQuery::Convert { time, from, to } => {
+                let Some(time) = from.today_at(time) else {
+                    return Vec::new();
+                };
+                (
+                    "world-clock-convert",
+                    to.format(time, "%H:%M"),
+                    format!(
+                        "{} {} in {} · {} · {}",
+                        from.format(time, "%H:%M"),
+                        from.label(),
+                        to.label(),
+                        to.format(time, "%a, %-d %b"),
+                        to.description(time)
+                    ),
+                )
+            }
+        };
+
+        vec![WorldClockHandler { value }.create_action(
+            ActionId::Builtin(id),
+            detail,
+            100,
+            db,
+            text_secondary_color,
+        )]
+    }
+}
+
+/// Copies the time to the clipboard
+#[derive(Clone)]
+pub struct WorldClockHandler {
+    value: String,
+}
+
+impl ActionHandler for WorldClockHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        copy_to_clipboard(&self.value)
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+impl WorldClockHandler {
+    fn create_action(
+        &self,
+        id: ActionId,
+        detail: String,
+        relevance: usize,
+        db: Arc<Database>,
+        text_secondary_color: gpui::Rgba,
+    ) -> ActionItem {
+        let name = self.value.clone();
+
+        ActionItem::new(
+            id,
+            name.clone(),
+            self.clone(),
+            move || {
+                div()
+                    .flex()
+                    .gap_4()
+                    .child(div().flex_none().child(name.clone()))
+                    .child(
+                        div()
+                            .flex_grow()
+                            .child(detail.clone())
+                            .text_color(text_secondary_color),
+                    )
+                    .into_any()
+            },
+            relevance,
+            10,
+            db,
+        )
+    }
+}
+
+fn parse_query(query: &str) -> Option<WorldClockQuery> {
+    let query = query.trim().to_lowercase();
+
+    if let Some(place) = TIME_IN_PREFIXES
+        .iter()
+        .find_map(|prefix| query.strip_prefix(prefix))
+    {
+        return Zone::resolve(place).map(WorldClockQuery::Now);
+    }
+
+    let query = query.strip_prefix(CONVERT_PREFIX).unwrap_or(&query);
+    let (source, target) = CONVERT_SEPARATORS
+        .iter()
+        .filter_map(|separator| query.rsplit_once(separator))
+        .max_by_key(|(source, _)| source.len())?;
+    let to = Zone::resolve(target)?;
+
+    // `3pm est`, `3 pm est`, `15:00` (local time)
+    let words: Vec<&str> = source.split_whitespace().collect();
+    (1..=words.len().min(2)).rev().find_map(|time_words| {
+        let time = parse_time(&words[..time_words].join(""))?;
+        let zone = &words[time_words..];
+        let from = if zone.is_empty() {
+            Zone::Local
+        } else {
+            Zone::resolve(&zone.join(" "))?
+        };
+        Some(WorldClockQuery::Convert {
+            time,
+            from,
+            to: to.clone(),
+        })
+    })
+}
+
+/// Times like `3pm`, `3:30pm`, `15:00`, `noon` and `midnight`. Bare numbers
+/// aren't times, `15` is more likely part of a calculation.
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    match time {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "midnight" => return NaiveTime::from_hms_opt(0, 0, 0),
+        _ => {}
+    }
+
+    let (time, pm) = match time.strip_suffix("am") {
+        Some(time) => (time, Some(false)),
+        None => match time.strip_suffix("pm") {
+            Some(time) => (time, Some(true)),
+            None => (time, None),
+        },
+    };
+    let (hour, minute) = match time.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour, minute.parse::<u32>().ok()?),
+        Some(_) => return None,
+        None if pm.is_some() => (time, 0),
+        None => return None,
+    };
+    if hour.is_empty() || hour.len() > 2 || !hour.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let hour: u32 = hour.parse().ok()?;
+
+    let hour = match pm {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some(true) => hour % 12 + 12,
+        Some(false) => hour % 12,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+fn title_case(place: &str) -> String {
+    place
+        .split(' ')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
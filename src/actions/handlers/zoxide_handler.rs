@@ -0,0 +1,219 @@
+use anyhow::Result;
+use gpui::{div, Context, Element, ParentElement, Styled};
+use log::warn;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{
+    ActionHandler, ActionId, ActionItem, CommandPreview, HandlerFactory, Keyword,
+};
+use crate::actions::action_ids::ZOXIDE;
+use crate::config::Config;
+use crate::database::Database;
+use crate::system::{terminal, zoxide};
+
+/// Queries starting with this word list zoxide's directories
+const ZOXIDE_PREFIX: &str = "z";
+
+const MAX_DIRECTORIES: usize = 5;
+
+/// What Enter does with a directory, set with `zoxide_action`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DirectoryAction {
+    FileManager,
+    Terminal,
+}
+
+impl DirectoryAction {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "file_manager" => Some(Self::FileManager),
+            "terminal" => Some(Self::Terminal),
+            _ => None,
+        }
+    }
+}
+
+/// Jumps to directories zoxide remembers with `z <keywords>`, opening them in
+/// the file manager or a terminal
+pub struct ZoxideHandlerFactory;
+
+impl HandlerFactory for ZoxideHandlerFactory {
+    fn get_id(&self) -> &'static str {
+        ZOXIDE
+    }
+
+    fn keywords(&self) -> Vec<Keyword> {
+        vec![Keyword::new(
+            format!("{} ", ZOXIDE_PREFIX),
+            "Open frecent directories from zoxide",
+        )]
+    }
+
+    fn create_handlers_for_query(
+        &self,
+        query: &str,
+        db: Arc<Database>,
+        cx: &mut Context<ActionListView>,
+    ) -> Vec<ActionItem> {
+        let Some(keywords) = query.trim_start().strip_prefix(ZOXIDE_PREFIX) else {
+            return Vec::new();
+        };
+        if !keywords.starts_with(' ') {
+            return Vec::new();
+        }
+        let keywords: Vec<&str> = keywords.split_whitespace().collect();
+
+        let config = cx.global::<Config>();
+        let text_secondary_color = config.text_secondary_color;
+        let terminal = config.terminal.clone();
+        let primary = DirectoryAction::from_name(&config.zoxide_action).unwrap_or_else(|| {
+            warn!(
+                "Unknown zoxide_action {:?}, opening the file manager",
+                config.zoxide_action
+            );
+            DirectoryAction::FileManager
+        });
+
+        let directories = match zoxide::query(&keywords) {
+            Ok(directories) => directories,
+            Err(e) => {
+                return vec![create_row(
+                    "zoxide-failed",
+                    "zoxide unavailable".to_string(),
+                    e.to_string(),
+                    ZoxideHandler::None,
+                    10,
+                    db,
+                    text_secondary_color,
+                )];
+            }
+        };
+
+        let mut handlers = Vec::new();
+        for (index, directory) in directories.into_iter().take(MAX_DIRECTORIES).enumerate() {
+            let path = directory.to_string_lossy().to_string();
+            let actions = [
+                (
+                    DirectoryAction::FileManager,
+                    "Open",
+                    ZoxideHandler::Open(directory.clone()),
+                ),
+                (
+                    DirectoryAction::Terminal,
+                    "Terminal in",
+                    ZoxideHandler::Terminal {
+                        directory: directory.clone(),
+                        terminal: terminal.clone(),
+                    },
+                ),
+            ];
+
+            for (action, verb, handler) in actions {
+                let id = Box::leak(format!("{}-{:?}-{}", ZOXIDE, action, path).into_boxed_str());
+                // The configured action of every directory comes first
+                let relevance = if action == primary {
+                    200usize.saturating_sub(index)
+                } else {
+                    100usize.saturating_sub(index)
+                };
+                handlers.push(create_row(
+                    id,
+                    format!("{} {}", verb, path),
+                    String::new(),
+                    handler,
+                    relevance,
+                    db.clone(),
+                    text_secondary_color,
+                ));
+            }
+        }
+
+        handlers
+    }
+}
+
+#[derive(Clone)]
+pub enum ZoxideHandler {
+    Open(PathBuf),
+    Terminal {
+        directory: PathBuf,
+        terminal: Option<String>,
+    },
+    /// Informational rows
+    None,
+}
+
+impl ZoxideHandler {
+    fn terminal_command(directory: &Path, terminal: Option<&str>) -> Command {
+        terminal::shell_in(terminal, directory)
+    }
+}
+
+impl ActionHandler for ZoxideHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        match self {
+            Self::Open(directory) => open::that(directory)?,
+            Self::Terminal {
+                directory,
+                terminal,
+            } => {
+                Self::terminal_command(directory, terminal.as_deref()).spawn()?;
+            }
+            Self::None => {}
+        }
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+
+    fn preview(&self, _input: &str) -> Option<CommandPreview> {
+        match self {
+            Self::Terminal {
+                directory,
+                terminal,
+            } => Some(CommandPreview::from_command(&Self::terminal_command(
+                directory,
+                terminal.as_deref(),
+            ))),
+            _ => None,
+        }
+    }
+}
+
+fn create_row(
+    id: &'static str,
+    name: String,
+    detail: String,
+    handler: ZoxideHandler,
+    relevance: usize,
+    db: Arc<Database>,
+    text_secondary_color: gpui::Rgba,
+) -> ActionItem {
+    ActionItem::new(
+        ActionId::Builtin(id),
+        name.clone(),
+        handler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(div().flex_none().child(name.clone()))
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        relevance,
+        10,
+        db,
+    )
+}
@@ -0,0 +1,27 @@
+//! Rendering of result names with the characters the query matched in the
+//! `match_highlight_color`, see [`score::match_ranges`](super::score::match_ranges).
+
+use gpui::{div, Div, ParentElement, Rgba, Styled};
+use std::ops::Range;
+
+/// The text split into runs, matched ones in the highlight color
+pub fn highlighted(text: &str, ranges: &[Range<usize>], color: Rgba) -> Div {
+    let mut runs = Vec::new();
+    let mut end = 0;
+    for range in ranges {
+        if range.start > end {
+            runs.push(div().child(text[end..range.start].to_string()));
+        }
+        runs.push(
+            div()
+                .text_color(color)
+                .child(text[range.clone()].to_string()),
+        );
+        end = range.end;
+    }
+    if end < text.len() {
+        runs.push(div().child(text[end..].to_string()));
+    }
+
+    div().flex().whitespace_nowrap().children(runs)
+}
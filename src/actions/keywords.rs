@@ -0,0 +1,90 @@
+//! Lists the prefixes and keywords of all enabled handlers when the query
+//! starts with `?`, so features can be discovered without the docs.
+
+use gpui::{div, Element, ParentElement, Rgba, Styled};
+use std::sync::Arc;
+
+use crate::actions::action_handler::{ActionHandler, ActionId, ActionItem, Keyword};
+use crate::database::Database;
+use crate::regex_tester::REGEX_PREFIX;
+
+/// Queries starting with this list keywords
+pub const HELP_PREFIX: &str = "?";
+
+/// Input modes that aren't handlers
+pub fn mode_keywords(commands_enabled: bool) -> Vec<Keyword> {
+    let mut keywords = vec![Keyword::new(HELP_PREFIX, "List prefixes and keywords")];
+    if commands_enabled {
+        keywords.push(Keyword::new(":", "Commands, e.g. :handlers or :enable"));
+        keywords.push(Keyword::new(
+            format!("{} ", REGEX_PREFIX),
+            "Test a regex against the clipboard",
+        ));
+    }
+    keywords
+}
+
+/// Rows for keywords matching the text after `?`. Selecting one inserts it
+/// into the input.
+pub fn create_items(
+    keywords: Vec<Keyword>,
+    filter: &str,
+    db: Arc<Database>,
+    text_secondary_color: Rgba,
+) -> Vec<ActionItem> {
+    let filter = filter.trim().to_lowercase();
+    let count = keywords.len();
+
+    keywords
+        .into_iter()
+        .filter(|keyword| {
+            keyword.prefix.to_lowercase().contains(&filter)
+                || keyword.description.to_lowercase().contains(&filter)
+        })
+        .enumerate()
+        .map(|(index, keyword)| {
+            let id = Box::leak(format!("keyword-{}", keyword.prefix).into_boxed_str());
+            let prefix = keyword.prefix.clone();
+            let description = keyword.description;
+
+            ActionItem::new(
+                ActionId::Builtin(id),
+                prefix.clone(),
+                KeywordHandler,
+                move || {
+                    div()
+                        .flex()
+                        .gap_4()
+                        .child(div().flex_none().child(prefix.trim_end().to_string()))
+                        .child(
+                            div()
+                                .flex_grow()
+                                .overflow_hidden()
+                                .child(description.clone())
+                                .text_color(text_secondary_color),
+                        )
+                        .into_any()
+                },
+                // Keep the order handlers were registered in
+                count - index,
+                1,
+                db.clone(),
+            )
+            .with_completion(keyword.prefix)
+        })
+        .collect()
+}
+
+/// Keyword rows are completed into the input instead of being run
+#[derive(Clone)]
+struct KeywordHandler;
+
+impl ActionHandler for KeywordHandler {
+    fn execute(&self, _input: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,87 @@
+//! Handlers that are still computing results in the background. While one
+//! is, its section shows a placeholder row and the other handlers' results
+//! are listed as usual. The handler refreshes the list once it's done.
+
+use anyhow::Result;
+use gpui::{div, Element, ParentElement, Rgba, Styled};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::actions::action_handler::{ActionHandler, ActionId, ActionItem};
+use crate::database::Database;
+
+lazy_static::lazy_static! {
+    /// Running background tasks per handler
+    static ref LOADING: Mutex<HashMap<&'static str, usize>> = Mutex::new(HashMap::new());
+}
+
+/// Call before spawning a background task, and `finish` when it's done
+pub fn start(handler_id: &'static str) {
+    *LOADING.lock().unwrap().entry(handler_id).or_default() += 1;
+}
+
+pub fn finish(handler_id: &'static str) {
+    let mut loading = LOADING.lock().unwrap();
+    if let Some(count) = loading.get_mut(handler_id) {
+        *count -= 1;
+        if *count == 0 {
+            loading.remove(handler_id);
+        }
+    }
+}
+
+pub fn is_loading(handler_id: &'static str) -> bool {
+    LOADING.lock().unwrap().contains_key(handler_id)
+}
+
+#[derive(Clone)]
+struct LoadingHandler;
+
+impl ActionHandler for LoadingHandler {
+    fn execute(&self, _input: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn clone_box(&self) -> Box<dyn ActionHandler> {
+        Box::new(self.clone())
+    }
+}
+
+/// Row listed after the handler's results while it's loading
+pub fn placeholder(
+    handler_id: &'static str,
+    db: Arc<Database>,
+    text_secondary_color: Rgba,
+) -> ActionItem {
+    let id = Box::leak(format!("{}-loading", handler_id).into_boxed_str());
+    let detail = format!("Waiting for {}", handler_id);
+
+    ActionItem::new(
+        ActionId::Builtin(id),
+        "Loading…".to_string(),
+        LoadingHandler,
+        move || {
+            div()
+                .flex()
+                .gap_4()
+                .child(
+                    div()
+                        .flex_none()
+                        .child("Loading…")
+                        .text_color(text_secondary_color),
+                )
+                .child(
+                    div()
+                        .flex_grow()
+                        .overflow_hidden()
+                        .child(detail.clone())
+                        .text_color(text_secondary_color),
+                )
+                .into_any()
+        },
+        0,
+        0,
+        db,
+    )
+    .staying_open()
+}
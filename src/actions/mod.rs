@@ -1,5 +1,11 @@
 pub mod action_ids;
 pub mod action_handler;
+pub mod data_access;
 pub mod handlers;
+pub mod highlight;
+pub mod keywords;
+pub mod loading;
 pub mod registry;
 pub mod scanner;
+pub mod score;
+pub mod watcher;
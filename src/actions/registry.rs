@@ -1,25 +1,59 @@
 use crate::action_list_view::ActionListView;
 use crate::actions::action_handler::ActionItem;
 use crate::actions::handlers::{
+    audio_handler::AudioHandlerFactory, bitwarden_handler::BitwardenHandlerFactory,
     browser_history_handler::BrowserHistoryHandlerFactory,
-    duckduckgo_handler::DuckDuckGoHandlerFactory, google_handler::GoogleHandlerFactory,
-    perplexity_handler::PerplexityHandlerFactory, url_handler::UrlHandlerFactory,
-    yandex_handler::YandexHandlerFactory,
+    browser_tabs_handler::BrowserTabsHandlerFactory, color_handler::ColorHandlerFactory,
+    display_handler::DisplayHandlerFactory, docker_handler::DockerHandlerFactory,
+    docs_handler::DocsHandlerFactory, duckduckgo_handler::DuckDuckGoHandlerFactory,
+    encoding_handler::EncodingHandlerFactory, file_search_handler::FileSearchHandlerFactory,
+    flathub_handler::FlathubHandlerFactory, format_handler::FormatHandlerFactory,
+    generator_handler::GeneratorHandlerFactory, git_repo_handler::GitRepoHandlerFactory,
+    github_handler::GithubHandlerFactory, google_handler::GoogleHandlerFactory,
+    ip_lookup_handler::IpLookupHandlerFactory, link_group_handler::LinkGroupHandlerFactory,
+    nix_handler::NixHandlerFactory, notes_handler::NotesHandlerFactory,
+    open_with_handler::OpenWithHandlerFactory, path_handler::PathHandlerFactory,
+    perplexity_handler::PerplexityHandlerFactory, plugin_handler::PluginHandlerFactory,
+    power_handler::PowerHandlerFactory, reminders_handler::RemindersHandlerFactory,
+    remote_handler::RemoteHandlerFactory, resources_handler::ResourcesHandlerFactory,
+    search_engine_handler::SearchEngineHandlerFactory, shell_handler::ShellHandlerFactory,
+    shortcut_handler::ShortcutHandlerFactory, text_case_handler::TextCaseHandlerFactory,
+    timestamp_handler::TimestampHandlerFactory, unicode_handler::UnicodeHandlerFactory,
+    url_handler::UrlHandlerFactory, wifi_handler::WifiHandlerFactory,
+    workspace_handler::WorkspaceHandlerFactory, world_clock_handler::WorldClockHandlerFactory,
+    yandex_handler::YandexHandlerFactory, zoxide_handler::ZoxideHandlerFactory,
 };
+use crate::cli;
+use crate::config::Config;
 use crate::database::Database;
+use crate::lock;
+use crate::plugins;
 use gpui::Context;
-use log::info;
+use log::{info, warn};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
+use url::Url;
 
-use super::action_handler::HandlerFactory;
+use super::action_handler::{HandlerFactory, Keyword};
+use super::data_access;
 use super::handlers::executable_handler::AppHandlerFactory;
+use super::keywords::{self, HELP_PREFIX};
+use super::loading;
 use super::scanner::ActionScanner;
+use super::score;
 use crate::database::ActionHandlerModel;
 
+/// Rows per group when `group_limits` doesn't set one
+const DEFAULT_GROUP_LIMIT: usize = 3;
+
 pub struct ActionRegistry {
     db: Arc<Database>,
     filtered_actions: Vec<ActionItem>,
     handler_factories: Vec<Box<dyn HandlerFactory>>,
+    /// Groups only showing their best result, see `toggle_group`
+    collapsed_groups: HashSet<&'static str>,
+    /// Results cut off by the group limits, per group
+    hidden_counts: HashMap<&'static str, usize>,
 }
 
 impl ActionRegistry {
@@ -30,28 +64,57 @@ impl ActionRegistry {
             db: db.clone(),
             filtered_actions: Vec::new(),
             handler_factories: Vec::new(),
+            collapsed_groups: HashSet::new(),
+            hidden_counts: HashMap::new(),
         };
 
-        registry.lazy_register_factories();
+        registry.lazy_register_factories(cx.global::<Config>());
         registry.set_filter("", cx);
 
         registry
     }
 
-    fn lazy_register_factories(&mut self) {
-        let factories: Vec<Box<dyn HandlerFactory>> = vec![
-            Box::new(AppHandlerFactory),
-            Box::new(UrlHandlerFactory),
-            Box::new(BrowserHistoryHandlerFactory),
-            Box::new(GoogleHandlerFactory),
-            Box::new(PerplexityHandlerFactory),
-            Box::new(DuckDuckGoHandlerFactory),
-            Box::new(YandexHandlerFactory),
-        ];
+    fn lazy_register_factories(&mut self, config: &Config) {
+        let mut factories = all_factories(config);
+
+        // `--handler` only asks one handler, whether it's enabled or not
+        if let Some(handler) = &cli::args().handler {
+            factories.retain(|factory| factory.get_id() == handler);
+            if factories.is_empty() {
+                warn!("Unknown handler {:?} given with --handler", handler);
+            }
+            if !config.restricted_mode {
+                self.handler_factories = factories;
+                return;
+            }
+        }
+
+        // Restricted mode only offers whitelisted handlers, regardless of what
+        // has been enabled in the database
+        if config.restricted_mode {
+            self.handler_factories = factories
+                .into_iter()
+                .filter(|factory| {
+                    config
+                        .restricted_handlers
+                        .iter()
+                        .any(|id| id == factory.get_id())
+                })
+                .collect();
+            return;
+        }
 
         for factory in factories {
             let id = factory.get_id();
             let _ = ActionHandlerModel::insert(self.db.connection(), id);
+            // `[handlers.<id>] enabled` wins over `:enable` and `:disable`
+            let enabled = config
+                .handlers
+                .get(id)
+                .and_then(|settings| settings.enabled);
+            if let Some(enabled) = enabled {
+                let _ = self.db.set_handler_enabled(id, enabled);
+            }
             
             let active_handlers = ActionHandlerModel::get_active_handlers(self.db.connection())
                 .unwrap_or_default();
@@ -61,24 +124,76 @@ impl ActionRegistry {
         }
     }
 
+    /// Enabled handlers reading sensitive data, what they read and whether
+    /// they may, `None` if the user hasn't been asked yet
+    pub fn data_access_states(&self) -> Vec<(&'static str, &'static str, Option<bool>)> {
+        self.handler_factories
+            .iter()
+            .filter_map(|factory| {
+                let data = factory.data_access()?;
+                let access = self.db.get_data_access(factory.get_id()).ok().flatten();
+                Some((factory.get_id(), data, access))
+            })
+            .collect()
+    }
+
+    /// Enabled handlers with their prefixes, bangs and keywords, for the
+    /// help view
+    pub fn handler_keywords(&self, config: &Config) -> Vec<(&'static str, Vec<Keyword>)> {
+        self.handler_factories
+            .iter()
+            .map(|factory| {
+                let mut keywords = factory.keywords();
+                keywords.extend(factory.bangs().into_iter().map(bang_keyword));
+                keywords.extend(handler_keyword(factory.as_ref(), config).map(scope_keyword));
+                (factory.get_id(), keywords)
+            })
+            .collect()
+    }
+
+    /// Every handler and plugin with its enable state
+    pub fn handler_states(&self) -> Vec<(String, bool)> {
+        for plugin in plugins::plugins() {
+            let _ = ActionHandlerModel::insert(self.db.connection(), &plugin.id);
+        }
+        self.db.get_handlers().unwrap_or_default()
+    }
+
+    pub fn has_handlers(&self) -> bool {
+        !self.handler_factories.is_empty()
+    }
+
+    pub fn is_scanning(&self) -> bool {
+        ActionScanner::is_scanning()
+    }
+
     pub fn needs_scan(&self) -> bool {
         ActionScanner::needs_scan(self.db.connection())
     }
 
     pub fn scan(&self, cx: &mut Context<ActionListView>) {
         if ActionScanner::needs_scan(self.db.connection()) {
-            info!("Starting background system scan");
-            let db = self.db.clone();
-            cx.spawn(|view, mut cx| async move {
-                ActionScanner::scan_system(&db);
-                let _ = view.update(&mut cx, |_this, cx| {
-                    cx.notify();
-                });
-            })
-            .detach();
+            self.rescan(cx);
         }
     }
 
+    /// Scans off the UI thread with its own connection and refreshes the
+    /// results once it's done
+    pub fn rescan(&self, cx: &mut Context<ActionListView>) {
+        info!("Starting background system scan");
+        cx.spawn(|view, mut cx| async move {
+            let scanned = cx
+                .background_executor()
+                .spawn(async { Database::new().map(|db| ActionScanner::scan_system(&db)) })
+                .await;
+            if let Err(e) = scanned {
+                warn!("Failed to open database for the system scan: {:#}", e);
+            }
+            let _ = view.update(&mut cx, |this, cx| this.refresh(cx));
+        })
+        .detach();
+    }
+
     pub fn register_factory(&mut self, factory: Box<dyn HandlerFactory>) {
         let id = factory.get_id();
         let _ = ActionHandlerModel::insert(self.db.connection(), id);
@@ -92,22 +207,338 @@ impl ActionRegistry {
 
     pub fn set_filter(&mut self, filter: &str, cx: &mut Context<ActionListView>) {
         let mut combined_handlers = Vec::new();
+        let config = cx.global::<Config>();
+        let allowed_actions = config
+            .restricted_mode
+            .then(|| config.restricted_actions.clone())
+            .filter(|actions| !actions.is_empty());
+        let text_secondary_color = config.text_secondary_color;
+        let group_results = config.group_results;
+        let group_limits = config.group_limits.clone();
+        let max_results = config.max_results;
+        let tuning: Vec<(Option<usize>, f32)> = self
+            .handler_factories
+            .iter()
+            .map(|factory| {
+                let id = factory.get_id();
+                (config.handler_limit(id), config.handler_boost(id))
+            })
+            .collect();
 
-        for factory in &self.handler_factories {
-            combined_handlers.extend(factory.create_handlers_for_query(
-                filter,
+        if let Some(keyword_filter) = filter.strip_prefix(HELP_PREFIX) {
+            let mut keywords = keywords::mode_keywords(!config.restricted_mode);
+            keywords.extend(
+                self.handler_factories
+                    .iter()
+                    .flat_map(|factory| factory.keywords()),
+            );
+            keywords.extend(
+                self.handler_factories
+                    .iter()
+                    .flat_map(|factory| factory.bangs().into_iter().map(bang_keyword)),
+            );
+            keywords.extend(self.handler_factories.iter().filter_map(|factory| {
+                handler_keyword(factory.as_ref(), config).map(scope_keyword)
+            }));
+            self.filtered_actions = keywords::create_items(
+                keywords,
+                keyword_filter,
                 self.db.clone(),
-                cx,
-            ));
+                config.text_secondary_color,
+            );
+            return;
+        }
+
+        // `!g rust gpui` only asks the engine with that bang, for "rust gpui",
+        // and `h rust` only the handler with the keyword `h`. Handlers can
+        // also take over the list, like the path navigator.
+        let bang = filter.strip_prefix('!').map(|rest| {
+            rest.split_once(' ')
+                .map_or((rest, ""), |(bang, terms)| (bang, terms.trim()))
+        });
+        let scoped = self.handler_factories.iter().find_map(|factory| {
+            let keyword = handler_keyword(factory.as_ref(), config)?;
+            let terms = filter.strip_prefix(keyword)?.strip_prefix(' ')?.trim();
+            // Without consent the query goes to every handler, which asks for it
+            let consented = factory.data_access().is_none()
+                || matches!(self.db.get_data_access(factory.get_id()), Ok(Some(true)));
+            consented.then(|| (factory, factory.keyword_query(terms), Some(terms)))
+        });
+        let exclusive = scoped.or_else(|| {
+            self.handler_factories
+                .iter()
+                .find_map(|factory| match bang {
+                    Some((bang, terms)) if factory.bangs().iter().any(|known| known == bang) => {
+                        Some((factory, terms.to_string(), Some(terms)))
+                    }
+                    _ if factory.claims_query(filter) => Some((factory, filter.to_string(), None)),
+                    _ => None,
+                })
+        });
+        if let Some((factory, query, input)) = exclusive {
+            let locked = lock::is_handler_locked(factory.get_id());
+            let mut items: Vec<ActionItem> = factory
+                .create_handlers_for_query(&query, self.db.clone(), cx)
+                .into_iter()
+                .map(|mut item| {
+                    item.locked = locked;
+                    match input {
+                        Some(input) => item.with_input(input.to_string()),
+                        None => item,
+                    }
+                })
+                .collect();
+            if let Some(allowed_actions) = allowed_actions {
+                items.retain(|item| allowed_actions.contains(&item.name));
+            }
+            items.sort();
+            items.truncate(max_results);
+            self.filtered_actions = items;
+            return;
         }
 
+        let mut prompts = Vec::new();
+        for (factory, (limit, boost)) in self.handler_factories.iter().zip(tuning) {
+            if let Some(data) = factory.data_access() {
+                match self.db.get_data_access(factory.get_id()) {
+                    Ok(Some(true)) => {}
+                    Ok(None) => {
+                        prompts.extend(data_access::prompt(
+                            factory.get_id(),
+                            data,
+                            self.db.clone(),
+                            text_secondary_color,
+                        ));
+                        continue;
+                    }
+                    Ok(Some(false)) | Err(_) => continue,
+                }
+            }
+
+            let locked = lock::is_handler_locked(factory.get_id());
+            let group = group_results.then(|| factory.group());
+            let mut items: Vec<ActionItem> = factory
+                .create_handlers_for_query(filter, self.db.clone(), cx)
+                .into_iter()
+                .map(|mut item| {
+                    item.locked = locked;
+                    // Sections like "Popular" are more specific
+                    item.section = item.section.or(group);
+                    if boost != 1.0 && item.relevance < score::FIXED_RELEVANCE {
+                        item.relevance = (item.relevance as f64 * boost as f64) as usize;
+                    }
+                    item
+                })
+                .collect();
+            if let Some(limit) = limit {
+                items.sort();
+                items.truncate(limit);
+            }
+            // Results arriving later refresh the list
+            if loading::is_loading(factory.get_id()) {
+                let mut placeholder =
+                    loading::placeholder(factory.get_id(), self.db.clone(), text_secondary_color);
+                placeholder.section = group;
+                items.push(placeholder);
+            }
+            combined_handlers.extend(items);
+        }
+
+        if let Some(allowed_actions) = allowed_actions {
+            combined_handlers.retain(|item| allowed_actions.contains(&item.name));
+            prompts.retain(|item| allowed_actions.contains(&item.name));
+        }
+
+        let mut combined_handlers = merge_equal_urls(combined_handlers);
         combined_handlers.sort();
+        if group_results {
+            combined_handlers = self.group_items(combined_handlers, &group_limits);
+        }
+        // Consent comes first, the handler's results are missing without it
+        let mut combined_handlers: Vec<ActionItem> =
+            prompts.into_iter().chain(combined_handlers).collect();
 
-        let end = combined_handlers.len().min(10);
-        self.filtered_actions = combined_handlers[0..end].to_vec();
+        // Groups have their own limits
+        if !group_results {
+            combined_handlers.truncate(max_results);
+        }
+        self.filtered_actions = combined_handlers;
+    }
+
+    /// Put the results of each group together, the group with the best result
+    /// first, and cut every group to its limit. Collapsed groups only keep
+    /// their best result.
+    fn group_items(
+        &mut self,
+        items: Vec<ActionItem>,
+        limits: &BTreeMap<String, usize>,
+    ) -> Vec<ActionItem> {
+        let mut groups: Vec<(&'static str, Vec<ActionItem>)> = Vec::new();
+        for item in items {
+            let section = item.section.unwrap_or_default();
+            match groups.iter_mut().find(|(group, _)| *group == section) {
+                Some((_, group_items)) => group_items.push(item),
+                None => groups.push((section, vec![item])),
+            }
+        }
+
+        let default_limit = limits
+            .get("default")
+            .copied()
+            .unwrap_or(DEFAULT_GROUP_LIMIT);
+        self.hidden_counts.clear();
+        let mut grouped = Vec::new();
+        for (group, mut group_items) in groups {
+            let limit = if self.collapsed_groups.contains(group) {
+                1
+            } else {
+                limits.get(group).copied().unwrap_or(default_limit)
+            };
+            if group_items.len() > limit {
+                self.hidden_counts.insert(group, group_items.len() - limit);
+                group_items.truncate(limit);
+            }
+            grouped.extend(group_items);
+        }
+        grouped
+    }
+
+    /// Collapse a group to its best result, or expand it again
+    pub fn toggle_group(&mut self, group: &'static str) {
+        if !self.collapsed_groups.remove(group) {
+            self.collapsed_groups.insert(group);
+        }
+    }
+
+    pub fn is_group_collapsed(&self, group: &str) -> bool {
+        self.collapsed_groups.contains(group)
+    }
+
+    /// Results of the group cut off by its limit
+    pub fn hidden_in_group(&self, group: &str) -> usize {
+        self.hidden_counts.get(group).copied().unwrap_or(0)
     }
 
     pub fn get_actions(&self) -> &Vec<ActionItem> {
         &self.filtered_actions
     }
 }
+
+/// Every built-in handler and the search engines of the config, enabled or not
+fn all_factories(config: &Config) -> Vec<Box<dyn HandlerFactory>> {
+    let mut factories: Vec<Box<dyn HandlerFactory>> = vec![
+        Box::new(AppHandlerFactory),
+        Box::new(UrlHandlerFactory),
+        Box::new(BrowserHistoryHandlerFactory),
+        Box::new(BrowserTabsHandlerFactory),
+        Box::new(FileSearchHandlerFactory),
+        Box::new(IpLookupHandlerFactory),
+        Box::new(FormatHandlerFactory),
+        Box::new(TimestampHandlerFactory),
+        Box::new(WorldClockHandlerFactory),
+        Box::new(ColorHandlerFactory),
+        Box::new(UnicodeHandlerFactory),
+        Box::new(EncodingHandlerFactory),
+        Box::new(TextCaseHandlerFactory),
+        Box::new(GeneratorHandlerFactory),
+        Box::new(ResourcesHandlerFactory),
+        Box::new(NotesHandlerFactory),
+        Box::new(RemoteHandlerFactory),
+        Box::new(RemindersHandlerFactory),
+        Box::new(PluginHandlerFactory),
+        Box::new(PowerHandlerFactory),
+        Box::new(WifiHandlerFactory),
+        Box::new(DisplayHandlerFactory),
+        Box::new(WorkspaceHandlerFactory),
+        Box::new(AudioHandlerFactory),
+        Box::new(BitwardenHandlerFactory),
+        Box::new(GithubHandlerFactory),
+        Box::new(DockerHandlerFactory),
+        Box::new(FlathubHandlerFactory),
+        Box::new(NixHandlerFactory),
+        Box::new(ZoxideHandlerFactory),
+        Box::new(GitRepoHandlerFactory),
+        Box::new(ShellHandlerFactory),
+        Box::new(PathHandlerFactory),
+        Box::new(OpenWithHandlerFactory),
+        Box::new(DocsHandlerFactory),
+        Box::new(LinkGroupHandlerFactory),
+        Box::new(ShortcutHandlerFactory),
+        Box::new(GoogleHandlerFactory),
+        Box::new(PerplexityHandlerFactory),
+        Box::new(DuckDuckGoHandlerFactory),
+        Box::new(YandexHandlerFactory),
+    ];
+    // Search engines from the config are registered like the built-in ones
+    for engine in &config.search_engines {
+        factories.push(Box::new(SearchEngineHandlerFactory::new(engine.clone())));
+    }
+    factories
+}
+
+/// Ids of every handler, for validating `[handlers.<id>]` sections
+pub fn handler_ids(config: &Config) -> Vec<&'static str> {
+    all_factories(config)
+        .iter()
+        .map(|factory| factory.get_id())
+        .collect()
+}
+
+/// Keep one row per address when several handlers open the same URL, e.g. a
+/// typed URL that is also in the browser history. The most relevant row is
+/// kept, as it carries the most details, like the page title.
+fn merge_equal_urls(items: Vec<ActionItem>) -> Vec<ActionItem> {
+    let mut merged: Vec<ActionItem> = Vec::with_capacity(items.len());
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for item in items {
+        let Some(key) = item.url.as_deref().and_then(normalize_url) else {
+            merged.push(item);
+            continue;
+        };
+        match positions.get(&key) {
+            Some(&position) if merged[position].relevance() < item.relevance() => {
+                merged[position] = item;
+            }
+            Some(_) => {}
+            None => {
+                positions.insert(key, merged.len());
+                merged.push(item);
+            }
+        }
+    }
+
+    merged
+}
+
+/// e.g. "example.com/page?q=1" for "https://www.Example.com/page/?q=1#top"
+fn normalize_url(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    let host = url.host_str()?;
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    let path = url.path().trim_end_matches('/');
+
+    Some(match url.query() {
+        Some(query) => format!("{}{}?{}", host, path, query),
+        None => format!("{}{}", host, path),
+    })
+}
+
+/// Word scoping queries to the handler, the `[handlers.<id>]` keyword over
+/// the handler's own. `keyword = ""` turns the handler's own keyword off.
+fn handler_keyword<'a>(factory: &dyn HandlerFactory, config: &'a Config) -> Option<&'a str> {
+    config
+        .handler_keyword(factory.get_id())
+        .or(factory.keyword())
+        .filter(|keyword| !keyword.is_empty())
+}
+
+/// `h ` for the keyword `h`
+fn scope_keyword(keyword: &str) -> Keyword {
+    Keyword::new(format!("{} ", keyword), "Only ask this handler")
+}
+
+/// `!g ` for the bang `g`
+fn bang_keyword(bang: String) -> Keyword {
+    Keyword::new(format!("!{} ", bang), "Search with this bang")
+}
@@ -1,28 +1,50 @@
 use crate::database::Database;
+use crate::demo;
+use crate::system::appimage::{self, scan_appimages};
 use crate::system::{scan_desktopentries, scan_path_executables};
-use log::info;
+use log::{info, warn};
 use rusqlite::Connection;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static SCANNING: AtomicBool = AtomicBool::new(false);
 
 pub struct ActionScanner;
 
 impl ActionScanner {
     pub fn needs_scan(conn: &Connection) -> bool {
-        // Check if we have any program or desktop entries
-        let count: i32 = conn
-            .query_row(
-                "SELECT COUNT(*) FROM actions WHERE action_type IN ('program', 'desktop')",
-                [],
-                |row| row.get(0),
-            )
-            .unwrap_or(0);
-
-        count == 0
+        // Check if we have any program or desktop entries. Applications
+        // without cached desktop files were found before the cache was
+        // cleared by a migration, which needs their files parsed again.
+        conn.query_row(
+            "SELECT NOT EXISTS (SELECT 1 FROM actions WHERE action_type IN ('program', 'desktop'))
+                OR (
+                    EXISTS (SELECT 1 FROM actions WHERE action_type = 'desktop')
+                    AND NOT EXISTS (SELECT 1 FROM desktop_entry_cache)
+                )",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(true)
+    }
+
+    pub fn is_scanning() -> bool {
+        SCANNING.load(Ordering::Relaxed)
     }
 
     pub fn scan_system(db: &Database) {
         info!("Starting system scan for actions");
+        SCANNING.store(true, Ordering::Relaxed);
         let scan_start = std::time::Instant::now();
 
+        if demo::is_enabled() {
+            if let Err(e) = demo::seed(db) {
+                warn!("Failed to fill the demo database: {}", e);
+            }
+            SCANNING.store(false, Ordering::Relaxed);
+            return;
+        }
+
         info!("Starting executable scan");
         let exec_start = std::time::Instant::now();
         let executables = scan_path_executables().unwrap_or_default();
@@ -33,11 +55,33 @@ impl ActionScanner {
             let _ = db.insert_binary(&elem.name, &elem.path.to_string_lossy());
         });
 
-        let applications = scan_desktopentries();
+        let desktop_start = std::time::Instant::now();
+        let (appimage_cache, cache): (Vec<_>, Vec<_>) = db
+            .get_desktop_entry_cache()
+            .unwrap_or_default()
+            .into_iter()
+            .partition(|cached| appimage::is_appimage(Path::new(&cached.path)));
+        let (mut applications, mut cache) = scan_desktopentries(cache);
+        info!("Desktop entry scan took {:?}", desktop_start.elapsed());
+
+        let appimage_start = std::time::Instant::now();
+        let (appimages, appimage_cache) = scan_appimages(appimage_cache);
+        info!(
+            "AppImage scan took {:?}, found {} AppImages",
+            appimage_start.elapsed(),
+            appimages.len()
+        );
+        applications.extend(appimages);
+        cache.extend(appimage_cache);
+
+        if let Err(e) = db.store_desktop_entry_cache(&cache) {
+            warn!("Failed to store desktop entry cache: {}", e);
+        }
         applications.iter().for_each(|elem| {
-            let _ = db.insert_application(&elem.name, &elem.exec);
+            let _ = db.insert_application(&elem.name, &elem.exec, &elem.search_keywords());
         });
 
+        SCANNING.store(false, Ordering::Relaxed);
         info!("System scan completed in {:?}", scan_start.elapsed());
     }
 }
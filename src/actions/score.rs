@@ -0,0 +1,146 @@
+//! Ranking shared by handlers whose results compete in the same list, so an
+//! often opened website and an often launched app end up in a sensible order.
+
+use std::ops::Range;
+
+/// Boost of results ranked with [`Score`]
+pub const RELEVANCE_BOOST: usize = 30;
+
+/// Relevances from here on place results at a fixed spot, like pinned
+/// actions above everything else, and aren't multiplied by handler boosts
+pub const FIXED_RELEVANCE: usize = usize::MAX / 128;
+
+/// How much a visit recorded by another program, e.g. a browser, counts
+/// compared to opening the result through crowbar
+const EXTERNAL_VISIT_WEIGHT: f64 = 0.1;
+
+/// Relevance of a result from how well it matches and how often it's used
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Score {
+    /// Time-decayed executions, see `Database::get_action_frecency`
+    pub frecency: f64,
+    /// Weight from [`match_quality`]
+    pub match_quality: f64,
+    /// Token score from [`search_score`]
+    pub search_score: f64,
+}
+
+impl Score {
+    pub fn new(frecency: f64, match_quality: f64, search_score: f64) -> Self {
+        Self {
+            frecency,
+            match_quality,
+            search_score,
+        }
+    }
+
+    /// Add visits counted outside of crowbar. They are log-scaled, so a few
+    /// launches through crowbar outweigh hundreds of visits.
+    pub fn with_external_visits(mut self, visits: i64) -> Self {
+        self.frecency += EXTERNAL_VISIT_WEIGHT * (visits.max(0) as f64).ln_1p();
+        self
+    }
+
+    /// Relevance to pass to `ActionItem::new` together with [`RELEVANCE_BOOST`]
+    pub fn relevance(&self) -> usize {
+        (self.frecency * self.match_quality * (1.0 + self.search_score)) as usize
+    }
+}
+
+/// Weight of how the filter matches a text, the same tiers the executable
+/// search uses
+pub fn match_quality(filter: &str, text: &str) -> f64 {
+    let text = text.to_lowercase();
+    if text == filter {
+        100.0
+    } else if text.starts_with(filter) {
+        50.0
+    } else if text.contains(filter) {
+        10.0
+    } else {
+        1.0
+    }
+}
+
+/// Share of filter tokens found in the text, with a bonus for tokens at the
+/// start of words
+pub fn search_score(filter_tokens: &[&str], text: &str) -> f64 {
+    if filter_tokens.is_empty() {
+        return 0.0;
+    }
+
+    // Count how many tokens match
+    let text = text.to_lowercase();
+    let mut matched_tokens = 0.0;
+
+    for token in filter_tokens {
+        // Check if token is in the text
+        if text.contains(token) {
+            matched_tokens += 1.0;
+
+            // Bonus for tokens that are at the start of words
+            if text.starts_with(token) {
+                matched_tokens += 0.5;
+            } else if text.split_whitespace().any(|word| word.starts_with(token)) {
+                matched_tokens += 0.3;
+            }
+        }
+    }
+
+    // Calculate the final score as a percentage of matched tokens
+    matched_tokens / filter_tokens.len() as f64
+}
+
+/// Byte ranges of `text` matched by the filter, for highlighting. Every
+/// token is looked up as a substring first and as a subsequence otherwise,
+/// like fzf does, ignoring case.
+pub fn match_ranges(filter: &str, text: &str) -> Vec<Range<usize>> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let char_range = |index: usize| {
+        let (start, c) = chars[index];
+        start..start + c.len_utf8()
+    };
+    let matches =
+        |index: usize, wanted: char| chars[index].1.to_lowercase().eq(wanted.to_lowercase());
+
+    let mut ranges = Vec::new();
+    for token in filter.split_whitespace() {
+        let token: Vec<char> = token.chars().collect();
+        let substring = (0..chars.len().saturating_sub(token.len() - 1)).find(|&start| {
+            token
+                .iter()
+                .enumerate()
+                .all(|(offset, &wanted)| matches(start + offset, wanted))
+        });
+        if let Some(start) = substring {
+            ranges.push(char_range(start).start..char_range(start + token.len() - 1).end);
+            continue;
+        }
+
+        let mut positions = Vec::new();
+        let mut next = 0;
+        for &wanted in &token {
+            match (next..chars.len()).find(|&index| matches(index, wanted)) {
+                Some(index) => {
+                    positions.push(char_range(index));
+                    next = index + 1;
+                }
+                None => break,
+            }
+        }
+        if positions.len() == token.len() {
+            ranges.extend(positions);
+        }
+    }
+
+    // Merge overlapping and adjacent ranges
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
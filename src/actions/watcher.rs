@@ -0,0 +1,205 @@
+//! Keeps applications and executables current while Crowbar runs.
+//!
+//! The directories in `PATH` and those with desktop files are watched with
+//! inotify. Changes are collected until none came for `SETTLE_TIME`, so
+//! installing a package updates the database once, and only the files that
+//! changed are read again. Directories created later, like a new
+//! `~/.local/bin`, are picked up by the next `:rescan`.
+
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsStr;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use inotify::{Event, EventMask, Inotify, WatchDescriptor, WatchMask};
+use log::{info, warn};
+
+use crate::actions::scanner::ActionScanner;
+use crate::config::Config;
+use crate::database::Database;
+use crate::demo;
+use crate::system::app_finder::{desktop_entry_dirs, read_desktop_file};
+use crate::system::executable_finder::{executable_dirs, executable_info};
+
+/// Quiet time after the last change before the database is updated
+const SETTLE_TIME: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Copy)]
+enum DirKind {
+    Executables,
+    DesktopFiles,
+}
+
+type Watched = HashMap<WatchDescriptor, (PathBuf, DirKind)>;
+
+/// Start watching if `watch_applications` is enabled
+pub fn init(config: &Config) {
+    if !config.watch_applications || demo::is_enabled() {
+        return;
+    }
+
+    match watch() {
+        Ok((inotify, watched)) => {
+            info!("Watching {} application directories", watched.len());
+            thread::spawn(move || run(inotify, watched));
+        }
+        Err(e) => warn!("Failed to watch application directories: {:#}", e),
+    }
+}
+
+fn watch() -> Result<(Inotify, Watched)> {
+    let inotify = Inotify::init().context("Failed to initialize inotify")?;
+    let mask = WatchMask::CREATE
+        | WatchMask::DELETE
+        | WatchMask::MOVED_FROM
+        | WatchMask::MOVED_TO
+        | WatchMask::CLOSE_WRITE
+        | WatchMask::ATTRIB;
+
+    let dirs = executable_dirs()
+        .into_iter()
+        .map(|dir| (dir, DirKind::Executables))
+        .chain(
+            desktop_entry_dirs()
+                .into_iter()
+                .map(|dir| (dir, DirKind::DesktopFiles)),
+        );
+    let mut watched = HashMap::new();
+    for (dir, kind) in dirs {
+        match inotify.watches().add(&dir, mask) {
+            Ok(descriptor) => {
+                watched.insert(descriptor, (dir, kind));
+            }
+            Err(e) => info!("Not watching {:?}: {}", dir, e),
+        }
+    }
+    Ok((inotify, watched))
+}
+
+fn run(mut inotify: Inotify, watched: Watched) {
+    let mut buffer = [0; 4096];
+
+    loop {
+        let mut changes = Changes::default();
+        match inotify.read_events_blocking(&mut buffer) {
+            Ok(events) => events.for_each(|event| changes.add(&watched, &event)),
+            Err(e) => {
+                warn!("Stopped watching application directories: {}", e);
+                return;
+            }
+        }
+
+        loop {
+            thread::sleep(SETTLE_TIME);
+            let count = match inotify.read_events(&mut buffer) {
+                Ok(events) => events.map(|event| changes.add(&watched, &event)).count(),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => 0,
+                Err(e) => {
+                    warn!("Stopped watching application directories: {}", e);
+                    return;
+                }
+            };
+            if count == 0 {
+                break;
+            }
+        }
+
+        // Opened only now, the view has migrated the database by then
+        match Database::new() {
+            Ok(db) => changes.apply(&db),
+            Err(e) => warn!("Failed to open the database for watching: {:#}", e),
+        }
+    }
+}
+
+/// What changed since the database was last updated
+#[derive(Debug, Default)]
+struct Changes {
+    /// Files created or changed in executable directories
+    executables: HashSet<PathBuf>,
+    executables_removed: bool,
+    /// Desktop files created, changed or removed
+    desktop_files: HashSet<PathBuf>,
+    /// The kernel dropped events, everything is scanned again
+    overflow: bool,
+}
+
+impl Changes {
+    fn add(&mut self, watched: &Watched, event: &Event<&OsStr>) {
+        if event.mask.contains(EventMask::Q_OVERFLOW) {
+            self.overflow = true;
+            return;
+        }
+        if event.mask.contains(EventMask::ISDIR) {
+            return;
+        }
+        let (Some((dir, kind)), Some(name)) = (watched.get(&event.wd), event.name) else {
+            return;
+        };
+
+        let path = dir.join(name);
+        let removed = event
+            .mask
+            .intersects(EventMask::DELETE | EventMask::MOVED_FROM);
+        match kind {
+            DirKind::Executables if removed => self.executables_removed = true,
+            DirKind::Executables => {
+                self.executables.insert(path);
+            }
+            DirKind::DesktopFiles => {
+                if path.extension() == Some(OsStr::new("desktop")) {
+                    self.desktop_files.insert(path);
+                }
+            }
+        }
+    }
+
+    fn apply(self, db: &Database) {
+        if self.overflow {
+            ActionScanner::scan_system(db);
+        }
+
+        for path in &self.executables {
+            if let Some(info) = executable_info(path) {
+                let _ = db.insert_binary(&info.name, &info.path.to_string_lossy());
+            }
+        }
+        if self.executables_removed || self.overflow {
+            if let Err(e) = db.remove_missing_binaries() {
+                warn!("Failed to remove deleted executables: {:#}", e);
+            }
+        }
+
+        for path in &self.desktop_files {
+            if let Err(e) = update_desktop_file(db, path) {
+                warn!("Failed to update desktop file {:?}: {:#}", path, e);
+            }
+        }
+        info!(
+            "Updated {} executables and {} desktop files",
+            self.executables.len(),
+            self.desktop_files.len()
+        );
+    }
+}
+
+/// Read the desktop file again, or forget it if it was removed
+fn update_desktop_file(db: &Database, path: &Path) -> Result<()> {
+    let previous = db.remove_desktop_entry(&path.to_string_lossy())?;
+    if path.exists() {
+        let cached = read_desktop_file(path);
+        db.store_desktop_entry(&cached)?;
+        if let Some(entry) = &cached.entry {
+            db.insert_application(&entry.name, &entry.exec, &entry.search_keywords())?;
+        }
+    }
+
+    // The application is gone or was renamed, unless another file has it
+    if let Some(entry) = previous.and_then(|cached| cached.entry) {
+        db.remove_application(&entry.name, &entry.exec)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,21 @@
+//! Whether the window is active. Background work like the status bar
+//! scheduler, plugin runs and searches by slower handlers only starts while
+//! it is, and catches up once the window is shown again.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static IDLE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Activity {
+    Active,
+    Idle,
+}
+
+pub fn set(activity: Activity) {
+    IDLE.store(activity == Activity::Idle, Ordering::Relaxed);
+}
+
+pub fn is_active() -> bool {
+    !IDLE.load(Ordering::Relaxed)
+}
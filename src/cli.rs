@@ -0,0 +1,186 @@
+//! Command-line flags overriding the config for a single run, e.g. from a
+//! window manager key binding or a script.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::Result;
+use clap::Parser;
+use gpui::App;
+use log::warn;
+
+use crate::actions::scanner::ActionScanner;
+use crate::config::Config;
+use crate::database::{self, Database};
+use crate::lock;
+
+static ARGS: OnceLock<Args> = OnceLock::new();
+
+#[derive(Parser, Debug, Default)]
+#[command(version, about = "A fast application launcher")]
+pub struct Args {
+    /// Start with this query typed in
+    #[arg(long)]
+    pub query: Option<String>,
+    /// Window width in pixels, over `window_width`
+    #[arg(long)]
+    pub width: Option<f32>,
+    /// Window height in pixels, over `window_height`
+    #[arg(long)]
+    pub height: Option<f32>,
+    /// Theme file from `themes/`, over `theme` and `:theme`
+    #[arg(long, value_name = "NAME")]
+    pub theme: Option<String>,
+    /// Config file to read instead of `~/.config/crowbar/crowbar.toml`
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+    /// Only ask the handler with this id, whether it's enabled or not
+    #[arg(long, value_name = "ID")]
+    pub handler: Option<String>,
+    /// Show a fixed fake dataset, see `demo`
+    #[arg(long)]
+    pub demo: bool,
+    /// Check the config files and exit without opening the window
+    #[arg(long)]
+    pub check_config: bool,
+    /// Write the usage history, pins and handler settings to a JSON file
+    #[arg(long, value_name = "PATH", conflicts_with = "import_data")]
+    pub export_data: Option<PathBuf>,
+    /// Merge a file written by `--export-data` into the database
+    #[arg(long, value_name = "PATH")]
+    pub import_data: Option<PathBuf>,
+    /// Undo the database schema versions after this one and exit, before
+    /// going back to an older Crowbar
+    #[arg(long, value_name = "VERSION")]
+    pub migrate_down: Option<i32>,
+    /// Read a passphrase from stdin and print the hash for
+    /// `lock_passphrase_hash`
+    #[arg(long)]
+    pub hash_passphrase: bool,
+}
+
+/// Parse the flags of this run, exits with the usage on invalid ones
+pub fn parse() -> &'static Args {
+    ARGS.get_or_init(Args::parse)
+}
+
+/// Flags of this run, none before `parse`
+pub fn args() -> &'static Args {
+    ARGS.get_or_init(Args::default)
+}
+
+/// Apply the window size and theme flags to the loaded config
+pub fn apply(cx: &mut App) {
+    let args = args();
+    let mut config = cx.global::<Config>().clone();
+    if let Some(width) = args.width {
+        config.window_width = width;
+    }
+    if let Some(height) = args.height {
+        config.window_height = height;
+    }
+    if let Some(theme) = &args.theme {
+        match config.with_theme(theme) {
+            Ok(themed) => config = themed,
+            Err(e) => warn!("Failed to load theme {}: {:#}", theme, e),
+        }
+    }
+    cx.set_global(config);
+}
+
+/// Run `--export-data` or `--import-data` and print the outcome, `None` if
+/// neither was given
+pub fn transfer_data() -> Option<bool> {
+    let args = args();
+    let result = match (&args.export_data, &args.import_data) {
+        (Some(path), _) => export_data(path),
+        (None, Some(path)) => import_data(path),
+        (None, None) => return None,
+    };
+    match result {
+        Ok(message) => {
+            println!("{}", message);
+            Some(true)
+        }
+        Err(e) => {
+            eprintln!("{:#}", e);
+            Some(false)
+        }
+    }
+}
+
+/// Run `--migrate-down` and print the outcome, `None` if it wasn't given
+pub fn migrate_down() -> Option<bool> {
+    let version = args().migrate_down?;
+    let config = Config::cached();
+    database::init(&config);
+    database::backup::init(&config);
+
+    match Database::migrate_down(version) {
+        Ok(previous) => {
+            println!(
+                "Migrated the database from schema version {} down to {}",
+                previous, version
+            );
+            Some(true)
+        }
+        Err(e) => {
+            eprintln!("{:#}", e);
+            Some(false)
+        }
+    }
+}
+
+/// Run `--hash-passphrase` and print the hash, `None` if it wasn't given
+pub fn hash_passphrase() -> Option<bool> {
+    if !args().hash_passphrase {
+        return None;
+    }
+
+    let mut passphrase = String::new();
+    let result = io::stdin()
+        .read_line(&mut passphrase)
+        .map_err(anyhow::Error::from)
+        .and_then(|_| lock::hash_passphrase(passphrase.trim_end_matches(['\r', '\n'])));
+    match result {
+        Ok(hash) => {
+            println!("{}", hash);
+            Some(true)
+        }
+        Err(e) => {
+            eprintln!("{:#}", e);
+            Some(false)
+        }
+    }
+}
+
+fn export_data(path: &Path) -> Result<String> {
+    database::init(&Config::cached());
+    let count = Database::new()?.export_data(path)?;
+    Ok(format!("Exported {} actions to {}", count, path.display()))
+}
+
+fn import_data(path: &Path) -> Result<String> {
+    database::init(&Config::cached());
+    let db = Database::new()?;
+    // On a new machine the applications aren't known yet
+    if ActionScanner::needs_scan(db.connection()) {
+        ActionScanner::scan_system(&db);
+    }
+
+    let summary = db.import_data(path)?;
+    let mut message = format!(
+        "Imported {} actions with {} executions from {}",
+        summary.actions,
+        summary.executions,
+        path.display()
+    );
+    if summary.missing > 0 {
+        message.push_str(&format!(
+            ", {} applications or executables aren't installed",
+            summary.missing
+        ));
+    }
+    Ok(message)
+}
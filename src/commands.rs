@@ -1,20 +1,46 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use anyhow::{anyhow, Context, Result};
+
+use crate::actions::scanner::ActionScanner;
+use crate::common::expand_tilde;
+use crate::config::{self, ResultView};
 use crate::database::Database;
+use crate::lock;
+use crate::remote::{self, LOCAL_PROFILE};
+
+const DEFAULT_NOTES_EXPORT: &str = "~/crowbar-notes.md";
+
+const DEFAULT_DATA_EXPORT: &str = "~/crowbar-data.json";
+
+/// Preference storing the view chosen with `:view`, over `result_view`
+pub const VIEW_PREFERENCE: &str = "result_view";
+
+/// Preference storing the theme chosen with `:theme`, over `theme`
+pub const THEME_PREFERENCE: &str = "theme";
 
-pub type CommandFn = Arc<dyn Fn(&[&str]) -> String + Send + Sync>;
+/// Preference set by `:debug scores`, see `ActionListView::show_scores`
+pub const SCORES_PREFERENCE: &str = "debug_scores";
+
+pub type CommandFn = Arc<dyn Fn(&[&str]) -> Result<String> + Send + Sync>;
 
 // Command definition struct to easily register commands
 pub struct CommandDefinition {
     pub name: &'static str,
-    pub handler: fn(&[&str]) -> String,
+    pub handler: fn(&[&str]) -> Result<String>,
 }
 
 pub struct CommandRegistry {
     commands: HashMap<String, CommandFn>,
 }
 
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CommandRegistry {
     pub fn new() -> Self {
         let mut registry = Self {
@@ -31,15 +57,30 @@ impl CommandRegistry {
             .unwrap_or(command_line)
             .trim();
 
-        let args = command_line.split_whitespace().collect::<Vec<&str>>();
+        // Line breaks of multi-line input stay in the arguments, like in notes
+        let args = command_line
+            .split([' ', '\t'])
+            .filter(|arg| !arg.is_empty())
+            .collect::<Vec<&str>>();
         let command = args[0];
         let args = &args[1..];
 
-        let result = self.commands.get(command).unwrap()(args);
+        if lock::is_command_locked(command) {
+            return CommandResult {
+                success: false,
+                message: "Command is locked, use :unlock <passphrase>".to_string(),
+            };
+        }
 
-        CommandResult {
-            success: true,
-            message: result,
+        match self.commands.get(command).unwrap()(args) {
+            Ok(message) => CommandResult {
+                success: true,
+                message,
+            },
+            Err(e) => CommandResult {
+                success: false,
+                message: format!("{:#}", e),
+            },
         }
     }
 
@@ -55,7 +96,7 @@ impl CommandRegistry {
                 handler: |args| {
                     let db = Arc::new(Database::new().unwrap());
                     let _ = db.set_handler_enabled(args[0], false);
-                    "Disable a module".to_string()
+                    Ok("Disable a module".to_string())
                 },
             },
             CommandDefinition {
@@ -63,7 +104,225 @@ impl CommandRegistry {
                 handler: |args| {
                     let db = Arc::new(Database::new().unwrap());
                     let _ = db.set_handler_enabled(args[0], true);
-                    "Enable a module".to_string()
+                    Ok("Enable a module".to_string())
+                },
+            },
+            CommandDefinition {
+                name: "handlers",
+                handler: |_| Ok("List modules and plugins".to_string()),
+            },
+            CommandDefinition {
+                name: "debug",
+                handler: |args| match args {
+                    ["scores"] => {
+                        let db = Database::new().unwrap();
+                        let shown = db.get_preference(SCORES_PREFERENCE).ok().flatten();
+                        let show = shown.as_deref() != Some("on");
+                        let value = if show { "on" } else { "off" };
+                        db.set_preference(SCORES_PREFERENCE, value)
+                            .context("Failed to toggle the score overlay")?;
+                        Ok(format!("Score overlay {}", value))
+                    }
+                    _ => Ok("Show scheduler statistics, :debug scores ranks results".to_string()),
+                },
+            },
+            CommandDefinition {
+                name: "settings",
+                handler: |_| Ok("Show options and where they are set".to_string()),
+            },
+            CommandDefinition {
+                name: "help",
+                handler: |_| Ok("Show keys, commands and handlers".to_string()),
+            },
+            CommandDefinition {
+                name: "view",
+                handler: |args| {
+                    let Some(view) = args.first().and_then(|name| ResultView::from_name(name))
+                    else {
+                        return Err(anyhow!("Usage: :view list|grid"));
+                    };
+                    let db = Database::new().unwrap();
+                    db.set_preference(VIEW_PREFERENCE, view.name())
+                        .context("Failed to change the view")?;
+                    Ok(format!("Showing results as a {}", view.name()))
+                },
+            },
+            CommandDefinition {
+                name: "theme",
+                handler: |args| {
+                    let themes = config::themes();
+                    if themes.is_empty() {
+                        return Err(anyhow!("No themes in ~/.config/crowbar/themes"));
+                    }
+                    let Some(name) = args
+                        .first()
+                        .filter(|name| themes.contains(&name.to_string()))
+                    else {
+                        return Err(anyhow!("Usage: :theme {}", themes.join("|")));
+                    };
+                    let db = Database::new().unwrap();
+                    db.set_preference(THEME_PREFERENCE, name)
+                        .context("Failed to change the theme")?;
+                    Ok(format!("Switched to the {} theme", name))
+                },
+            },
+            CommandDefinition {
+                name: "pin",
+                handler: |args| set_pinned(args, true),
+            },
+            CommandDefinition {
+                name: "unpin",
+                handler: |args| set_pinned(args, false),
+            },
+            CommandDefinition {
+                name: "permissions",
+                handler: |args| {
+                    let db = Database::new().unwrap();
+                    let (access, verb, handler) = match args {
+                        ["allow", handler] => (Some(true), "Allowed", handler),
+                        ["deny", handler] => (Some(false), "Denied", handler),
+                        ["revoke", handler] => (None, "Revoked", handler),
+                        _ => {
+                            return Err(anyhow!("Usage: :permissions allow|deny|revoke <handler>"))
+                        }
+                    };
+                    db.set_data_access(handler, access)
+                        .context("Failed to change data access")?;
+                    Ok(format!("{} data access of {}", verb, handler))
+                },
+            },
+            CommandDefinition {
+                name: "unlock",
+                handler: |args| {
+                    lock::unlock(&args.join(" "))?;
+                    Ok("Unlocked for this session".to_string())
+                },
+            },
+            CommandDefinition {
+                name: "note",
+                handler: |args| {
+                    let db = Database::new().unwrap();
+                    match args {
+                        ["export", path @ ..] => {
+                            let path = path.first().copied().unwrap_or(DEFAULT_NOTES_EXPORT);
+                            let count = db
+                                .export_notes(&expand_tilde(path))
+                                .context("Failed to export notes")?;
+                            Ok(format!("Exported {} notes to {}", count, path))
+                        }
+                        [] => Err(anyhow!("Usage: :note <text> | :note export [path]")),
+                        _ => {
+                            db.add_note(&args.join(" ")).context("Failed to add note")?;
+                            Ok("Added note".to_string())
+                        }
+                    }
+                },
+            },
+            CommandDefinition {
+                name: "group",
+                handler: |args| {
+                    let db = Database::new().unwrap();
+                    match args {
+                        ["add", name, urls @ ..] if !urls.is_empty() => {
+                            db.add_to_link_group(name, urls)
+                                .context("Failed to add links")?;
+                            Ok(format!("Added {} links to {}", urls.len(), name))
+                        }
+                        ["remove", name, urls @ ..] => {
+                            match db
+                                .remove_from_link_group(name, urls)
+                                .context("Failed to remove links")?
+                            {
+                                0 => Err(anyhow!("No links removed from {}", name)),
+                                count => Ok(format!("Removed {} links from {}", count, name)),
+                            }
+                        }
+                        [] => match db.get_link_groups() {
+                            Ok(groups) if !groups.is_empty() => Ok(groups
+                                .iter()
+                                .map(|group| format!("{} ({})", group.name, group.urls.len()))
+                                .collect::<Vec<_>>()
+                                .join(", ")),
+                            _ => Ok("No link groups added".to_string()),
+                        },
+                        _ => Err(anyhow!(
+                            "Usage: :group [add <name> <url>... | remove <name> [url...]]"
+                        )),
+                    }
+                },
+            },
+            CommandDefinition {
+                name: "rescan",
+                handler: |_| {
+                    let db = Database::new().unwrap();
+                    ActionScanner::scan_system(&db);
+                    Ok("Rescanned applications and executables".to_string())
+                },
+            },
+            CommandDefinition {
+                name: "backup",
+                handler: |args| {
+                    let db = Database::new().unwrap();
+                    let dir = args.first().map(|dir| expand_tilde(dir));
+                    let path = db
+                        .backup(dir.as_deref())
+                        .context("Failed to back up database")?;
+                    Ok(format!("Backed up database to {}", path.display()))
+                },
+            },
+            CommandDefinition {
+                name: "export",
+                handler: |args| {
+                    let db = Database::new().unwrap();
+                    let path = args.first().copied().unwrap_or(DEFAULT_DATA_EXPORT);
+                    let count = db
+                        .export_data(&expand_tilde(path))
+                        .context("Failed to export data")?;
+                    Ok(format!("Exported {} actions to {}", count, path))
+                },
+            },
+            CommandDefinition {
+                name: "restore",
+                handler: |args| match args {
+                    [file] => {
+                        let mut db = Database::new().unwrap();
+                        db.restore(&expand_tilde(file))
+                            .context("Failed to restore database")?;
+                        Ok(format!("Restored database from {}", file))
+                    }
+                    _ => Err(anyhow!("Usage: :restore <file>")),
+                },
+            },
+            CommandDefinition {
+                name: "remote",
+                handler: |args| {
+                    let db = Database::new().unwrap();
+                    match args {
+                        [] => {
+                            let active = remote::active_profile(&db)
+                                .map(|profile| profile.name)
+                                .unwrap_or_else(|| LOCAL_PROFILE.to_string());
+                            let available = remote::profiles()
+                                .iter()
+                                .map(|profile| profile.name.as_str())
+                                .collect::<Vec<_>>()
+                                .join(", ");
+                            Ok(format!("Running on {} (profiles: {})", active, available))
+                        }
+                        ["rescan"] => match remote::active_profile(&db) {
+                            Some(profile) => {
+                                let _ = db.clear_remote_binaries(&profile.host);
+                                remote::set_scan_state(&profile.host, None);
+                                Ok(format!("Binaries of {} will be rescanned", profile.name))
+                            }
+                            None => Err(anyhow!("No remote profile active")),
+                        },
+                        [name] => {
+                            remote::switch(&db, name)?;
+                            Ok(format!("Running actions on {}", name))
+                        }
+                        _ => Err(anyhow!("Usage: :remote [<profile> | local | rescan]")),
+                    }
                 },
             },
         ];
@@ -81,3 +340,25 @@ pub struct CommandResult {
     pub success: bool,
     pub message: String,
 }
+
+/// `:pin <name>` and `:unpin <name>` of applications and binaries
+fn set_pinned(args: &[&str], pinned: bool) -> Result<String> {
+    let (command, verb) = if pinned {
+        ("pin", "Pinned")
+    } else {
+        ("unpin", "Unpinned")
+    };
+    if args.is_empty() {
+        return Err(anyhow!("Usage: :{} <application>", command));
+    }
+
+    let name = args.join(" ");
+    let db = Database::new().unwrap();
+    match db
+        .set_pinned_by_name(&name, pinned)
+        .with_context(|| format!("Failed to {} {}", command, name))?
+    {
+        0 => Err(anyhow!("No application or binary named {}", name)),
+        _ => Ok(format!("{} {}", verb, name)),
+    }
+}
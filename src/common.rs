@@ -1,6 +1,16 @@
 use std::env;
 use std::path::PathBuf;
 
+use anyhow::{anyhow, Result};
+
+/// Standard locations of the XDG base directories that may be unset
+const XDG_DEFAULTS: &[(&str, &str)] = &[
+    ("XDG_CONFIG_HOME", "~/.config"),
+    ("XDG_DATA_HOME", "~/.local/share"),
+    ("XDG_STATE_HOME", "~/.local/state"),
+    ("XDG_CACHE_HOME", "~/.cache"),
+];
+
 /// Expands the tilde (~) in paths to the user's home directory
 pub fn expand_tilde(path: &str) -> PathBuf {
     if path.starts_with('~') {
@@ -10,3 +20,73 @@ pub fn expand_tilde(path: &str) -> PathBuf {
     }
     PathBuf::from(path)
 }
+
+/// Expands `${NAME}` to the environment variable and `${NAME:-fallback}` to
+/// the fallback where it's unset or empty. Unset XDG base directories expand
+/// to their standard locations, `$${` is a literal `${`.
+pub fn expand_env(text: &str) -> Result<String> {
+    let mut expanded = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            expanded.push_str(&rest[..start - 1]);
+            expanded.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        expanded.push_str(&rest[..start]);
+
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or_else(|| anyhow!("Missing }} after ${{ in {:?}", text))?;
+        let (name, fallback) = match rest[start + 2..end].split_once(":-") {
+            Some((name, fallback)) => (name, Some(fallback)),
+            None => (&rest[start + 2..end], None),
+        };
+        let value = env::var(name)
+            .ok()
+            .filter(|value| !value.is_empty())
+            .or_else(|| fallback.map(str::to_string))
+            .or_else(|| {
+                let (_, default) = XDG_DEFAULTS.iter().find(|(xdg, _)| *xdg == name)?;
+                Some(expand_tilde(default).to_string_lossy().into_owned())
+            })
+            .ok_or_else(|| anyhow!("${{{}}} is not set", name))?;
+        expanded.push_str(&value);
+        rest = &rest[end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// `$XDG_CONFIG_HOME`, `~/.config` if it's unset
+pub fn config_home() -> PathBuf {
+    xdg_dir("XDG_CONFIG_HOME", "~/.config")
+}
+
+/// `$XDG_DATA_HOME`, `~/.local/share` if it's unset
+pub fn data_home() -> PathBuf {
+    xdg_dir("XDG_DATA_HOME", "~/.local/share")
+}
+
+/// Relative paths are invalid according to the XDG spec and ignored
+fn xdg_dir(variable: &str, fallback: &str) -> PathBuf {
+    env::var_os(variable)
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .unwrap_or_else(|| expand_tilde(fallback))
+}
+
+/// e.g. "5m ago" or "3d ago" for the time since an event
+pub fn time_ago(elapsed: chrono::Duration) -> String {
+    if elapsed.num_minutes() < 1 {
+        "just now".to_string()
+    } else if elapsed.num_hours() < 1 {
+        format!("{}m ago", elapsed.num_minutes())
+    } else if elapsed.num_days() < 1 {
+        format!("{}h ago", elapsed.num_hours())
+    } else {
+        format!("{}d ago", elapsed.num_days())
+    }
+}
@@ -1,4 +1,9 @@
-use std::{env, fs, path::PathBuf, sync::OnceLock};
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
 
 use anyhow::{Context, Result};
 use gpui::{App, Global, Rgba};
@@ -6,8 +11,73 @@ use log;
 use serde::{Deserialize, Serialize};
 use toml;
 
+use crate::common::{config_home, expand_env, expand_tilde};
+use crate::demo;
+
 static CONFIG_CACHE: OnceLock<Config> = OnceLock::new();
 
+/// Organization defaults, layered under the user config
+const SYSTEM_CONFIG_PATH: &str = "/etc/crowbar/crowbar.toml";
+
+/// Path of the user config, replacing `$XDG_CONFIG_HOME/crowbar/crowbar.toml`
+pub const CONFIG_FILE_ENV: &str = "CROWBAR_CONFIG_FILE";
+
+/// User config given with `--config`, over `CROWBAR_CONFIG_FILE`
+static CONFIG_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Directory of theme files, next to the config file
+const THEMES_DIR: &str = "themes";
+
+/// Option of a config file listing files merged in before its own options
+const INCLUDE_KEY: &str = "include";
+
+/// Options a theme file may set, others are ignored
+const THEME_KEYS: &[&str] = &[
+    "text_primary_color",
+    "text_secondary_color",
+    "text_selected_primary_color",
+    "text_selected_secondary_color",
+    "background_color",
+    "border_color",
+    "selected_background_color",
+    "match_highlight_color",
+    "corner_radius",
+    "window_padding",
+    "padding",
+    "row_padding",
+    "gap",
+];
+
+/// Letters, digits and symbols that are easy to type
+const DEFAULT_PASSWORD_CHARSET: &str =
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789!#$%&*+-=?@^_";
+
+/// Highest `boost` of a handler, higher ones are lowered to it
+pub const MAX_HANDLER_BOOST: f32 = 10.0;
+
+/// Every option with its effective value and where it was set
+static SETTINGS: OnceLock<Vec<(String, String, ConfigSource)>> = OnceLock::new();
+
+/// Where the effective value of an option comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    Default,
+    System,
+    User,
+    Theme,
+}
+
+impl ConfigSource {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::System => "system",
+            Self::User => "user",
+            Self::Theme => "theme",
+        }
+    }
+}
+
 /// A color in RGB format
 #[derive(Clone, Copy, Serialize, Deserialize, Debug)]
 #[serde(from = "String", into = "String")]
@@ -79,8 +149,38 @@ impl From<Color> for String {
 #[derive(Clone, Serialize, Deserialize, Debug)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum StatusItem {
-    Text { content: String },
-    DateTime { format: String },
+    Text {
+        content: String,
+    },
+    DateTime {
+        format: String,
+    },
+    /// Battery level of connected Bluetooth devices, optionally only those
+    /// whose name contains `device`
+    Bluetooth {
+        device: Option<String>,
+    },
+    /// Charge of the machine's battery, see `status_bar` for the placeholders
+    /// of `format` in these metrics
+    Battery {
+        format: Option<String>,
+    },
+    Cpu {
+        format: Option<String>,
+    },
+    Memory {
+        format: Option<String>,
+    },
+    /// Transfer rates of all interfaces but loopback
+    Network {
+        format: Option<String>,
+    },
+    /// First line of the output of a shell command, run every `interval`
+    /// seconds
+    Script {
+        command: String,
+        interval: Option<u64>,
+    },
 }
 
 impl Default for StatusItem {
@@ -91,6 +191,162 @@ impl Default for StatusItem {
     }
 }
 
+/// How results are laid out
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultView {
+    #[default]
+    List,
+    /// Tiles with the icon above the name, like an app drawer
+    Grid,
+}
+
+impl ResultView {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::List => "list",
+            Self::Grid => "grid",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "list" => Some(Self::List),
+            "grid" => Some(Self::Grid),
+            _ => None,
+        }
+    }
+}
+
+/// What the column right of applications and binaries shows about their use
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageColumn {
+    /// How often it was run
+    #[default]
+    Count,
+    /// When it was run last, e.g. "2h ago"
+    LastUsed,
+    /// A bar filled by how often and how recently it was run
+    Frecency,
+    None,
+}
+
+/// Monitor the window appears on
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Monitor {
+    /// Position in gpui's list of displays, starting at 0
+    Index(usize),
+    Named(MonitorName),
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MonitorName {
+    Primary,
+    /// The monitor of the focused workspace in i3, sway and Hyprland
+    Focused,
+}
+
+impl Default for Monitor {
+    fn default() -> Self {
+        Self::Named(MonitorName::Primary)
+    }
+}
+
+/// Where on the monitor the window is placed before the offsets
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowAnchor {
+    #[default]
+    Center,
+    /// Centered horizontally, a third down from the top like Spotlight
+    TopThird,
+}
+
+/// Where the query input sits
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InputPosition {
+    /// Above the results, like rofi
+    Top,
+    #[default]
+    Bottom,
+}
+
+/// A `[handlers.<id>]` section, options left out keep the handler's defaults
+#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+pub struct HandlerSettings {
+    /// Enables or disables the handler at startup, over `:enable` and `:disable`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Queries starting with this word and a space only ask this handler
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyword: Option<String>,
+    /// Factor the relevance of the handler's results is multiplied by
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boost: Option<f32>,
+    /// Results listed at most, over `handler_limits`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// A machine that actions can be run on over SSH
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct RemoteProfile {
+    pub name: String,
+    /// SSH destination, e.g. `admin@backup.lan`
+    pub host: String,
+    /// Extra arguments passed to ssh, e.g. `["-p", "2222"]`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ssh_args: Vec<String>,
+}
+
+/// Links opened together by one action, e.g. the tabs of a morning routine
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct LinkGroup {
+    pub name: String,
+    pub urls: Vec<String>,
+}
+
+/// A launcher from the `[[shortcuts]]` config, running a shell command or
+/// opening a URL
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Shortcut {
+    pub name: String,
+    /// Other words the shortcut is found by
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    /// Run with `sh -c`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Run the command in `terminal`
+    #[serde(default)]
+    pub terminal: bool,
+}
+
+/// A web search engine from the `[[search_engines]]` config, e.g. Kagi
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SearchEngine {
+    pub name: String,
+    /// Queries starting with this word only search this engine
+    pub keyword: String,
+    /// Search page, `{query}` is replaced with the encoded query
+    pub url: String,
+    /// Shown in front of the name, e.g. an emoji
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+
+impl SearchEngine {
+    pub fn search_url(&self, query: &str) -> String {
+        self.url.replace("{query}", &urlencoding::encode(query))
+    }
+}
+
 /// Application configuration
 #[derive(Clone)]
 pub struct Config {
@@ -108,6 +364,142 @@ pub struct Config {
     pub status_bar_left: Vec<StatusItem>,
     pub status_bar_center: Vec<StatusItem>,
     pub status_bar_right: Vec<StatusItem>,
+    /// Argon2 hash of the passphrase that unlocks locked handlers and
+    /// commands, from `crowbar --hash-passphrase`
+    pub lock_passphrase_hash: Option<String>,
+    pub locked_handlers: Vec<String>,
+    pub locked_commands: Vec<String>,
+    /// Only whitelisted handlers and actions are available, command mode is disabled
+    pub restricted_mode: bool,
+    pub restricted_handlers: Vec<String>,
+    /// Names of allowed actions. Empty allows every action of the allowed handlers.
+    pub restricted_actions: Vec<String>,
+    /// Machines that actions can be run on over SSH, see `:remote`
+    pub remote_profiles: Vec<RemoteProfile>,
+    /// iCalendar files or directories for reminders on the empty-query screen
+    pub calendar_paths: Vec<String>,
+    pub reminders_show_events: bool,
+    pub reminders_show_todos: bool,
+    /// `fd`, `locate`, `plocate` or `index` for the built-in index
+    pub file_search_backend: String,
+    pub file_search_roots: Vec<String>,
+    /// Broadcast query, result and execution events on a Unix socket
+    pub event_stream: bool,
+    /// Clients must send this token before they receive events
+    pub event_stream_token: Option<String>,
+    /// Defaults to `$XDG_RUNTIME_DIR/crowbar/events.sock`
+    pub event_stream_socket: Option<String>,
+    /// Where `:backup` and automatic backups before migrations are written
+    pub backup_dir: Option<String>,
+    /// Power actions that need Enter pressed twice, e.g. `shutdown`, `lock`
+    pub power_confirm: Vec<String>,
+    /// Percent the audio volume entries change the volume by
+    pub audio_volume_step: u8,
+    /// Ctrl-Enter runs the top action if it matches the query exactly and
+    /// clearly outscores the next one
+    pub auto_accept: bool,
+    /// Typing a space after such a query runs the action, too
+    pub auto_accept_on_space: bool,
+    /// How many times the top score has to exceed the runner-up's
+    pub auto_accept_margin: f32,
+    /// Crowbar commands (`:rescan`) and shell commands run in order at startup
+    pub startup_actions: Vec<String>,
+    /// Personal access token for GitHub searches, raises the rate limit
+    pub github_token: Option<String>,
+    /// Terminal emulator for interactive commands, `$TERMINAL` if unset
+    pub terminal: Option<String>,
+    /// Link groups opened all at once, see also `:group add`
+    pub link_groups: Vec<LinkGroup>,
+    /// Shrink the window to the number of results, `window_height` is the maximum
+    pub window_auto_height: bool,
+    /// Receive open tabs from the companion browser extension, see `tab_bridge`
+    pub browser_bridge: bool,
+    /// The extension must send this token with every request
+    pub browser_bridge_token: Option<String>,
+    pub browser_bridge_port: u16,
+    /// Keys per action, replacing the default keys, see `keybindings`
+    pub key_bindings: BTreeMap<String, Vec<String>>,
+    /// What Enter does with a zoxide directory, `file_manager` or `terminal`
+    pub zoxide_action: String,
+    /// Directories searched for git repositories
+    pub git_repo_roots: Vec<String>,
+    /// Command that opens a directory in the editor, `$VISUAL` or `$EDITOR` in
+    /// `terminal` if unset
+    pub editor: Option<String>,
+    /// Length of generated passwords without a length in the query
+    pub password_length: usize,
+    /// Characters generated passwords are made of
+    pub password_charset: String,
+    /// Length of generated PINs without a length in the query
+    pub pin_length: usize,
+    /// Directories with Zeal or Dash docsets
+    pub docset_dirs: Vec<String>,
+    /// Command opened from the `sys` rows, `top` in `terminal` if unset
+    pub system_monitor: Option<String>,
+    /// Directories searched for AppImages
+    pub appimage_dirs: Vec<String>,
+    /// Web search engines added next to the built-in ones
+    pub search_engines: Vec<SearchEngine>,
+    /// Color of the query characters matched in result names
+    pub match_highlight_color: Rgba,
+    /// Results are listed under a header per handler group instead of interleaved
+    pub group_results: bool,
+    /// Rows per group by group name, `default` for the others
+    pub group_limits: BTreeMap<String, usize>,
+    /// Results listed at most, unless `group_results` is on
+    pub max_results: usize,
+    /// Results of single handlers listed at most, by handler id
+    pub handler_limits: BTreeMap<String, usize>,
+    /// Results as a list or as a grid of tiles, `:view` overrides it
+    pub result_view: ResultView,
+    /// Tiles per row of the grid view, at least 1
+    pub grid_columns: usize,
+    /// Failed actions also show a desktop notification
+    pub error_notifications: bool,
+    /// Shell commands running at least this many seconds notify when they finish
+    pub notify_after_secs: Option<u64>,
+    /// Shows 1-9 next to the first results, run with Ctrl+1 to Ctrl+9
+    pub quick_select_hints: bool,
+    /// Column right of applications and binaries
+    pub usage_column: UsageColumn,
+    /// Name of a file in `themes/` next to the config, overriding the colors
+    pub theme: Option<String>,
+    /// `[handlers.<id>]` sections tuning single handlers
+    pub handlers: BTreeMap<String, HandlerSettings>,
+    /// Launchers of the `[[shortcuts]]` config, ranked like applications
+    pub shortcuts: Vec<Shortcut>,
+    /// Database file, `crowbar.db` in `$XDG_DATA_HOME/crowbar` if unset
+    pub database_path: Option<String>,
+    /// Opacity of `background_color`, from 0.0 to 1.0
+    pub background_opacity: f32,
+    /// Ask the compositor to blur what's behind a translucent window
+    pub background_blur: bool,
+    /// Query input above or below the results
+    pub input_position: InputPosition,
+    pub show_status_bar: bool,
+    /// Horizontal padding of the status bar, the result rows and the input
+    pub padding: f32,
+    /// Padding above and below each result row, sets the row height
+    pub row_padding: f32,
+    /// Radius of the window corners and of popups and tiles
+    pub corner_radius: f32,
+    /// Space between the window border and its content
+    pub window_padding: f32,
+    /// Space between the columns of result rows
+    pub gap: f32,
+    pub monitor: Monitor,
+    pub window_anchor: WindowAnchor,
+    /// Pixels the window is moved right and down from the anchor
+    pub window_offset_x: f32,
+    pub window_offset_y: f32,
+    /// Days after which logged executions are merged into daily counts, 0
+    /// keeps them
+    pub history_max_age: u32,
+    /// Logged executions kept per action before the oldest are merged into
+    /// daily counts, 0 keeps them all
+    pub history_max_executions: u32,
+    /// Update applications and executables when their directories change
+    pub watch_applications: bool,
 }
 
 impl Default for Config {
@@ -166,6 +558,84 @@ impl Default for Config {
             status_bar_right: vec![StatusItem::DateTime {
                 format: "%Y-%m-%d".to_string(),
             }],
+            lock_passphrase_hash: None,
+            locked_handlers: vec![],
+            locked_commands: vec![],
+            restricted_mode: false,
+            restricted_handlers: vec![],
+            restricted_actions: vec![],
+            remote_profiles: vec![],
+            calendar_paths: vec![],
+            reminders_show_events: true,
+            reminders_show_todos: true,
+            file_search_backend: String::from("index"),
+            file_search_roots: vec![String::from("~")],
+            event_stream: false,
+            event_stream_token: None,
+            event_stream_socket: None,
+            backup_dir: None,
+            power_confirm: ["shutdown", "reboot", "hibernate", "logout"]
+                .map(String::from)
+                .to_vec(),
+            audio_volume_step: 5,
+            auto_accept: false,
+            auto_accept_on_space: false,
+            auto_accept_margin: 3.0,
+            startup_actions: vec![],
+            github_token: None,
+            terminal: None,
+            link_groups: vec![],
+            window_auto_height: true,
+            browser_bridge: false,
+            browser_bridge_token: None,
+            browser_bridge_port: 47813,
+            key_bindings: BTreeMap::new(),
+            zoxide_action: String::from("file_manager"),
+            git_repo_roots: vec![String::from("~")],
+            editor: None,
+            password_length: 20,
+            password_charset: String::from(DEFAULT_PASSWORD_CHARSET),
+            pin_length: 6,
+            docset_dirs: vec![String::from("~/.local/share/Zeal/Zeal/docsets")],
+            system_monitor: None,
+            appimage_dirs: vec![String::from("~/Applications")],
+            search_engines: vec![],
+            match_highlight_color: Rgba {
+                r: 137.0 / 255.0,
+                g: 180.0 / 255.0,
+                b: 250.0 / 255.0,
+                a: 1.0,
+            },
+            group_results: false,
+            group_limits: BTreeMap::new(),
+            max_results: 10,
+            handler_limits: BTreeMap::new(),
+            result_view: ResultView::List,
+            grid_columns: 5,
+            error_notifications: false,
+            notify_after_secs: None,
+            quick_select_hints: true,
+            usage_column: UsageColumn::Count,
+            theme: None,
+            handlers: BTreeMap::new(),
+            shortcuts: Vec::new(),
+            database_path: None,
+            background_opacity: 1.0,
+            background_blur: false,
+            input_position: InputPosition::Bottom,
+            show_status_bar: true,
+            padding: 16.,
+            row_padding: 8.,
+            corner_radius: 0.,
+            window_padding: 0.,
+            gap: 16.,
+            monitor: Monitor::default(),
+            window_anchor: WindowAnchor::Center,
+            window_offset_x: 0.,
+            window_offset_y: 0.,
+            history_max_age: 90,
+            history_max_executions: 200,
+            watch_applications: true,
         }
     }
 }
@@ -190,6 +660,139 @@ struct ConfigToml {
     status_bar_center: Option<Vec<StatusItem>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     status_bar_right: Option<Vec<StatusItem>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lock_passphrase_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    locked_handlers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    locked_commands: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    restricted_mode: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    restricted_handlers: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    restricted_actions: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    remote_profiles: Option<Vec<RemoteProfile>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    calendar_paths: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reminders_show_events: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reminders_show_todos: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_search_backend: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_search_roots: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event_stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event_stream_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event_stream_socket: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backup_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    power_confirm: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    audio_volume_step: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auto_accept: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auto_accept_on_space: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auto_accept_margin: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    startup_actions: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    github_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    terminal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    link_groups: Option<Vec<LinkGroup>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    window_auto_height: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    browser_bridge: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    browser_bridge_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    browser_bridge_port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key_bindings: Option<BTreeMap<String, Vec<String>>>,
+    zoxide_action: Option<String>,
+    git_repo_roots: Option<Vec<String>>,
+    editor: Option<String>,
+    password_length: Option<usize>,
+    password_charset: Option<String>,
+    pin_length: Option<usize>,
+    docset_dirs: Option<Vec<String>>,
+    system_monitor: Option<String>,
+    appimage_dirs: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    search_engines: Option<Vec<SearchEngine>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    match_highlight_color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group_results: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group_limits: Option<BTreeMap<String, usize>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_results: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    handler_limits: Option<BTreeMap<String, usize>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result_view: Option<ResultView>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    grid_columns: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_notifications: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    notify_after_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quick_select_hints: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    usage_column: Option<UsageColumn>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    theme: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    handlers: Option<BTreeMap<String, HandlerSettings>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shortcuts: Option<Vec<Shortcut>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    database_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    background_opacity: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    background_blur: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input_position: Option<InputPosition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    show_status_bar: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    padding: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    row_padding: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    corner_radius: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    window_padding: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gap: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    monitor: Option<Monitor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    window_anchor: Option<WindowAnchor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    window_offset_x: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    window_offset_y: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    history_max_age: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    history_max_executions: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    watch_applications: Option<bool>,
 }
 
 impl From<&Config> for ConfigToml {
@@ -218,6 +821,86 @@ impl From<&Config> for ConfigToml {
                 .then(|| config.status_bar_center.clone()),
             status_bar_right: (!config.status_bar_right.is_empty())
                 .then(|| config.status_bar_right.clone()),
+            lock_passphrase_hash: config.lock_passphrase_hash.clone(),
+            locked_handlers: (!config.locked_handlers.is_empty())
+                .then(|| config.locked_handlers.clone()),
+            locked_commands: (!config.locked_commands.is_empty())
+                .then(|| config.locked_commands.clone()),
+            restricted_mode: config.restricted_mode.then_some(true),
+            restricted_handlers: (!config.restricted_handlers.is_empty())
+                .then(|| config.restricted_handlers.clone()),
+            restricted_actions: (!config.restricted_actions.is_empty())
+                .then(|| config.restricted_actions.clone()),
+            remote_profiles: (!config.remote_profiles.is_empty())
+                .then(|| config.remote_profiles.clone()),
+            calendar_paths: (!config.calendar_paths.is_empty())
+                .then(|| config.calendar_paths.clone()),
+            reminders_show_events: (!config.reminders_show_events).then_some(false),
+            reminders_show_todos: (!config.reminders_show_todos).then_some(false),
+            file_search_backend: Some(config.file_search_backend.clone()),
+            file_search_roots: Some(config.file_search_roots.clone()),
+            event_stream: config.event_stream.then_some(true),
+            event_stream_token: config.event_stream_token.clone(),
+            event_stream_socket: config.event_stream_socket.clone(),
+            backup_dir: config.backup_dir.clone(),
+            power_confirm: Some(config.power_confirm.clone()),
+            audio_volume_step: Some(config.audio_volume_step),
+            auto_accept: config.auto_accept.then_some(true),
+            auto_accept_on_space: config.auto_accept_on_space.then_some(true),
+            auto_accept_margin: Some(config.auto_accept_margin),
+            startup_actions: (!config.startup_actions.is_empty())
+                .then(|| config.startup_actions.clone()),
+            github_token: config.github_token.clone(),
+            terminal: config.terminal.clone(),
+            link_groups: (!config.link_groups.is_empty()).then(|| config.link_groups.clone()),
+            window_auto_height: Some(config.window_auto_height),
+            browser_bridge: config.browser_bridge.then_some(true),
+            browser_bridge_token: config.browser_bridge_token.clone(),
+            browser_bridge_port: Some(config.browser_bridge_port),
+            key_bindings: (!config.key_bindings.is_empty()).then(|| config.key_bindings.clone()),
+            zoxide_action: Some(config.zoxide_action.clone()),
+            git_repo_roots: Some(config.git_repo_roots.clone()),
+            editor: config.editor.clone(),
+            password_length: Some(config.password_length),
+            password_charset: Some(config.password_charset.clone()),
+            pin_length: Some(config.pin_length),
+            docset_dirs: Some(config.docset_dirs.clone()),
+            system_monitor: config.system_monitor.clone(),
+            appimage_dirs: Some(config.appimage_dirs.clone()),
+            search_engines: (!config.search_engines.is_empty())
+                .then(|| config.search_engines.clone()),
+            match_highlight_color: Some(rgba_to_hex(&config.match_highlight_color)),
+            group_results: Some(config.group_results),
+            group_limits: (!config.group_limits.is_empty()).then(|| config.group_limits.clone()),
+            max_results: Some(config.max_results),
+            handler_limits: (!config.handler_limits.is_empty())
+                .then(|| config.handler_limits.clone()),
+            result_view: Some(config.result_view),
+            grid_columns: Some(config.grid_columns),
+            error_notifications: Some(config.error_notifications),
+            notify_after_secs: config.notify_after_secs,
+            quick_select_hints: Some(config.quick_select_hints),
+            usage_column: Some(config.usage_column),
+            theme: config.theme.clone(),
+            handlers: (!config.handlers.is_empty()).then(|| config.handlers.clone()),
+            shortcuts: (!config.shortcuts.is_empty()).then(|| config.shortcuts.clone()),
+            database_path: config.database_path.clone(),
+            background_opacity: Some(config.background_opacity),
+            background_blur: Some(config.background_blur),
+            input_position: Some(config.input_position),
+            show_status_bar: Some(config.show_status_bar),
+            padding: Some(config.padding),
+            row_padding: Some(config.row_padding),
+            corner_radius: Some(config.corner_radius),
+            window_padding: Some(config.window_padding),
+            gap: Some(config.gap),
+            monitor: Some(config.monitor),
+            window_anchor: Some(config.window_anchor),
+            window_offset_x: Some(config.window_offset_x),
+            window_offset_y: Some(config.window_offset_y),
+            history_max_age: Some(config.history_max_age),
+            history_max_executions: Some(config.history_max_executions),
+            watch_applications: Some(config.watch_applications),
         }
     }
 }
@@ -236,7 +919,11 @@ impl TryFrom<ConfigToml> for Config {
             text_secondary_color: hex_to_rgba(toml.text_secondary_color)?,
             text_selected_primary_color: hex_to_rgba(toml.text_selected_primary_color)?,
             text_selected_secondary_color: hex_to_rgba(toml.text_selected_secondary_color)?,
-            background_color: hex_to_rgba(toml.background_color)?,
+            // The color is translucent, so every panel painting it is, too
+            background_color: Rgba {
+                a: toml.background_opacity.unwrap_or(1.0).clamp(0.0, 1.0),
+                ..hex_to_rgba(toml.background_color)?
+            },
             border_color: hex_to_rgba(toml.border_color)?,
             selected_background_color: hex_to_rgba(toml.selected_background_color)?,
             font_family: toml.font_family,
@@ -246,6 +933,115 @@ impl TryFrom<ConfigToml> for Config {
             status_bar_left: toml.status_bar_left.unwrap_or_default(),
             status_bar_center: toml.status_bar_center.unwrap_or_default(),
             status_bar_right: toml.status_bar_right.unwrap_or_default(),
+            lock_passphrase_hash: toml.lock_passphrase_hash,
+            locked_handlers: toml.locked_handlers.unwrap_or_default(),
+            locked_commands: toml.locked_commands.unwrap_or_default(),
+            restricted_mode: toml.restricted_mode.unwrap_or_default(),
+            restricted_handlers: toml.restricted_handlers.unwrap_or_default(),
+            restricted_actions: toml.restricted_actions.unwrap_or_default(),
+            remote_profiles: toml.remote_profiles.unwrap_or_default(),
+            calendar_paths: toml.calendar_paths.unwrap_or_default(),
+            reminders_show_events: toml.reminders_show_events.unwrap_or(true),
+            reminders_show_todos: toml.reminders_show_todos.unwrap_or(true),
+            file_search_backend: toml
+                .file_search_backend
+                .unwrap_or_else(|| Config::default().file_search_backend),
+            file_search_roots: toml
+                .file_search_roots
+                .unwrap_or_else(|| Config::default().file_search_roots),
+            event_stream: toml.event_stream.unwrap_or_default(),
+            event_stream_token: toml.event_stream_token,
+            event_stream_socket: toml.event_stream_socket,
+            backup_dir: toml.backup_dir,
+            power_confirm: toml
+                .power_confirm
+                .unwrap_or_else(|| Config::default().power_confirm),
+            audio_volume_step: toml
+                .audio_volume_step
+                .unwrap_or_else(|| Config::default().audio_volume_step),
+            auto_accept: toml.auto_accept.unwrap_or_default(),
+            auto_accept_on_space: toml.auto_accept_on_space.unwrap_or_default(),
+            auto_accept_margin: toml
+                .auto_accept_margin
+                .unwrap_or_else(|| Config::default().auto_accept_margin),
+            startup_actions: toml.startup_actions.unwrap_or_default(),
+            github_token: toml.github_token,
+            terminal: toml.terminal,
+            link_groups: toml.link_groups.unwrap_or_default(),
+            window_auto_height: toml.window_auto_height.unwrap_or(true),
+            browser_bridge: toml.browser_bridge.unwrap_or_default(),
+            browser_bridge_token: toml.browser_bridge_token,
+            browser_bridge_port: toml
+                .browser_bridge_port
+                .unwrap_or_else(|| Config::default().browser_bridge_port),
+            key_bindings: toml.key_bindings.unwrap_or_default(),
+            zoxide_action: toml
+                .zoxide_action
+                .unwrap_or_else(|| Config::default().zoxide_action),
+            git_repo_roots: toml
+                .git_repo_roots
+                .unwrap_or_else(|| Config::default().git_repo_roots),
+            editor: toml.editor,
+            password_length: toml
+                .password_length
+                .unwrap_or_else(|| Config::default().password_length),
+            password_charset: toml
+                .password_charset
+                .unwrap_or_else(|| Config::default().password_charset),
+            pin_length: toml
+                .pin_length
+                .unwrap_or_else(|| Config::default().pin_length),
+            docset_dirs: toml
+                .docset_dirs
+                .unwrap_or_else(|| Config::default().docset_dirs),
+            system_monitor: toml.system_monitor,
+            appimage_dirs: toml
+                .appimage_dirs
+                .unwrap_or_else(|| Config::default().appimage_dirs),
+            search_engines: toml.search_engines.unwrap_or_default(),
+            match_highlight_color: match toml.match_highlight_color {
+                Some(color) => hex_to_rgba(color)?,
+                None => Config::default().match_highlight_color,
+            },
+            group_results: toml.group_results.unwrap_or_default(),
+            group_limits: toml.group_limits.unwrap_or_default(),
+            max_results: toml
+                .max_results
+                .unwrap_or_else(|| Config::default().max_results),
+            handler_limits: toml.handler_limits.unwrap_or_default(),
+            result_view: toml.result_view.unwrap_or_default(),
+            grid_columns: toml
+                .grid_columns
+                .unwrap_or_else(|| Config::default().grid_columns)
+                .max(1),
+            error_notifications: toml.error_notifications.unwrap_or_default(),
+            notify_after_secs: toml.notify_after_secs,
+            quick_select_hints: toml.quick_select_hints.unwrap_or(true),
+            usage_column: toml.usage_column.unwrap_or_default(),
+            theme: toml.theme,
+            handlers: toml.handlers.unwrap_or_default(),
+            shortcuts: toml.shortcuts.unwrap_or_default(),
+            database_path: toml.database_path,
+            background_opacity: toml.background_opacity.unwrap_or(1.0).clamp(0.0, 1.0),
+            background_blur: toml.background_blur.unwrap_or_default(),
+            input_position: toml.input_position.unwrap_or_default(),
+            show_status_bar: toml.show_status_bar.unwrap_or(true),
+            padding: toml.padding.unwrap_or(16.).max(0.),
+            row_padding: toml.row_padding.unwrap_or(8.).max(0.),
+            corner_radius: toml.corner_radius.unwrap_or_default().max(0.),
+            window_padding: toml.window_padding.unwrap_or_default().max(0.),
+            gap: toml.gap.unwrap_or(16.).max(0.),
+            monitor: toml.monitor.unwrap_or_default(),
+            window_anchor: toml.window_anchor.unwrap_or_default(),
+            window_offset_x: toml.window_offset_x.unwrap_or_default(),
+            window_offset_y: toml.window_offset_y.unwrap_or_default(),
+            history_max_age: toml
+                .history_max_age
+                .unwrap_or_else(|| Config::default().history_max_age),
+            history_max_executions: toml
+                .history_max_executions
+                .unwrap_or_else(|| Config::default().history_max_executions),
+            watch_applications: toml.watch_applications.unwrap_or(true),
         })
     }
 }
@@ -271,84 +1067,382 @@ impl<'de> Deserialize<'de> for Config {
 
 impl Config {
     pub fn init(cx: &mut App) {
-        let config = CONFIG_CACHE.get_or_init(|| {
-            Self::load_fast().unwrap_or_else(|e| {
-                log::error!("Failed to load config: {}", e);
-                Config::default()
+        cx.set_global(Self::cached());
+    }
+
+    /// The config read at startup, the defaults if it can't be read
+    pub fn cached() -> Config {
+        CONFIG_CACHE
+            .get_or_init(|| {
+                // The demo looks the same everywhere
+                if demo::is_enabled() {
+                    return Config::default();
+                }
+                Self::load_fast().unwrap_or_else(|e| {
+                    log::error!("Failed to load config: {}", e);
+                    Config::default()
+                })
             })
-        });
-        cx.set_global((*config).clone());
+            .clone()
     }
 
-    fn load_fast() -> Result<Self> {
-        let config_path = Self::config_path()?;
-        
-        if !config_path.exists() {
-            return Ok(Config::default());
-        }
+    /// Results the handler lists at most, see `handler_limits`
+    pub fn result_limit(&self, handler: &str) -> usize {
+        self.handler_limit(handler).unwrap_or(self.max_results)
+    }
+
+    /// Limit of the handler's `[handlers.<id>]` section or `handler_limits`
+    pub fn handler_limit(&self, handler: &str) -> Option<usize> {
+        self.handlers
+            .get(handler)
+            .and_then(|settings| settings.limit)
+            .or_else(|| self.handler_limits.get(handler).copied())
+    }
 
-        let config_str = fs::read_to_string(&config_path)
-            .with_context(|| format!("Failed to read config file at {:?}", config_path))?;
+    /// Factor the relevance of the handler's results is multiplied by, at
+    /// most `MAX_HANDLER_BOOST`
+    pub fn handler_boost(&self, handler: &str) -> f32 {
+        self.handlers
+            .get(handler)
+            .and_then(|settings| settings.boost)
+            .map_or(1.0, |boost| boost.clamp(0.0, MAX_HANDLER_BOOST))
+    }
 
-        toml::from_str(&config_str)
-            .or_else(|_| Ok(Config::default()))
+    /// Word scoping queries to the handler, e.g. `h` for `h rust`
+    pub fn handler_keyword(&self, handler: &str) -> Option<&str> {
+        self.handlers
+            .get(handler)
+            .and_then(|settings| settings.keyword.as_deref())
     }
 
-    /// Load configuration from disk, creating a default if none exists
-    fn load() -> Result<Self> {
-        let config_path = Self::config_path()?;
+    /// Config files read at startup, the system-wide one first and the
+    /// included files before the file including them
+    pub fn files() -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        for path in [PathBuf::from(SYSTEM_CONFIG_PATH), Self::config_path()?] {
+            collect_files(&path, &mut Vec::new(), &mut files);
+        }
+        Ok(files)
+    }
 
-        let config = if !config_path.exists() {
-            log::info!(
-                "No config file found at {:?}, creating default config",
-                config_path
-            );
-            Config::default()
-        } else {
-            log::info!("Loading config from {:?}", config_path);
-            let config_str = fs::read_to_string(&config_path)
-                .with_context(|| format!("Failed to read config file at {:?}", config_path))?;
-
-            match toml::from_str(&config_str) {
-                Ok(config) => {
-                    log::info!("Successfully loaded config file");
-                    config
+    /// The defaults with the options of a config file, failing where
+    /// `load_fast` would fall back to the defaults
+    pub fn with_layer(layer: toml::Table) -> Result<Self> {
+        let mut table = toml::Table::try_from(Config::default())?;
+        merge_tables(&mut table, layer);
+        Ok(table.try_into::<Config>()?)
+    }
+
+    /// Layer the system-wide config and the user config over the defaults
+    fn load_fast() -> Result<Self> {
+        let mut table = toml::Table::try_from(Config::default())?;
+        let mut sources: Vec<(String, ConfigSource)> = table
+            .keys()
+            .map(|key| (key.clone(), ConfigSource::Default))
+            .collect();
+
+        let user_config = Self::config_path()?;
+        if let Err(e) = Self::create_default(&user_config) {
+            log::warn!("{:#}", e);
+        }
+
+        let layers = [
+            (PathBuf::from(SYSTEM_CONFIG_PATH), ConfigSource::System),
+            (user_config, ConfigSource::User),
+        ];
+        for (path, source) in layers {
+            let Some(layer) = Self::read_layer(&path) else {
+                continue;
+            };
+            for key in layer.keys() {
+                match sources.iter_mut().find(|(name, _)| name == key) {
+                    Some((_, current)) => *current = source,
+                    None => sources.push((key.clone(), source)),
                 }
-                Err(e) => {
-                    log::warn!("Failed to parse config file: {}", e);
-                    // Backup invalid config file
-                    let backup_path = config_path.with_extension("toml.bak");
-                    if let Err(e) = fs::rename(&config_path, &backup_path) {
-                        log::error!("Failed to backup invalid config: {}", e);
-                    } else {
-                        log::info!("Backed up invalid config to {:?}", backup_path);
+            }
+            merge_tables(&mut table, layer);
+        }
+
+        // The theme's colors win over colors set in the config files
+        if let Some(toml::Value::String(name)) = table.get("theme").cloned() {
+            match Self::read_theme(&name) {
+                Ok(theme) => {
+                    for key in theme.keys() {
+                        match sources.iter_mut().find(|(name, _)| name == key) {
+                            Some((_, current)) => *current = ConfigSource::Theme,
+                            None => sources.push((key.clone(), ConfigSource::Theme)),
+                        }
                     }
-                    Config::default()
+                    merge_tables(&mut table, theme);
                 }
+                Err(e) => log::warn!("Ignoring theme {:?}: {:#}", name, e),
+            }
+        }
+
+        let config = table.clone().try_into::<Config>().unwrap_or_else(|e| {
+            log::error!("Invalid config, using defaults: {}", e);
+            Config::default()
+        });
+
+        let settings = sources
+            .into_iter()
+            .map(|(key, source)| {
+                let value = table.get(&key).map(|v| v.to_string()).unwrap_or_default();
+                (key, value, source)
+            })
+            .collect();
+        let _ = SETTINGS.set(settings);
+
+        Ok(config)
+    }
+
+    /// The config with the colors of a theme file, for switching themes
+    /// without restarting
+    pub fn with_theme(&self, name: &str) -> Result<Self> {
+        let mut table = toml::Table::try_from(self)?;
+        merge_tables(&mut table, Self::read_theme(name)?);
+        table.insert("theme".to_string(), toml::Value::String(name.to_string()));
+        Ok(table.try_into::<Config>()?)
+    }
+
+    /// Colors of `themes/<name>.toml`
+    fn read_theme(name: &str) -> Result<toml::Table> {
+        let path = themes_dir()?.join(format!("{}.toml", name));
+        let theme = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read theme file at {:?}", path))?;
+        let mut theme = toml::from_str::<toml::Table>(&theme)
+            .with_context(|| format!("Invalid theme file at {:?}", path))?;
+        theme.retain(|key, _| {
+            let known = THEME_KEYS.contains(&key.as_str());
+            if !known {
+                log::warn!(
+                    "Ignoring {:?} in theme {:?}, themes only set colors and spacing",
+                    key,
+                    name
+                );
             }
-        };
+            known
+        });
+        Ok(theme)
+    }
+
+    /// Parse a config file with the files it includes, skipping it when
+    /// it's missing or invalid
+    fn read_layer(path: &Path) -> Option<toml::Table> {
+        Self::read_including(path, &mut Vec::new())
+    }
 
-        if let Some(parent) = config_path.parent() {
+    /// The included files are merged in order, then the file's own options
+    /// override theirs. `including` are the files on the way here, an include
+    /// of one of them would never end.
+    fn read_including(path: &Path, including: &mut Vec<PathBuf>) -> Option<toml::Table> {
+        let mut layer = Self::read_file(path)?;
+        if including.iter().any(|file| file == path) {
+            log::warn!("Ignoring include of {:?}, it includes itself", path);
+            return None;
+        }
+
+        let mut merged = toml::Table::new();
+        including.push(path.to_path_buf());
+        for include in included_files(path, &layer) {
+            if !include.exists() {
+                log::warn!("Included config file {:?} does not exist", include);
+            }
+            if let Some(included) = Self::read_including(&include, including) {
+                merge_tables(&mut merged, included);
+            }
+        }
+        including.pop();
+
+        layer.remove(INCLUDE_KEY);
+        merge_tables(&mut merged, layer);
+        Some(merged)
+    }
+
+    fn read_file(path: &Path) -> Option<toml::Table> {
+        if !path.exists() {
+            return None;
+        }
+
+        let layer = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file at {:?}", path))
+            .and_then(|config_str| Ok(toml::from_str::<toml::Table>(&config_str)?));
+        match layer {
+            Ok(mut layer) => {
+                for (key, e) in interpolate(&mut layer) {
+                    log::warn!("Not expanding variables in {} of {:?}: {:#}", key, path, e);
+                }
+                Some(layer)
+            }
+            Err(e) => {
+                log::warn!("Ignoring config file {:?}: {:#}", path, e);
+                None
+            }
+        }
+    }
+
+    /// Read the user config from `path` instead, before `init`
+    pub fn use_file(path: PathBuf) {
+        let _ = CONFIG_FILE.set(path);
+    }
+
+    /// Write the defaults on first run, so there's a file to edit. A file
+    /// given with `--config` or `CROWBAR_CONFIG_FILE` is never written, it
+    /// may be read-only like configs in the Nix store.
+    fn create_default(path: &Path) -> Result<()> {
+        if Self::config_file_override().is_some() || path.exists() {
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)
                 .with_context(|| format!("Failed to create config directory at {:?}", parent))?;
         }
+        fs::write(path, toml::to_string_pretty(&Config::default())?)
+            .with_context(|| format!("Failed to write config to {:?}", path))?;
 
-        // Write config (ensures a valid config always exists)
-        fs::write(&config_path, toml::to_string_pretty(&config)?)
-            .with_context(|| format!("Failed to write config to {:?}", config_path))?;
-
-        log::info!("Wrote config to {:?}", config_path);
+        log::info!("Wrote default config to {:?}", path);
+        Ok(())
+    }
 
-        Ok(config)
+    fn config_file_override() -> Option<PathBuf> {
+        if let Some(path) = CONFIG_FILE.get() {
+            return Some(path.clone());
+        }
+        env::var_os(CONFIG_FILE_ENV)
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
     }
 
     fn config_path() -> Result<PathBuf> {
-        let home = env::var("HOME")
-            .or_else(|_| env::var("USERPROFILE"))
-            .context("Could not determine home directory")?;
+        if let Some(path) = Self::config_file_override() {
+            return Ok(path);
+        }
 
-        Ok(PathBuf::from(home).join(".config/crowbar/crowbar.toml"))
+        Ok(config_home().join("crowbar").join("crowbar.toml"))
     }
 }
 
 impl Global for Config {}
+
+/// Option names, their effective values and sources, sorted by name
+pub fn settings() -> Vec<(String, String, ConfigSource)> {
+    let mut settings = SETTINGS.get().cloned().unwrap_or_default();
+    settings.sort_by(|a, b| a.0.cmp(&b.0));
+    settings
+}
+
+/// Names of the theme files, sorted
+pub fn themes() -> Vec<String> {
+    let Ok(entries) = themes_dir().and_then(|dir| Ok(fs::read_dir(dir)?)) else {
+        return Vec::new();
+    };
+    let mut themes: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "toml")
+        })
+        .filter_map(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+        .collect();
+    themes.sort();
+    themes
+}
+
+fn themes_dir() -> Result<PathBuf> {
+    let config = Config::config_path()?;
+    let dir = config.parent().context("Config file has no directory")?;
+    Ok(dir.join(THEMES_DIR))
+}
+
+/// Files in the `include` option of a config file, relative to its directory
+pub fn included_files(path: &Path, table: &toml::Table) -> Vec<PathBuf> {
+    let includes = match table.get(INCLUDE_KEY) {
+        Some(toml::Value::Array(includes)) => includes.clone(),
+        Some(include) => vec![include.clone()],
+        None => return Vec::new(),
+    };
+    let dir = path.parent().unwrap_or(Path::new(""));
+
+    includes
+        .iter()
+        .filter_map(|include| match include {
+            toml::Value::String(include) => Some(dir.join(expand_tilde(include))),
+            _ => {
+                log::warn!(
+                    "Ignoring include {} in {:?}, it's not a path",
+                    include,
+                    path
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+/// Every file in the order `read_including` reads them
+fn collect_files(path: &Path, including: &mut Vec<PathBuf>, files: &mut Vec<PathBuf>) {
+    if including.iter().any(|file| file == path) {
+        return;
+    }
+    if let Some(mut table) = fs::read_to_string(path)
+        .ok()
+        .and_then(|text| toml::from_str::<toml::Table>(&text).ok())
+    {
+        interpolate(&mut table);
+        including.push(path.to_path_buf());
+        for include in included_files(path, &table) {
+            collect_files(&include, including, files);
+        }
+        including.pop();
+    }
+    files.push(path.to_path_buf());
+}
+
+/// Expand environment variables in every string of a config file, see
+/// `expand_env`. Strings that fail to expand are kept as they are, the
+/// errors are returned with the option they're in.
+pub fn interpolate(table: &mut toml::Table) -> Vec<(String, anyhow::Error)> {
+    let mut errors = Vec::new();
+    for (key, value) in table.iter_mut() {
+        let mut value_errors = Vec::new();
+        interpolate_value(value, &mut value_errors);
+        errors.extend(value_errors.into_iter().map(|e| (key.clone(), e)));
+    }
+    errors
+}
+
+fn interpolate_value(value: &mut toml::Value, errors: &mut Vec<anyhow::Error>) {
+    match value {
+        toml::Value::String(text) => match expand_env(text) {
+            Ok(expanded) => *text = expanded,
+            Err(e) => errors.push(e),
+        },
+        toml::Value::Array(values) => {
+            for value in values {
+                interpolate_value(value, errors);
+            }
+        }
+        toml::Value::Table(table) => {
+            for value in table.values_mut() {
+                interpolate_value(value, errors);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Recursively merge `overlay` into `base`, values in `overlay` win
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => {
+                merge_tables(base, overlay)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//! `crowbar --check-config` reads the config files like at startup and lists
+//! every problem with its line, without opening the window.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use chrono::format::{Item, StrftimeItems};
+use gpui::Keystroke;
+
+use crate::actions::registry;
+use crate::config::{self, Config, StatusItem, MAX_HANDLER_BOOST};
+use crate::keybindings;
+use crate::plugins;
+use crate::status_bar;
+
+/// Font sizes outside of this range are most likely typos
+const FONT_SIZES: std::ops::RangeInclusive<f32> = 4.0..=96.0;
+
+/// An option that is invalid, e.g. `handlers.google.boost`
+struct Problem {
+    field: String,
+    message: String,
+}
+
+impl Problem {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Print the problems of every config file, true if there are none
+pub fn run() -> bool {
+    let files = match Config::files() {
+        Ok(files) => files,
+        Err(e) => {
+            println!("{:#}", e);
+            return false;
+        }
+    };
+
+    let mut valid = true;
+    for path in files.iter().filter(|path| path.exists()) {
+        let problems = check_file(path);
+        if problems.is_empty() {
+            println!("{}: OK", path.display());
+        }
+        for problem in problems {
+            println!("{}", problem);
+            valid = false;
+        }
+    }
+    valid
+}
+
+/// Problems as `path:line: field: message`
+fn check_file(path: &Path) -> Vec<String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => return vec![format!("{}: {}", path.display(), e)],
+    };
+    // The parse error has the line and column
+    let mut table = match toml::from_str::<toml::Table>(&text) {
+        Ok(table) => table,
+        Err(e) => return vec![format!("{}: {}", path.display(), e)],
+    };
+    let mut problems: Vec<Problem> = config::interpolate(&mut table)
+        .into_iter()
+        .map(|(key, error)| Problem::new(key, format!("{:#}", error)))
+        .collect();
+    for include in config::included_files(path, &table) {
+        if !include.exists() {
+            problems.push(Problem::new(
+                "include",
+                format!("{} does not exist", include.display()),
+            ));
+        }
+    }
+
+    // Options are checked one by one, so each error names its option
+    problems.extend(table.iter().filter_map(|(key, value)| {
+        let layer = toml::Table::from_iter([(key.clone(), value.clone())]);
+        let error = Config::with_layer(layer).err()?;
+        Some(Problem::new(key, format!("{:#}", error)))
+    }));
+    problems.extend(check_grid(&table));
+    if let Ok(config) = Config::with_layer(table) {
+        problems.extend(check_font(&config));
+        problems.extend(check_status_items(&config));
+        problems.extend(check_key_bindings(&config));
+        problems.extend(check_handlers(&config));
+        problems.extend(check_theme(&config));
+        problems.extend(check_lock(&config));
+    }
+
+    problems
+        .into_iter()
+        .map(|problem| {
+            let line = line_of(&text, &problem.field)
+                .map(|line| format!(":{}", line))
+                .unwrap_or_default();
+            format!(
+                "{}{}: {}: {}",
+                path.display(),
+                line,
+                problem.field,
+                problem.message
+            )
+        })
+        .collect()
+}
+
+/// Checked on the file, the loaded config uses at least one column
+fn check_grid(table: &toml::Table) -> Option<Problem> {
+    let columns = table.get("grid_columns")?.as_integer()?;
+    (columns == 0).then(|| Problem::new("grid_columns", "must be at least 1"))
+}
+
+fn check_font(config: &Config) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    if !FONT_SIZES.contains(&config.font_size) {
+        problems.push(Problem::new(
+            "font_size",
+            format!(
+                "{} is not between {} and {}",
+                config.font_size,
+                FONT_SIZES.start(),
+                FONT_SIZES.end()
+            ),
+        ));
+    }
+    // Without fontconfig there's no way to tell
+    let installed = Command::new("fc-list")
+        .arg("-q")
+        .arg(&config.font_family)
+        .status()
+        .map_or(true, |status| status.success());
+    if config.font_family.trim().is_empty() || !installed {
+        problems.push(Problem::new(
+            "font_family",
+            format!("font {:?} is not installed", config.font_family),
+        ));
+    }
+    problems
+}
+
+fn check_status_items(config: &Config) -> Vec<Problem> {
+    let sections = [
+        ("status_bar_left", &config.status_bar_left),
+        ("status_bar_center", &config.status_bar_center),
+        ("status_bar_right", &config.status_bar_right),
+    ];
+    sections
+        .into_iter()
+        .flat_map(|(section, items)| {
+            items.iter().filter_map(move |item| match item {
+                StatusItem::DateTime { format }
+                    if StrftimeItems::new(format).any(|item| item == Item::Error) =>
+                {
+                    Some(Problem::new(
+                        section,
+                        format!("invalid date format {:?}", format),
+                    ))
+                }
+                StatusItem::Script { command, .. } if command.trim().is_empty() => {
+                    Some(Problem::new(section, "script without a command"))
+                }
+                _ => status_bar::unknown_placeholder(item).map(|placeholder| {
+                    Problem::new(section, format!("unknown placeholder {{{}}}", placeholder))
+                }),
+            })
+        })
+        .collect()
+}
+
+fn check_key_bindings(config: &Config) -> Vec<Problem> {
+    let unknown = keybindings::unknown_actions(config);
+    let mut problems: Vec<Problem> = unknown
+        .iter()
+        .map(|action| Problem::new(format!("key_bindings.{}", action), "unknown action"))
+        .collect();
+
+    for (action, keys) in &config.key_bindings {
+        // Key sequences like `ctrl-k ctrl-c` are keystrokes separated by spaces
+        let invalid = keys.iter().filter(|keys| {
+            keys.split_whitespace()
+                .any(|key| Keystroke::parse(key).is_err())
+        });
+        for keys in invalid {
+            problems.push(Problem::new(
+                format!("key_bindings.{}", action),
+                format!("invalid keys {:?}", keys),
+            ));
+        }
+    }
+    problems
+}
+
+fn check_handlers(config: &Config) -> Vec<Problem> {
+    let handler_ids = registry::handler_ids(config);
+    let plugins = plugins::plugins();
+    let mut problems = Vec::new();
+
+    for (id, settings) in &config.handlers {
+        let field = |option: &str| format!("handlers.{}.{}", id, option);
+        let known =
+            handler_ids.contains(&id.as_str()) || plugins.iter().any(|plugin| plugin.id == *id);
+        if !known {
+            problems.push(Problem::new(format!("handlers.{}", id), "unknown handler"));
+        }
+        if settings.boost.is_some_and(|boost| boost <= 0.0) {
+            problems.push(Problem::new(field("boost"), "must be greater than 0"));
+        }
+        if settings
+            .boost
+            .is_some_and(|boost| boost > MAX_HANDLER_BOOST)
+        {
+            problems.push(Problem::new(
+                field("boost"),
+                format!("must be at most {}", MAX_HANDLER_BOOST),
+            ));
+        }
+        if settings.limit == Some(0) {
+            problems.push(Problem::new(field("limit"), "must be at least 1"));
+        }
+        if let Some(keyword) = &settings.keyword {
+            if keyword.contains(char::is_whitespace) {
+                problems.push(Problem::new(field("keyword"), "must be a single word"));
+            }
+        }
+    }
+    problems
+}
+
+fn check_lock(config: &Config) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    if let Some(hash) = &config.lock_passphrase_hash {
+        if let Err(e) = argon2::PasswordHash::new(hash.trim()) {
+            problems.push(Problem::new(
+                "lock_passphrase_hash",
+                format!("not a hash from `crowbar --hash-passphrase`: {}", e),
+            ));
+        }
+    }
+    problems
+}
+
+fn check_theme(config: &Config) -> Option<Problem> {
+    let theme = config.theme.as_ref()?;
+    if config::themes().contains(theme) {
+        return None;
+    }
+    Some(Problem::new(
+        "theme",
+        format!("no theme file {}.toml in the themes directory", theme),
+    ))
+}
+
+/// Line of an option, e.g. `boost` under `[handlers.google]` for
+/// `handlers.google.boost`. Tables like `[handlers.google]` are found by
+/// their header, `handlers` by its first table.
+fn line_of(text: &str, field: &str) -> Option<usize> {
+    let (section, key) = field.rsplit_once('.').unwrap_or(("", field));
+    // Top-level options come before the first table
+    let mut in_section = section.is_empty();
+
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            let header = line.trim_matches(|c| c == '[' || c == ']').trim();
+            if header == field || header.starts_with(&format!("{}.", field)) {
+                return Some(index + 1);
+            }
+            in_section = header == section;
+        } else if in_section && line.split('=').next().map(str::trim) == Some(key) {
+            return Some(index + 1);
+        }
+    }
+    None
+}
@@ -0,0 +1,179 @@
+//! Compressed backups of the database.
+//!
+//! `:backup` writes a gzip-compressed copy of the database to the backup
+//! directory and `:restore <file>` reads one back after checking that it's an
+//! intact Crowbar database. A rotating backup is also taken automatically
+//! before the schema is migrated.
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Context, Result};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use rusqlite::backup::Progress;
+use rusqlite::{Connection, DatabaseName, OpenFlags};
+
+use super::schema::{Schema, CURRENT_VERSION};
+use crate::common::expand_tilde;
+use crate::config::Config;
+
+const BACKUP_EXTENSION: &str = "db.gz";
+
+/// Prefix of backups taken before migrations, only these are rotated
+const MIGRATION_PREFIX: &str = "crowbar-pre-migration";
+
+/// Number of automatic backups kept before migrations
+const MAX_MIGRATION_BACKUPS: usize = 5;
+
+/// Tables a backup must contain to be restored
+const REQUIRED_TABLES: &[&str] = &["schema_version", "actions", "action_executions", "handlers"];
+
+static BACKUP_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Read the backup directory from the config
+pub fn init(config: &Config) {
+    if let Some(dir) = &config.backup_dir {
+        let _ = BACKUP_DIR.set(expand_tilde(dir));
+    }
+}
+
+/// Configured backup directory, `backups` in the state directory by default
+pub fn backup_dir() -> PathBuf {
+    BACKUP_DIR.get().cloned().unwrap_or_else(|| {
+        super::state_dir()
+            .map(|dir| dir.join("backups"))
+            .unwrap_or_else(|_| expand_tilde("~/.local/share/crowbar/backups"))
+    })
+}
+
+/// Write a timestamped, compressed copy of the database to `dir`
+pub fn create(conn: &Connection, dir: &Path) -> Result<PathBuf> {
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    write_backup(conn, dir, &format!("crowbar-{}", timestamp))
+}
+
+/// Back up the database before migrating it from `version`, keeping only the
+/// most recent migration backups
+pub fn create_before_migration(conn: &Connection, version: i32) -> Result<PathBuf> {
+    let dir = backup_dir();
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let path = write_backup(
+        conn,
+        &dir,
+        &format!("{}-v{}-{}", MIGRATION_PREFIX, version, timestamp),
+    )?;
+
+    let mut backups: Vec<PathBuf> = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with(MIGRATION_PREFIX))
+        })
+        .collect();
+    backups.sort_by_key(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok());
+    let excess = backups.len().saturating_sub(MAX_MIGRATION_BACKUPS);
+    for old in backups.drain(..excess) {
+        let _ = fs::remove_file(old);
+    }
+
+    Ok(path)
+}
+
+/// Replace the contents of the database with a backup. Backups of older
+/// versions are migrated afterwards.
+pub fn restore(conn: &mut Connection, file: &Path) -> Result<()> {
+    let dir = file.parent().unwrap_or(Path::new("."));
+    let temp = dir.join(".crowbar-restore.tmp");
+
+    let result = decompress(file, &temp)
+        .and_then(|_| validate(&temp))
+        .and_then(|_| {
+            conn.restore(DatabaseName::Main, &temp, None::<fn(Progress)>)
+                .context("Failed to restore database")
+        });
+    let _ = fs::remove_file(&temp);
+    result?;
+
+    Schema::initialize(conn)
+}
+
+fn write_backup(conn: &Connection, dir: &Path, name: &str) -> Result<PathBuf> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    let temp = dir.join(format!(".{}.tmp", name));
+    let path = dir.join(format!("{}.{}", name, BACKUP_EXTENSION));
+
+    // The backup API gives a consistent snapshot even while the database is in use
+    let result = conn
+        .backup(DatabaseName::Main, &temp, None)
+        .context("Failed to copy database")
+        .and_then(|_| without_wal(&temp))
+        .and_then(|_| compress(&temp, &path));
+    let _ = fs::remove_file(&temp);
+    result?;
+
+    Ok(path)
+}
+
+/// The copy takes over WAL mode, which would leave `-wal` and `-shm` files
+/// next to a restored backup when it's opened read-only for validation
+fn without_wal(path: &Path) -> Result<()> {
+    let conn = Connection::open(path)?;
+    conn.query_row("PRAGMA journal_mode = DELETE", [], |_| Ok(()))?;
+    Ok(())
+}
+
+fn compress(source: &Path, target: &Path) -> Result<()> {
+    let mut reader = BufReader::new(File::open(source)?);
+    let file = File::create(target).with_context(|| format!("Failed to create {:?}", target))?;
+    let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+    io::copy(&mut reader, &mut encoder)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+fn decompress(source: &Path, target: &Path) -> Result<()> {
+    let file = File::open(source).with_context(|| format!("Failed to open {:?}", source))?;
+    let mut decoder = GzDecoder::new(BufReader::new(file));
+    let mut writer = BufWriter::new(File::create(target)?);
+    io::copy(&mut decoder, &mut writer).context("Backup is not a gzip file")?;
+    Ok(())
+}
+
+/// Check that the file is an intact Crowbar database this version can read
+fn validate(path: &Path) -> Result<()> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+
+    let integrity: String = conn
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .context("Backup is not an SQLite database")?;
+    if integrity != "ok" {
+        return Err(anyhow!("Backup is corrupted: {}", integrity));
+    }
+
+    for table in REQUIRED_TABLES {
+        let exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            [table],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(anyhow!("Backup has no {} table", table));
+        }
+    }
+
+    let version: i32 = conn
+        .query_row("SELECT version FROM schema_version", [], |row| row.get(0))
+        .context("Backup has no schema version")?;
+    if version > CURRENT_VERSION {
+        return Err(anyhow!(
+            "Backup has schema version {}, this Crowbar supports up to {}",
+            version,
+            CURRENT_VERSION
+        ));
+    }
+
+    Ok(())
+}
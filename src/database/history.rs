@@ -0,0 +1,61 @@
+//! Retention of the execution log.
+//!
+//! Every run of an action adds a row to `action_executions`, which the
+//! frecency scores sum up on each query. Rows older than `history_max_age`
+//! days, or beyond the newest `history_max_executions` of an action, are
+//! merged into `action_execution_days`, one row per action and day. The
+//! frecency scores read both tables, the time of day relevance only the rows
+//! still logged one by one.
+
+use anyhow::Result;
+use rusqlite::Connection;
+
+/// Rows past either limit, a limit of 0 is ignored
+const SQL_MARK_EXPIRED: &str = "
+INSERT INTO expired_executions (id)
+SELECT id FROM (
+    SELECT
+        rowid AS id,
+        julianday(execution_timestamp) AS time,
+        ROW_NUMBER() OVER (
+            PARTITION BY action_id
+            ORDER BY julianday(execution_timestamp) DESC
+        ) AS position
+    FROM action_executions
+)
+WHERE (?1 > 0 AND julianday('now') - time > ?1)
+OR (?2 > 0 AND position > ?2)
+";
+
+const SQL_MERGE_EXPIRED: &str = "
+INSERT INTO action_execution_days (action_id, day, executions)
+SELECT action_id, date(execution_timestamp), COUNT(*)
+FROM action_executions
+WHERE rowid IN (SELECT id FROM expired_executions)
+GROUP BY action_id, date(execution_timestamp)
+ON CONFLICT (action_id, day) DO UPDATE SET executions = executions + excluded.executions
+";
+
+/// Merge the executions past the limits into daily counts. Returns the
+/// number of rows merged.
+pub fn merge_expired(conn: &Connection, max_age: u32, max_executions: u32) -> Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "CREATE TEMP TABLE IF NOT EXISTS expired_executions (id INTEGER PRIMARY KEY)",
+        [],
+    )?;
+    tx.execute("DELETE FROM expired_executions", [])?;
+
+    let expired = tx.execute(SQL_MARK_EXPIRED, (max_age, max_executions))?;
+    if expired > 0 {
+        tx.execute(SQL_MERGE_EXPIRED, [])?;
+        tx.execute(
+            "DELETE FROM action_executions WHERE rowid IN (SELECT id FROM expired_executions)",
+            [],
+        )?;
+    }
+    tx.execute("DELETE FROM expired_executions", [])?;
+    tx.commit()?;
+
+    Ok(expired)
+}
@@ -0,0 +1,33 @@
+CREATE TABLE actions (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    searchname TEXT NOT NULL,
+    action_type TEXT NOT NULL,
+    UNIQUE(name, action_type)
+);
+
+CREATE TABLE program_items (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    path TEXT NOT NULL,
+    UNIQUE(path, name)
+);
+
+CREATE TABLE desktop_items (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    exec TEXT NOT NULL,
+    accepts_args BOOLEAN NOT NULL DEFAULT 0,
+    UNIQUE(exec, name)
+);
+
+CREATE TABLE action_executions (
+    action_id TEXT NOT NULL,
+    execution_timestamp TEXT NOT NULL,
+    FOREIGN KEY(action_id) REFERENCES actions(id)
+);
+
+CREATE TABLE handlers (
+    id TEXT PRIMARY KEY,
+    enabled BOOLEAN NOT NULL DEFAULT 1
+);
@@ -0,0 +1,8 @@
+CREATE TABLE remote_binaries (
+    id INTEGER PRIMARY KEY,
+    host TEXT NOT NULL,
+    name TEXT NOT NULL,
+    path TEXT NOT NULL,
+    scanned_at TEXT NOT NULL,
+    UNIQUE(host, path)
+);
@@ -0,0 +1 @@
+DROP TABLE desktop_entry_cache;
@@ -0,0 +1,12 @@
+-- Parsed desktop files by path. `name` is NULL for files that aren't
+-- applications
+CREATE TABLE desktop_entry_cache (
+    path TEXT PRIMARY KEY,
+    mtime INTEGER NOT NULL,
+    name TEXT,
+    exec TEXT,
+    icon TEXT,
+    filename TEXT,
+    takes_args BOOLEAN,
+    categories TEXT
+);
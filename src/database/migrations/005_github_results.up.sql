@@ -0,0 +1,8 @@
+-- Recent GitHub search results, for recall while offline
+CREATE TABLE github_results (
+    url TEXT PRIMARY KEY,
+    kind TEXT NOT NULL,
+    title TEXT NOT NULL,
+    detail TEXT NOT NULL,
+    fetched_at TEXT NOT NULL
+);
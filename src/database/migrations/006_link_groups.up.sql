@@ -0,0 +1,7 @@
+-- Links of the groups added with `:group add`, in the order they are opened
+CREATE TABLE link_groups (
+    name TEXT NOT NULL,
+    url TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    PRIMARY KEY(name, url)
+);
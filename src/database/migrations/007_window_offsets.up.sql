@@ -0,0 +1,6 @@
+-- Window position per monitor, as an offset from the centered position
+CREATE TABLE window_offsets (
+    display TEXT PRIMARY KEY,
+    x REAL NOT NULL,
+    y REAL NOT NULL
+);
@@ -0,0 +1,2 @@
+-- Consent of handlers reading sensitive data, NULL until asked
+ALTER TABLE handlers ADD COLUMN data_access BOOLEAN;
@@ -0,0 +1,7 @@
+-- Git repositories found under `git_repo_roots`
+CREATE TABLE git_repos (
+    path TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    remote_url TEXT,
+    scanned_at TEXT NOT NULL
+);
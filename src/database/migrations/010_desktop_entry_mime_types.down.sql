@@ -0,0 +1 @@
+ALTER TABLE desktop_entry_cache DROP COLUMN mime_types;
@@ -0,0 +1,4 @@
+-- File types of applications. The cache is cleared, so all desktop files are
+-- parsed again for them.
+ALTER TABLE desktop_entry_cache ADD COLUMN mime_types TEXT;
+DELETE FROM desktop_entry_cache;
@@ -0,0 +1,5 @@
+-- Choices made in the launcher, like the view set with `:view`
+CREATE TABLE preferences (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
@@ -0,0 +1,2 @@
+-- Actions pinned to the top of the empty-query results
+ALTER TABLE actions ADD COLUMN pinned BOOLEAN NOT NULL DEFAULT 0;
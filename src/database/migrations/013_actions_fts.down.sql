@@ -0,0 +1,5 @@
+DROP TRIGGER actions_fts_update;
+DROP TRIGGER actions_fts_delete;
+DROP TRIGGER actions_fts_insert;
+DROP TABLE actions_fts;
+ALTER TABLE actions DROP COLUMN keywords;
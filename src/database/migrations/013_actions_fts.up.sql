@@ -0,0 +1,34 @@
+-- Full-text index of the actions, kept in sync by the triggers below and
+-- filled from the existing ones. Prefixes of up to three characters are
+-- indexed, so typing stays fast.
+ALTER TABLE actions ADD COLUMN keywords TEXT NOT NULL DEFAULT '';
+
+CREATE VIRTUAL TABLE actions_fts USING fts5(
+    name,
+    searchname,
+    keywords,
+    content = 'actions',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2',
+    prefix = '1 2 3'
+);
+
+CREATE TRIGGER actions_fts_insert AFTER INSERT ON actions BEGIN
+    INSERT INTO actions_fts (rowid, name, searchname, keywords)
+    VALUES (new.id, new.name, new.searchname, new.keywords);
+END;
+
+CREATE TRIGGER actions_fts_delete AFTER DELETE ON actions BEGIN
+    INSERT INTO actions_fts (actions_fts, rowid, name, searchname, keywords)
+    VALUES ('delete', old.id, old.name, old.searchname, old.keywords);
+END;
+
+CREATE TRIGGER actions_fts_update
+AFTER UPDATE OF name, searchname, keywords ON actions BEGIN
+    INSERT INTO actions_fts (actions_fts, rowid, name, searchname, keywords)
+    VALUES ('delete', old.id, old.name, old.searchname, old.keywords);
+    INSERT INTO actions_fts (rowid, name, searchname, keywords)
+    VALUES (new.id, new.name, new.searchname, new.keywords);
+END;
+
+INSERT INTO actions_fts (actions_fts) VALUES ('rebuild');
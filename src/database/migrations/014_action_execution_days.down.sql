@@ -0,0 +1,13 @@
+-- Daily counts go back into the log as one execution each at noon, so the
+-- frecency stays the same
+WITH RECURSIVE expanded (action_id, day, remaining) AS (
+    SELECT action_id, day, executions FROM action_execution_days
+    UNION ALL
+    SELECT action_id, day, remaining - 1 FROM expanded WHERE remaining > 1
+)
+INSERT INTO action_executions (action_id, execution_timestamp)
+SELECT action_id, day || 'T12:00:00Z' FROM expanded;
+
+DROP VIEW action_history;
+DROP TABLE action_execution_days;
+DROP INDEX action_executions_action;
@@ -0,0 +1,22 @@
+-- Executions older than the retention limits, counted per UTC day, see
+-- `history`
+CREATE INDEX action_executions_action
+ON action_executions (action_id, execution_timestamp);
+
+CREATE TABLE action_execution_days (
+    action_id TEXT NOT NULL,
+    day TEXT NOT NULL,
+    executions INTEGER NOT NULL,
+    PRIMARY KEY(action_id, day)
+);
+
+-- Logged executions and the daily counts together, for the statistics of a
+-- single action. Daily counts are dated at noon. Queries over all actions
+-- read the tables one by one, SQLite doesn't use their indexes through the
+-- view there.
+CREATE VIEW action_history AS
+SELECT action_id, execution_timestamp, 1 AS executions
+FROM action_executions
+UNION ALL
+SELECT action_id, day || 'T12:00:00Z', executions
+FROM action_execution_days;
@@ -0,0 +1,4 @@
+ALTER TABLE desktop_entry_cache DROP COLUMN keywords;
+ALTER TABLE desktop_entry_cache DROP COLUMN generic_name;
+ALTER TABLE desktop_entry_cache DROP COLUMN comment;
+UPDATE actions SET keywords = '' WHERE action_type = 'desktop';
@@ -0,0 +1,6 @@
+-- Comments, generic names and keywords of applications. The cache is
+-- cleared, so the next start scans again and indexes them.
+ALTER TABLE desktop_entry_cache ADD COLUMN comment TEXT;
+ALTER TABLE desktop_entry_cache ADD COLUMN generic_name TEXT;
+ALTER TABLE desktop_entry_cache ADD COLUMN keywords TEXT;
+DELETE FROM desktop_entry_cache;
@@ -0,0 +1,55 @@
+//! Changes to the database schema, in the order they were made.
+//!
+//! Each version has a file `NNN_name.up.sql` applying it and, unless it
+//! can't be undone, `NNN_name.down.sql` undoing it. A new version adds its
+//! files and an entry at the end of `MIGRATIONS`. Applied versions are never
+//! changed, databases that have them won't run them again. Undoing may use
+//! `ALTER TABLE ... DROP COLUMN`, which needs SQLite 3.35.0, checked before
+//! anything is undone.
+
+pub struct Migration {
+    pub version: i32,
+    pub name: &'static str,
+    pub up: &'static str,
+    /// `None` for versions that can't be undone
+    pub down: Option<&'static str>,
+}
+
+macro_rules! migration {
+    ($version:literal, $name:literal) => {
+        Migration {
+            version: $version,
+            name: $name,
+            up: include_str!(concat!($name, ".up.sql")),
+            down: Some(include_str!(concat!($name, ".down.sql"))),
+        }
+    };
+    ($version:literal, $name:literal, irreversible) => {
+        Migration {
+            version: $version,
+            name: $name,
+            up: include_str!(concat!($name, ".up.sql")),
+            down: None,
+        }
+    };
+}
+
+/// All versions, each one above the previous
+pub const MIGRATIONS: &[Migration] = &[
+    migration!(1, "001_initial", irreversible),
+    migration!(2, "002_notes"),
+    migration!(3, "003_remote_binaries"),
+    migration!(4, "004_desktop_entry_cache"),
+    migration!(5, "005_github_results"),
+    migration!(6, "006_link_groups"),
+    migration!(7, "007_window_offsets"),
+    migration!(8, "008_handler_data_access"),
+    migration!(9, "009_git_repos"),
+    migration!(10, "010_desktop_entry_mime_types"),
+    migration!(11, "011_preferences"),
+    migration!(12, "012_pinned_actions"),
+    migration!(13, "013_actions_fts"),
+    migration!(14, "014_action_execution_days"),
+    migration!(15, "015_desktop_entry_keywords"),
+    migration!(16, "016_notes_fts"),
+];
@@ -1,33 +1,138 @@
+pub mod backup;
+mod history;
+mod migrations;
 mod models;
+mod pool;
 mod schema;
+mod transfer;
 
 use anyhow::{Context, Result};
-use rusqlite::Connection;
-use std::{env, fs, path::PathBuf};
+use log::warn;
+use rusqlite::{Connection, OptionalExtension};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
 
-pub use models::{ActionHandlerModel, DesktopItem, ProgramItem};
+use crate::common::{data_home, expand_tilde};
+use crate::config::{Config, LinkGroup};
+use crate::demo;
+use crate::system::git_repos::GitRepo;
+use crate::system::github::{GithubResult, SearchKind};
+use crate::system::{CachedDesktopEntry, DesktopEntry};
+
+pub use models::{
+    ActionHandlerModel, DesktopEntryCacheItem, DesktopItem, GitRepoItem, GithubResultItem,
+    LinkGroupItem, Note, NoteItem, ProgramItem, RemoteBinary, RemoteBinaryItem,
+};
+pub use pool::Reader;
+use pool::{ReadPool, BUSY_TIMEOUT};
+pub use transfer::ImportSummary;
+
+pub const STATE_DIR_ENV: &str = "CROWBAR_STATE_DIR";
+
+/// Path of the database file, over `database_path`
+pub const DATABASE_ENV: &str = "CROWBAR_DATABASE";
+
+/// Day the execution history was last pruned
+const HISTORY_PRUNED_KEY: &str = "history_pruned_on";
+
+static DATABASE_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Read the database path from the config, before the database is opened
+pub fn init(config: &Config) {
+    if let Some(path) = &config.database_path {
+        let _ = DATABASE_PATH.set(expand_tilde(path));
+    }
+}
 
 #[derive(Debug)]
 pub struct Database {
     conn: Connection,
+    readers: ReadPool,
 }
 
 impl Database {
     pub fn new() -> Result<Self> {
-        let conn = Self::initialize_database()?;
-        Ok(Database { conn })
+        let db_path = Self::get_database_path()?;
+        let conn = Self::initialize_database(&db_path)?;
+        Ok(Database {
+            conn,
+            readers: ReadPool::new(db_path),
+        })
+    }
+
+    /// Undo the schema versions after `version`, so an older Crowbar can
+    /// open the database. Returns the version it had.
+    pub fn migrate_down(version: i32) -> Result<i32> {
+        let db_path = Self::get_database_path()?;
+        let conn = Self::open_connection(&db_path)?;
+        schema::Schema::migrate_down(&conn, version)
     }
 
     pub fn connection(&self) -> &Connection {
         &self.conn
     }
 
+    /// Read-only connection for queries that shouldn't wait for writes
+    pub fn reader(&self) -> Result<Reader<'_>> {
+        self.readers.get()
+    }
+
     pub fn insert_binary(&self, name: &str, path: &str) -> Result<i64> {
         ProgramItem::insert(&self.conn, name, path)
     }
 
-    pub fn insert_application(&self, name: &str, exec: &str) -> Result<i64> {
-        DesktopItem::insert(&self.conn, name, exec, true)
+    /// `keywords` are searched along with the name, see `DesktopEntry::search_keywords`
+    pub fn insert_application(&self, name: &str, exec: &str, keywords: &str) -> Result<i64> {
+        DesktopItem::insert(&self.conn, name, exec, true, keywords)
+    }
+
+    /// Remove executables whose file was deleted
+    pub fn remove_missing_binaries(&self) -> Result<usize> {
+        ProgramItem::remove_missing(&self.conn)
+    }
+
+    /// Remove the application, unless another desktop file still provides it
+    pub fn remove_application(&self, name: &str, exec: &str) -> Result<()> {
+        DesktopItem::remove(&self.conn, name, exec)
+    }
+
+    pub fn get_desktop_entry_cache(&self) -> Result<Vec<CachedDesktopEntry>> {
+        DesktopEntryCacheItem::all(&self.conn)
+    }
+
+    pub fn store_desktop_entry_cache(&self, cached: &[CachedDesktopEntry]) -> Result<()> {
+        DesktopEntryCacheItem::replace_all(&self.conn, cached)
+    }
+
+    pub fn store_desktop_entry(&self, cached: &CachedDesktopEntry) -> Result<()> {
+        DesktopEntryCacheItem::store(&self.conn, cached)
+    }
+
+    /// Forget the desktop file, returning its last parsed entry
+    pub fn remove_desktop_entry(&self, path: &str) -> Result<Option<CachedDesktopEntry>> {
+        DesktopEntryCacheItem::remove(&self.conn, path)
+    }
+
+    /// Applications that open files of the type, e.g. `image/png`, by name.
+    /// Entries listing `image/*` open all images.
+    pub fn get_applications_for_type(&self, mime_type: &str) -> Result<Vec<DesktopEntry>> {
+        let group = mime_type.split('/').next().unwrap_or_default();
+        let mut applications: Vec<DesktopEntry> = DesktopEntryCacheItem::all(&self.conn)?
+            .into_iter()
+            .filter_map(|cached| cached.entry)
+            .filter(|entry| {
+                entry
+                    .mime_types
+                    .iter()
+                    .any(|known| known == mime_type || known.strip_suffix("/*") == Some(group))
+            })
+            .collect();
+        applications.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        applications.dedup_by(|a, b| a.name == b.name);
+        Ok(applications)
     }
 
     pub fn set_handler_enabled(&self, handler_id: &str, enabled: bool) -> Result<()> {
@@ -35,8 +140,237 @@ impl Database {
         Ok(())
     }
 
+    pub fn get_handlers(&self) -> Result<Vec<(String, bool)>> {
+        ActionHandlerModel::all(&self.conn)
+    }
+
+    pub fn get_data_access(&self, handler_id: &str) -> Result<Option<bool>> {
+        ActionHandlerModel::data_access(&self.conn, handler_id)
+    }
+
+    /// Allow or deny a handler to read its data, or ask again with `None`
+    pub fn set_data_access(&self, handler_id: &str, access: Option<bool>) -> Result<()> {
+        ActionHandlerModel::set_data_access(&self.conn, handler_id, access)
+    }
+
+    pub fn add_note(&self, content: &str) -> Result<i64> {
+        NoteItem::insert(&self.conn, content)
+    }
+
+    pub fn search_notes(&self, query: &str, limit: usize) -> Result<Vec<Note>> {
+        NoteItem::search(&self.conn, query, limit)
+    }
+
+    /// Write all notes to a Markdown file, oldest first
+    pub fn export_notes(&self, path: &Path) -> Result<usize> {
+        let notes = NoteItem::all(&self.conn)?;
+        let mut markdown = String::from("# Crowbar Notes\n");
+
+        for note in &notes {
+            let created_at = chrono::DateTime::parse_from_rfc3339(&note.created_at)
+                .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+                .unwrap_or_else(|_| note.created_at.clone());
+            markdown.push_str(&format!("\n## {}\n\n{}\n", created_at, note.content.trim()));
+        }
+
+        fs::write(path, markdown)
+            .with_context(|| format!("Failed to write notes to {:?}", path))?;
+        Ok(notes.len())
+    }
+
+    /// Write the usage history, pins and handler settings to a JSON file, see
+    /// `transfer`. Returns the number of actions written.
+    pub fn export_data(&self, path: &Path) -> Result<usize> {
+        let data = transfer::export(&self.conn)?;
+        let json = serde_json::to_string_pretty(&data)?;
+        fs::write(path, json).with_context(|| format!("Failed to write {:?}", path))?;
+        Ok(data.actions.len())
+    }
+
+    /// Merge a file written by `export_data` into the database
+    pub fn import_data(&self, path: &Path) -> Result<ImportSummary> {
+        let json =
+            fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+        let data = serde_json::from_str(&json).context("Not a Crowbar data export")?;
+        transfer::import(&self.conn, &data)
+    }
+
+    pub fn store_remote_binaries(&self, host: &str, binaries: &[RemoteBinary]) -> Result<()> {
+        RemoteBinaryItem::replace_all(&self.conn, host, binaries)
+    }
+
+    pub fn clear_remote_binaries(&self, host: &str) -> Result<()> {
+        RemoteBinaryItem::clear(&self.conn, host)
+    }
+
+    pub fn has_remote_binaries(&self, host: &str) -> bool {
+        RemoteBinaryItem::count(&self.conn, host).is_ok_and(|count| count > 0)
+    }
+
+    pub fn search_remote_binaries(
+        &self,
+        host: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<RemoteBinary>> {
+        RemoteBinaryItem::search(&self.conn, host, query, limit)
+    }
+
+    pub fn store_github_results(&self, results: &[GithubResult]) -> Result<()> {
+        GithubResultItem::store(&self.conn, results)
+    }
+
+    pub fn search_github_results(
+        &self,
+        kind: SearchKind,
+        terms: &str,
+        limit: usize,
+    ) -> Result<Vec<GithubResult>> {
+        GithubResultItem::search(&self.conn, kind, terms, limit)
+    }
+
+    pub fn add_to_link_group(&self, name: &str, urls: &[&str]) -> Result<()> {
+        LinkGroupItem::add(&self.conn, name, urls)
+    }
+
+    pub fn remove_from_link_group(&self, name: &str, urls: &[&str]) -> Result<usize> {
+        LinkGroupItem::remove(&self.conn, name, urls)
+    }
+
+    /// Groups added with `:group add`
+    pub fn get_link_groups(&self) -> Result<Vec<LinkGroup>> {
+        LinkGroupItem::all(&self.conn)
+    }
+
+    pub fn store_git_repos(&self, repos: &[GitRepo]) -> Result<()> {
+        GitRepoItem::replace_all(&self.conn, repos)
+    }
+
+    /// When the repositories were last scanned, as RFC 3339
+    pub fn git_repos_scanned_at(&self) -> Result<Option<String>> {
+        GitRepoItem::scanned_at(&self.conn)
+    }
+
+    pub fn search_git_repos(&self, terms: &str, limit: usize) -> Result<Vec<GitRepo>> {
+        GitRepoItem::search(&self.conn, terms, limit)
+    }
+
+    /// Offset of the window from the center of a monitor
+    pub fn get_window_offset(&self, display: &str) -> Result<Option<(f32, f32)>> {
+        let offset = self
+            .conn
+            .query_row(
+                "SELECT x, y FROM window_offsets WHERE display = ?1",
+                [display],
+                |row| Ok((row.get::<_, f64>(0)? as f32, row.get::<_, f64>(1)? as f32)),
+            )
+            .optional()?;
+        Ok(offset)
+    }
+
+    pub fn set_window_offset(&self, display: &str, (x, y): (f32, f32)) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO window_offsets (display, x, y) VALUES (?1, ?2, ?3)",
+            (display, x as f64, y as f64),
+        )?;
+        Ok(())
+    }
+
+    pub fn get_preference(&self, key: &str) -> Result<Option<String>> {
+        let value = self
+            .conn
+            .query_row(
+                "SELECT value FROM preferences WHERE key = ?1",
+                [key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value)
+    }
+
+    pub fn set_preference(&self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO preferences (key, value) VALUES (?1, ?2)",
+            (key, value),
+        )?;
+        Ok(())
+    }
+
+    /// Icon name of the desktop entry running `exec`
+    pub fn get_desktop_entry_icon(&self, exec: &str) -> Result<Option<String>> {
+        let icon = self
+            .conn
+            .query_row(
+                "SELECT icon FROM desktop_entry_cache WHERE exec = ?1 AND icon != '' LIMIT 1",
+                [exec],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(icon)
+    }
+
+    pub fn is_action_pinned(&self, action_id: usize) -> Result<bool> {
+        let pinned = self
+            .conn
+            .query_row(
+                "SELECT pinned FROM actions WHERE id = ?1",
+                [action_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(pinned.unwrap_or(false))
+    }
+
+    pub fn set_action_pinned(&self, action_id: usize, pinned: bool) -> Result<()> {
+        self.conn.execute(
+            "UPDATE actions SET pinned = ?2 WHERE id = ?1",
+            (action_id, pinned),
+        )?;
+        Ok(())
+    }
+
+    /// Pin or unpin the actions named `name`, ignoring case. Returns the
+    /// number of actions changed.
+    pub fn set_pinned_by_name(&self, name: &str, pinned: bool) -> Result<usize> {
+        let changed = self.conn.execute(
+            "UPDATE actions SET pinned = ?2 WHERE name = ?1 COLLATE NOCASE",
+            (name, pinned),
+        )?;
+        Ok(changed)
+    }
+
+    /// Write a compressed backup to `dir`, or the configured backup directory
+    pub fn backup(&self, dir: Option<&Path>) -> Result<PathBuf> {
+        backup::create(&self.conn, dir.unwrap_or(&backup::backup_dir()))
+    }
+
+    pub fn restore(&mut self, file: &Path) -> Result<()> {
+        backup::restore(&mut self.conn, file)
+    }
+
+    /// Merge old executions into daily counts, see `history`. Only runs once
+    /// a day, returns the number of executions merged.
+    pub fn prune_history(&self, max_age: u32, max_executions: u32) -> Result<usize> {
+        let today = chrono::Local::now().date_naive().to_string();
+        if self.get_preference(HISTORY_PRUNED_KEY)?.as_deref() == Some(today.as_str()) {
+            return Ok(0);
+        }
+
+        let merged = history::merge_expired(&self.conn, max_age, max_executions)?;
+        self.set_preference(HISTORY_PRUNED_KEY, &today)?;
+        Ok(merged)
+    }
+
     pub fn log_execution(&self, action_id: &str) -> Result<()> {
-        let timestamp = chrono::Local::now().to_rfc3339();
+        self.log_execution_at(action_id, chrono::Local::now())
+    }
+
+    pub fn log_execution_at(
+        &self,
+        action_id: &str,
+        time: chrono::DateTime<chrono::Local>,
+    ) -> Result<()> {
+        let timestamp = time.to_rfc3339();
         self.conn.execute(
             "INSERT INTO action_executions (action_id, execution_timestamp) VALUES (?1, ?2)",
             (action_id, timestamp),
@@ -45,28 +379,55 @@ impl Database {
     }
 
     pub fn get_execution_count(&self, action_id: &str) -> Result<i32> {
-        let count: i32 = self.conn.query_row(
-            "SELECT COUNT(*) FROM action_executions WHERE action_id = ?1",
+        let count: i32 = self.reader()?.query_row(
+            "SELECT COALESCE(SUM(executions), 0) FROM action_history WHERE action_id = ?1",
             [action_id],
             |row| row.get(0),
         )?;
         Ok(count)
     }
 
+    pub fn get_last_execution(
+        &self,
+        action_id: &str,
+    ) -> Result<Option<chrono::DateTime<chrono::FixedOffset>>> {
+        // The newest executions are never merged into daily counts
+        let timestamp: Option<String> = self.reader()?.query_row(
+            "SELECT COALESCE(
+                (SELECT MAX(execution_timestamp) FROM action_executions WHERE action_id = ?1),
+                (SELECT MAX(execution_timestamp) FROM action_history WHERE action_id = ?1)
+            )",
+            [action_id],
+            |row| row.get(0),
+        )?;
+        Ok(timestamp.and_then(|timestamp| chrono::DateTime::parse_from_rfc3339(&timestamp).ok()))
+    }
+
     pub fn get_action_relevance(&self, action_id: &str) -> Result<(usize, i32)> {
-        let (rank_score, count): (f64, i32) = self.conn.query_row(
+        let (rank_score, count) = self.action_stats(action_id)?;
+        Ok(((rank_score * 1000.0) as usize, count))
+    }
+
+    /// Time-decayed execution frequency of an action, on the same scale as
+    /// the base score of executables
+    pub fn get_action_frecency(&self, action_id: &str) -> Result<f64> {
+        Ok(self.action_stats(action_id)?.0)
+    }
+
+    fn action_stats(&self, action_id: &str) -> Result<(f64, i32)> {
+        let (rank_score, count): (f64, i32) = self.reader()?.query_row(
             "
             WITH action_stats AS (
                 SELECT 
                     -- Base frequency score (number of executions with time decay)
                     COALESCE(
                         SUM(
-                            1.0 / (1.0 + (
+                            executions / (1.0 + (
                                 (julianday('now') - julianday(execution_timestamp)) * 24.0 * 60.0
                             ) / (24.0 * 60.0)
                         )
                     ), 0) as base_score,
-                    COUNT(*) as execution_count,
+                    COALESCE(SUM(executions), 0) as execution_count,
                     -- Time of day relevance
                     COALESCE((
                         SELECT 0.5 * COUNT(*)
@@ -74,7 +435,7 @@ impl Database {
                         WHERE ae2.action_id = ?1
                         AND strftime('%H', ae2.execution_timestamp) = strftime('%H', 'now')
                     ), 0) as time_bonus
-                FROM action_executions
+                FROM action_history
                 WHERE action_id = ?1
             )
             SELECT 
@@ -85,12 +446,11 @@ impl Database {
             |row| Ok((row.get(0)?, row.get(1)?)),
         )?;
 
-        Ok(((rank_score * 1000.0) as usize, count))
+        Ok((rank_score, count))
     }
 
-    fn initialize_database() -> Result<Connection> {
-        let db_path = Self::get_database_path()?;
-        let conn = Connection::open(&db_path)?;
+    fn initialize_database(db_path: &Path) -> Result<Connection> {
+        let conn = Self::open_connection(db_path)?;
 
         // Initialize schema
         schema::Schema::initialize(&conn)?;
@@ -98,18 +458,51 @@ impl Database {
         Ok(conn)
     }
 
+    fn open_connection(db_path: &Path) -> Result<Connection> {
+        let conn = Connection::open(db_path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+
+        // Readers don't block the writer and the other way around. The mode
+        // is stored in the file, it only fails on file systems without
+        // shared memory, where the default rollback journal is kept.
+        let journal_mode: String =
+            conn.query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))?;
+        if !journal_mode.eq_ignore_ascii_case("wal") {
+            warn!("Database stays in {} journal mode", journal_mode);
+        }
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+
+        Ok(conn)
+    }
+
     fn get_database_path() -> Result<PathBuf> {
-        let home = env::var("HOME")
-            .or_else(|_| env::var("USERPROFILE"))
-            .context("Failed to determine home directory")?;
+        if demo::is_enabled() {
+            return Ok(demo::database_path());
+        }
 
-        let config_dir = PathBuf::from(home)
-            .join(".local")
-            .join("share")
-            .join("crowbar");
+        let path = match env::var_os(DATABASE_ENV).filter(|path| !path.is_empty()) {
+            Some(path) => PathBuf::from(path),
+            None => match DATABASE_PATH.get() {
+                Some(path) => path.clone(),
+                None => state_dir()?.join("crowbar.db"),
+            },
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create database directory {:?}", dir))?;
+        }
 
-        fs::create_dir_all(&config_dir).context("Failed to create config directory")?;
+        Ok(path)
+    }
+}
 
-        Ok(config_dir.join("crowbar.db"))
+/// Directory of the database and its backups, `$XDG_DATA_HOME/crowbar`
+/// unless `CROWBAR_STATE_DIR` is set. State never goes next to the config,
+/// which may be read-only.
+pub fn state_dir() -> Result<PathBuf> {
+    if let Some(dir) = env::var_os(STATE_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        return Ok(PathBuf::from(dir));
     }
+
+    Ok(data_home().join("crowbar"))
 }
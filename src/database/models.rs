@@ -1,5 +1,12 @@
+use std::path::Path;
+
 use anyhow::Result;
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::config::LinkGroup;
+use crate::system::git_repos::GitRepo;
+use crate::system::github::{GithubResult, SearchKind};
+use crate::system::{CachedDesktopEntry, Category, DesktopEntry};
 
 #[derive(Debug)]
 pub struct Action;
@@ -13,6 +20,42 @@ pub struct DesktopItem;
 #[derive(Debug)]
 pub struct ActionHandlerModel;
 
+#[derive(Debug)]
+pub struct NoteItem;
+
+#[derive(Debug)]
+pub struct RemoteBinaryItem;
+
+#[derive(Debug)]
+pub struct DesktopEntryCacheItem;
+
+#[derive(Debug)]
+pub struct GithubResultItem;
+
+#[derive(Debug)]
+pub struct LinkGroupItem;
+
+#[derive(Debug)]
+pub struct GitRepoItem;
+
+/// Number of GitHub results kept for offline recall
+const MAX_GITHUB_RESULTS: usize = 500;
+
+/// A single scratchpad note
+#[derive(Debug, Clone)]
+pub struct Note {
+    pub id: i64,
+    pub content: String,
+    pub created_at: String,
+}
+
+/// An executable found on a remote machine
+#[derive(Debug, Clone)]
+pub struct RemoteBinary {
+    pub name: String,
+    pub path: String,
+}
+
 impl Action {
     pub fn insert(conn: &Connection, name: &str, action_type: &str) -> Result<i64> {
         // Create a searchable name by removing special chars and converting to lowercase
@@ -35,6 +78,16 @@ impl Action {
 
         Ok(id)
     }
+
+    /// Other words the full-text index finds the action by
+    pub fn set_keywords(conn: &Connection, id: i64, keywords: &str) -> Result<()> {
+        // Unchanged keywords would rewrite the index entry on every scan
+        conn.execute(
+            "UPDATE actions SET keywords = ?2 WHERE id = ?1 AND keywords != ?2",
+            (id, keywords),
+        )?;
+        Ok(())
+    }
 }
 
 impl ProgramItem {
@@ -48,11 +101,38 @@ impl ProgramItem {
 
         Ok(action_id)
     }
+
+    /// Remove executables whose file is gone. Returns the number removed.
+    pub fn remove_missing(conn: &Connection) -> Result<usize> {
+        let mut stmt = conn.prepare("SELECT id, path FROM program_items")?;
+        let missing: Vec<i64> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get::<_, String>(1)?)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|(_, path)| !Path::new(path).exists())
+            .map(|(id, _)| id)
+            .collect();
+
+        let tx = conn.unchecked_transaction()?;
+        for id in &missing {
+            tx.execute("DELETE FROM program_items WHERE id = ?1", [id])?;
+            tx.execute("DELETE FROM actions WHERE id = ?1", [id])?;
+        }
+        tx.commit()?;
+        Ok(missing.len())
+    }
 }
 
 impl DesktopItem {
-    pub fn insert(conn: &Connection, name: &str, exec: &str, accepts_args: bool) -> Result<i64> {
+    pub fn insert(
+        conn: &Connection,
+        name: &str,
+        exec: &str,
+        accepts_args: bool,
+        keywords: &str,
+    ) -> Result<i64> {
         let action_id = Action::insert(conn, name, "desktop")?;
+        Action::set_keywords(conn, action_id, keywords)?;
 
         conn.execute(
             "INSERT OR IGNORE INTO desktop_items (id, name, exec, accepts_args) VALUES (?1, ?2, ?3, ?4)",
@@ -61,6 +141,29 @@ impl DesktopItem {
 
         Ok(action_id)
     }
+
+    /// Remove the application unless another cached desktop file still
+    /// provides it
+    pub fn remove(conn: &Connection, name: &str, exec: &str) -> Result<()> {
+        let id: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM desktop_items
+                 WHERE name = ?1 AND exec = ?2
+                 AND NOT EXISTS (
+                     SELECT 1 FROM desktop_entry_cache WHERE name = ?1 AND exec = ?2
+                 )",
+                (name, exec),
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(id) = id {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute("DELETE FROM desktop_items WHERE id = ?1", [id])?;
+            tx.execute("DELETE FROM actions WHERE id = ?1", [id])?;
+            tx.commit()?;
+        }
+        Ok(())
+    }
 }
 
 impl ActionHandlerModel {
@@ -77,6 +180,40 @@ impl ActionHandlerModel {
         Ok(handlers)
     }
 
+    /// All known handlers with their enable state, sorted by id
+    pub fn all(conn: &Connection) -> Result<Vec<(String, bool)>> {
+        let mut stmt = conn.prepare("SELECT id, enabled FROM handlers ORDER BY id")?;
+        let handlers = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(handlers)
+    }
+
+    /// Whether the user allowed the handler to read its data, `None` if they
+    /// haven't been asked yet
+    pub fn data_access(conn: &Connection, handler_id: &str) -> Result<Option<bool>> {
+        let access = conn
+            .query_row(
+                "SELECT data_access FROM handlers WHERE id = ?1",
+                [handler_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(access.flatten())
+    }
+
+    pub fn set_data_access(
+        conn: &Connection,
+        handler_id: &str,
+        access: Option<bool>,
+    ) -> Result<()> {
+        conn.execute(
+            "UPDATE handlers SET data_access = ?1 WHERE id = ?2",
+            (access, handler_id),
+        )?;
+        Ok(())
+    }
+
     pub fn set_enabled(conn: &Connection, handler_id: &str, enabled: bool) -> Result<()> {
         dbg!(&handler_id, &enabled);
         conn.execute(
@@ -86,3 +223,413 @@ impl ActionHandlerModel {
         Ok(())
     }
 }
+
+impl NoteItem {
+    pub fn insert(conn: &Connection, content: &str) -> Result<i64> {
+        let timestamp = chrono::Local::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO notes (content, created_at) VALUES (?1, ?2)",
+            (content, timestamp),
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Find notes with words starting with all terms of the query through the
+    /// full-text index, newest first. An empty query lists the newest notes.
+    pub fn search(conn: &Connection, query: &str, limit: usize) -> Result<Vec<Note>> {
+        let terms: Vec<String> = query
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|term| !term.is_empty())
+            .map(|term| format!("\"{}\"*", term))
+            .collect();
+
+        if terms.is_empty() {
+            let mut stmt = conn.prepare(
+                "SELECT id, content, created_at FROM notes ORDER BY created_at DESC LIMIT ?1",
+            )?;
+            let notes = stmt
+                .query_map([limit], Self::row_to_note)?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            return Ok(notes);
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT n.id, n.content, n.created_at FROM notes_fts
+             JOIN notes n ON n.id = notes_fts.rowid
+             WHERE notes_fts MATCH ?1
+             ORDER BY n.created_at DESC
+             LIMIT ?2",
+        )?;
+        let notes = stmt
+            .query_map(rusqlite::params![terms.join(" "), limit], Self::row_to_note)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(notes)
+    }
+
+    pub fn all(conn: &Connection) -> Result<Vec<Note>> {
+        let mut stmt =
+            conn.prepare("SELECT id, content, created_at FROM notes ORDER BY created_at ASC")?;
+        let notes = stmt
+            .query_map([], Self::row_to_note)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(notes)
+    }
+
+    fn row_to_note(row: &rusqlite::Row) -> rusqlite::Result<Note> {
+        Ok(Note {
+            id: row.get(0)?,
+            content: row.get(1)?,
+            created_at: row.get(2)?,
+        })
+    }
+}
+
+impl RemoteBinaryItem {
+    /// Replace the cached binaries of a host with a fresh scan
+    pub fn replace_all(conn: &Connection, host: &str, binaries: &[RemoteBinary]) -> Result<()> {
+        let timestamp = chrono::Local::now().to_rfc3339();
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM remote_binaries WHERE host = ?1", (host,))?;
+        for binary in binaries {
+            tx.execute(
+                "INSERT OR IGNORE INTO remote_binaries (host, name, path, scanned_at) VALUES (?1, ?2, ?3, ?4)",
+                (host, &binary.name, &binary.path, &timestamp),
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn clear(conn: &Connection, host: &str) -> Result<()> {
+        conn.execute("DELETE FROM remote_binaries WHERE host = ?1", (host,))?;
+        Ok(())
+    }
+
+    pub fn count(conn: &Connection, host: &str) -> Result<usize> {
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM remote_binaries WHERE host = ?1",
+            (host,),
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// Find binaries whose name contains the query, shortest names first
+    pub fn search(
+        conn: &Connection,
+        host: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<RemoteBinary>> {
+        let mut stmt = conn.prepare(
+            "SELECT name, path FROM remote_binaries
+             WHERE host = ?1 AND name LIKE '%' || ?2 || '%'
+             ORDER BY LENGTH(name), name
+             LIMIT ?3",
+        )?;
+        let binaries = stmt
+            .query_map((host, query, limit as i64), |row| {
+                Ok(RemoteBinary {
+                    name: row.get(0)?,
+                    path: row.get(1)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(binaries)
+    }
+}
+
+impl DesktopEntryCacheItem {
+    pub fn all(conn: &Connection) -> Result<Vec<CachedDesktopEntry>> {
+        let mut stmt = conn.prepare(
+            "SELECT path, mtime, name, exec, icon, filename, takes_args, categories, mime_types,
+                 comment, generic_name, keywords
+             FROM desktop_entry_cache",
+        )?;
+        let cached = stmt
+            .query_map([], Self::from_row)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(cached)
+    }
+
+    /// Replace the cache with the files of the latest scan
+    pub fn replace_all(conn: &Connection, cached: &[CachedDesktopEntry]) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM desktop_entry_cache", [])?;
+        for item in cached {
+            Self::insert(&tx, item)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Add or update the file of one entry
+    pub fn store(conn: &Connection, item: &CachedDesktopEntry) -> Result<()> {
+        conn.execute(
+            "DELETE FROM desktop_entry_cache WHERE path = ?1",
+            [&item.path],
+        )?;
+        Self::insert(conn, item)
+    }
+
+    /// Remove the file from the cache, returning what it held
+    pub fn remove(conn: &Connection, path: &str) -> Result<Option<CachedDesktopEntry>> {
+        let cached = conn
+            .query_row(
+                "SELECT path, mtime, name, exec, icon, filename, takes_args, categories, mime_types,
+                     comment, generic_name, keywords
+                 FROM desktop_entry_cache WHERE path = ?1",
+                [path],
+                Self::from_row,
+            )
+            .optional()?;
+        conn.execute("DELETE FROM desktop_entry_cache WHERE path = ?1", [path])?;
+        Ok(cached)
+    }
+
+    fn insert(conn: &Connection, item: &CachedDesktopEntry) -> Result<()> {
+        let entry = item.entry.as_ref();
+        let categories = entry.map(|entry| {
+            entry
+                .categories
+                .iter()
+                .map(Category::as_str)
+                .collect::<Vec<_>>()
+                .join(";")
+        });
+        let mime_types = entry.map(|entry| entry.mime_types.join(";"));
+        let keywords = entry.map(|entry| entry.keywords.join(";"));
+        conn.execute(
+            "INSERT INTO desktop_entry_cache (path, mtime, name, exec, icon, filename, takes_args, categories, mime_types,
+                 comment, generic_name, keywords)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            (
+                &item.path,
+                item.mtime,
+                entry.map(|entry| &entry.name),
+                entry.map(|entry| &entry.exec),
+                entry.map(|entry| &entry.icon),
+                entry.map(|entry| &entry.filename),
+                entry.map(|entry| entry.takes_args),
+                categories,
+                mime_types,
+                entry.map(|entry| &entry.comment),
+                entry.map(|entry| &entry.generic_name),
+                keywords,
+            ),
+        )?;
+        Ok(())
+    }
+
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<CachedDesktopEntry> {
+        let name: Option<String> = row.get(2)?;
+        let entry = match name {
+            Some(name) => Some(DesktopEntry {
+                name,
+                exec: row.get(3)?,
+                icon: row.get(4)?,
+                filename: row.get(5)?,
+                takes_args: row.get(6)?,
+                categories: row
+                    .get::<_, String>(7)?
+                    .split(';')
+                    .filter_map(Category::from_str)
+                    .collect(),
+                mime_types: row
+                    .get::<_, Option<String>>(8)?
+                    .unwrap_or_default()
+                    .split(';')
+                    .filter(|mime_type| !mime_type.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                comment: row.get::<_, Option<String>>(9)?.unwrap_or_default(),
+                generic_name: row.get::<_, Option<String>>(10)?.unwrap_or_default(),
+                keywords: row
+                    .get::<_, Option<String>>(11)?
+                    .unwrap_or_default()
+                    .split(';')
+                    .filter(|keyword| !keyword.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            }),
+            None => None,
+        };
+        Ok(CachedDesktopEntry {
+            path: row.get(0)?,
+            mtime: row.get(1)?,
+            entry,
+        })
+    }
+}
+
+impl GithubResultItem {
+    /// Remember results, dropping the oldest ones beyond the limit
+    pub fn store(conn: &Connection, results: &[GithubResult]) -> Result<()> {
+        let timestamp = chrono::Local::now().to_rfc3339();
+        let tx = conn.unchecked_transaction()?;
+        for result in results {
+            tx.execute(
+                "INSERT OR REPLACE INTO github_results (url, kind, title, detail, fetched_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                (
+                    &result.url,
+                    result.kind.name(),
+                    &result.title,
+                    &result.detail,
+                    &timestamp,
+                ),
+            )?;
+        }
+        tx.execute(
+            "DELETE FROM github_results WHERE url NOT IN (
+                SELECT url FROM github_results ORDER BY fetched_at DESC LIMIT ?1
+             )",
+            (MAX_GITHUB_RESULTS as i64,),
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Remembered results whose title or detail contains every term
+    pub fn search(
+        conn: &Connection,
+        kind: SearchKind,
+        terms: &str,
+        limit: usize,
+    ) -> Result<Vec<GithubResult>> {
+        let mut stmt = conn.prepare(
+            "SELECT url, title, detail FROM github_results
+             WHERE kind = ?1
+             ORDER BY fetched_at DESC",
+        )?;
+        let terms: Vec<String> = terms.split_whitespace().map(str::to_lowercase).collect();
+        let results = stmt
+            .query_map((kind.name(),), |row| {
+                Ok(GithubResult {
+                    kind,
+                    url: row.get(0)?,
+                    title: row.get(1)?,
+                    detail: row.get(2)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|result| {
+                let text = format!("{} {}", result.title, result.detail).to_lowercase();
+                terms.iter().all(|term| text.contains(term))
+            })
+            .take(limit)
+            .collect();
+        Ok(results)
+    }
+}
+
+impl LinkGroupItem {
+    /// Append links to a group, creating it if needed. Links already in the
+    /// group keep their place.
+    pub fn add(conn: &Connection, name: &str, urls: &[&str]) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        for url in urls {
+            tx.execute(
+                "INSERT OR IGNORE INTO link_groups (name, url, position)
+                 SELECT ?1, ?2, COALESCE(MAX(position) + 1, 0)
+                 FROM link_groups WHERE name = ?1",
+                (name, url),
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Remove links from a group, or the whole group if no links are given.
+    /// Returns the number of removed links.
+    pub fn remove(conn: &Connection, name: &str, urls: &[&str]) -> Result<usize> {
+        if urls.is_empty() {
+            return Ok(conn.execute("DELETE FROM link_groups WHERE name = ?1", (name,))?);
+        }
+
+        let mut removed = 0;
+        for url in urls {
+            removed += conn.execute(
+                "DELETE FROM link_groups WHERE name = ?1 AND url = ?2",
+                (name, url),
+            )?;
+        }
+        Ok(removed)
+    }
+
+    pub fn all(conn: &Connection) -> Result<Vec<LinkGroup>> {
+        let mut stmt = conn.prepare("SELECT name, url FROM link_groups ORDER BY name, position")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut groups: Vec<LinkGroup> = Vec::new();
+        for (name, url) in rows {
+            match groups.last_mut() {
+                Some(group) if group.name == name => group.urls.push(url),
+                _ => groups.push(LinkGroup {
+                    name,
+                    urls: vec![url],
+                }),
+            }
+        }
+        Ok(groups)
+    }
+}
+
+impl GitRepoItem {
+    /// Replace the repositories with the ones of the latest scan
+    pub fn replace_all(conn: &Connection, repos: &[GitRepo]) -> Result<()> {
+        let timestamp = chrono::Local::now().to_rfc3339();
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM git_repos", [])?;
+        for repo in repos {
+            tx.execute(
+                "INSERT OR REPLACE INTO git_repos (path, name, remote_url, scanned_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                (
+                    repo.path.to_string_lossy(),
+                    &repo.name,
+                    &repo.remote_url,
+                    &timestamp,
+                ),
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// When the repositories were last scanned, `None` if they never were or
+    /// none were found
+    pub fn scanned_at(conn: &Connection) -> Result<Option<String>> {
+        let scanned_at = conn.query_row("SELECT MAX(scanned_at) FROM git_repos", [], |row| {
+            row.get(0)
+        })?;
+        Ok(scanned_at)
+    }
+
+    /// Repositories whose path contains every term, sorted by name
+    pub fn search(conn: &Connection, terms: &str, limit: usize) -> Result<Vec<GitRepo>> {
+        let mut stmt =
+            conn.prepare("SELECT path, name, remote_url FROM git_repos ORDER BY name")?;
+        let terms: Vec<String> = terms.split_whitespace().map(str::to_lowercase).collect();
+        let repos = stmt
+            .query_map([], |row| {
+                Ok(GitRepo {
+                    path: row.get::<_, String>(0)?.into(),
+                    name: row.get(1)?,
+                    remote_url: row.get(2)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|repo| {
+                let path = repo.path.to_string_lossy().to_lowercase();
+                terms.iter().all(|term| path.contains(term))
+            })
+            .take(limit)
+            .collect();
+        Ok(repos)
+    }
+}
@@ -0,0 +1,86 @@
+//! Read-only connections to the database.
+//!
+//! The database runs in WAL mode, so readers never wait for the writer and
+//! the writer only waits for other writers, up to `BUSY_TIMEOUT`. Searches
+//! and ranking queries of the UI borrow a reader from the pool while the
+//! scanner and the execution log write through their own connection.
+
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OpenFlags};
+
+/// How long a connection waits for a lock held by another one
+pub const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Idle readers kept open, more are opened while all of them are borrowed
+const MAX_IDLE_READERS: usize = 4;
+
+#[derive(Debug)]
+pub struct ReadPool {
+    path: PathBuf,
+    idle: Mutex<Vec<Connection>>,
+}
+
+impl ReadPool {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// An idle reader, or a new one if there's none
+    pub fn get(&self) -> Result<Reader<'_>> {
+        let idle = self.idle.lock().unwrap().pop();
+        let conn = match idle {
+            Some(conn) => conn,
+            None => self.open()?,
+        };
+        Ok(Reader {
+            conn: Some(conn),
+            pool: self,
+        })
+    }
+
+    fn open(&self) -> Result<Connection> {
+        let conn = Connection::open_with_flags(
+            &self.path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .with_context(|| format!("Failed to open {:?} for reading", self.path))?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        Ok(conn)
+    }
+}
+
+/// Read-only connection, returned to the pool when dropped
+pub struct Reader<'a> {
+    conn: Option<Connection>,
+    pool: &'a ReadPool,
+}
+
+impl Deref for Reader<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn
+            .as_ref()
+            .expect("Reader used after it was returned")
+    }
+}
+
+impl Drop for Reader<'_> {
+    fn drop(&mut self) {
+        let Some(conn) = self.conn.take() else {
+            return;
+        };
+        let mut idle = self.pool.idle.lock().unwrap();
+        if idle.len() < MAX_IDLE_READERS {
+            idle.push(conn);
+        }
+    }
+}
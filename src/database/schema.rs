@@ -1,125 +1,203 @@
-use anyhow::Result;
+//! Brings the database to the schema of this version.
+//!
+//! The versions are the files in `migrations`. Every one is applied in its
+//! own transaction together with its entry in `schema_migrations`, so a
+//! failing one leaves the database at the version before it. New databases
+//! are built by applying all of them, the same way older ones are upgraded.
+
+use anyhow::{anyhow, Context, Result};
+use log::{info, warn};
 use rusqlite::Connection;
 
-pub const CURRENT_VERSION: i32 = 1;
+use super::backup;
+use super::migrations::{Migration, MIGRATIONS};
 
+pub const CURRENT_VERSION: i32 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
+/// Undoing versions drops columns, which SQLite supports from 3.35.0 on
+const MIN_SQLITE_VERSION_DOWN: i32 = 3_035_000;
+
+/// Version of the database, kept for older versions of Crowbar that read it
 pub const TABLE_SCHEMA_VERSION: &str = "
 CREATE TABLE IF NOT EXISTS schema_version (
     version INTEGER NOT NULL
 )";
 
-pub const TABLE_ACTIONS: &str = "
-CREATE TABLE IF NOT EXISTS actions (
-    id INTEGER PRIMARY KEY,
-    name TEXT NOT NULL,
-    searchname TEXT NOT NULL,
-    action_type TEXT NOT NULL,
-    UNIQUE(name, action_type)
-)";
-
-pub const TABLE_PROGRAM_ITEMS: &str = "
-CREATE TABLE IF NOT EXISTS program_items (
-    id INTEGER PRIMARY KEY,
+/// Versions applied to the database. `applied_at` is NULL for those applied
+/// before the history was recorded.
+pub const TABLE_SCHEMA_MIGRATIONS: &str = "
+CREATE TABLE IF NOT EXISTS schema_migrations (
+    version INTEGER PRIMARY KEY,
     name TEXT NOT NULL,
-    path TEXT NOT NULL,
-    UNIQUE(path, name)
-)";
-
-pub const TABLE_DESKTOP_ITEMS: &str = "
-CREATE TABLE IF NOT EXISTS desktop_items (
-    id INTEGER PRIMARY KEY,
-    name TEXT NOT NULL,
-    exec TEXT NOT NULL,
-    accepts_args BOOLEAN NOT NULL DEFAULT 0,
-    UNIQUE(exec, name)
-)";
-
-pub const TABLE_ACTION_EXECUTIONS: &str = "
-CREATE TABLE IF NOT EXISTS action_executions (
-    action_id TEXT NOT NULL,
-    execution_timestamp TEXT NOT NULL,
-    FOREIGN KEY(action_id) REFERENCES actions(id)
-)";
-
-pub const TABLE_HANDLERS: &str = "
-CREATE TABLE IF NOT EXISTS handlers (
-    id TEXT PRIMARY KEY,
-    enabled BOOLEAN NOT NULL DEFAULT 1
+    applied_at TEXT
 )";
 
-// Schema version migration steps
-struct MigrationStep {
-    target_version: i32,
-    migration_fn: fn(&Connection) -> Result<()>,
-}
-
 pub struct Schema;
 
 impl Schema {
     pub fn initialize(conn: &Connection) -> Result<()> {
-        // create tables
-        Self::create_tables(conn)?;
-
-        // Get current version
-        let version: Option<i32> = conn
-            .query_row("SELECT version FROM schema_version", [], |row| row.get(0))
-            .ok();
-
-        match version {
-            None => {
-                // First time initialization
-                Self::create_tables(conn)?;
-                conn.execute(
-                    "INSERT INTO schema_version (version) VALUES (?1)",
-                    [CURRENT_VERSION],
-                )?;
-            }
-            Some(v) if v < CURRENT_VERSION => {
-                // Migrate database schema
-                Self::migrate_schema(conn, v)?;
-                conn.execute("UPDATE schema_version SET version = ?1", [CURRENT_VERSION])?;
+        conn.execute(TABLE_SCHEMA_VERSION, [])?;
+        conn.execute(TABLE_SCHEMA_MIGRATIONS, [])?;
+
+        let version = Self::version(conn)?;
+        Self::record_earlier(conn, version)?;
+
+        if version > CURRENT_VERSION {
+            warn!(
+                "Database has schema version {}, this Crowbar knows up to {}. \
+                 Run `crowbar --migrate-down {}` with the newer version before going back.",
+                version, CURRENT_VERSION, CURRENT_VERSION
+            );
+            return Ok(());
+        }
+
+        if version > 0 && version < CURRENT_VERSION {
+            // Keep a copy in case the migration goes wrong
+            match backup::create_before_migration(conn, version) {
+                Ok(path) => info!("Backed up database to {:?}", path),
+                Err(e) => warn!("Failed to back up database before migration: {:#}", e),
             }
-            _ => (), // Schema is up to date
+        }
+
+        for migration in MIGRATIONS.iter().filter(|m| m.version > version) {
+            Self::apply(conn, migration)?;
+            info!("Migrated schema to version {}", migration.version);
         }
 
         Ok(())
     }
 
-    fn create_tables(conn: &Connection) -> Result<()> {
-        // Execute each table creation statement
+    /// Undo the versions after `target`, for going back to an older Crowbar.
+    /// Returns the version the database had.
+    pub fn migrate_down(conn: &Connection, target: i32) -> Result<i32> {
         conn.execute(TABLE_SCHEMA_VERSION, [])?;
-        conn.execute(TABLE_ACTIONS, [])?;
-        conn.execute(TABLE_PROGRAM_ITEMS, [])?;
-        conn.execute(TABLE_DESKTOP_ITEMS, [])?;
-        conn.execute(TABLE_ACTION_EXECUTIONS, [])?;
-        conn.execute(TABLE_HANDLERS, [])?;
+        conn.execute(TABLE_SCHEMA_MIGRATIONS, [])?;
+
+        let version = Self::version(conn)?;
+        if version > CURRENT_VERSION {
+            return Err(anyhow!(
+                "Database has schema version {}, this Crowbar knows up to {}",
+                version,
+                CURRENT_VERSION
+            ));
+        }
+        if target >= version {
+            return Err(anyhow!("Database already has schema version {}", version));
+        }
+        Self::record_earlier(conn, version)?;
+
+        // Checked before anything is undone, so it's all or nothing
+        let mut undone = Vec::new();
+        for migration in MIGRATIONS
+            .iter()
+            .rev()
+            .filter(|m| m.version > target && m.version <= version)
+        {
+            let down = migration.down.ok_or_else(|| {
+                anyhow!(
+                    "Schema version {} ({}) can't be undone",
+                    migration.version,
+                    migration.name
+                )
+            })?;
+            undone.push((migration, down));
+        }
+        if rusqlite::version_number() < MIN_SQLITE_VERSION_DOWN {
+            return Err(anyhow!(
+                "Migrating down needs SQLite 3.35.0 or newer, Crowbar uses {}",
+                rusqlite::version()
+            ));
+        }
+
+        let path = backup::create_before_migration(conn, version)
+            .context("Failed to back up database before migration")?;
+        info!("Backed up database to {:?}", path);
+
+        for (migration, down) in undone {
+            Self::undo(conn, migration, down)?;
+            info!("Migrated schema down from version {}", migration.version);
+        }
 
+        Ok(version)
+    }
+
+    /// 0 for a new database
+    fn version(conn: &Connection) -> Result<i32> {
+        let version = conn.query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM schema_version",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(version)
+    }
+
+    fn set_version(conn: &Connection, version: i32) -> Result<()> {
+        conn.execute("DELETE FROM schema_version", [])?;
+        conn.execute(
+            "INSERT INTO schema_version (version) VALUES (?1)",
+            [version],
+        )?;
         Ok(())
     }
 
-    fn migrate_schema(conn: &Connection, current_version: i32) -> Result<()> {
-        // Define the migration steps
-        let migration_steps = [
-            // Add migration steps for future versions
-            MigrationStep {
-                target_version: 1,
-                migration_fn: Self::migrate_to_v1,
-            },
-        ];
-
-        // Execute migrations in order, skipping those already applied
-        for step in migration_steps.iter() {
-            if current_version < step.target_version {
-                (step.migration_fn)(conn)?;
-                println!("Migrated schema to version {}", step.target_version);
-            }
+    /// Databases migrated before the history was kept get entries for the
+    /// versions they have
+    fn record_earlier(conn: &Connection, version: i32) -> Result<()> {
+        let recorded: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM schema_migrations)",
+            [],
+            |row| row.get(0),
+        )?;
+        if recorded {
+            return Ok(());
         }
 
+        let tx = conn.unchecked_transaction()?;
+        for migration in MIGRATIONS.iter().filter(|m| m.version <= version) {
+            tx.execute(
+                "INSERT INTO schema_migrations (version, name) VALUES (?1, ?2)",
+                (migration.version, migration.name),
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn apply(conn: &Connection, migration: &Migration) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(migration.up).with_context(|| {
+            format!(
+                "Failed to migrate schema to version {} ({})",
+                migration.version, migration.name
+            )
+        })?;
+        tx.execute(
+            "INSERT INTO schema_migrations (version, name, applied_at) VALUES (?1, ?2, ?3)",
+            (
+                migration.version,
+                migration.name,
+                chrono::Utc::now().to_rfc3339(),
+            ),
+        )?;
+        Self::set_version(&tx, migration.version)?;
+        tx.commit()?;
         Ok(())
     }
 
-    fn migrate_to_v1(conn: &Connection) -> Result<()> {
-        Self::create_tables(conn)?;
+    fn undo(conn: &Connection, migration: &Migration, down: &str) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(down).with_context(|| {
+            format!(
+                "Failed to migrate schema down from version {} ({})",
+                migration.version, migration.name
+            )
+        })?;
+        tx.execute(
+            "DELETE FROM schema_migrations WHERE version = ?1",
+            [migration.version],
+        )?;
+        Self::set_version(&tx, migration.version - 1)?;
+        tx.commit()?;
         Ok(())
     }
 }
@@ -0,0 +1,239 @@
+//! Usage data moved between machines as JSON.
+//!
+//! `--export-data` and `:export` write the actions that were run or pinned
+//! with their execution history, and which handlers are enabled or allowed
+//! to read their data. `--import-data` merges such a file into the database,
+//! so the ranking carries over. Applications and executables are matched by
+//! name and kind, their ids differ on every machine, other actions by their
+//! id. Importing a file twice doesn't count its executions twice.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Increased when the format changes in a way older versions can't read
+const FORMAT_VERSION: u32 = 1;
+
+/// Applications and executables that were run or pinned
+const SQL_USED_ACTIONS: &str = "
+SELECT a.id, a.name, a.action_type, a.pinned
+FROM actions a
+WHERE a.pinned
+OR EXISTS (SELECT 1 FROM action_executions ae WHERE ae.action_id = CAST(a.id AS TEXT))
+OR EXISTS (SELECT 1 FROM action_execution_days ad WHERE ad.action_id = CAST(a.id AS TEXT))
+ORDER BY a.id
+";
+
+/// Executed actions of handlers, which aren't in the actions table. Numeric
+/// ids are of applications removed since, they'd be another one elsewhere.
+const SQL_USED_BUILTINS: &str = "
+SELECT DISTINCT action_id
+FROM action_history
+WHERE action_id GLOB '*[^0-9]*'
+ORDER BY action_id
+";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UsageData {
+    pub version: u32,
+    /// RFC 3339
+    pub exported_at: String,
+    #[serde(default)]
+    pub actions: Vec<ActionUsage>,
+    #[serde(default)]
+    pub handlers: Vec<HandlerSettings>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ActionUsage {
+    #[serde(flatten)]
+    pub action: ActionKey,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    /// RFC 3339 timestamps of the logged executions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub executions: Vec<String>,
+    /// Executions merged into daily counts by the retention, by UTC day
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub daily: BTreeMap<String, u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ActionKey {
+    /// Application or executable found by the scan
+    Scanned { name: String, action_type: String },
+    /// Action of a handler, e.g. a web search
+    Builtin { id: String },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HandlerSettings {
+    pub id: String,
+    pub enabled: bool,
+    /// `None` until the user was asked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_access: Option<bool>,
+}
+
+/// Outcome of an import
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    pub actions: usize,
+    pub executions: usize,
+    /// Applications and executables not installed here
+    pub missing: usize,
+}
+
+pub fn export(conn: &Connection) -> Result<UsageData> {
+    let mut actions = Vec::new();
+
+    let mut stmt = conn.prepare(SQL_USED_ACTIONS)?;
+    let scanned = stmt
+        .query_map([], |row| {
+            let action = ActionKey::Scanned {
+                name: row.get(1)?,
+                action_type: row.get(2)?,
+            };
+            Ok((row.get::<_, i64>(0)?, action, row.get::<_, bool>(3)?))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    for (id, action, pinned) in scanned {
+        actions.push(usage(conn, &id.to_string(), action, pinned)?);
+    }
+
+    let mut stmt = conn.prepare(SQL_USED_BUILTINS)?;
+    let builtins = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    for id in builtins {
+        let action = ActionKey::Builtin { id: id.clone() };
+        actions.push(usage(conn, &id, action, false)?);
+    }
+
+    let mut stmt = conn.prepare("SELECT id, enabled, data_access FROM handlers ORDER BY id")?;
+    let handlers = stmt
+        .query_map([], |row| {
+            Ok(HandlerSettings {
+                id: row.get(0)?,
+                enabled: row.get(1)?,
+                data_access: row.get(2)?,
+            })
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    Ok(UsageData {
+        version: FORMAT_VERSION,
+        exported_at: chrono::Local::now().to_rfc3339(),
+        actions,
+        handlers,
+    })
+}
+
+fn usage(
+    conn: &Connection,
+    action_id: &str,
+    action: ActionKey,
+    pinned: bool,
+) -> Result<ActionUsage> {
+    let mut stmt = conn.prepare(
+        "SELECT execution_timestamp FROM action_executions
+        WHERE action_id = ?1 ORDER BY execution_timestamp",
+    )?;
+    let executions = stmt
+        .query_map([action_id], |row| row.get(0))?
+        .collect::<std::result::Result<Vec<String>, _>>()?;
+
+    let mut stmt =
+        conn.prepare("SELECT day, executions FROM action_execution_days WHERE action_id = ?1")?;
+    let daily = stmt
+        .query_map([action_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<std::result::Result<BTreeMap<String, u32>, _>>()?;
+
+    Ok(ActionUsage {
+        action,
+        pinned,
+        executions,
+        daily,
+    })
+}
+
+/// Merge the data into the database. Pins are added, not removed, and the
+/// handler settings of the file win.
+pub fn import(conn: &Connection, data: &UsageData) -> Result<ImportSummary> {
+    if data.version > FORMAT_VERSION {
+        return Err(anyhow!(
+            "The file has format version {}, this Crowbar reads up to {}",
+            data.version,
+            FORMAT_VERSION
+        ));
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    let mut summary = ImportSummary::default();
+
+    for usage in &data.actions {
+        let action_id = match &usage.action {
+            ActionKey::Scanned { name, action_type } => {
+                let id: Option<i64> = tx
+                    .query_row(
+                        "SELECT id FROM actions WHERE name = ?1 AND action_type = ?2",
+                        (name, action_type),
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                let Some(id) = id else {
+                    summary.missing += 1;
+                    continue;
+                };
+                if usage.pinned {
+                    tx.execute("UPDATE actions SET pinned = 1 WHERE id = ?1", [id])?;
+                }
+                id.to_string()
+            }
+            ActionKey::Builtin { id } => id.clone(),
+        };
+
+        for timestamp in &usage.executions {
+            chrono::DateTime::parse_from_rfc3339(timestamp)
+                .map_err(|e| anyhow!("Invalid execution time {:?}: {}", timestamp, e))?;
+            summary.executions += tx.execute(
+                "INSERT INTO action_executions (action_id, execution_timestamp)
+                SELECT ?1, ?2
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM action_executions
+                    WHERE action_id = ?1 AND execution_timestamp = ?2
+                )",
+                (&action_id, timestamp),
+            )?;
+        }
+        for (day, executions) in &usage.daily {
+            let changed = tx.execute(
+                "INSERT INTO action_execution_days (action_id, day, executions)
+                VALUES (?1, ?2, ?3)
+                ON CONFLICT (action_id, day) DO UPDATE SET executions = excluded.executions
+                WHERE excluded.executions > executions",
+                (&action_id, day, executions),
+            )?;
+            if changed > 0 {
+                summary.executions += *executions as usize;
+            }
+        }
+        summary.actions += 1;
+    }
+
+    for handler in &data.handlers {
+        tx.execute(
+            "INSERT INTO handlers (id, enabled, data_access) VALUES (?1, ?2, ?3)
+            ON CONFLICT (id) DO UPDATE SET
+                enabled = excluded.enabled,
+                data_access = COALESCE(excluded.data_access, data_access)",
+            (&handler.id, handler.enabled, handler.data_access),
+        )?;
+    }
+
+    tx.commit()?;
+    Ok(summary)
+}
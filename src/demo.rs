@@ -0,0 +1,155 @@
+//! Demo mode, started with `crowbar --demo`. Applications, executables,
+//! browser history and files come from a fixed fake dataset instead of the
+//! system, and the database lives in a temporary file. The results look the
+//! same on every machine, which helps with screenshots, UI work and
+//! reproducing bug reports.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::Result;
+use chrono::{Duration, Local};
+use log::info;
+
+use crate::actions::handlers::browser_history_handler::HistoryEntry;
+use crate::database::Database;
+use crate::system::file_finder;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Name and command of the fake desktop applications
+const APPLICATIONS: &[(&str, &str)] = &[
+    ("Firefox", "firefox %u"),
+    ("Files", "nautilus --new-window %U"),
+    ("Terminal", "gnome-terminal"),
+    ("Text Editor", "gnome-text-editor %U"),
+    ("Calculator", "gnome-calculator"),
+    ("Settings", "gnome-control-center"),
+    ("Spotify", "spotify %U"),
+    ("Thunderbird", "thunderbird %u"),
+    ("GIMP", "gimp %U"),
+    ("LibreOffice Writer", "libreoffice --writer %U"),
+    ("Visual Studio Code", "code %F"),
+    ("System Monitor", "gnome-system-monitor"),
+];
+
+/// Name and path of the fake executables on `PATH`
+const EXECUTABLES: &[(&str, &str)] = &[
+    ("bash", "/usr/bin/bash"),
+    ("cargo", "/usr/bin/cargo"),
+    ("git", "/usr/bin/git"),
+    ("htop", "/usr/bin/htop"),
+    ("nvim", "/usr/bin/nvim"),
+    ("python3", "/usr/bin/python3"),
+    ("ssh", "/usr/bin/ssh"),
+    ("top", "/usr/bin/top"),
+];
+
+/// Launches of the fake applications, as name and hours ago, so some rank
+/// higher than others
+const EXECUTIONS: &[(&str, i64)] = &[
+    ("Firefox", 1),
+    ("Firefox", 3),
+    ("Firefox", 26),
+    ("Terminal", 2),
+    ("Terminal", 50),
+    ("Visual Studio Code", 5),
+    ("Spotify", 30),
+];
+
+/// Title, URL and visit count of the fake browser history, most recent first
+const HISTORY: &[(&str, &str, i64)] = &[
+    (
+        "Rust Programming Language",
+        "https://www.rust-lang.org/",
+        42,
+    ),
+    ("docs.rs", "https://docs.rs/", 31),
+    ("GitHub", "https://github.com/", 57),
+    ("Hacker News", "https://news.ycombinator.com/", 12),
+    ("Wikipedia", "https://en.wikipedia.org/wiki/Main_Page", 9),
+    ("The Rust Book", "https://doc.rust-lang.org/book/", 18),
+    ("crates.io", "https://crates.io/", 7),
+    ("OpenStreetMap", "https://www.openstreetmap.org/", 3),
+];
+
+/// Paths of the fake files
+const FILES: &[&str] = &[
+    "/home/demo/Documents/report.pdf",
+    "/home/demo/Documents/budget.ods",
+    "/home/demo/Documents/notes.md",
+    "/home/demo/Music/album/01 Intro.flac",
+    "/home/demo/Pictures/holiday.jpg",
+    "/home/demo/Pictures/screenshot.png",
+    "/home/demo/projects/crowbar/Cargo.toml",
+    "/home/demo/projects/crowbar/README.md",
+];
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Switch to the fake dataset, starting with an empty database
+pub fn enable() -> Result<()> {
+    ENABLED.store(true, Ordering::Relaxed);
+
+    let path = database_path();
+    if path.exists() {
+        fs::remove_file(&path)?;
+    }
+    info!("Demo mode, using database at {:?}", path);
+    Ok(())
+}
+
+/// Temporary database of this process
+pub fn database_path() -> PathBuf {
+    env::temp_dir().join(format!("crowbar-demo-{}.db", std::process::id()))
+}
+
+/// Fill the database with the fake applications and executables, in place of
+/// a system scan
+pub fn seed(db: &Database) -> Result<()> {
+    for (name, path) in EXECUTABLES {
+        db.insert_binary(name, path)?;
+    }
+
+    let now = Local::now();
+    for (name, exec) in APPLICATIONS {
+        let id = db.insert_application(name, exec, "")?;
+        for (_, hours_ago) in EXECUTIONS.iter().filter(|(app, _)| app == name) {
+            db.log_execution_at(&id.to_string(), now - Duration::hours(*hours_ago))?;
+        }
+    }
+    Ok(())
+}
+
+/// Fake history entries whose title or URL contains the search term
+pub fn history(search_term: &str) -> Vec<HistoryEntry> {
+    let search_term = search_term.to_lowercase();
+    HISTORY
+        .iter()
+        .enumerate()
+        .filter(|(_, (title, url, _))| {
+            title.to_lowercase().contains(&search_term) || url.contains(&search_term)
+        })
+        .map(|(age, (title, url, visit_count))| HistoryEntry {
+            title: title.to_string(),
+            url: url.to_string(),
+            visit_count: *visit_count,
+            last_visit: (HISTORY.len() - age) as i64,
+        })
+        .collect()
+}
+
+/// Fake files matching all terms
+pub fn files(terms: &str, limit: usize) -> Vec<PathBuf> {
+    let terms: Vec<&str> = terms.split_whitespace().collect();
+    FILES
+        .iter()
+        .map(PathBuf::from)
+        .filter(|path| file_finder::matches_terms(path, &terms))
+        .take(limit)
+        .collect()
+}
@@ -0,0 +1,158 @@
+//! Event stream for external UIs.
+//!
+//! When `event_stream` is enabled, Crowbar listens on a Unix socket and
+//! broadcasts what it's doing, so overlays and bar modules can follow along.
+//! A client first sends the configured token on a line of its own and then
+//! receives one JSON object per line:
+//!
+//! ```text
+//! {"event":"query","query":"fire"}
+//! {"event":"results","query":"fire","names":["Firefox","firewall-config"]}
+//! {"event":"execute","query":"fire","name":"Firefox"}
+//! ```
+
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use log::{info, warn};
+use serde::Serialize;
+
+use crate::common::expand_tilde;
+use crate::config::Config;
+
+/// Clients that don't authenticate within this time are dropped
+const AUTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Clients that don't read their events within this time are dropped
+const WRITE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Events waiting for the writer thread. Further events are dropped while
+/// it's full, so slow clients never hold up the UI.
+const QUEUE_SIZE: usize = 256;
+
+/// Set once the event stream is listening
+static QUEUE: OnceLock<SyncSender<String>> = OnceLock::new();
+static CLIENTS: Mutex<Vec<UnixStream>> = Mutex::new(Vec::new());
+
+#[derive(Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum Event<'a> {
+    /// The query changed
+    Query { query: &'a str },
+    /// Actions shown for the query, in ranking order
+    Results { query: &'a str, names: Vec<&'a str> },
+    /// An action was run
+    Execute { query: &'a str, name: &'a str },
+}
+
+/// Start listening if the event stream is enabled
+pub fn init(config: &Config) {
+    if !config.event_stream {
+        return;
+    }
+
+    let Some(token) = config.event_stream_token.clone().filter(|t| !t.is_empty()) else {
+        warn!("event_stream is enabled but event_stream_token is not set, not starting");
+        return;
+    };
+
+    let path = config
+        .event_stream_socket
+        .as_deref()
+        .map(expand_tilde)
+        .unwrap_or_else(default_socket_path);
+
+    match listen(&path) {
+        Ok(listener) => {
+            info!("Broadcasting events on {:?}", path);
+            let (sender, receiver) = mpsc::sync_channel(QUEUE_SIZE);
+            let _ = QUEUE.set(sender);
+            thread::spawn(move || broadcast(receiver));
+            thread::spawn(move || accept_clients(listener, token));
+        }
+        Err(e) => warn!("Failed to start event stream: {:#}", e),
+    }
+}
+
+/// Send an event to all connected clients. Only queues it, the writer
+/// thread does the sending.
+pub fn emit(event: Event) {
+    let Some(queue) = QUEUE.get() else {
+        return;
+    };
+
+    let Ok(mut line) = serde_json::to_string(&event) else {
+        return;
+    };
+    line.push('\n');
+
+    if queue.try_send(line).is_err() {
+        warn!("Event stream clients are falling behind, dropped an event");
+    }
+}
+
+/// Write queued events to the clients, dropping those that fail or time out
+fn broadcast(queue: Receiver<String>) {
+    for line in queue {
+        CLIENTS
+            .lock()
+            .unwrap()
+            .retain_mut(|client| client.write_all(line.as_bytes()).is_ok());
+    }
+}
+
+fn default_socket_path() -> PathBuf {
+    env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(env::temp_dir)
+        .join("crowbar")
+        .join("events.sock")
+}
+
+fn listen(path: &Path) -> Result<UnixListener> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // A socket left behind by a previous instance would make bind fail
+    let _ = fs::remove_file(path);
+
+    let listener =
+        UnixListener::bind(path).with_context(|| format!("Failed to bind {:?}", path))?;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+fn accept_clients(listener: UnixListener, token: String) {
+    for stream in listener.incoming().filter_map(|stream| stream.ok()) {
+        let token = token.clone();
+        thread::spawn(move || {
+            if let Err(e) = authenticate(stream, &token) {
+                info!("Event stream client rejected: {}", e);
+            }
+        });
+    }
+}
+
+fn authenticate(mut stream: UnixStream, token: &str) -> Result<()> {
+    stream.set_read_timeout(Some(AUTH_TIMEOUT))?;
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line)?;
+
+    if line.trim_end() != token {
+        stream.write_all(b"{\"error\":\"invalid token\"}\n")?;
+        return Err(anyhow!("invalid token"));
+    }
+
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    CLIENTS.lock().unwrap().push(stream);
+    Ok(())
+}
@@ -0,0 +1,164 @@
+//! Test harness for handler factories.
+//!
+//! Runs a factory against synthetic queries with a throwaway home directory,
+//! so the database, notes and other state written by handlers never touch the
+//! real user data. Only compiled with the `test-support` feature, see
+//! `tests/handlers.rs`:
+//!
+//! ```ignore
+//! use crowbar::actions::handlers::timestamp_handler::TimestampHandlerFactory;
+//! use crowbar::handler_test::HandlerHarness;
+//!
+//! #[gpui::test]
+//! fn converts_unix_seconds(cx: &mut gpui::TestAppContext) {
+//!     let harness = HandlerHarness::new(cx);
+//!     let result = harness.query(&TimestampHandlerFactory, "1700000000", cx);
+//!
+//!     result.assert_contains("2023-11-14T22:13:20Z");
+//!     result.assert_ranked_before("1700000000", "1700000000000");
+//!     let _ = result.execute("1700000000");
+//!     assert_eq!(harness.execution_count(result.item("1700000000")), 1);
+//! }
+//! ```
+
+use std::env;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use gpui::{AppContext, Entity, TestAppContext};
+use tempfile::TempDir;
+
+use crate::action_list_view::ActionListView;
+use crate::actions::action_handler::{ActionItem, HandlerFactory};
+use crate::config::{self, Config};
+use crate::database::{self, Database};
+
+/// `HOME` is process-wide, so harnesses must not run concurrently
+static HOME_LOCK: Mutex<()> = Mutex::new(());
+
+pub struct HandlerHarness {
+    db: Arc<Database>,
+    view: Entity<ActionListView>,
+    _home: TempDir,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl HandlerHarness {
+    pub fn new(cx: &mut TestAppContext) -> Self {
+        Self::with_config(Config::default(), cx)
+    }
+
+    pub fn with_config(config: Config, cx: &mut TestAppContext) -> Self {
+        // A panicking test poisons the lock, which is fine for the next one
+        let guard = HOME_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let home = TempDir::new().expect("Failed to create temporary home");
+        env::set_var("HOME", home.path());
+        // The XDG directories and overrides would point outside of the
+        // temporary home
+        env::remove_var("XDG_CONFIG_HOME");
+        env::remove_var("XDG_DATA_HOME");
+        env::remove_var(database::DATABASE_ENV);
+        env::remove_var(database::STATE_DIR_ENV);
+        env::remove_var(config::CONFIG_FILE_ENV);
+
+        cx.update(|cx| cx.set_global(config));
+        let db = Arc::new(Database::new().expect("Failed to create test database"));
+        let view = cx.new(|cx| ActionListView::new(cx));
+
+        Self {
+            db,
+            view,
+            _home: home,
+            _guard: guard,
+        }
+    }
+
+    pub fn db(&self) -> &Arc<Database> {
+        &self.db
+    }
+
+    /// Run the factory for `query`, returning its items in ranking order
+    pub fn query(
+        &self,
+        factory: &dyn HandlerFactory,
+        query: &str,
+        cx: &mut TestAppContext,
+    ) -> QueryResult {
+        let mut items = self.view.update(cx, |_, cx| {
+            factory.create_handlers_for_query(query, self.db.clone(), cx)
+        });
+        items.sort();
+        QueryResult { items }
+    }
+
+    /// How often an item has been executed
+    pub fn execution_count(&self, item: &ActionItem) -> i32 {
+        self.db.get_execution_count(item.id.as_str()).unwrap_or(0)
+    }
+}
+
+/// Items produced by a factory for a single query
+pub struct QueryResult {
+    pub items: Vec<ActionItem>,
+}
+
+impl QueryResult {
+    pub fn names(&self) -> Vec<&str> {
+        self.items.iter().map(|item| item.name.as_str()).collect()
+    }
+
+    /// Names with their final scores, highest first
+    pub fn scores(&self) -> Vec<(&str, usize)> {
+        self.items
+            .iter()
+            .map(|item| (item.name.as_str(), item.relevance()))
+            .collect()
+    }
+
+    pub fn item(&self, name: &str) -> &ActionItem {
+        self.items
+            .iter()
+            .find(|item| item.name == name)
+            .unwrap_or_else(|| panic!("No item named {:?} in {:?}", name, self.names()))
+    }
+
+    pub fn assert_empty(&self) {
+        assert!(
+            self.items.is_empty(),
+            "Expected no items, got {:?}",
+            self.names()
+        );
+    }
+
+    pub fn assert_names(&self, expected: &[&str]) {
+        assert_eq!(self.names(), expected);
+    }
+
+    pub fn assert_contains(&self, name: &str) {
+        self.item(name);
+    }
+
+    pub fn assert_ranked_before(&self, higher: &str, lower: &str) {
+        let position = |name| {
+            self.names()
+                .iter()
+                .position(|item| *item == name)
+                .unwrap_or_else(|| panic!("No item named {:?} in {:?}", name, self.names()))
+        };
+        assert!(
+            position(higher) < position(lower),
+            "Expected {:?} to rank before {:?}, scores: {:?}",
+            higher,
+            lower,
+            self.scores()
+        );
+    }
+
+    /// Execute an item the same way the launcher does, logging the execution
+    pub fn execute(&self, name: &str) -> anyhow::Result<()> {
+        self.execute_with_input(name, "")
+    }
+
+    pub fn execute_with_input(&self, name: &str, input: &str) -> anyhow::Result<()> {
+        self.item(name).execute(input)
+    }
+}
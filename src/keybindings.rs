@@ -0,0 +1,442 @@
+//! Key bindings of the window, kept in one table so the cheat sheet (F1 or
+//! `:help`) shows exactly what is bound. `key_bindings` in the config replaces the
+//! keys of single actions:
+//!
+//! ```toml
+//! [key_bindings]
+//! down = ["down", "ctrl-j"]
+//! ```
+
+use gpui::{div, prelude::*, AnyElement, KeyBinding};
+
+use crate::actions::action_handler::Keyword;
+use crate::actions::keywords;
+use crate::config::Config;
+use crate::{
+    AutoAccept, Backspace, ClearLine, Copy, Cut, Delete, DeleteToWhitespace, DeleteWordLeft,
+    DeleteWordRight, Down, End, Enter, Escape, Home, Left, MoveWindowDown, MoveWindowLeft,
+    MoveWindowRight, MoveWindowUp, Newline, PageDown, PageUp, Paste, QuickSelect1, QuickSelect2,
+    QuickSelect3, QuickSelect4, QuickSelect5, QuickSelect6, QuickSelect7, QuickSelect8,
+    QuickSelect9, Right, SecondaryActions, SelectAll, SelectLeft, SelectRight, ShiftTab, Tab,
+    TileLeft, TileRight, ToggleCheatSheet, ToggleGroup, TogglePin, Up, WordLeft, WordRight,
+};
+
+pub struct Binding {
+    /// Name of the action in the `key_bindings` config table
+    pub action: &'static str,
+    pub default_keys: &'static [&'static str],
+    pub description: &'static str,
+    bind: fn(&str) -> KeyBinding,
+}
+
+pub const BINDINGS: &[Binding] = &[
+    Binding {
+        action: "enter",
+        default_keys: &["enter"],
+        description: "Run the selected action",
+        bind: |keys| KeyBinding::new(keys, Enter, None),
+    },
+    Binding {
+        action: "dry_run",
+        default_keys: &["shift-enter"],
+        description: "Preview the selected action, or insert a line break",
+        bind: |keys| KeyBinding::new(keys, Newline, None),
+    },
+    Binding {
+        action: "auto_accept",
+        default_keys: &["ctrl-enter"],
+        description: "Run the top action if it's an unambiguous match",
+        bind: |keys| KeyBinding::new(keys, AutoAccept, None),
+    },
+    Binding {
+        action: "quick_select_1",
+        default_keys: &["ctrl-1"],
+        description: "Run result 1",
+        bind: |keys| KeyBinding::new(keys, QuickSelect1, None),
+    },
+    Binding {
+        action: "quick_select_2",
+        default_keys: &["ctrl-2"],
+        description: "Run result 2",
+        bind: |keys| KeyBinding::new(keys, QuickSelect2, None),
+    },
+    Binding {
+        action: "quick_select_3",
+        default_keys: &["ctrl-3"],
+        description: "Run result 3",
+        bind: |keys| KeyBinding::new(keys, QuickSelect3, None),
+    },
+    Binding {
+        action: "quick_select_4",
+        default_keys: &["ctrl-4"],
+        description: "Run result 4",
+        bind: |keys| KeyBinding::new(keys, QuickSelect4, None),
+    },
+    Binding {
+        action: "quick_select_5",
+        default_keys: &["ctrl-5"],
+        description: "Run result 5",
+        bind: |keys| KeyBinding::new(keys, QuickSelect5, None),
+    },
+    Binding {
+        action: "quick_select_6",
+        default_keys: &["ctrl-6"],
+        description: "Run result 6",
+        bind: |keys| KeyBinding::new(keys, QuickSelect6, None),
+    },
+    Binding {
+        action: "quick_select_7",
+        default_keys: &["ctrl-7"],
+        description: "Run result 7",
+        bind: |keys| KeyBinding::new(keys, QuickSelect7, None),
+    },
+    Binding {
+        action: "quick_select_8",
+        default_keys: &["ctrl-8"],
+        description: "Run result 8",
+        bind: |keys| KeyBinding::new(keys, QuickSelect8, None),
+    },
+    Binding {
+        action: "quick_select_9",
+        default_keys: &["ctrl-9"],
+        description: "Run result 9",
+        bind: |keys| KeyBinding::new(keys, QuickSelect9, None),
+    },
+    Binding {
+        action: "secondary_actions",
+        default_keys: &["alt-enter"],
+        description: "Show other ways to run the selected action",
+        bind: |keys| KeyBinding::new(keys, SecondaryActions, None),
+    },
+    Binding {
+        action: "toggle_group",
+        default_keys: &["ctrl-g"],
+        description: "Collapse or expand the group of the selected result",
+        bind: |keys| KeyBinding::new(keys, ToggleGroup, None),
+    },
+    Binding {
+        action: "toggle_pin",
+        default_keys: &["alt-p"],
+        description: "Pin the selected application to the top, or unpin it",
+        bind: |keys| KeyBinding::new(keys, TogglePin, None),
+    },
+    Binding {
+        action: "up",
+        default_keys: &["up", "ctrl-k", "ctrl-p"],
+        description: "Select the previous result",
+        bind: |keys| KeyBinding::new(keys, Up, None),
+    },
+    Binding {
+        action: "down",
+        default_keys: &["down", "ctrl-j", "ctrl-n"],
+        description: "Select the next result",
+        bind: |keys| KeyBinding::new(keys, Down, None),
+    },
+    Binding {
+        action: "page_up",
+        default_keys: &["pageup"],
+        description: "Select the result a page up",
+        bind: |keys| KeyBinding::new(keys, PageUp, None),
+    },
+    Binding {
+        action: "page_down",
+        default_keys: &["pagedown"],
+        description: "Select the result a page down",
+        bind: |keys| KeyBinding::new(keys, PageDown, None),
+    },
+    Binding {
+        action: "tile_left",
+        default_keys: &["ctrl-h"],
+        description: "Select the previous tile of the grid view",
+        bind: |keys| KeyBinding::new(keys, TileLeft, None),
+    },
+    Binding {
+        action: "tile_right",
+        default_keys: &["ctrl-l"],
+        description: "Select the next tile of the grid view",
+        bind: |keys| KeyBinding::new(keys, TileRight, None),
+    },
+    Binding {
+        action: "escape",
+        default_keys: &["escape"],
+        description: "Close the cheat sheet, or quit",
+        bind: |keys| KeyBinding::new(keys, Escape, None),
+    },
+    Binding {
+        action: "cheat_sheet",
+        default_keys: &["f1"],
+        description: "Show or hide this cheat sheet",
+        bind: |keys| KeyBinding::new(keys, ToggleCheatSheet, None),
+    },
+    Binding {
+        action: "move_window_left",
+        default_keys: &["super-left"],
+        description: "Move the window left",
+        bind: |keys| KeyBinding::new(keys, MoveWindowLeft, None),
+    },
+    Binding {
+        action: "move_window_right",
+        default_keys: &["super-right"],
+        description: "Move the window right",
+        bind: |keys| KeyBinding::new(keys, MoveWindowRight, None),
+    },
+    Binding {
+        action: "move_window_up",
+        default_keys: &["super-up"],
+        description: "Move the window up",
+        bind: |keys| KeyBinding::new(keys, MoveWindowUp, None),
+    },
+    Binding {
+        action: "move_window_down",
+        default_keys: &["super-down"],
+        description: "Move the window down",
+        bind: |keys| KeyBinding::new(keys, MoveWindowDown, None),
+    },
+    Binding {
+        action: "tab",
+        default_keys: &["tab"],
+        description: "Complete the query to the selected result",
+        bind: |keys| KeyBinding::new(keys, Tab, None),
+    },
+    Binding {
+        action: "shift_tab",
+        default_keys: &["shift-tab"],
+        description: "Focus the input",
+        bind: |keys| KeyBinding::new(keys, ShiftTab, None),
+    },
+    Binding {
+        action: "backspace",
+        default_keys: &["backspace"],
+        description: "Delete the character before the cursor",
+        bind: |keys| KeyBinding::new(keys, Backspace, None),
+    },
+    Binding {
+        action: "delete",
+        default_keys: &["delete"],
+        description: "Delete the character after the cursor",
+        bind: |keys| KeyBinding::new(keys, Delete, None),
+    },
+    Binding {
+        action: "delete_word_left",
+        default_keys: &["ctrl-backspace"],
+        description: "Delete the word before the cursor",
+        bind: |keys| KeyBinding::new(keys, DeleteWordLeft, None),
+    },
+    Binding {
+        action: "delete_word_right",
+        default_keys: &["ctrl-delete"],
+        description: "Delete the word after the cursor",
+        bind: |keys| KeyBinding::new(keys, DeleteWordRight, None),
+    },
+    Binding {
+        action: "delete_to_whitespace",
+        default_keys: &["ctrl-w"],
+        description: "Delete back to the previous space",
+        bind: |keys| KeyBinding::new(keys, DeleteToWhitespace, None),
+    },
+    Binding {
+        action: "clear_line",
+        default_keys: &["ctrl-u"],
+        description: "Delete everything before the cursor on its line",
+        bind: |keys| KeyBinding::new(keys, ClearLine, None),
+    },
+    Binding {
+        action: "left",
+        default_keys: &["left"],
+        description: "Move the cursor left",
+        bind: |keys| KeyBinding::new(keys, Left, None),
+    },
+    Binding {
+        action: "right",
+        default_keys: &["right"],
+        description: "Move the cursor right, or show other ways to run the selected action",
+        bind: |keys| KeyBinding::new(keys, Right, None),
+    },
+    Binding {
+        action: "word_left",
+        default_keys: &["ctrl-left"],
+        description: "Move the cursor to the start of the word",
+        bind: |keys| KeyBinding::new(keys, WordLeft, None),
+    },
+    Binding {
+        action: "word_right",
+        default_keys: &["ctrl-right"],
+        description: "Move the cursor to the end of the word",
+        bind: |keys| KeyBinding::new(keys, WordRight, None),
+    },
+    Binding {
+        action: "select_left",
+        default_keys: &["shift-left"],
+        description: "Extend the selection left",
+        bind: |keys| KeyBinding::new(keys, SelectLeft, None),
+    },
+    Binding {
+        action: "select_right",
+        default_keys: &["shift-right"],
+        description: "Extend the selection right",
+        bind: |keys| KeyBinding::new(keys, SelectRight, None),
+    },
+    Binding {
+        action: "select_all",
+        default_keys: &["ctrl-a"],
+        description: "Select the whole query",
+        bind: |keys| KeyBinding::new(keys, SelectAll, None),
+    },
+    Binding {
+        action: "home",
+        default_keys: &["home"],
+        description: "Move the cursor to the start",
+        bind: |keys| KeyBinding::new(keys, Home, None),
+    },
+    Binding {
+        action: "end",
+        default_keys: &["end"],
+        description: "Move the cursor to the end",
+        bind: |keys| KeyBinding::new(keys, End, None),
+    },
+    Binding {
+        action: "copy",
+        default_keys: &["ctrl-c"],
+        description: "Copy the selection",
+        bind: |keys| KeyBinding::new(keys, Copy, None),
+    },
+    Binding {
+        action: "cut",
+        default_keys: &["ctrl-x"],
+        description: "Cut the selection",
+        bind: |keys| KeyBinding::new(keys, Cut, None),
+    },
+    Binding {
+        action: "paste",
+        default_keys: &["ctrl-v"],
+        description: "Paste from the clipboard",
+        bind: |keys| KeyBinding::new(keys, Paste, None),
+    },
+];
+
+impl Binding {
+    /// Keys from the config, or the defaults if the action isn't configured
+    pub fn keys(&self, config: &Config) -> Vec<String> {
+        match config.key_bindings.get(self.action) {
+            Some(keys) => keys.clone(),
+            None => self
+                .default_keys
+                .iter()
+                .map(|key| key.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Bindings to register with the app
+pub fn key_bindings(config: &Config) -> Vec<KeyBinding> {
+    BINDINGS
+        .iter()
+        .flat_map(|binding| {
+            binding
+                .keys(config)
+                .into_iter()
+                .map(move |keys| (binding.bind)(&keys))
+        })
+        .collect()
+}
+
+/// Names in `key_bindings` that don't match any action
+pub fn unknown_actions(config: &Config) -> Vec<&str> {
+    config
+        .key_bindings
+        .keys()
+        .map(String::as_str)
+        .filter(|action| !BINDINGS.iter().any(|binding| binding.action == *action))
+        .collect()
+}
+
+/// Commands and handlers listed below the key bindings
+pub struct Help {
+    /// Empty in restricted mode, which has no commands
+    pub commands: Vec<String>,
+    /// Enabled handlers with their prefixes and bangs
+    pub handlers: Vec<(&'static str, Vec<Keyword>)>,
+}
+
+/// Every binding, input mode, command and enabled handler, shown with F1
+pub fn render_cheat_sheet(config: &Config, help: Help) -> AnyElement {
+    let row = |keys: String, description: &str| {
+        div()
+            .flex()
+            .gap_4()
+            .child(div().w_48().flex_none().child(keys))
+            .child(
+                div()
+                    .flex_grow()
+                    .text_color(config.text_secondary_color)
+                    .child(description.to_string()),
+            )
+    };
+    let heading = |title: &'static str| {
+        div()
+            .pt_2()
+            .text_color(config.text_secondary_color)
+            .child(title)
+    };
+
+    let bindings = BINDINGS
+        .iter()
+        .map(|binding| {
+            let keys = binding.keys(config);
+            let keys = if keys.is_empty() {
+                "unbound".to_string()
+            } else {
+                keys.join(", ")
+            };
+            row(
+                keys,
+                &format!("{} ({})", binding.description, binding.action),
+            )
+        })
+        .collect::<Vec<_>>();
+    let modes = keywords::mode_keywords(!config.restricted_mode)
+        .into_iter()
+        .map(|keyword| row(keyword.prefix.trim_end().to_string(), &keyword.description))
+        .collect::<Vec<_>>();
+    let commands = help
+        .commands
+        .iter()
+        .map(|command| div().child(format!(":{}", command)))
+        .collect::<Vec<_>>();
+    // Handlers without keywords answer every query
+    let handlers = help
+        .handlers
+        .into_iter()
+        .map(|(id, keywords)| {
+            let prefixes = keywords
+                .iter()
+                .map(|keyword| keyword.prefix.trim_end())
+                .collect::<Vec<_>>();
+            if prefixes.is_empty() {
+                row(id.to_string(), "any query")
+            } else {
+                row(id.to_string(), &prefixes.join(", "))
+            }
+        })
+        .collect::<Vec<_>>();
+
+    div()
+        .id("cheat-sheet")
+        .size_full()
+        .overflow_y_scroll()
+        .px_4()
+        .py_2()
+        .flex()
+        .flex_col()
+        .child(heading("Keys"))
+        .children(bindings)
+        .child(heading("Modes"))
+        .children(modes)
+        .when(!commands.is_empty(), |x| {
+            x.child(heading("Commands")).children(commands)
+        })
+        .child(heading("Handlers (? lists their keywords)"))
+        .children(handlers)
+        .into_any_element()
+}
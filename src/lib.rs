@@ -0,0 +1,84 @@
+//! The launcher window, its handlers and commands. The `crowbar` binary runs
+//! them, handler tests import them with the `test-support` feature.
+
+pub mod action_list_view;
+pub mod actions;
+pub mod activity;
+pub mod cli;
+pub mod commands;
+pub mod common;
+pub mod config;
+pub mod config_check;
+pub mod database;
+pub mod demo;
+pub mod events;
+#[cfg(feature = "test-support")]
+pub mod handler_test;
+pub mod keybindings;
+pub mod lock;
+pub mod plugins;
+pub mod regex_tester;
+pub mod remote;
+pub mod scheduler;
+pub mod startup;
+pub mod status_bar;
+pub mod system;
+pub mod tab_bridge;
+pub mod text_input;
+pub mod window_position;
+pub mod window_size;
+
+use gpui::actions;
+
+actions!(
+    text_input,
+    [
+        Enter,
+        Backspace,
+        Delete,
+        Left,
+        Right,
+        SelectLeft,
+        SelectRight,
+        SelectAll,
+        WordLeft,
+        WordRight,
+        DeleteWordLeft,
+        DeleteWordRight,
+        DeleteToWhitespace,
+        ClearLine,
+        Home,
+        End,
+        Paste,
+        Cut,
+        Copy,
+        Escape,
+        Up,
+        Down,
+        PageUp,
+        PageDown,
+        TileLeft,
+        TileRight,
+        Tab,
+        ShiftTab,
+        Newline,
+        AutoAccept,
+        QuickSelect1,
+        QuickSelect2,
+        QuickSelect3,
+        QuickSelect4,
+        QuickSelect5,
+        QuickSelect6,
+        QuickSelect7,
+        QuickSelect8,
+        QuickSelect9,
+        SecondaryActions,
+        ToggleGroup,
+        TogglePin,
+        ToggleCheatSheet,
+        MoveWindowLeft,
+        MoveWindowRight,
+        MoveWindowUp,
+        MoveWindowDown
+    ]
+);
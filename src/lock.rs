@@ -0,0 +1,173 @@
+//! Passphrase protection for handlers and commands on shared machines.
+//!
+//! Handlers and commands listed in the config are locked until the user
+//! enters the passphrase with `:unlock <passphrase>`. The config holds an
+//! Argon2 hash of it, from `crowbar --hash-passphrase`. The unlock lasts for
+//! the login session: a random token is stored in `$XDG_RUNTIME_DIR`, which
+//! is cleared on logout, and its digest in the database. Without a private
+//! runtime directory it only lasts while Crowbar runs.
+
+use std::env;
+use std::fs::{self, DirBuilder, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Context, Result};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use log::warn;
+use sha2::{Digest, Sha256};
+
+use crate::config::Config;
+use crate::database::Database;
+
+const TOKEN_PREFERENCE: &str = "unlock_token";
+
+static LOCK_SETTINGS: OnceLock<LockSettings> = OnceLock::new();
+static UNLOCKED: AtomicBool = AtomicBool::new(false);
+/// Whether an earlier unlock was found, read once per process
+static MARKER_CHECKED: OnceLock<bool> = OnceLock::new();
+
+struct LockSettings {
+    passphrase_hash: String,
+    handlers: Vec<String>,
+    commands: Vec<String>,
+}
+
+/// Read lock settings from the config. Locking is disabled without a passphrase.
+pub fn init(config: &Config) {
+    let Some(hash) = &config.lock_passphrase_hash else {
+        return;
+    };
+    let _ = LOCK_SETTINGS.set(LockSettings {
+        passphrase_hash: hash.trim().to_string(),
+        handlers: config.locked_handlers.clone(),
+        commands: config.locked_commands.clone(),
+    });
+}
+
+pub fn is_handler_locked(handler_id: &str) -> bool {
+    LOCK_SETTINGS
+        .get()
+        .is_some_and(|settings| settings.handlers.iter().any(|id| id == handler_id))
+        && !is_session_unlocked()
+}
+
+pub fn is_command_locked(command: &str) -> bool {
+    LOCK_SETTINGS
+        .get()
+        .is_some_and(|settings| settings.commands.iter().any(|name| name == command))
+        && !is_session_unlocked()
+}
+
+/// Unlock the session if the passphrase matches the configured hash
+pub fn unlock(passphrase: &str) -> Result<()> {
+    let settings = LOCK_SETTINGS
+        .get()
+        .ok_or_else(|| anyhow!("No passphrase configured"))?;
+    let parsed = PasswordHash::new(&settings.passphrase_hash)
+        .map_err(|e| anyhow!("Invalid lock_passphrase_hash: {}", e))?;
+    if Argon2::default()
+        .verify_password(passphrase.as_bytes(), &parsed)
+        .is_err()
+    {
+        return Err(anyhow!("Wrong passphrase"));
+    }
+
+    UNLOCKED.store(true, Ordering::Relaxed);
+    if let Err(e) = remember_session(&settings.passphrase_hash) {
+        warn!("Unlocked until Crowbar exits: {:#}", e);
+    }
+    Ok(())
+}
+
+/// Argon2 hash of the passphrase with a random salt, for `lock_passphrase_hash`
+pub fn hash_passphrase(passphrase: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut rand::rngs::OsRng);
+    let hash = Argon2::default()
+        .hash_password(passphrase.as_bytes(), &salt)
+        .map_err(|e| anyhow!("Failed to hash passphrase: {}", e))?;
+    Ok(hash.to_string())
+}
+
+fn is_session_unlocked() -> bool {
+    if UNLOCKED.load(Ordering::Relaxed) {
+        return true;
+    }
+
+    *MARKER_CHECKED.get_or_init(|| {
+        LOCK_SETTINGS
+            .get()
+            .is_some_and(|settings| has_session_token(&settings.passphrase_hash))
+    })
+}
+
+/// Store a random token in the marker and its digest in the database. Neither
+/// can be derived from the config, and changing the passphrase locks again.
+fn remember_session(hash: &str) -> Result<()> {
+    let token = to_hex(&rand::random::<[u8; 32]>());
+    write_marker(&token)?;
+    Database::new()?.set_preference(TOKEN_PREFERENCE, &token_digest(hash, &token))
+}
+
+fn has_session_token(hash: &str) -> bool {
+    let Some(token) = marker_path().and_then(|path| fs::read_to_string(path).ok()) else {
+        return false;
+    };
+    Database::new()
+        .and_then(|db| db.get_preference(TOKEN_PREFERENCE))
+        .ok()
+        .flatten()
+        .is_some_and(|digest| digest == token_digest(hash, token.trim()))
+}
+
+fn token_digest(hash: &str, token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(hash.as_bytes());
+    hasher.update(token.as_bytes());
+    to_hex(&hasher.finalize())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn write_marker(token: &str) -> Result<()> {
+    let path = marker_path().ok_or_else(|| anyhow!("No private $XDG_RUNTIME_DIR"))?;
+    // Replaced instead of truncated, the new file gets the permissions below
+    let _ = fs::remove_file(&path);
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&path)
+        .with_context(|| format!("Failed to write unlock marker at {:?}", path))?;
+    file.write_all(token.as_bytes())?;
+    Ok(())
+}
+
+/// Marker in `$XDG_RUNTIME_DIR/crowbar`, `None` unless both directories are
+/// only accessible to their owner. Other users can't write it then.
+fn marker_path() -> Option<PathBuf> {
+    let runtime_dir = PathBuf::from(env::var_os("XDG_RUNTIME_DIR")?);
+    if !is_private(&runtime_dir) {
+        return None;
+    }
+
+    let dir = runtime_dir.join("crowbar");
+    if !dir.exists() {
+        DirBuilder::new().mode(0o700).create(&dir).ok()?;
+    }
+    let same_owner = fs::metadata(&runtime_dir)
+        .and_then(|runtime| Ok(runtime.uid() == fs::metadata(&dir)?.uid()))
+        .unwrap_or(false);
+    (same_owner && is_private(&dir)).then(|| dir.join("unlocked"))
+}
+
+/// A directory without permissions for the group or others
+fn is_private(dir: &Path) -> bool {
+    fs::symlink_metadata(dir).is_ok_and(|meta| meta.is_dir() && meta.mode() & 0o077 == 0)
+}
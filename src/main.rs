@@ -1,15 +1,22 @@
-mod action_list_view;
-mod actions;
-mod commands;
-mod common;
-mod config;
-mod database;
-mod system;
-mod text_input;
-
-use action_list_view::ActionListView;
-use config::{Config, StatusItem};
-use text_input::TextInput;
+use crowbar::action_list_view::{ActionListView, RunSelected};
+use crowbar::activity::Activity;
+use crowbar::config::{Config, InputPosition, StatusItem};
+use crowbar::regex_tester::REGEX_PREFIX;
+use crowbar::scheduler::{Scheduler, TICK_INTERVAL};
+use crowbar::status_bar::{Metrics, ScriptOutput};
+use crowbar::system::{device_batteries, DeviceBattery};
+use crowbar::text_input::TextInput;
+use crowbar::window_position::Direction;
+use crowbar::{
+    actions, activity, cli, config_check, database, demo, events, keybindings, lock, remote,
+    startup, status_bar, system, tab_bridge, window_position, window_size,
+};
+use crowbar::{
+    AutoAccept, Down, Enter, Escape, MoveWindowDown, MoveWindowLeft, MoveWindowRight, MoveWindowUp,
+    Newline, PageDown, PageUp, QuickSelect1, QuickSelect2, QuickSelect3, QuickSelect4,
+    QuickSelect5, QuickSelect6, QuickSelect7, QuickSelect8, QuickSelect9, Right, SecondaryActions,
+    ShiftTab, Tab, TileLeft, TileRight, ToggleCheatSheet, ToggleGroup, TogglePin, Up,
+};
 
 use chrono::Local;
 use std::collections::HashMap;
@@ -17,35 +24,24 @@ use std::error::Error;
 use std::time::Duration;
 
 use gpui::{
-    actions, div, prelude::*, px, App, AppContext, Application, Bounds, Context, Entity,
-    FocusHandle, Focusable, KeyBinding, Size, Timer, Window, WindowBounds, WindowOptions,
+    div, prelude::*, px, App, AppContext, Application, Bounds, Context, Entity, FocusHandle,
+    Focusable, KeyBinding, Pixels, Size, Task, Timer, Window, WindowBackgroundAppearance,
+    WindowBounds, WindowOptions,
 };
 
-use log::{debug, info};
-
-actions!(
-    text_input,
-    [
-        Enter,
-        Backspace,
-        Delete,
-        Left,
-        Right,
-        SelectLeft,
-        SelectRight,
-        SelectAll,
-        Home,
-        End,
-        Paste,
-        Cut,
-        Copy,
-        Escape,
-        Up,
-        Down,
-        Tab,
-        ShiftTab
-    ]
-);
+use log::{debug, info, warn};
+
+/// How often Bluetooth device battery levels are read for the status bar
+const DEVICE_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often the results are recomputed while the query doesn't change
+const RESULTS_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Queries starting with these prefixes switch the input to multi-line editing
+const MULTILINE_PREFIXES: &[&str] = &[REGEX_PREFIX, "note ", ":note "];
+
+/// The passphrase after this prefix is masked in the input
+const UNLOCK_PREFIX: &str = ":unlock ";
 
 struct Crowbar {
     query_input: Entity<TextInput>,
@@ -53,6 +49,19 @@ struct Crowbar {
     focus_handle: FocusHandle,
     current_time: String,
     status_formats: HashMap<String, String>,
+    device_batteries: Vec<DeviceBattery>,
+    /// Values of the battery, CPU, memory and network items
+    metrics: Metrics,
+    /// Output of the `script` items by command
+    scripts: HashMap<String, ScriptOutput>,
+    activity: Activity,
+    scheduler: Scheduler<Crowbar>,
+    /// Runs due jobs while active, dropping it pauses the scheduler
+    ticker: Option<Task<()>>,
+    /// Height the window was last resized to
+    fitted_height: Option<Pixels>,
+    /// F1 shows the key bindings instead of the results
+    show_cheat_sheet: bool,
 }
 
 impl Focusable for Crowbar {
@@ -76,7 +85,44 @@ impl Crowbar {
         cx.focus_view(&self.query_input, wd);
     }
 
-    fn handle_tab(&mut self, _: &Tab, _: &mut Window, _: &mut Context<Self>) {}
+    fn tile_left(&mut self, _: &TileLeft, wd: &mut Window, cx: &mut Context<Self>) {
+        self.action_list.update(cx, |list, cx| {
+            list.navigate_left(cx);
+        });
+        cx.focus_view(&self.query_input, wd);
+    }
+
+    fn tile_right(&mut self, _: &TileRight, wd: &mut Window, cx: &mut Context<Self>) {
+        self.action_list.update(cx, |list, cx| {
+            list.navigate_right(cx);
+        });
+        cx.focus_view(&self.query_input, wd);
+    }
+
+    fn page_up(&mut self, _: &PageUp, wd: &mut Window, cx: &mut Context<Self>) {
+        self.navigate_page(-1, wd, cx);
+    }
+
+    fn page_down(&mut self, _: &PageDown, wd: &mut Window, cx: &mut Context<Self>) {
+        self.navigate_page(1, wd, cx);
+    }
+
+    /// Move the selection by the rows visible in the window
+    fn navigate_page(&mut self, direction: isize, wd: &mut Window, cx: &mut Context<Self>) {
+        let rows = window_size::visible_rows(cx.global::<Config>(), wd.viewport_size().height);
+        self.action_list.update(cx, |list, cx| {
+            list.navigate_page(direction * rows as isize, cx);
+        });
+        cx.focus_view(&self.query_input, wd);
+    }
+
+    fn handle_tab(&mut self, _: &Tab, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(completion) = self.action_list.read(cx).selected_tab_completion() {
+            self.query_input.update(cx, |input, cx| {
+                input.set_content(&completion, cx);
+            });
+        }
+    }
 
     fn handle_shift_tab(&mut self, _: &ShiftTab, wd: &mut Window, cx: &mut Context<Self>) {
         debug!("Shift Tab pressed, switching focus");
@@ -84,11 +130,114 @@ impl Crowbar {
     }
 
     fn escape(&mut self, _: &Escape, _: &mut Window, cx: &mut Context<Self>) {
+        if self.show_cheat_sheet {
+            self.show_cheat_sheet = false;
+            cx.notify();
+            return;
+        }
+        if self
+            .action_list
+            .update(cx, |list, cx| list.close_secondary_menu(cx))
+        {
+            return;
+        }
         info!("Escape pressed, quitting application");
         cx.quit();
     }
 
+    fn toggle_cheat_sheet(&mut self, _: &ToggleCheatSheet, _: &mut Window, cx: &mut Context<Self>) {
+        self.show_cheat_sheet = !self.show_cheat_sheet;
+        cx.notify();
+    }
+
+    fn move_window_left(
+        &mut self,
+        _: &MoveWindowLeft,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_window(Direction::Left, window, cx);
+    }
+
+    fn move_window_right(
+        &mut self,
+        _: &MoveWindowRight,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_window(Direction::Right, window, cx);
+    }
+
+    fn move_window_up(&mut self, _: &MoveWindowUp, window: &mut Window, cx: &mut Context<Self>) {
+        self.move_window(Direction::Up, window, cx);
+    }
+
+    fn move_window_down(
+        &mut self,
+        _: &MoveWindowDown,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_window(Direction::Down, window, cx);
+    }
+
+    /// gpui can't move a window, so the view is reopened in a new window at
+    /// the new position and the old one is closed
+    fn move_window(&mut self, direction: Direction, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(display) = window.display(cx) else {
+            return;
+        };
+        let config = cx.global::<Config>();
+        let bounds = window_position::step(display, window.bounds().size, direction, config);
+        let crowbar = cx.entity();
+        let old_window = window.window_handle();
+
+        // The view can't be attached to the new window while it's being updated
+        cx.defer(move |cx| {
+            let options = window_options(bounds, cx.global::<Config>());
+            let reopened = cx.open_window(options, |window, cx| {
+                crowbar.update(cx, |crowbar, cx| crowbar.attach(window, cx));
+                crowbar
+            });
+            match reopened {
+                Ok(_) => {
+                    old_window
+                        .update(cx, |_, window, _| window.remove_window())
+                        .ok();
+                }
+                Err(e) => warn!("Failed to move the window: {:#}", e),
+            }
+        });
+    }
+
+    /// Run background work while the window is active and focus the input
+    fn attach(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.start_ticker(window, cx);
+        cx.observe_window_activation(window, |crowbar, window, cx| {
+            let activity = if window.is_window_active() {
+                Activity::Active
+            } else {
+                Activity::Idle
+            };
+            crowbar.set_activity(activity, window, cx);
+        })
+        .detach();
+        cx.focus_view(&self.query_input, window);
+    }
+
     fn handle_enter(&mut self, _: &Enter, _: &mut Window, cx: &mut Context<Self>) {
+        self.run_selected(cx);
+    }
+
+    /// Run the selected action, or insert its completion into the input
+    fn run_selected(&mut self, cx: &mut Context<Self>) {
+        if let Some(completion) = self.action_list.read(cx).selected_completion() {
+            self.query_input.update(cx, |input, cx| {
+                input.set_content(&completion, cx);
+            });
+            return;
+        }
+
         if self
             .action_list
             .update(cx, |list, cx| list.run_selected_action(cx))
@@ -100,7 +249,126 @@ impl Crowbar {
         }
     }
 
-    fn update_time(&mut self, cx: &mut Context<Self>) {
+    /// Ctrl-Enter runs the top action right away if it's an unambiguous match
+    fn handle_auto_accept(&mut self, _: &AutoAccept, _: &mut Window, cx: &mut Context<Self>) {
+        if self.action_list.update(cx, |list, cx| list.auto_accept(cx)) {
+            cx.quit();
+        }
+    }
+
+    /// Ctrl-1 to Ctrl-9 run one of the first nine results without navigating
+    fn quick_select(&mut self, number: usize, cx: &mut Context<Self>) {
+        if self
+            .action_list
+            .update(cx, |list, cx| list.quick_select(number, cx))
+        {
+            self.run_selected(cx);
+        }
+    }
+
+    /// Alt-Enter opens or closes the secondary actions of the selected action
+    fn toggle_secondary_actions(
+        &mut self,
+        _: &SecondaryActions,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.action_list.update(cx, |list, cx| {
+            if !list.close_secondary_menu(cx) {
+                list.open_secondary_menu(cx);
+            }
+        });
+    }
+
+    /// Right reaches the view only at the end of the query, where it opens
+    /// the secondary actions
+    fn open_secondary_actions(&mut self, _: &Right, _: &mut Window, cx: &mut Context<Self>) {
+        self.action_list
+            .update(cx, |list, cx| list.open_secondary_menu(cx));
+    }
+
+    /// Collapse or expand the group of the selected result, see `group_results`
+    fn toggle_group(&mut self, _: &ToggleGroup, _: &mut Window, cx: &mut Context<Self>) {
+        self.action_list
+            .update(cx, |list, cx| list.toggle_selected_group(cx));
+    }
+
+    /// Pin the selected result to the top of the empty-query results
+    fn toggle_pin(&mut self, _: &TogglePin, _: &mut Window, cx: &mut Context<Self>) {
+        self.action_list
+            .update(cx, |list, cx| list.toggle_selected_pin(cx));
+    }
+
+    /// Shift-Enter outside of multi-line editing previews the selected action
+    fn handle_dry_run(&mut self, _: &Newline, _: &mut Window, cx: &mut Context<Self>) {
+        self.action_list
+            .update(cx, |list, cx| list.toggle_dry_run(cx));
+    }
+
+    /// Pause background work while the window is hidden or unfocused and
+    /// resume it when it's shown again
+    fn set_activity(&mut self, activity: Activity, window: &mut Window, cx: &mut Context<Self>) {
+        if self.activity == activity {
+            return;
+        }
+        debug!("Window is now {:?}", activity);
+        self.activity = activity;
+        activity::set(activity);
+
+        match activity {
+            Activity::Active => {
+                // Everything shown is outdated after a pause
+                self.with_scheduler(cx, |scheduler, this, cx| scheduler.run_all(this, cx));
+                self.start_ticker(window, cx);
+            }
+            Activity::Idle => self.ticker = None,
+        }
+    }
+
+    /// Periodic work, run by the ticker while the window is active
+    fn scheduler() -> Scheduler<Self> {
+        Scheduler::default()
+            .every("clock", TICK_INTERVAL, Self::update_clock)
+            .every("metrics", TICK_INTERVAL, Self::refresh_metrics)
+            .every("scripts", TICK_INTERVAL, Self::run_scripts)
+            .every(
+                "devices",
+                DEVICE_REFRESH_INTERVAL,
+                Self::refresh_device_batteries,
+            )
+            .every("results", RESULTS_REFRESH_INTERVAL, Self::refresh_results)
+    }
+
+    fn start_ticker(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.ticker = Some(cx.spawn_in(window, |view, mut cx| async move {
+            loop {
+                Timer::after(TICK_INTERVAL).await;
+
+                let ticked = cx.update(|_, cx| {
+                    view.update(cx, |view, cx| {
+                        view.with_scheduler(cx, |scheduler, this, cx| scheduler.run_due(this, cx));
+                    })
+                    .ok()
+                });
+                if !matches!(ticked, Ok(Some(()))) {
+                    break;
+                }
+            }
+        }));
+    }
+
+    /// Jobs need the view mutably, so the scheduler is taken out while they run
+    fn with_scheduler(
+        &mut self,
+        cx: &mut Context<Self>,
+        f: impl FnOnce(&mut Scheduler<Self>, &mut Self, &mut Context<Self>),
+    ) {
+        let mut scheduler = std::mem::take(&mut self.scheduler);
+        f(&mut scheduler, self, cx);
+        self.scheduler = scheduler;
+    }
+
+    fn update_clock(&mut self, cx: &mut Context<Self>) {
         self.current_time = Local::now().format("%H:%M:%S").to_string();
 
         let theme = cx.global::<Config>();
@@ -119,6 +387,126 @@ impl Crowbar {
         cx.notify();
     }
 
+    /// Sample the system metrics the status bar shows, reading `/proc` and
+    /// `/sys` is quick enough for the UI thread
+    fn refresh_metrics(&mut self, cx: &mut Context<Self>) {
+        let theme = cx.global::<Config>();
+        let items = theme
+            .status_bar_left
+            .iter()
+            .chain(theme.status_bar_center.iter())
+            .chain(theme.status_bar_right.iter())
+            .filter(|item| status_bar::is_metric(item));
+        if items.clone().next().is_none() {
+            return;
+        }
+
+        self.metrics = Metrics::sample(items);
+        cx.notify();
+    }
+
+    /// Run the `script` items whose interval has passed in the background
+    fn run_scripts(&mut self, cx: &mut Context<Self>) {
+        let theme = cx.global::<Config>();
+        let due: Vec<String> = theme
+            .status_bar_left
+            .iter()
+            .chain(theme.status_bar_center.iter())
+            .chain(theme.status_bar_right.iter())
+            .filter_map(|item| match item {
+                StatusItem::Script { command, interval } => Some((command, *interval)),
+                _ => None,
+            })
+            .filter(|(command, interval)| {
+                self.scripts
+                    .get(*command)
+                    .map_or(true, |script| script.is_due(*interval))
+            })
+            .map(|(command, _)| command.clone())
+            .collect();
+
+        for command in due {
+            self.scripts.entry(command.clone()).or_default().start();
+            cx.spawn(|view, mut cx| async move {
+                let output = cx
+                    .background_executor()
+                    .spawn({
+                        let command = command.clone();
+                        async move { status_bar::run_script(&command) }
+                    })
+                    .await;
+                let _ = view.update(&mut cx, |this, cx| {
+                    this.scripts
+                        .entry(command.clone())
+                        .or_default()
+                        .finish(&command, output);
+                    cx.notify();
+                });
+            })
+            .detach();
+        }
+    }
+
+    /// Read device battery levels in the background if the status bar shows them
+    fn refresh_device_batteries(&mut self, cx: &mut Context<Self>) {
+        let theme = cx.global::<Config>();
+        let has_bluetooth_items = theme
+            .status_bar_left
+            .iter()
+            .chain(theme.status_bar_center.iter())
+            .chain(theme.status_bar_right.iter())
+            .any(|item| matches!(item, StatusItem::Bluetooth { .. }));
+        if !has_bluetooth_items {
+            return;
+        }
+
+        cx.spawn(|view, mut cx| async move {
+            let devices = cx
+                .background_executor()
+                .spawn(async { device_batteries() })
+                .await;
+            let _ = view.update(&mut cx, |this, cx| {
+                this.device_batteries = devices;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Keep time-dependent results like reminders and "Usually at this time"
+    /// current while the launcher stays open
+    fn refresh_results(&mut self, cx: &mut Context<Self>) {
+        self.action_list.update(cx, |list, cx| list.refresh(cx));
+    }
+
+    /// Resize the window to the number of results, up to `window_height`
+    fn fit_window_height(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let config = cx.global::<Config>();
+        if !config.window_auto_height {
+            return;
+        }
+
+        // A multi-line query needs room to grow
+        let rows = if self.show_cheat_sheet || self.query_input.read(cx).multiline {
+            None
+        } else {
+            self.action_list.read(cx).fitted_row_count()
+        };
+        let display_height = window
+            .display(cx)
+            .map(|display| display.bounds().size.height);
+        let height =
+            window_size::fitted_height(config, rows, display_height, window.scale_factor());
+
+        if self.fitted_height != Some(height) {
+            self.fitted_height = Some(height);
+            window.resize(Size {
+                width: px(config.window_width),
+                height,
+            });
+        }
+    }
+
     fn render_status_items(&self, items: &[StatusItem]) -> Vec<impl IntoElement> {
         items
             .iter()
@@ -132,6 +520,28 @@ impl Crowbar {
                         .unwrap_or_else(|| Local::now().format(format).to_string());
                     div().child(formatted)
                 }
+                StatusItem::Bluetooth { device } => div().child(
+                    self.device_batteries
+                        .iter()
+                        .filter(|battery| {
+                            device
+                                .as_ref()
+                                .map_or(true, |device| battery.name.contains(device.as_str()))
+                        })
+                        .map(|battery| format!("{} {}%", battery.name, battery.percentage))
+                        .collect::<Vec<_>>()
+                        .join("  "),
+                ),
+                StatusItem::Battery { .. }
+                | StatusItem::Cpu { .. }
+                | StatusItem::Memory { .. }
+                | StatusItem::Network { .. } => div().child(self.metrics.format(item)),
+                StatusItem::Script { command, .. } => div().child(
+                    self.scripts
+                        .get(command)
+                        .map(|script| script.text.clone())
+                        .unwrap_or_default(),
+                ),
             })
             .collect()
     }
@@ -139,190 +549,304 @@ impl Crowbar {
 
 impl Render for Crowbar {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.fit_window_height(window, cx);
         let config = cx.global::<Config>();
+        let input_on_top = config.input_position == InputPosition::Top;
 
-        cx.spawn_in(window, |view, mut cx| async move {
-            loop {
-                Timer::after(Duration::from_secs(1)).await;
-
-                let _ = cx.update(|_, cx| {
-                    view.update(cx, |view, cx| {
-                        view.update_time(cx);
-                    })
-                    .ok()
-                });
-            }
-        })
-        .detach();
+        let results = if self.show_cheat_sheet {
+            keybindings::render_cheat_sheet(config, self.action_list.read(cx).help(config))
+        } else {
+            self.action_list.clone().into_any_element()
+        };
+        let input = div()
+            .w_full()
+            .border_color(config.border_color)
+            .when(input_on_top, |x| x.border_b_1())
+            .when(!input_on_top, |x| x.border_t_1())
+            .child(
+                div()
+                    .mt_auto()
+                    .flex()
+                    .flex_row()
+                    .child(div().w_full().child(self.query_input.clone())),
+            );
 
         div()
             .id("crowbar")
             .text_size(px(config.font_size))
             .track_focus(&self.focus_handle(cx))
             .on_action(cx.listener(Self::handle_enter))
+            .on_action(cx.listener(Self::handle_dry_run))
+            .on_action(cx.listener(Self::handle_auto_accept))
+            .on_action(cx.listener(|this, _: &QuickSelect1, _, cx| this.quick_select(1, cx)))
+            .on_action(cx.listener(|this, _: &QuickSelect2, _, cx| this.quick_select(2, cx)))
+            .on_action(cx.listener(|this, _: &QuickSelect3, _, cx| this.quick_select(3, cx)))
+            .on_action(cx.listener(|this, _: &QuickSelect4, _, cx| this.quick_select(4, cx)))
+            .on_action(cx.listener(|this, _: &QuickSelect5, _, cx| this.quick_select(5, cx)))
+            .on_action(cx.listener(|this, _: &QuickSelect6, _, cx| this.quick_select(6, cx)))
+            .on_action(cx.listener(|this, _: &QuickSelect7, _, cx| this.quick_select(7, cx)))
+            .on_action(cx.listener(|this, _: &QuickSelect8, _, cx| this.quick_select(8, cx)))
+            .on_action(cx.listener(|this, _: &QuickSelect9, _, cx| this.quick_select(9, cx)))
+            .on_action(cx.listener(Self::toggle_secondary_actions))
+            .on_action(cx.listener(Self::open_secondary_actions))
+            .on_action(cx.listener(Self::toggle_group))
+            .on_action(cx.listener(Self::toggle_pin))
             .on_action(cx.listener(Self::escape))
+            .on_action(cx.listener(Self::toggle_cheat_sheet))
+            .on_action(cx.listener(Self::move_window_left))
+            .on_action(cx.listener(Self::move_window_right))
+            .on_action(cx.listener(Self::move_window_up))
+            .on_action(cx.listener(Self::move_window_down))
             .on_action(cx.listener(Self::navigate_up))
             .on_action(cx.listener(Self::navigate_down))
+            .on_action(cx.listener(Self::tile_left))
+            .on_action(cx.listener(Self::tile_right))
+            .on_action(cx.listener(Self::page_up))
+            .on_action(cx.listener(Self::page_down))
             .on_action(cx.listener(Self::handle_tab))
             .on_action(cx.listener(Self::handle_shift_tab))
             .font_family(config.font_family.clone())
             .bg(config.background_color)
             .border_1()
             .border_color(config.border_color)
+            .rounded(px(config.corner_radius))
+            .p(px(config.window_padding))
             .text_color(config.text_primary_color)
             .flex()
             .flex_col()
             .size_full()
             // Header
-            .child(
-                div()
-                    .w_full()
-                    .text_sm()
-                    .px_4()
-                    .py_1()
-                    .border_b_1()
-                    .border_color(config.border_color)
-                    .flex()
-                    .flex_row()
-                    .items_center()
-                    .justify_between()
-                    .children(vec![
-                        div()
-                            .flex()
-                            .flex_row()
-                            .gap_2()
-                            .items_center()
-                            .children(self.render_status_items(&config.status_bar_left)),
-                        div()
-                            .flex()
-                            .flex_row()
-                            .gap_2()
-                            .items_center()
-                            .justify_center()
-                            .children(self.render_status_items(&config.status_bar_center)),
-                        div()
-                            .flex()
-                            .flex_row()
-                            .gap_2()
-                            .items_center()
-                            .justify_end()
-                            .children(self.render_status_items(&config.status_bar_right)),
-                    ]),
-            )
-            .child(self.action_list.clone())
-            .child(
-                div()
-                    .w_full()
-                    .border_t_1()
-                    .border_color(config.border_color)
-                    .child(
-                        div()
-                            .mt_auto()
-                            .flex()
-                            .flex_row()
-                            .child(div().child(self.query_input.clone())),
-                    ),
-            )
+            .when(config.show_status_bar, |x| {
+                x.child(
+                    div()
+                        .w_full()
+                        .text_sm()
+                        .px(px(config.padding))
+                        .py_1()
+                        .border_b_1()
+                        .border_color(config.border_color)
+                        .flex()
+                        .flex_row()
+                        .items_center()
+                        .justify_between()
+                        .children(vec![
+                            div()
+                                .flex()
+                                .flex_row()
+                                .gap_2()
+                                .items_center()
+                                .children(self.render_status_items(&config.status_bar_left)),
+                            div()
+                                .flex()
+                                .flex_row()
+                                .gap_2()
+                                .items_center()
+                                .justify_center()
+                                .children(self.render_status_items(&config.status_bar_center)),
+                            div()
+                                .flex()
+                                .flex_row()
+                                .gap_2()
+                                .items_center()
+                                .justify_end()
+                                .children(self.render_status_items(&config.status_bar_right)),
+                        ]),
+                )
+            })
+            .map(|x| {
+                if input_on_top {
+                    x.child(input).child(results)
+                } else {
+                    x.child(results).child(input)
+                }
+            })
+    }
+}
+
+fn window_options(bounds: Bounds<Pixels>, config: &Config) -> WindowOptions {
+    // The window is only see-through with a translucent background, or
+    // behind the rounded corners
+    let window_background = if config.background_blur {
+        WindowBackgroundAppearance::Blurred
+    } else if config.background_opacity < 1.0 || config.corner_radius > 0.0 {
+        WindowBackgroundAppearance::Transparent
+    } else {
+        WindowBackgroundAppearance::Opaque
+    };
+
+    WindowOptions {
+        window_bounds: Some(WindowBounds::Windowed(bounds)),
+        window_background,
+        ..Default::default()
     }
 }
 
+/// Merge old executions into daily counts through a connection of its own,
+/// once the view has opened and migrated the database
+fn prune_history(cx: &App) {
+    let config = cx.global::<Config>();
+    let (max_age, max_executions) = (config.history_max_age, config.history_max_executions);
+    cx.background_executor()
+        .spawn(async move {
+            let merged =
+                database::Database::new().and_then(|db| db.prune_history(max_age, max_executions));
+            match merged {
+                Ok(0) => {}
+                Ok(merged) => info!("Merged {} old executions into daily counts", merged),
+                Err(e) => warn!("Failed to prune the execution history: {:#}", e),
+            }
+        })
+        .detach();
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
     env_logger::builder()
         .filter_level(log::LevelFilter::Warn)
         .init();
 
+    let args = cli::parse();
+    if args.demo {
+        demo::enable()?;
+    }
+    if let Some(path) = &args.config {
+        Config::use_file(path.clone());
+    }
+    if args.check_config {
+        let valid = config_check::run();
+        std::process::exit(if valid { 0 } else { 1 });
+    }
+    if let Some(done) = cli::transfer_data() {
+        std::process::exit(if done { 0 } else { 1 });
+    }
+    if let Some(done) = cli::migrate_down() {
+        std::process::exit(if done { 0 } else { 1 });
+    }
+    if let Some(done) = cli::hash_passphrase() {
+        std::process::exit(if done { 0 } else { 1 });
+    }
+
     Application::new().run(|cx: &mut App| {
         Config::init(cx);
+        cli::apply(cx);
         let theme = cx.global::<Config>();
+        database::init(theme);
+        lock::init(theme);
+        remote::init(theme);
+        events::init(theme);
+        tab_bridge::init(theme);
+        actions::watcher::init(theme);
+        database::backup::init(theme);
+        system::appimage::init(theme);
+        startup::run(theme);
 
         let size = Size {
             width: px(theme.window_width),
             height: px(theme.window_height),
         };
 
-        let bounds = Bounds::centered(None, size, cx);
-
-        cx.bind_keys([
-            KeyBinding::new("enter", Enter, None),
-            KeyBinding::new("backspace", Backspace, None),
-            KeyBinding::new("delete", Delete, None),
-            KeyBinding::new("left", Left, None),
-            KeyBinding::new("right", Right, None),
-            KeyBinding::new("shift-left", SelectLeft, None),
-            KeyBinding::new("shift-right", SelectRight, None),
-            KeyBinding::new("ctrl-a", SelectAll, None),
-            KeyBinding::new("ctrl-v", Paste, None),
-            KeyBinding::new("ctrl-c", Copy, None),
-            KeyBinding::new("ctrl-x", Cut, None),
-            KeyBinding::new("home", Home, None),
-            KeyBinding::new("end", End, None),
-            KeyBinding::new("escape", Escape, None),
-            KeyBinding::new("up", Up, None),
-            KeyBinding::new("down", Down, None),
-            KeyBinding::new("ctrl-k", Up, None),
-            KeyBinding::new("ctrl-j", Down, None),
-            KeyBinding::new("ctrl-p", Up, None),
-            KeyBinding::new("ctrl-n", Down, None),
-            KeyBinding::new("tab", Tab, None),
-            KeyBinding::new("shift-tab", ShiftTab, None),
-        ]);
+        let bindings = keybindings::key_bindings(theme);
+        for action in keybindings::unknown_actions(theme) {
+            warn!("Unknown action {:?} in key_bindings", action);
+        }
 
-        let window = cx
-            .open_window(
-                WindowOptions {
-                    window_bounds: Some(WindowBounds::Windowed(bounds)),
-                    ..Default::default()
-                },
-                |_, cx| {
-                    let text_input = cx.new(|cx| TextInput {
-                        focus_handle: cx.focus_handle(),
-                        content: "".into(),
-                        placeholder: "Type to search or enter a command...".into(),
-                        selected_range: 0..0,
-                        selection_reversed: false,
-                        marked_range: None,
-                        last_layout: None,
-                        last_bounds: None,
-                        is_selecting: false,
-                    });
+        let display = window_position::display(theme.monitor, cx);
+        let bounds = window_position::bounds(display, size, theme);
+        let options = window_options(bounds, theme);
 
-                    let action_list = cx.new(|cx| ActionListView::new(cx));
-                    let weak_ref = action_list.downgrade();
+        cx.bind_keys(bindings);
 
-                    let crowbar = cx.new(|cx| Crowbar {
-                        query_input: text_input.clone(),
-                        action_list: action_list.clone(),
-                        focus_handle: cx.focus_handle(),
-                        current_time: Local::now().format("%H:%M:%S").to_string(),
-                        status_formats: HashMap::new(),
-                    });
+        let window = cx
+            .open_window(options, |window, cx| {
+                let text_input = cx.new(|cx| TextInput {
+                    focus_handle: cx.focus_handle(),
+                    content: "".into(),
+                    placeholder: "Type to search or enter a command...".into(),
+                    selected_range: 0..0,
+                    selection_reversed: false,
+                    marked_range: None,
+                    last_layout: None,
+                    last_bounds: None,
+                    is_selecting: false,
+                    multiline: false,
+                    last_lines: None,
+                    mask_from: None,
+                });
+
+                let action_list = cx.new(|cx| ActionListView::new(cx));
+                let weak_ref = action_list.downgrade();
+
+                let crowbar = cx.new(|cx| Crowbar {
+                    query_input: text_input.clone(),
+                    action_list: action_list.clone(),
+                    focus_handle: cx.focus_handle(),
+                    current_time: Local::now().format("%H:%M:%S").to_string(),
+                    status_formats: HashMap::new(),
+                    device_batteries: Vec::new(),
+                    metrics: Metrics::default(),
+                    scripts: HashMap::new(),
+                    activity: Activity::Active,
+                    scheduler: Crowbar::scheduler(),
+                    ticker: None,
+                    fitted_height: None,
+                    show_cheat_sheet: false,
+                });
 
-                    cx.subscribe(&text_input, move |_view, event, cx| {
-                        let _ = weak_ref.clone().update(cx, move |this, cx| {
-                            this.set_filter(&event.content, cx);
-                            cx.notify();
-                        });
+                crowbar.update(cx, |crowbar, cx| {
+                    // Clicked results run like on Enter. Subscribed once, as
+                    // moving the window attaches the view again.
+                    cx.subscribe(&crowbar.action_list, |crowbar, _, _: &RunSelected, cx| {
+                        crowbar.run_selected(cx);
                     })
                     .detach();
+                    crowbar.attach(window, cx);
+                });
 
-                    crowbar
-                },
-            )
+                cx.subscribe(&text_input, move |input, event, cx| {
+                    let accepted = weak_ref
+                        .clone()
+                        .update(cx, |this, cx| this.auto_accept_space(&event.content, cx))
+                        .unwrap_or(false);
+                    if accepted {
+                        cx.quit();
+                        return;
+                    }
+
+                    let multiline = MULTILINE_PREFIXES
+                        .iter()
+                        .any(|prefix| event.content.starts_with(prefix));
+                    let mask_from = event
+                        .content
+                        .starts_with(UNLOCK_PREFIX)
+                        .then_some(UNLOCK_PREFIX.len());
+                    input.update(cx, |input, cx| {
+                        input.set_multiline(multiline, cx);
+                        input.mask_from = mask_from;
+                    });
+
+                    let _ = weak_ref.clone().update(cx, move |this, cx| {
+                        this.set_filter(&event.content, cx);
+                        cx.notify();
+                    });
+                })
+                .detach();
+
+                if let Some(query) = &cli::args().query {
+                    text_input.update(cx, |input, cx| input.set_content(query, cx));
+                }
+
+                crowbar
+            })
             .unwrap();
 
-        cx.on_keyboard_layout_change({
-            move |cx| {
-                window.update(cx, |_, _, cx| cx.notify()).ok();
-            }
+        // The view outlives its window when the window is moved
+        let crowbar = window.update(cx, |_, _, cx| cx.entity()).unwrap();
+        cx.on_keyboard_layout_change(move |cx| {
+            crowbar.update(cx, |_, cx| cx.notify());
         })
         .detach();
 
-        window
-            .update(cx, |view, window, cx| {
-                cx.focus_view(&view.query_input, window);
-                cx.activate(true);
-            })
-            .unwrap();
+        prune_history(cx);
+        cx.activate(true);
     });
 
     Ok(())
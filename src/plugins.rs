@@ -0,0 +1,335 @@
+//! Script plugins.
+//!
+//! Each directory in `~/.config/crowbar/plugins/` is a plugin with a
+//! `plugin.toml` manifest and an executable script. When a query starts with
+//! the plugin's prefix, the script is run with the rest of the query as its
+//! argument and prints one result per line:
+//!
+//! ```text
+//! name<TAB>detail<TAB>command
+//! ```
+//!
+//! Enter runs `command` with `sh -c`. Plugins are reloaded when their files
+//! change, and each plugin can be enabled or disabled as `plugin:<directory>`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+use serde::Deserialize;
+
+use crate::common::config_home;
+
+/// How often the plugin directory is checked for changes
+const RELOAD_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Cached plugin output is dropped when it grows beyond this many queries
+const MAX_CACHED_QUERIES: usize = 200;
+
+/// Prefix of plugin ids in the handlers table
+pub const PLUGIN_ID_PREFIX: &str = "plugin:";
+
+/// How results of a plugin are reused
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CachePolicy {
+    /// Run the script for every query
+    None,
+    /// Reuse results for the same query for `interval` seconds
+    #[default]
+    Query,
+    /// Run the script once per `interval` without a query and filter its
+    /// results locally, for plugins that list a fixed set of items
+    Static,
+}
+
+/// Contents of `plugin.toml`
+#[derive(Debug, Clone, Deserialize)]
+pub struct Manifest {
+    pub name: String,
+    /// Queries starting with this word are sent to the plugin
+    pub prefix: String,
+    /// Script to run, relative to the plugin directory
+    #[serde(default = "default_exec")]
+    pub exec: String,
+    /// Seconds results are cached for
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+    #[serde(default)]
+    pub cache: CachePolicy,
+    /// Emoji or short text shown in front of results
+    pub icon: Option<String>,
+}
+
+fn default_exec() -> String {
+    String::from("plugin")
+}
+
+fn default_interval() -> u64 {
+    60
+}
+
+/// A loaded plugin
+#[derive(Debug, Clone)]
+pub struct Plugin {
+    /// Id in the handlers table, `plugin:<directory>`
+    pub id: String,
+    pub dir: PathBuf,
+    pub manifest: Manifest,
+}
+
+/// A single line of plugin output
+#[derive(Debug, Clone)]
+pub struct PluginResult {
+    pub name: String,
+    pub detail: String,
+    pub command: Option<String>,
+}
+
+struct CachedOutput {
+    results: Vec<PluginResult>,
+    fetched_at: Instant,
+}
+
+#[derive(Default)]
+struct PluginState {
+    plugins: Vec<Plugin>,
+    /// Last load or run error per plugin id
+    errors: HashMap<String, String>,
+    /// Modification times of all plugin files at the last load, `None`
+    /// before the first load
+    fingerprint: Option<Vec<(PathBuf, SystemTime)>>,
+    checked_at: Option<Instant>,
+    cache: HashMap<(String, String), CachedOutput>,
+}
+
+lazy_static::lazy_static! {
+    static ref STATE: Mutex<PluginState> = Mutex::new(PluginState::default());
+}
+
+pub fn plugins_dir() -> PathBuf {
+    config_home().join("crowbar").join("plugins")
+}
+
+/// All loaded plugins, reloading them first if their files changed
+pub fn plugins() -> Vec<Plugin> {
+    let mut state = STATE.lock().unwrap();
+    reload_if_changed(&mut state);
+    state.plugins.clone()
+}
+
+/// Last load or run error of every plugin that has one
+pub fn errors() -> Vec<(String, String)> {
+    let mut state = STATE.lock().unwrap();
+    reload_if_changed(&mut state);
+    let mut errors: Vec<(String, String)> = state
+        .errors
+        .iter()
+        .map(|(id, error)| (id.clone(), error.clone()))
+        .collect();
+    errors.sort();
+    errors
+}
+
+/// Find the plugin whose prefix starts the query, returning it with the
+/// remaining query text
+pub fn match_query(query: &str) -> Option<(Plugin, String)> {
+    let (word, rest) = query.split_once(' ').unwrap_or((query, ""));
+    plugins()
+        .into_iter()
+        .find(|plugin| plugin.manifest.prefix == word)
+        .map(|plugin| (plugin, rest.trim().to_string()))
+}
+
+/// Cached results for the query, if still fresh according to the plugin's
+/// cache policy
+pub fn cached_results(plugin: &Plugin, query: &str) -> Option<Vec<PluginResult>> {
+    let state = STATE.lock().unwrap();
+    let key = cache_key(plugin, query);
+    let cached = state.cache.get(&key)?;
+
+    let max_age = Duration::from_secs(plugin.manifest.interval);
+    (plugin.manifest.cache != CachePolicy::None && cached.fetched_at.elapsed() < max_age)
+        .then(|| filter_results(plugin, &cached.results, query))
+}
+
+/// Run the plugin script and cache its output. Blocks until the script exits.
+pub fn run(plugin: &Plugin, query: &str) -> Result<Vec<PluginResult>> {
+    let script_query = match plugin.manifest.cache {
+        CachePolicy::Static => "",
+        _ => query,
+    };
+
+    let output = Command::new(plugin.dir.join(&plugin.manifest.exec))
+        .arg(script_query)
+        .current_dir(&plugin.dir)
+        .output()
+        .with_context(|| format!("Failed to run {}", plugin.manifest.exec));
+
+    let mut state = STATE.lock().unwrap();
+    let output = match output {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            let error = format!(
+                "{} exited with {}: {}",
+                plugin.manifest.exec,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            state.errors.insert(plugin.id.clone(), error.clone());
+            return Err(anyhow!(error));
+        }
+        Err(e) => {
+            state.errors.insert(plugin.id.clone(), e.to_string());
+            return Err(e);
+        }
+    };
+
+    let results: Vec<PluginResult> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_line)
+        .collect();
+
+    state.errors.remove(&plugin.id);
+    if state.cache.len() >= MAX_CACHED_QUERIES {
+        state.cache.clear();
+    }
+    state.cache.insert(
+        cache_key(plugin, query),
+        CachedOutput {
+            results: results.clone(),
+            fetched_at: Instant::now(),
+        },
+    );
+
+    Ok(filter_results(plugin, &results, query))
+}
+
+fn parse_line(line: &str) -> Option<PluginResult> {
+    let mut fields = line.split('\t');
+    let name = fields.next().filter(|name| !name.trim().is_empty())?;
+    Some(PluginResult {
+        name: name.to_string(),
+        detail: fields.next().unwrap_or_default().to_string(),
+        command: fields
+            .next()
+            .filter(|command| !command.is_empty())
+            .map(str::to_string),
+    })
+}
+
+fn cache_key(plugin: &Plugin, query: &str) -> (String, String) {
+    match plugin.manifest.cache {
+        CachePolicy::Static => (plugin.id.clone(), String::new()),
+        _ => (plugin.id.clone(), query.to_string()),
+    }
+}
+
+/// Static plugins list everything, so their results are filtered here
+fn filter_results(plugin: &Plugin, results: &[PluginResult], query: &str) -> Vec<PluginResult> {
+    if plugin.manifest.cache != CachePolicy::Static {
+        return results.to_vec();
+    }
+
+    let query = query.to_lowercase();
+    results
+        .iter()
+        .filter(|result| result.name.to_lowercase().contains(&query))
+        .cloned()
+        .collect()
+}
+
+fn reload_if_changed(state: &mut PluginState) {
+    if state
+        .checked_at
+        .is_some_and(|checked_at| checked_at.elapsed() < RELOAD_CHECK_INTERVAL)
+    {
+        return;
+    }
+    state.checked_at = Some(Instant::now());
+
+    let dir = plugins_dir();
+    let fingerprint = fingerprint(&dir);
+    if state.fingerprint.as_ref() == Some(&fingerprint) {
+        return;
+    }
+
+    state.plugins.clear();
+    state.errors.clear();
+    state.cache.clear();
+    state.fingerprint = Some(fingerprint);
+
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return;
+    };
+
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let plugin_dir = entry.path();
+        if !plugin_dir.is_dir() {
+            continue;
+        }
+
+        let id = format!(
+            "{}{}",
+            PLUGIN_ID_PREFIX,
+            entry.file_name().to_string_lossy()
+        );
+        match load_manifest(&plugin_dir) {
+            Ok(manifest) => state.plugins.push(Plugin {
+                id,
+                dir: plugin_dir,
+                manifest,
+            }),
+            Err(e) => {
+                state.errors.insert(id, format!("{:#}", e));
+            }
+        }
+    }
+
+    info!("Loaded {} plugins from {:?}", state.plugins.len(), dir);
+}
+
+fn load_manifest(dir: &Path) -> Result<Manifest> {
+    let path = dir.join("plugin.toml");
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    let manifest: Manifest =
+        toml::from_str(&content).with_context(|| format!("Invalid manifest {:?}", path))?;
+
+    if manifest.prefix.is_empty() || manifest.prefix.contains(' ') {
+        return Err(anyhow!("Prefix must be a single word"));
+    }
+    if !dir.join(&manifest.exec).is_file() {
+        return Err(anyhow!("Script {:?} not found", manifest.exec));
+    }
+    Ok(manifest)
+}
+
+/// Modification times of the plugin directories and the files inside them
+fn fingerprint(dir: &Path) -> Vec<(PathBuf, SystemTime)> {
+    let mut paths = vec![dir.to_path_buf()];
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            if let Ok(files) = fs::read_dir(&path) {
+                paths.extend(files.filter_map(|file| file.ok()).map(|file| file.path()));
+            }
+            paths.push(path);
+        }
+    }
+
+    let mut fingerprint: Vec<(PathBuf, SystemTime)> = paths
+        .into_iter()
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect();
+    fingerprint.sort();
+    fingerprint
+}
@@ -0,0 +1,160 @@
+//! Live regex tester shown for `:regex` queries.
+//!
+//! The first line after the prefix is the pattern. Any further lines are the
+//! text to match against; without them the clipboard content is used.
+
+use std::ops::Range;
+
+use gpui::{div, prelude::FluentBuilder, AnyElement, IntoElement, ParentElement, Styled};
+use regex::Regex;
+
+use crate::config::Config;
+
+pub const REGEX_PREFIX: &str = ":regex";
+
+/// Upper bound of matches listed below the highlighted text
+const MAX_LISTED_MATCHES: usize = 20;
+
+pub struct RegexTester {
+    pattern: String,
+    subject: String,
+}
+
+impl RegexTester {
+    pub fn is_regex_query(query: &str) -> bool {
+        query.starts_with(REGEX_PREFIX)
+    }
+
+    pub fn parse(query: &str, clipboard: &str) -> Self {
+        let rest = query.strip_prefix(REGEX_PREFIX).unwrap_or(query);
+        let rest = rest.strip_prefix(' ').unwrap_or(rest);
+
+        match rest.split_once('\n') {
+            Some((pattern, subject)) => Self {
+                pattern: pattern.to_string(),
+                subject: subject.to_string(),
+            },
+            None => Self {
+                pattern: rest.to_string(),
+                subject: clipboard.to_string(),
+            },
+        }
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn render(&self, config: &Config) -> AnyElement {
+        let container = div().size_full().flex().flex_col().gap_2().px_4().py_2();
+
+        if self.pattern.is_empty() {
+            return container
+                .text_color(config.text_secondary_color)
+                .child("Type a pattern to test against the clipboard content")
+                .into_any_element();
+        }
+
+        let regex = match Regex::new(&self.pattern) {
+            Ok(regex) => regex,
+            Err(e) => {
+                return container
+                    .text_color(config.text_secondary_color)
+                    .child(format!("Invalid pattern: {}", e))
+                    .into_any_element()
+            }
+        };
+
+        let matches: Vec<Range<usize>> = regex
+            .find_iter(&self.subject)
+            .map(|m| m.range())
+            .filter(|range| !range.is_empty())
+            .collect();
+
+        let mut offset = 0;
+        let lines = self.subject.split('\n').map(|line| {
+            let segments = highlight_segments(line, offset, &matches);
+            offset += line.len() + 1;
+
+            div()
+                .flex()
+                .flex_row()
+                .flex_wrap()
+                .children(segments.into_iter().map(|(text, highlighted)| {
+                    div().child(text).when(highlighted, |d| {
+                        d.bg(config.selected_background_color)
+                            .text_color(config.text_selected_primary_color)
+                    })
+                }))
+        });
+
+        let captures = regex
+            .captures_iter(&self.subject)
+            .take(MAX_LISTED_MATCHES)
+            .enumerate()
+            .map(|(index, captures)| {
+                let groups = regex
+                    .capture_names()
+                    .enumerate()
+                    .skip(1)
+                    .filter_map(|(group, name)| {
+                        let value = captures.get(group)?.as_str();
+                        let label = name.map_or_else(|| group.to_string(), str::to_string);
+                        Some(format!("{}: {:?}", label, value))
+                    })
+                    .collect::<Vec<_>>();
+
+                let whole = captures.get(0).map_or("", |m| m.as_str());
+                if groups.is_empty() {
+                    format!("Match {}: {:?}", index + 1, whole)
+                } else {
+                    format!("Match {}: {:?} ({})", index + 1, whole, groups.join(", "))
+                }
+            })
+            .collect::<Vec<_>>();
+
+        container
+            .child(div().text_color(config.text_secondary_color).child(format!(
+                "{} matches",
+                regex.find_iter(&self.subject).count()
+            )))
+            .child(div().flex().flex_col().children(lines))
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .text_color(config.text_secondary_color)
+                    .children(captures),
+            )
+            .into_any_element()
+    }
+}
+
+/// Split a line into plain and highlighted segments. `offset` is the byte
+/// position of the line within the whole subject.
+fn highlight_segments(line: &str, offset: usize, matches: &[Range<usize>]) -> Vec<(String, bool)> {
+    let line_range = offset..offset + line.len();
+    let mut segments = Vec::new();
+    let mut position = 0;
+
+    for range in matches {
+        let start = range.start.max(line_range.start);
+        let end = range.end.min(line_range.end);
+        if start >= end {
+            continue;
+        }
+
+        let (start, end) = (start - offset, end - offset);
+        if start > position {
+            segments.push((line[position..start].to_string(), false));
+        }
+        segments.push((line[start..end].to_string(), true));
+        position = end;
+    }
+
+    if position < line.len() || segments.is_empty() {
+        segments.push((line[position..].to_string(), false));
+    }
+
+    segments
+}
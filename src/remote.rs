@@ -0,0 +1,131 @@
+//! Remote machine profiles for running actions over SSH.
+//!
+//! Profiles are defined in the config and switched with `:remote <name>`,
+//! which is kept as a preference until switched again.
+//! While a profile is active, the remote handler lists the binaries found on
+//! that machine and runs them with ssh. The binary list is scanned once and
+//! cached in the database until `:remote rescan`.
+
+use std::collections::{HashMap, HashSet};
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{anyhow, Result};
+
+use crate::config::{Config, RemoteProfile};
+use crate::database::{Database, RemoteBinary};
+
+/// Profile name that switches back to the local machine
+pub const LOCAL_PROFILE: &str = "local";
+
+/// Preference storing the profile chosen with `:remote`
+const PROFILE_PREFERENCE: &str = "remote_profile";
+
+/// Lists every executable file in the remote user's PATH
+const SCAN_SCRIPT: &str =
+    r#"IFS=:; for dir in $PATH; do find "$dir" -maxdepth 1 -type f -perm -u+x 2>/dev/null; done"#;
+
+static PROFILES: OnceLock<Vec<RemoteProfile>> = OnceLock::new();
+
+/// State of a scan that hasn't been stored in the database (yet)
+#[derive(Debug, Clone)]
+pub enum ScanState {
+    Running,
+    Failed(String),
+}
+
+lazy_static::lazy_static! {
+    static ref SCAN_STATES: Mutex<HashMap<String, ScanState>> = Mutex::new(HashMap::new());
+}
+
+pub fn init(config: &Config) {
+    let _ = PROFILES.set(config.remote_profiles.clone());
+}
+
+pub fn profiles() -> &'static [RemoteProfile] {
+    PROFILES.get().map(Vec::as_slice).unwrap_or_default()
+}
+
+/// The profile actions are currently run on, `None` for the local machine
+/// or a profile that was removed from the config
+pub fn active_profile(db: &Database) -> Option<RemoteProfile> {
+    let name = db.get_preference(PROFILE_PREFERENCE).ok().flatten()?;
+    profiles()
+        .iter()
+        .find(|profile| profile.name == name)
+        .cloned()
+}
+
+/// Switch to the named profile, or back to the local machine
+pub fn switch(db: &Database, name: &str) -> Result<()> {
+    if name != LOCAL_PROFILE && !profiles().iter().any(|profile| profile.name == name) {
+        return Err(anyhow!("Unknown remote profile: {}", name));
+    }
+
+    db.set_preference(PROFILE_PREFERENCE, name)
+}
+
+/// Build an ssh command that runs `remote_command` on the profile's host
+pub fn ssh_command(profile: &RemoteProfile, remote_command: &str) -> Command {
+    let mut command = Command::new("ssh");
+    command
+        .args(["-o", "BatchMode=yes"])
+        .args(&profile.ssh_args)
+        .arg(&profile.host)
+        .arg(remote_command);
+    command
+}
+
+/// Run a command on the remote machine without waiting for it to finish
+pub fn run(profile: &RemoteProfile, remote_command: &str) -> Result<()> {
+    ssh_command(profile, remote_command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
+
+/// List the executables available on the remote machine
+pub fn scan(profile: &RemoteProfile) -> Result<Vec<RemoteBinary>> {
+    let output = ssh_command(profile, SCAN_SCRIPT).output()?;
+    if !output.status.success() && output.stdout.is_empty() {
+        return Err(anyhow!(
+            "ssh {} failed: {}",
+            profile.host,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let mut binaries: Vec<RemoteBinary> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|path| {
+            let name = path.rsplit('/').next()?;
+            (!name.is_empty()).then(|| RemoteBinary {
+                name: name.to_string(),
+                path: path.to_string(),
+            })
+        })
+        .collect();
+
+    // Earlier PATH entries shadow later ones, like in a shell
+    let mut seen = HashSet::new();
+    binaries.retain(|binary| seen.insert(binary.name.clone()));
+
+    if binaries.is_empty() {
+        return Err(anyhow!("No executables found on {}", profile.host));
+    }
+    Ok(binaries)
+}
+
+pub fn scan_state(host: &str) -> Option<ScanState> {
+    SCAN_STATES.lock().unwrap().get(host).cloned()
+}
+
+pub fn set_scan_state(host: &str, state: Option<ScanState>) {
+    let mut states = SCAN_STATES.lock().unwrap();
+    match state {
+        Some(state) => states.insert(host.to_string(), state),
+        None => states.remove(host),
+    };
+}
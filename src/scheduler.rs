@@ -0,0 +1,115 @@
+//! Recurring background jobs.
+//!
+//! Periodic work such as the status bar clock is registered as named jobs
+//! with their own interval. A single ticker started at startup runs the jobs
+//! that are due, so nothing spawns timers on its own. Run statistics are kept
+//! for the `:debug` view.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use gpui::Context;
+
+/// How often the ticker checks for due jobs, the shortest possible interval
+pub const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Run statistics of a job
+#[derive(Debug, Clone, Default)]
+pub struct JobStats {
+    pub interval: Duration,
+    pub runs: u64,
+    pub total_time: Duration,
+    pub last_run: Option<Instant>,
+}
+
+lazy_static::lazy_static! {
+    static ref STATS: Mutex<HashMap<&'static str, JobStats>> = Mutex::new(HashMap::new());
+}
+
+struct Job<T> {
+    name: &'static str,
+    interval: Duration,
+    last_run: Option<Instant>,
+    run: fn(&mut T, &mut Context<T>),
+}
+
+/// Jobs run on the view `T`
+pub struct Scheduler<T> {
+    jobs: Vec<Job<T>>,
+}
+
+impl<T> Default for Scheduler<T> {
+    fn default() -> Self {
+        Self { jobs: Vec::new() }
+    }
+}
+
+impl<T: 'static> Scheduler<T> {
+    /// Run `run` every `interval`, starting with the next tick
+    pub fn every(
+        mut self,
+        name: &'static str,
+        interval: Duration,
+        run: fn(&mut T, &mut Context<T>),
+    ) -> Self {
+        STATS.lock().unwrap().insert(
+            name,
+            JobStats {
+                interval,
+                ..Default::default()
+            },
+        );
+        self.jobs.push(Job {
+            name,
+            interval,
+            last_run: None,
+            run,
+        });
+        self
+    }
+
+    /// Run all jobs whose interval has passed
+    pub fn run_due(&mut self, view: &mut T, cx: &mut Context<T>) {
+        for job in &mut self.jobs {
+            if job
+                .last_run
+                .is_some_and(|last_run| last_run.elapsed() < job.interval)
+            {
+                continue;
+            }
+            Self::run_job(job, view, cx);
+        }
+    }
+
+    /// Run every job right away, e.g. when resuming after a pause
+    pub fn run_all(&mut self, view: &mut T, cx: &mut Context<T>) {
+        for job in &mut self.jobs {
+            Self::run_job(job, view, cx);
+        }
+    }
+
+    fn run_job(job: &mut Job<T>, view: &mut T, cx: &mut Context<T>) {
+        let start = Instant::now();
+        (job.run)(view, cx);
+        job.last_run = Some(start);
+
+        if let Some(stats) = STATS.lock().unwrap().get_mut(job.name) {
+            stats.runs += 1;
+            stats.total_time += start.elapsed();
+            stats.last_run = Some(start);
+        }
+    }
+}
+
+/// Statistics of all registered jobs, sorted by name
+pub fn stats() -> Vec<(&'static str, JobStats)> {
+    let mut stats: Vec<_> = STATS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(name, stats)| (*name, stats.clone()))
+        .collect();
+    stats.sort_by_key(|(name, _)| *name);
+    stats
+}
@@ -0,0 +1,61 @@
+//! Actions run once when Crowbar starts, configured with `startup_actions`.
+//!
+//! Entries starting with `:` are Crowbar commands, anything else is run with
+//! `sh -c`. The actions run one after another on a background thread, so they
+//! don't delay the window. Failures are logged and the remaining actions still
+//! run.
+
+use std::process::Command;
+use std::thread;
+
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+
+use crate::commands::CommandRegistry;
+use crate::config::Config;
+
+pub fn run(config: &Config) {
+    if config.startup_actions.is_empty() {
+        return;
+    }
+
+    let actions = config.startup_actions.clone();
+    thread::spawn(move || {
+        let commands = CommandRegistry::new();
+        for action in &actions {
+            match run_action(&commands, action) {
+                Ok(message) => info!("Startup action {:?}: {}", action, message),
+                Err(e) => warn!("Startup action {:?} failed: {:#}", action, e),
+            }
+        }
+    });
+}
+
+fn run_action(commands: &CommandRegistry, action: &str) -> Result<String> {
+    let action = action.trim();
+
+    if let Some(command_line) = action.strip_prefix(':') {
+        let name = command_line.split_whitespace().next().unwrap_or_default();
+        if !commands
+            .get_command_list()
+            .iter()
+            .any(|command| command == name)
+        {
+            return Err(anyhow!("Unknown command :{}", name));
+        }
+
+        let result = commands.execute_command(action);
+        if !result.success {
+            return Err(anyhow!(result.message));
+        }
+        return Ok(result.message);
+    }
+
+    // Waits for the command, long running programs have to be put in the
+    // background with `&`
+    let status = Command::new("sh").arg("-c").arg(action).status()?;
+    if !status.success() {
+        return Err(anyhow!("Exited with {}", status));
+    }
+    Ok("Finished".to_string())
+}
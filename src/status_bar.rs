@@ -0,0 +1,226 @@
+//! System metrics shown in the status bar, sampled every second while the
+//! window is active. Each item has a `format` with placeholders in braces:
+//!
+//! - `battery`: `{percent}`, `{status}` and `{name}`
+//! - `cpu`: `{usage}` in percent and the load averages `{load1}`, `{load5}`
+//!   and `{load15}`
+//! - `memory`: `{used}` and `{total}` in GiB and `{percent}`
+//! - `network`: `{down}` and `{up}` per second
+//!
+//! `script` items show the output of a shell command instead, like custom
+//! modules of waybar.
+
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use log::warn;
+
+use crate::config::StatusItem;
+use crate::system::resources::{self, Battery, Usage};
+
+/// How often a script runs without an `interval`
+const SCRIPT_INTERVAL: Duration = Duration::from_secs(10);
+
+const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+const BATTERY_FORMAT: &str = "BAT {percent}%";
+const CPU_FORMAT: &str = "CPU {usage}%";
+const MEMORY_FORMAT: &str = "RAM {percent}%";
+const NETWORK_FORMAT: &str = "↓{down} ↑{up}";
+
+/// Latest values, only those of items in the status bar are sampled
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    battery: Option<Battery>,
+    cpu_usage: Option<f64>,
+    cpu_load: Option<[f64; 3]>,
+    memory: Option<Usage>,
+    network: Option<(f64, f64)>,
+}
+
+impl Metrics {
+    /// Read the metrics shown by `items`
+    pub fn sample<'a>(items: impl IntoIterator<Item = &'a StatusItem>) -> Self {
+        let mut metrics = Self::default();
+        for item in items {
+            match item {
+                StatusItem::Battery { .. } => {
+                    metrics.battery = resources::batteries().into_iter().next();
+                }
+                StatusItem::Cpu { .. } => {
+                    metrics.cpu_usage = resources::cpu_usage();
+                    metrics.cpu_load = resources::cpu_load().map(|(_, load)| load);
+                }
+                StatusItem::Memory { .. } => metrics.memory = resources::memory(),
+                StatusItem::Network { .. } => metrics.network = resources::network_rates(),
+                _ => {}
+            }
+        }
+        metrics
+    }
+
+    /// Text of a metric item, empty until its value could be read
+    pub fn format(&self, item: &StatusItem) -> String {
+        let (format, values) = match item {
+            StatusItem::Battery { format } => {
+                let Some(battery) = &self.battery else {
+                    return String::new();
+                };
+                (
+                    format.as_deref().unwrap_or(BATTERY_FORMAT),
+                    vec![
+                        ("percent", battery.percentage.to_string()),
+                        ("status", battery.status.clone()),
+                        ("name", battery.name.clone()),
+                    ],
+                )
+            }
+            StatusItem::Cpu { format } => {
+                let Some(usage) = self.cpu_usage else {
+                    return String::new();
+                };
+                let [load1, load5, load15] = self.cpu_load.unwrap_or_default();
+                (
+                    format.as_deref().unwrap_or(CPU_FORMAT),
+                    vec![
+                        ("usage", format!("{:.0}", usage)),
+                        ("load1", format!("{:.2}", load1)),
+                        ("load5", format!("{:.2}", load5)),
+                        ("load15", format!("{:.2}", load15)),
+                    ],
+                )
+            }
+            StatusItem::Memory { format } => {
+                let Some(memory) = self.memory else {
+                    return String::new();
+                };
+                (
+                    format.as_deref().unwrap_or(MEMORY_FORMAT),
+                    vec![
+                        ("used", format!("{:.1}", memory.used as f64 / GIB)),
+                        ("total", format!("{:.1}", memory.total as f64 / GIB)),
+                        ("percent", format!("{:.0}", memory.percent())),
+                    ],
+                )
+            }
+            StatusItem::Network { format } => {
+                let Some((down, up)) = self.network else {
+                    return String::new();
+                };
+                (
+                    format.as_deref().unwrap_or(NETWORK_FORMAT),
+                    vec![("down", format_rate(down)), ("up", format_rate(up))],
+                )
+            }
+            _ => return String::new(),
+        };
+
+        values
+            .iter()
+            .fold(format.to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{}}}", name), value)
+            })
+    }
+}
+
+/// Battery, CPU, memory and network items, the others aren't sampled here
+pub fn is_metric(item: &StatusItem) -> bool {
+    matches!(
+        item,
+        StatusItem::Battery { .. }
+            | StatusItem::Cpu { .. }
+            | StatusItem::Memory { .. }
+            | StatusItem::Network { .. }
+    )
+}
+
+/// First `{name}` in the `format` of a metric item that it doesn't fill in
+pub fn unknown_placeholder(item: &StatusItem) -> Option<&str> {
+    let (format, known): (_, &[&str]) = match item {
+        StatusItem::Battery { format } => (format, &["percent", "status", "name"]),
+        StatusItem::Cpu { format } => (format, &["usage", "load1", "load5", "load15"]),
+        StatusItem::Memory { format } => (format, &["used", "total", "percent"]),
+        StatusItem::Network { format } => (format, &["down", "up"]),
+        _ => return None,
+    };
+    format
+        .as_deref()?
+        .split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}'))
+        .map(|(name, _)| name)
+        .find(|name| !known.contains(name))
+}
+
+/// Output of a `script` item
+#[derive(Debug, Clone, Default)]
+pub struct ScriptOutput {
+    pub text: String,
+    last_run: Option<Instant>,
+    running: bool,
+}
+
+impl ScriptOutput {
+    /// Whether the interval has passed, a script still running isn't started
+    /// again
+    pub fn is_due(&self, interval: Option<u64>) -> bool {
+        let interval = interval.map_or(SCRIPT_INTERVAL, Duration::from_secs);
+        !self.running
+            && self
+                .last_run
+                .map_or(true, |last_run| last_run.elapsed() >= interval)
+    }
+
+    pub fn start(&mut self) {
+        self.running = true;
+        self.last_run = Some(Instant::now());
+    }
+
+    /// Show the output, or a short error that the log explains
+    pub fn finish(&mut self, command: &str, output: Result<String>) {
+        self.running = false;
+        self.text = output.unwrap_or_else(|error| {
+            warn!("Status bar script `{}` failed: {:#}", command, error);
+            "⚠".to_string()
+        });
+    }
+}
+
+/// First line of the command's output, run with `sh -c`
+pub fn run_script(command: &str) -> Result<String> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .context("Failed to run sh")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!(
+            "{}: {}",
+            output.status,
+            stderr.lines().next().unwrap_or_default()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string())
+}
+
+/// Bytes per second with a binary unit, e.g. `1.2 MiB/s`
+fn format_rate(bytes: f64) -> String {
+    const UNITS: [&str; 4] = ["B/s", "KiB/s", "MiB/s", "GiB/s"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{:.0} {}", value, UNITS[unit]),
+        _ => format!("{:.1} {}", value, UNITS[unit]),
+    }
+}
@@ -2,11 +2,16 @@
 //!
 //! This module provides functionality to find and parse `.desktop` files from
 //! standard system locations, extracting application information such as name,
-//! executable path, and icon location.
+//! executable path, and icon location. Parsed files are cached by modification
+//! time, so rescans only parse files that changed.
 
+use std::collections::HashMap;
 use std::fs;
 use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use log::debug;
 
 use crate::common::expand_tilde;
 use super::desktop_entry_categories::Category;
@@ -52,40 +57,122 @@ pub struct DesktopEntry {
     pub filename: String,
     pub takes_args: bool,
     pub categories: Vec<Category>,
+    /// Types of files the application opens, e.g. `image/png`
+    pub mime_types: Vec<String>,
+    /// Tooltip like "Browse the World Wide Web"
+    pub comment: String,
+    /// Kind of application like "Web Browser"
+    pub generic_name: String,
+    /// Other words to find the application by
+    pub keywords: Vec<String>,
+}
+
+impl DesktopEntry {
+    /// The generic name, comment and keywords for the search index
+    pub fn search_keywords(&self) -> String {
+        [self.generic_name.as_str(), self.comment.as_str()]
+            .into_iter()
+            .chain(self.keywords.iter().map(String::as_str))
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// A desktop file as it was last parsed. `entry` is `None` for files that
+/// aren't launchable applications, so those aren't parsed again either
+#[derive(Debug, Clone)]
+pub struct CachedDesktopEntry {
+    pub path: String,
+    /// Modification time in nanoseconds since the Unix epoch
+    pub mtime: i64,
+    pub entry: Option<DesktopEntry>,
 }
 
 /// Scan system directories for desktop entries and return a list of valid applications
-pub fn scan_desktopentries() -> Vec<DesktopEntry> {
-    DESKTOP_ENTRIES_UNIX_PATHS
+///
+/// Files whose modification time matches their entry in `cache` are taken from
+/// the cache instead of being parsed. Also returns the updated cache, which
+/// contains exactly the files found by this scan.
+pub fn scan_desktopentries(
+    cache: Vec<CachedDesktopEntry>,
+) -> (Vec<DesktopEntry>, Vec<CachedDesktopEntry>) {
+    let mut cache: HashMap<String, CachedDesktopEntry> = cache
+        .into_iter()
+        .map(|cached| (cached.path.clone(), cached))
+        .collect();
+    let mut parsed = 0;
+
+    let scanned: Vec<CachedDesktopEntry> = DESKTOP_ENTRIES_UNIX_PATHS
         .iter()
-        .flat_map(|path| {
-            let expanded_path = expand_tilde(path);
-            let mut apps = Vec::new();
-            scan_directory(&expanded_path, &mut apps);
-            apps
+        .flat_map(|path| desktop_files(&expand_tilde(path)))
+        .map(|path| {
+            let key = path.to_string_lossy().into_owned();
+            let mtime = modified_time(&path);
+            match cache.remove(&key) {
+                Some(cached) if cached.mtime == mtime => cached,
+                _ => {
+                    parsed += 1;
+                    CachedDesktopEntry {
+                        path: key,
+                        mtime,
+                        entry: parse_desktop_file(&path),
+                    }
+                }
+            }
         })
+        .collect();
+    debug!("Parsed {} of {} desktop files", parsed, scanned.len());
+
+    let apps = scanned
+        .iter()
+        .filter_map(|cached| cached.entry.clone())
+        .collect();
+    (apps, scanned)
+}
+
+/// Existing directories the scan reads desktop files from
+pub fn desktop_entry_dirs() -> Vec<PathBuf> {
+    DESKTOP_ENTRIES_UNIX_PATHS
+        .iter()
+        .map(|path| expand_tilde(path))
+        .filter(|dir| dir.is_dir())
         .collect()
 }
 
-fn scan_directory(dir: &PathBuf, apps: &mut Vec<DesktopEntry>) {
-    if !dir.exists() {
-        return;
+/// Parse one desktop file for the cache, like the scan does
+pub fn read_desktop_file(path: &Path) -> CachedDesktopEntry {
+    CachedDesktopEntry {
+        path: path.to_string_lossy().into_owned(),
+        mtime: modified_time(path),
+        entry: parse_desktop_file(path),
     }
+}
 
-    if let Ok(entries) = fs::read_dir(dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) == Some("desktop") {
-                if let Some(app_info) = parse_desktop_file(&path) {
-                    apps.push(app_info);
-                }
-            }
-        }
-    }
+fn desktop_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("desktop"))
+        .collect()
+}
+
+/// Unreadable files get 0, which is compared like any other time
+pub(super) fn modified_time(path: &Path) -> i64 {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos() as i64)
+        .unwrap_or_default()
 }
 
 /// Parse a desktop entry file and return application information if valid
-fn parse_desktop_file(path: &PathBuf) -> Option<DesktopEntry> {
+pub(super) fn parse_desktop_file(path: &Path) -> Option<DesktopEntry> {
     let file = fs::File::open(path).ok()?;
     let reader = BufReader::new(file);
     let filename = path.file_name()?.to_string_lossy().into_owned();
@@ -95,6 +182,10 @@ fn parse_desktop_file(path: &PathBuf) -> Option<DesktopEntry> {
     let mut icon = String::new();
     let mut type_entry = String::new();
     let mut categories = Vec::new();
+    let mut mime_types = Vec::new();
+    let mut comment = String::new();
+    let mut generic_name = String::new();
+    let mut keywords = Vec::new();
     let mut in_desktop_entry = false;
 
     for line in reader.lines().flatten() {
@@ -110,6 +201,16 @@ fn parse_desktop_file(path: &PathBuf) -> Option<DesktopEntry> {
                         "Exec" => exec = value.trim().to_string(),
                         "Icon" => icon = value.trim().to_string(),
                         "Type" => type_entry = value.trim().to_string(),
+                        "Comment" => comment = value.trim().to_string(),
+                        "GenericName" => generic_name = value.trim().to_string(),
+                        "Keywords" => {
+                            keywords = value
+                                .split(';')
+                                .map(str::trim)
+                                .filter(|s| !s.is_empty())
+                                .map(str::to_string)
+                                .collect();
+                        }
                         "Categories" => {
                             categories = value
                                 .split(';')
@@ -117,6 +218,14 @@ fn parse_desktop_file(path: &PathBuf) -> Option<DesktopEntry> {
                                 .filter_map(|s| Category::from_str(s.trim()))
                                 .collect();
                         }
+                        "MimeType" => {
+                            mime_types = value
+                                .split(';')
+                                .map(str::trim)
+                                .filter(|s| !s.is_empty())
+                                .map(str::to_string)
+                                .collect();
+                        }
                         _ => {}
                     }
                 }
@@ -150,5 +259,9 @@ fn parse_desktop_file(path: &PathBuf) -> Option<DesktopEntry> {
         filename,
         takes_args,
         categories,
+        mime_types,
+        comment,
+        generic_name,
+        keywords,
     })
 }
@@ -0,0 +1,179 @@
+//! AppImages in configured directories, indexed like installed applications.
+//!
+//! The name and icon come from the desktop entry inside the AppImage, which
+//! is extracted with the AppImage's own `--appimage-extract`. Extracted
+//! entries are kept in the desktop entry cache, keyed by the AppImage's path,
+//! so an AppImage is only extracted again once it changes.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+use log::{debug, warn};
+
+use super::app_finder::{modified_time, parse_desktop_file, CachedDesktopEntry, DesktopEntry};
+use crate::common::expand_tilde;
+use crate::config::Config;
+
+/// Extracted desktop entries and icons, one directory per AppImage
+const EXTRACT_DIR: &str = "~/.cache/crowbar/appimages";
+
+static APPIMAGE_DIRS: OnceLock<Vec<PathBuf>> = OnceLock::new();
+
+/// Read the directories to scan from the config
+pub fn init(config: &Config) {
+    let _ = APPIMAGE_DIRS.set(
+        config
+            .appimage_dirs
+            .iter()
+            .map(|dir| expand_tilde(dir))
+            .collect(),
+    );
+}
+
+pub fn is_appimage(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("appimage"))
+}
+
+/// Find AppImages in the configured directories, taking entries of unchanged
+/// files from `cache`. Returns the applications and the updated cache, which
+/// contains exactly the AppImages found.
+pub fn scan_appimages(
+    cache: Vec<CachedDesktopEntry>,
+) -> (Vec<DesktopEntry>, Vec<CachedDesktopEntry>) {
+    let dirs = APPIMAGE_DIRS.get().cloned().unwrap_or_default();
+
+    let scanned: Vec<CachedDesktopEntry> = dirs
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| is_appimage(path) && path.is_file())
+        .map(|path| {
+            let key = path.to_string_lossy().into_owned();
+            let mtime = modified_time(&path);
+            match cache.iter().find(|cached| cached.path == key) {
+                Some(cached) if cached.mtime == mtime => cached.clone(),
+                _ => CachedDesktopEntry {
+                    path: key,
+                    mtime,
+                    entry: Some(extract_entry(&path)),
+                },
+            }
+        })
+        .collect();
+
+    let apps = scanned
+        .iter()
+        .filter_map(|cached| cached.entry.clone())
+        .collect();
+    (apps, scanned)
+}
+
+/// The AppImage's desktop entry, launching the AppImage itself. AppImages
+/// without a readable entry are named after their file.
+fn extract_entry(appimage: &Path) -> DesktopEntry {
+    let stem = appimage
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let fallback = DesktopEntry {
+        name: stem.clone(),
+        exec: appimage.to_string_lossy().into_owned(),
+        icon: String::new(),
+        filename: appimage
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        takes_args: false,
+        categories: Vec::new(),
+        mime_types: Vec::new(),
+        comment: String::new(),
+        generic_name: String::new(),
+        keywords: Vec::new(),
+    };
+
+    let dir = expand_tilde(EXTRACT_DIR).join(&stem);
+    let _ = fs::remove_dir_all(&dir);
+    if let Err(e) = fs::create_dir_all(&dir) {
+        warn!("Failed to create {:?}: {}", dir, e);
+        return fallback;
+    }
+
+    // Desktop entries and icons sit at the root of the image
+    if !extract(appimage, &dir, "*.desktop") {
+        return fallback;
+    }
+    let root = dir.join("squashfs-root");
+    let Some(parsed) = fs::read_dir(&root)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "desktop")
+        })
+        .find_map(|path| parse_desktop_file(&path))
+    else {
+        debug!("No desktop entry in {:?}", appimage);
+        return fallback;
+    };
+
+    let icon = if parsed.icon.is_empty() {
+        String::new()
+    } else {
+        extract(appimage, &dir, &format!("{}.*", parsed.icon));
+        fs::read_dir(&root)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .find(|path| {
+                path.file_stem()
+                    .is_some_and(|stem| stem.to_string_lossy() == parsed.icon)
+            })
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or(parsed.icon)
+    };
+
+    DesktopEntry {
+        name: parsed.name,
+        icon,
+        categories: parsed.categories,
+        mime_types: parsed.mime_types,
+        comment: parsed.comment,
+        generic_name: parsed.generic_name,
+        keywords: parsed.keywords,
+        ..fallback
+    }
+}
+
+/// Extract files matching `pattern` into `dir/squashfs-root`
+fn extract(appimage: &Path, dir: &Path, pattern: &str) -> bool {
+    match Command::new(appimage)
+        .arg("--appimage-extract")
+        .arg(pattern)
+        .current_dir(dir)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+    {
+        Ok(status) if status.success() => true,
+        Ok(status) => {
+            debug!(
+                "Extracting {} from {:?} failed: {}",
+                pattern, appimage, status
+            );
+            false
+        }
+        Err(e) => {
+            warn!("Failed to run {:?}: {}", appimage, e);
+            false
+        }
+    }
+}
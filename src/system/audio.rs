@@ -0,0 +1,122 @@
+//! Audio devices of PulseAudio or PipeWire (through `pipewire-pulse`), using
+//! `pactl`.
+
+use std::process::Command;
+
+use anyhow::{anyhow, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceKind {
+    /// Output device
+    Sink,
+    /// Input device
+    Source,
+}
+
+impl DeviceKind {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Sink => "sink",
+            Self::Source => "source",
+        }
+    }
+
+    /// Placeholder `pactl` resolves to the current default device
+    fn default_placeholder(self) -> &'static str {
+        match self {
+            Self::Sink => "@DEFAULT_SINK@",
+            Self::Source => "@DEFAULT_SOURCE@",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AudioDevice {
+    pub kind: DeviceKind,
+    /// Identifier used by `pactl`
+    pub name: String,
+    /// Human readable name, e.g. "Built-in Audio Analog Stereo"
+    pub description: String,
+    pub is_default: bool,
+}
+
+/// All sinks and sources, except the monitor sources that mirror each sink
+pub fn devices() -> Result<Vec<AudioDevice>> {
+    let mut devices = Vec::new();
+    for kind in [DeviceKind::Sink, DeviceKind::Source] {
+        let default = pactl(&[&format!("get-default-{}", kind.name())])?;
+        let list = pactl(&["list", &format!("{}s", kind.name())])?;
+        devices.extend(
+            parse_devices(kind, &list)
+                .into_iter()
+                .filter(|device| !device.name.ends_with(".monitor"))
+                .map(|device| AudioDevice {
+                    is_default: device.name == default.trim(),
+                    ..device
+                }),
+        );
+    }
+    Ok(devices)
+}
+
+pub fn set_default_command(device: &AudioDevice) -> Command {
+    let mut command = Command::new("pactl");
+    command
+        .arg(format!("set-default-{}", device.kind.name()))
+        .arg(&device.name);
+    command
+}
+
+pub fn toggle_mute_command(kind: DeviceKind) -> Command {
+    let mut command = Command::new("pactl");
+    command
+        .arg(format!("set-{}-mute", kind.name()))
+        .arg(kind.default_placeholder())
+        .arg("toggle");
+    command
+}
+
+/// Change the volume of the default sink by `step` percent
+pub fn change_volume_command(step: i32) -> Command {
+    let mut command = Command::new("pactl");
+    command
+        .arg("set-sink-volume")
+        .arg(DeviceKind::Sink.default_placeholder())
+        .arg(format!("{:+}%", step));
+    command
+}
+
+/// Read the `Name:` and `Description:` of each device in `pactl list` output
+fn parse_devices(kind: DeviceKind, list: &str) -> Vec<AudioDevice> {
+    let mut devices: Vec<AudioDevice> = Vec::new();
+    for line in list.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix("Name: ") {
+            devices.push(AudioDevice {
+                kind,
+                name: name.to_string(),
+                description: name.to_string(),
+                is_default: false,
+            });
+        } else if let Some(description) = line.strip_prefix("Description: ") {
+            if let Some(device) = devices.last_mut() {
+                device.description = description.to_string();
+            }
+        }
+    }
+    devices
+}
+
+fn pactl(args: &[&str]) -> Result<String> {
+    // The field names of `pactl list` are translated
+    let output = Command::new("pactl")
+        .args(args)
+        .env("LC_ALL", "C")
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,109 @@
+//! Bitwarden vault access through the `bw` CLI.
+//!
+//! `bw` needs the session key printed by `bw unlock`. It's read from the
+//! `BW_SESSION` environment variable, or from the keyring with
+//! `secret-tool lookup service crowbar account bitwarden`.
+
+use std::env;
+use std::process::Command;
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+/// Fields of a login that can be copied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Password,
+    Username,
+    Totp,
+}
+
+impl Field {
+    /// Object name used by `bw get`
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Password => "password",
+            Self::Username => "username",
+            Self::Totp => "totp",
+        }
+    }
+}
+
+/// A login of the vault, without its secrets
+#[derive(Debug, Clone)]
+pub struct VaultItem {
+    pub id: String,
+    pub name: String,
+    pub username: Option<String>,
+    pub has_totp: bool,
+}
+
+#[derive(Deserialize)]
+struct RawItem {
+    id: String,
+    name: String,
+    login: Option<RawLogin>,
+}
+
+#[derive(Deserialize)]
+struct RawLogin {
+    username: Option<String>,
+    totp: Option<String>,
+}
+
+/// Session key from the environment or the keyring
+pub fn session_token() -> Option<String> {
+    if let Ok(token) = env::var("BW_SESSION") {
+        return Some(token);
+    }
+
+    let output = Command::new("secret-tool")
+        .args(["lookup", "service", "crowbar", "account", "bitwarden"])
+        .output()
+        .ok()?;
+    let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !token.is_empty()).then_some(token)
+}
+
+/// All logins of the vault. Passwords are dropped while parsing and read
+/// again with [`get`] when needed.
+pub fn items(session: &str) -> Result<Vec<VaultItem>> {
+    let output = bw(&["list", "items"], session)?;
+    let items: Vec<RawItem> = serde_json::from_str(&output)?;
+
+    Ok(items
+        .into_iter()
+        .filter_map(|item| {
+            let login = item.login?;
+            Some(VaultItem {
+                id: item.id,
+                name: item.name,
+                username: login.username.filter(|username| !username.is_empty()),
+                has_totp: login.totp.is_some_and(|totp| !totp.is_empty()),
+            })
+        })
+        .collect())
+}
+
+/// Read a field of an item, generating the current code for TOTP
+pub fn get(field: Field, id: &str, session: &str) -> Result<String> {
+    Ok(bw(&["get", field.name(), id], session)?
+        .trim_end()
+        .to_string())
+}
+
+fn bw(args: &[&str], session: &str) -> Result<String> {
+    // Passed through the environment, so it doesn't show up in the process list
+    let output = Command::new("bw")
+        .args(args)
+        .arg("--nointeraction")
+        .env("BW_SESSION", session)
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
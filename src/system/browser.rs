@@ -0,0 +1,54 @@
+//! The default web browser, from `xdg-settings`.
+
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// Desktop file prefix of a browser, its program and private window flag
+const PRIVATE_WINDOW_FLAGS: &[(&str, &str, &str)] = &[
+    ("firefox", "firefox", "--private-window"),
+    ("librewolf", "librewolf", "--private-window"),
+    ("chromium", "chromium", "--incognito"),
+    ("google-chrome", "google-chrome", "--incognito"),
+    ("brave", "brave-browser", "--incognito"),
+    ("vivaldi", "vivaldi", "--incognito"),
+    ("microsoft-edge", "microsoft-edge", "--inprivate"),
+];
+
+static DEFAULT_BROWSER: OnceLock<Option<String>> = OnceLock::new();
+
+/// Desktop file id of the default browser, e.g. `firefox.desktop`
+fn default_browser() -> Option<&'static str> {
+    DEFAULT_BROWSER
+        .get_or_init(|| {
+            let output = Command::new("xdg-settings")
+                .args(["get", "default-web-browser"])
+                .output()
+                .ok()?;
+            let browser = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            (!browser.is_empty()).then_some(browser)
+        })
+        .as_deref()
+}
+
+/// Whether the default browser's private window flag is known
+pub fn supports_private_window() -> bool {
+    private_window_flag().is_some()
+}
+
+/// Opens the URL in a private window of the default browser, `None` if the
+/// browser isn't known
+pub fn private_window_command(url: &str) -> Option<Command> {
+    let (program, flag) = private_window_flag()?;
+    let mut command = Command::new(program);
+    command.arg(flag).arg(url);
+    Some(command)
+}
+
+/// Program and private window flag of the default browser
+fn private_window_flag() -> Option<(&'static str, &'static str)> {
+    let browser = default_browser()?.to_lowercase();
+    PRIVATE_WINDOW_FLAGS
+        .iter()
+        .find(|(desktop_file, _, _)| browser.starts_with(desktop_file))
+        .map(|(_, program, flag)| (*program, *flag))
+}
@@ -0,0 +1,212 @@
+//! Minimal iCalendar reader for upcoming events and due todos.
+//!
+//! Reads `.ics` files as written by most calendar tools and sync clients
+//! (e.g. vdirsyncer). Only the properties needed for reminders are parsed, and
+//! recurring events are shown on the date of their first occurrence only.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+use crate::common::expand_tilde;
+
+/// A calendar event
+#[derive(Debug, Clone)]
+pub struct CalendarEvent {
+    pub summary: String,
+    pub start: DateTime<Local>,
+    pub all_day: bool,
+    pub location: Option<String>,
+}
+
+/// An open todo
+#[derive(Debug, Clone)]
+pub struct Todo {
+    pub summary: String,
+    pub due: Option<DateTime<Local>>,
+}
+
+/// Events and todos read from a set of calendars
+#[derive(Debug, Default)]
+pub struct Calendar {
+    pub events: Vec<CalendarEvent>,
+    pub todos: Vec<Todo>,
+}
+
+impl Calendar {
+    /// Load all `.ics` files from the given files and directories
+    pub fn load(paths: &[String]) -> Self {
+        let mut calendar = Self::default();
+
+        for path in paths {
+            for file in ics_files(&expand_tilde(path)) {
+                if let Ok(content) = fs::read_to_string(&file) {
+                    calendar.parse(&content);
+                }
+            }
+        }
+
+        calendar
+    }
+
+    /// Events of today that haven't started yet, plus today's all-day events
+    pub fn upcoming_today(&self) -> Vec<&CalendarEvent> {
+        let now = Local::now();
+        let mut events: Vec<&CalendarEvent> = self
+            .events
+            .iter()
+            .filter(|event| event.start.date_naive() == now.date_naive())
+            .filter(|event| event.all_day || event.start >= now)
+            .collect();
+        events.sort_by_key(|event| (!event.all_day, event.start));
+        events
+    }
+
+    /// Open todos that are due today or overdue, most urgent first
+    pub fn due_todos(&self) -> Vec<&Todo> {
+        let today = Local::now().date_naive();
+        let mut todos: Vec<&Todo> = self
+            .todos
+            .iter()
+            .filter(|todo| todo.due.is_some_and(|due| due.date_naive() <= today))
+            .collect();
+        todos.sort_by_key(|todo| todo.due);
+        todos
+    }
+
+    fn parse(&mut self, content: &str) {
+        let mut component: Option<&str> = None;
+        let mut properties: Vec<(String, String)> = Vec::new();
+
+        for line in unfold(content) {
+            let Some((name, value)) = line.split_once(':') else {
+                continue;
+            };
+            // Strip parameters like "DTSTART;TZID=Europe/Berlin"
+            let name = name.split(';').next().unwrap_or(name).to_uppercase();
+
+            match (name.as_str(), value) {
+                ("BEGIN", "VEVENT") => component = Some("VEVENT"),
+                ("BEGIN", "VTODO") => component = Some("VTODO"),
+                ("END", "VEVENT") | ("END", "VTODO") => {
+                    match component {
+                        Some("VEVENT") => self.events.extend(event_from(&properties)),
+                        Some("VTODO") => self.todos.extend(todo_from(&properties)),
+                        _ => {}
+                    }
+                    component = None;
+                    properties.clear();
+                }
+                _ if component.is_some() => properties.push((name, unescape(value))),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn event_from(properties: &[(String, String)]) -> Option<CalendarEvent> {
+    let (start, all_day) = parse_time(property(properties, "DTSTART")?)?;
+    Some(CalendarEvent {
+        summary: property(properties, "SUMMARY")?.to_string(),
+        start,
+        all_day,
+        location: property(properties, "LOCATION").map(str::to_string),
+    })
+}
+
+fn todo_from(properties: &[(String, String)]) -> Option<Todo> {
+    let completed = property(properties, "COMPLETED").is_some()
+        || matches!(
+            property(properties, "STATUS"),
+            Some("COMPLETED") | Some("CANCELLED")
+        );
+    if completed {
+        return None;
+    }
+
+    Some(Todo {
+        summary: property(properties, "SUMMARY")?.to_string(),
+        due: property(properties, "DUE")
+            .and_then(parse_time)
+            .map(|(due, _)| due),
+    })
+}
+
+fn property<'a>(properties: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    properties
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Parse an iCalendar date or date-time. Times without a `Z` suffix are
+/// treated as local time. The flag is set for date-only values.
+fn parse_time(value: &str) -> Option<(DateTime<Local>, bool)> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some((Utc.from_utc_datetime(&naive).with_timezone(&Local), false));
+    }
+
+    if let Ok(naive) = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S") {
+        return Local
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|time| (time, false));
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()
+        .map(|time| (time, true))
+}
+
+/// Join folded lines, which continue with a leading space or tab
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        if let Some(continuation) = line.strip_prefix([' ', '\t']) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(continuation);
+                continue;
+            }
+        }
+        lines.push(line.to_string());
+    }
+    lines
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+fn ics_files(path: &Path) -> Vec<PathBuf> {
+    if path.is_file() {
+        return vec![path.to_path_buf()];
+    }
+
+    let Ok(entries) = fs::read_dir(path) else {
+        return Vec::new();
+    };
+
+    // Calendar directories (e.g. from vdirsyncer) contain one file per item
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .flat_map(|path| {
+            if path.is_dir() {
+                ics_files(&path)
+            } else if path.extension().is_some_and(|ext| ext == "ics") {
+                vec![path]
+            } else {
+                Vec::new()
+            }
+        })
+        .collect()
+}
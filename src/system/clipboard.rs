@@ -0,0 +1,58 @@
+//! Clipboard access through the platform's command line tools.
+//!
+//! Action handlers run outside of the GPUI context, so they cannot use the
+//! window's clipboard directly. Instead we shell out to `wl-copy`/`wl-paste` on
+//! Wayland and `xclip` on X11.
+
+use std::env;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context, Result};
+
+fn is_wayland() -> bool {
+    env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+/// Copies the given text to the system clipboard
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    let mut command = if is_wayland() {
+        Command::new("wl-copy")
+    } else {
+        let mut command = Command::new("xclip");
+        command.args(["-selection", "clipboard"]);
+        command
+    };
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .spawn()
+        .context("Failed to start clipboard tool")?;
+
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("Failed to open clipboard tool stdin"))?
+        .write_all(text.as_bytes())?;
+
+    child.wait()?;
+    Ok(())
+}
+
+/// Reads the current text content of the system clipboard
+pub fn read_clipboard() -> Result<String> {
+    let output = if is_wayland() {
+        Command::new("wl-paste").arg("--no-newline").output()
+    } else {
+        Command::new("xclip")
+            .args(["-selection", "clipboard", "-o"])
+            .output()
+    }
+    .context("Failed to start clipboard tool")?;
+
+    if !output.status.success() {
+        return Err(anyhow!("Clipboard tool exited with {}", output.status));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -301,4 +301,145 @@ impl Category {
             _ => None,
         }
     }
+
+    /// Name used in the `Categories` key of desktop files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AudioVideo => "AudioVideo",
+            Self::Audio => "Audio",
+            Self::Video => "Video",
+            Self::Development => "Development",
+            Self::Education => "Education",
+            Self::Game => "Game",
+            Self::Graphics => "Graphics",
+            Self::Network => "Network",
+            Self::Office => "Office",
+            Self::Settings => "Settings",
+            Self::Utility => "Utility",
+            Self::Building => "Building",
+            Self::Debugger => "Debugger",
+            Self::IDE => "IDE",
+            Self::GUIDesigner => "GUIDesigner",
+            Self::Profiling => "Profiling",
+            Self::RevisionControl => "RevisionControl",
+            Self::Translation => "Translation",
+            Self::Calendar => "Calendar",
+            Self::ContactManagement => "ContactManagement",
+            Self::Database => "Database",
+            Self::Dictionary => "Dictionary",
+            Self::Chart => "Chart",
+            Self::Email => "Email",
+            Self::Finance => "Finance",
+            Self::FlowChart => "FlowChart",
+            Self::PDA => "PDA",
+            Self::ProjectManagement => "ProjectManagement",
+            Self::Presentation => "Presentation",
+            Self::Spreadsheet => "Spreadsheet",
+            Self::WordProcessor => "WordProcessor",
+            Self::Graphics2D => "2DGraphics",
+            Self::VectorGraphics => "VectorGraphics",
+            Self::RasterGraphics => "RasterGraphics",
+            Self::Graphics3D => "3DGraphics",
+            Self::Scanning => "Scanning",
+            Self::OCR => "OCR",
+            Self::Photography => "Photography",
+            Self::Publishing => "Publishing",
+            Self::Viewer => "Viewer",
+            Self::TextTools => "TextTools",
+            Self::DesktopSettings => "DesktopSettings",
+            Self::HardwareSettings => "HardwareSettings",
+            Self::Printing => "Printing",
+            Self::PackageManager => "PackageManager",
+            Self::Dialup => "Dialup",
+            Self::InstantMessaging => "InstantMessaging",
+            Self::Chat => "Chat",
+            Self::IRCClient => "IRCClient",
+            Self::FileTransfer => "FileTransfer",
+            Self::HamRadio => "HamRadio",
+            Self::News => "News",
+            Self::P2P => "P2P",
+            Self::RemoteAccess => "RemoteAccess",
+            Self::Telephony => "Telephony",
+            Self::TelephonyTools => "TelephonyTools",
+            Self::VideoConference => "VideoConference",
+            Self::WebBrowser => "WebBrowser",
+            Self::WebDevelopment => "WebDevelopment",
+            Self::Midi => "Midi",
+            Self::Mixer => "Mixer",
+            Self::Sequencer => "Sequencer",
+            Self::Tuner => "Tuner",
+            Self::TV => "TV",
+            Self::AudioVideoEditing => "AudioVideoEditing",
+            Self::Player => "Player",
+            Self::Recorder => "Recorder",
+            Self::DiscBurning => "DiscBurning",
+            Self::ActionGame => "ActionGame",
+            Self::AdventureGame => "AdventureGame",
+            Self::ArcadeGame => "ArcadeGame",
+            Self::BoardGame => "BoardGame",
+            Self::BlocksGame => "BlocksGame",
+            Self::CardGame => "CardGame",
+            Self::KidsGame => "KidsGame",
+            Self::LogicGame => "LogicGame",
+            Self::RolePlaying => "RolePlaying",
+            Self::Simulation => "Simulation",
+            Self::SportsGame => "SportsGame",
+            Self::StrategyGame => "StrategyGame",
+            Self::Art => "Art",
+            Self::Construction => "Construction",
+            Self::Music => "Music",
+            Self::Languages => "Languages",
+            Self::Science => "Science",
+            Self::ArtificialIntelligence => "ArtificialIntelligence",
+            Self::Astronomy => "Astronomy",
+            Self::Biology => "Biology",
+            Self::Chemistry => "Chemistry",
+            Self::ComputerScience => "ComputerScience",
+            Self::DataVisualization => "DataVisualization",
+            Self::Economy => "Economy",
+            Self::Electricity => "Electricity",
+            Self::Geography => "Geography",
+            Self::Geology => "Geology",
+            Self::Geoscience => "Geoscience",
+            Self::History => "History",
+            Self::ImageProcessing => "ImageProcessing",
+            Self::Literature => "Literature",
+            Self::Math => "Math",
+            Self::NumericalAnalysis => "NumericalAnalysis",
+            Self::MedicalSoftware => "MedicalSoftware",
+            Self::Physics => "Physics",
+            Self::Robotics => "Robotics",
+            Self::Sports => "Sports",
+            Self::ParallelComputing => "ParallelComputing",
+            Self::Amusement => "Amusement",
+            Self::Archiving => "Archiving",
+            Self::Compression => "Compression",
+            Self::Electronics => "Electronics",
+            Self::Emulator => "Emulator",
+            Self::Engineering => "Engineering",
+            Self::FileTools => "FileTools",
+            Self::FileManager => "FileManager",
+            Self::TerminalEmulator => "TerminalEmulator",
+            Self::Filesystem => "Filesystem",
+            Self::Monitor => "Monitor",
+            Self::Security => "Security",
+            Self::Accessibility => "Accessibility",
+            Self::Calculator => "Calculator",
+            Self::Clock => "Clock",
+            Self::TextEditor => "TextEditor",
+            Self::Documentation => "Documentation",
+            Self::Core => "Core",
+            Self::KDE => "KDE",
+            Self::GNOME => "GNOME",
+            Self::GTK => "GTK",
+            Self::Qt => "Qt",
+            Self::Motif => "Motif",
+            Self::Java => "Java",
+            Self::ConsoleOnly => "ConsoleOnly",
+            Self::Screensaver => "Screensaver",
+            Self::TrayIcon => "TrayIcon",
+            Self::Applet => "Applet",
+            Self::Shell => "Shell",
+        }
+    }
 }
@@ -0,0 +1,93 @@
+//! Battery levels of connected peripherals such as headphones and mice.
+//!
+//! UPower exposes most Bluetooth devices that report their battery through
+//! BlueZ. Devices UPower doesn't know about are queried with `bluetoothctl`.
+
+use std::process::Command;
+
+/// Battery level of a connected device
+#[derive(Debug, Clone)]
+pub struct DeviceBattery {
+    pub name: String,
+    pub percentage: u8,
+}
+
+/// UPower object paths that don't belong to peripherals
+const UPOWER_IGNORED: &[&str] = &["DisplayDevice", "line_power", "battery_BAT"];
+
+/// Returns the battery levels of all connected devices that report one
+pub fn device_batteries() -> Vec<DeviceBattery> {
+    let mut devices = upower_batteries();
+
+    for device in bluez_batteries() {
+        if !devices.iter().any(|known| known.name == device.name) {
+            devices.push(device);
+        }
+    }
+
+    devices
+}
+
+fn upower_batteries() -> Vec<DeviceBattery> {
+    let Some(paths) = run("upower", &["-e"]) else {
+        return Vec::new();
+    };
+
+    paths
+        .lines()
+        .filter(|path| !UPOWER_IGNORED.iter().any(|ignored| path.contains(ignored)))
+        .filter_map(|path| {
+            let info = run("upower", &["-i", path])?;
+            let name = field(&info, "model:")?;
+            let percentage = field(&info, "percentage:")?
+                .trim_end_matches('%')
+                .split('.')
+                .next()?
+                .parse()
+                .ok()?;
+            Some(DeviceBattery { name, percentage })
+        })
+        .collect()
+}
+
+fn bluez_batteries() -> Vec<DeviceBattery> {
+    let Some(devices) = run("bluetoothctl", &["devices", "Connected"]) else {
+        return Vec::new();
+    };
+
+    devices
+        .lines()
+        .filter_map(|line| {
+            // Lines look like "Device 00:11:22:33:44:55 WH-1000XM4"
+            let mut parts = line.strip_prefix("Device ")?.splitn(2, ' ');
+            let address = parts.next()?;
+            let name = parts.next()?.to_string();
+
+            // "Battery Percentage: 0x46 (70)"
+            let info = run("bluetoothctl", &["info", address])?;
+            let percentage = field(&info, "Battery Percentage:")?
+                .rsplit('(')
+                .next()?
+                .trim_end_matches(')')
+                .parse()
+                .ok()?;
+            Some(DeviceBattery { name, percentage })
+        })
+        .collect()
+}
+
+fn field(output: &str, key: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix(key))
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,175 @@
+//! Display layouts, through autorandr and xrandr on X11 or kanshi and
+//! wlr-randr on Wayland.
+
+use std::env;
+use std::fs;
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::common::expand_tilde;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    X11,
+    Wayland,
+}
+
+impl Backend {
+    pub fn current() -> Self {
+        if env::var_os("WAYLAND_DISPLAY").is_some() {
+            Self::Wayland
+        } else {
+            Self::X11
+        }
+    }
+
+    /// Tool the profiles come from
+    pub fn profile_tool(&self) -> &'static str {
+        match self {
+            Self::X11 => "autorandr",
+            Self::Wayland => "kanshi",
+        }
+    }
+}
+
+/// Saved profiles and connected outputs
+#[derive(Debug, Clone)]
+pub struct Layout {
+    pub profiles: Vec<String>,
+    /// Connected outputs, the primary one first
+    pub outputs: Vec<String>,
+}
+
+/// Profiles and outputs of the backend. Missing tools leave their part
+/// empty; fails only if neither is available.
+pub fn layout(backend: Backend) -> Result<Layout> {
+    let profiles = match backend {
+        Backend::X11 => autorandr_profiles(),
+        Backend::Wayland => kanshi_profiles(),
+    };
+    let outputs = match backend {
+        Backend::X11 => xrandr_outputs(),
+        Backend::Wayland => wlr_randr_outputs(),
+    };
+
+    match (profiles, outputs) {
+        (Err(e), Err(_)) => Err(e),
+        (profiles, outputs) => Ok(Layout {
+            profiles: profiles.unwrap_or_default(),
+            outputs: outputs.unwrap_or_default(),
+        }),
+    }
+}
+
+pub fn apply_profile_command(backend: Backend, profile: &str) -> Command {
+    match backend {
+        Backend::X11 => {
+            let mut command = Command::new("autorandr");
+            command.args(["--load", profile]);
+            command
+        }
+        Backend::Wayland => {
+            let mut command = Command::new("kanshictl");
+            command.args(["switch", profile]);
+            command
+        }
+    }
+}
+
+/// Show the same picture on `output` as on `primary`. wlr-randr can't mirror
+/// outputs, so this is `None` on Wayland.
+pub fn mirror_command(backend: Backend, primary: &str, output: &str) -> Option<Command> {
+    match backend {
+        Backend::X11 => {
+            let mut command = Command::new("xrandr");
+            command.args(["--output", output, "--auto", "--same-as", primary]);
+            Some(command)
+        }
+        Backend::Wayland => None,
+    }
+}
+
+/// Turn `output` on to the right of `primary`
+pub fn extend_command(backend: Backend, primary: &str, output: &str) -> Command {
+    let (program, turn_on) = match backend {
+        Backend::X11 => ("xrandr", "--auto"),
+        Backend::Wayland => ("wlr-randr", "--on"),
+    };
+    let mut command = Command::new(program);
+    command.args(["--output", output, turn_on, "--right-of", primary]);
+    command
+}
+
+fn autorandr_profiles() -> Result<Vec<String>> {
+    Ok(run("autorandr", &["--list"])?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Named `profile <name> {` blocks of the kanshi config
+fn kanshi_profiles() -> Result<Vec<String>> {
+    let path = env::var("XDG_CONFIG_HOME")
+        .map(|dir| expand_tilde(&dir).join("kanshi/config"))
+        .unwrap_or_else(|_| expand_tilde("~/.config/kanshi/config"));
+    let config = fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+
+    Ok(config
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("profile "))
+        .filter_map(|rest| rest.split_whitespace().next())
+        .filter(|name| *name != "{")
+        .map(str::to_string)
+        .collect())
+}
+
+/// e.g. `eDP-1 connected primary 1920x1080+0+0 ...`
+fn xrandr_outputs() -> Result<Vec<String>> {
+    let mut outputs: Vec<(bool, String)> = run("xrandr", &["--query"])?
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let name = fields.next()?;
+            (fields.next()? == "connected")
+                .then(|| (fields.next() == Some("primary"), name.to_string()))
+        })
+        .collect();
+    outputs.sort_by_key(|(primary, _)| !primary);
+    Ok(outputs.into_iter().map(|(_, name)| name).collect())
+}
+
+/// Outputs start unindented, e.g. `eDP-1 "Sharp Corporation 0x14FA"`,
+/// followed by indented properties. Enabled outputs come first.
+fn wlr_randr_outputs() -> Result<Vec<String>> {
+    let mut outputs: Vec<(bool, String)> = Vec::new();
+    for line in run("wlr-randr", &[])?.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            if let Some(name) = line.split_whitespace().next() {
+                outputs.push((false, name.to_string()));
+            }
+        } else if line.trim() == "Enabled: yes" {
+            if let Some(output) = outputs.last_mut() {
+                output.0 = true;
+            }
+        }
+    }
+    outputs.sort_by_key(|(enabled, _)| !enabled);
+    Ok(outputs.into_iter().map(|(_, name)| name).collect())
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run {}", program))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,161 @@
+//! Docker containers and images through the Engine API on the Docker socket.
+
+use std::env;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+const DEFAULT_SOCKET: &str = "/var/run/docker.sock";
+
+/// Stopping a container waits up to 10 seconds for it to exit
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone)]
+pub struct Container {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    pub running: bool,
+    /// e.g. "Up 3 hours" or "Exited (0) 2 days ago"
+    pub status: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub id: String,
+    /// `repository:tag`, or the short id of untagged images
+    pub name: String,
+    pub size: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawContainer {
+    id: String,
+    names: Vec<String>,
+    image: String,
+    state: String,
+    status: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawImage {
+    id: String,
+    repo_tags: Option<Vec<String>>,
+    size: u64,
+}
+
+/// Running and stopped containers
+pub fn containers() -> Result<Vec<Container>> {
+    let body = request("GET", "/containers/json?all=true")?;
+    let containers: Vec<RawContainer> = serde_json::from_str(&body)?;
+
+    Ok(containers
+        .into_iter()
+        .map(|container| Container {
+            name: container
+                .names
+                .first()
+                .map(|name| name.trim_start_matches('/').to_string())
+                .unwrap_or_else(|| short_id(&container.id)),
+            id: container.id,
+            image: container.image,
+            running: container.state == "running",
+            status: container.status,
+        })
+        .collect())
+}
+
+pub fn images() -> Result<Vec<Image>> {
+    let body = request("GET", "/images/json")?;
+    let images: Vec<RawImage> = serde_json::from_str(&body)?;
+
+    Ok(images
+        .into_iter()
+        .map(|image| Image {
+            name: image
+                .repo_tags
+                .and_then(|tags| tags.into_iter().find(|tag| tag != "<none>:<none>"))
+                .unwrap_or_else(|| short_id(&image.id)),
+            id: image.id,
+            size: image.size,
+        })
+        .collect())
+}
+
+pub fn start(id: &str) -> Result<()> {
+    request("POST", &format!("/containers/{}/start", id)).map(|_| ())
+}
+
+pub fn stop(id: &str) -> Result<()> {
+    request("POST", &format!("/containers/{}/stop", id)).map(|_| ())
+}
+
+pub fn remove_container(id: &str) -> Result<()> {
+    request("DELETE", &format!("/containers/{}", id)).map(|_| ())
+}
+
+pub fn remove_image(id: &str) -> Result<()> {
+    request("DELETE", &format!("/images/{}", id)).map(|_| ())
+}
+
+/// Arguments opening an interactive shell in a running container
+pub fn exec_shell_args(id: &str) -> Vec<String> {
+    ["docker", "exec", "-it", id, "sh", "-c", "bash || sh"]
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
+/// e.g. "sha256:0123456789abcdef..." becomes "0123456789ab"
+fn short_id(id: &str) -> String {
+    id.trim_start_matches("sha256:").chars().take(12).collect()
+}
+
+/// `DOCKER_HOST` if it points to a Unix socket, the default socket otherwise
+fn socket_path() -> PathBuf {
+    env::var("DOCKER_HOST")
+        .ok()
+        .and_then(|host| host.strip_prefix("unix://").map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_SOCKET))
+}
+
+/// Send a request without a body and return the response body. HTTP/1.0
+/// makes the daemon close the connection after responding, without chunked
+/// encoding.
+fn request(method: &str, path: &str) -> Result<String> {
+    let socket = socket_path();
+    let mut stream = UnixStream::connect(&socket)
+        .with_context(|| format!("Failed to connect to {:?}", socket))?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    write!(
+        stream,
+        "{} {} HTTP/1.0\r\nHost: docker\r\nContent-Length: 0\r\n\r\n",
+        method, path
+    )?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| anyhow!("Invalid response from the Docker daemon"))?;
+    let status: u16 = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| anyhow!("Invalid response from the Docker daemon"))?;
+
+    if status >= 400 {
+        let message = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|json| json["message"].as_str().map(str::to_string))
+            .unwrap_or_else(|| format!("Docker daemon responded with {}", status));
+        return Err(anyhow!(message));
+    }
+    Ok(body.to_string())
+}
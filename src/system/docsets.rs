@@ -0,0 +1,142 @@
+//! Symbols of Dash docsets installed by Zeal or Dash.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use rusqlite::{Connection, OpenFlags};
+
+#[derive(Debug, Clone)]
+pub struct Docset {
+    /// Name of the docset's directory without `.docset`, e.g. `Rust`
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl Docset {
+    fn index(&self) -> PathBuf {
+        self.path.join("Contents/Resources/docSet.dsidx")
+    }
+
+    fn documents(&self) -> PathBuf {
+        self.path.join("Contents/Resources/Documents")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DocEntry {
+    pub name: String,
+    /// Kind of symbol, e.g. `Function` or `Struct`
+    pub kind: String,
+    pub docset: String,
+    /// Page of the symbol, a `file://` URL for local documents
+    pub url: String,
+}
+
+/// Docsets in the given directories, sorted by name
+pub fn installed(dirs: &[PathBuf]) -> Vec<Docset> {
+    let mut docsets: Vec<Docset> = dirs
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let name = path
+                .file_name()?
+                .to_string_lossy()
+                .strip_suffix(".docset")?
+                .to_string();
+            Some(Docset { name, path })
+        })
+        .filter(|docset| docset.index().exists())
+        .collect();
+    docsets.sort_by_key(|docset| docset.name.to_lowercase());
+    docsets
+}
+
+/// Symbols containing `term` in one docset, exact and prefix matches first
+pub fn search(docset: &Docset, term: &str, limit: usize) -> Result<Vec<DocEntry>> {
+    let conn = Connection::open_with_flags(docset.index(), OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+
+    let dash_format: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'searchIndex'",
+        [],
+        |row| row.get(0),
+    )?;
+    // Docsets converted from Apple's format keep their Core Data tables
+    let sql = if dash_format {
+        "SELECT name, type, path, NULL FROM searchIndex
+         WHERE name LIKE ?1 ESCAPE '\\'
+         ORDER BY name = ?2 COLLATE NOCASE DESC, name LIKE ?3 ESCAPE '\\' DESC, length(name)
+         LIMIT ?4"
+    } else {
+        "SELECT ztokenname, ztypename, zpath, zanchor FROM ztoken
+         LEFT JOIN ztokenmetainformation ON ztoken.zmetainformation = ztokenmetainformation.z_pk
+         LEFT JOIN zfilepath ON ztokenmetainformation.zfile = zfilepath.z_pk
+         LEFT JOIN ztokentype ON ztoken.ztokentype = ztokentype.z_pk
+         WHERE ztokenname LIKE ?1 ESCAPE '\\'
+         ORDER BY ztokenname = ?2 COLLATE NOCASE DESC, ztokenname LIKE ?3 ESCAPE '\\' DESC,
+                  length(ztokenname)
+         LIMIT ?4"
+    };
+
+    let escaped = term
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    let mut stmt = conn.prepare(sql)?;
+    let entries = stmt
+        .query_map(
+            (
+                format!("%{}%", escaped),
+                term,
+                format!("{}%", escaped),
+                limit as i64,
+            ),
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                ))
+            },
+        )?
+        .filter_map(|row| row.ok())
+        .filter_map(|(name, kind, path, anchor)| {
+            let mut url = document_url(&docset.documents(), &path?);
+            if let Some(anchor) = anchor.filter(|anchor| !anchor.is_empty()) {
+                url = format!("{}#{}", url, anchor);
+            }
+            Some(DocEntry {
+                name,
+                kind: kind.unwrap_or_default(),
+                docset: docset.name.clone(),
+                url,
+            })
+        })
+        .collect();
+    Ok(entries)
+}
+
+/// URL of a page of the index. Dash paths can start with `<dash_entry_...>`
+/// tags for its table of contents, which aren't part of the file name.
+fn document_url(documents: &Path, path: &str) -> String {
+    let mut path = path;
+    while path.starts_with('<') {
+        match path.find('>') {
+            Some(end) => path = &path[end + 1..],
+            None => break,
+        }
+    }
+    if path.starts_with("http://") || path.starts_with("https://") {
+        return path.to_string();
+    }
+    format!("file://{}/{}", documents.display(), path)
+}
+
+/// Search page of devdocs.io, for docs that aren't installed
+pub fn devdocs_url(terms: &str) -> String {
+    format!("https://devdocs.io/#q={}", urlencoding::encode(terms))
+}
@@ -3,6 +3,7 @@
 //! Scans PATH for executables and identifies their type (ELF, Mach-O, scripts) using magic numbers.
 //!
 //! ```no_run
+//! # use crowbar::system::executable_finder::scan_path_executables;
 //! let executables = scan_path_executables().unwrap();
 //! for exe in executables {
 //!     println!("{} at {:?}: {:?}", exe.name, exe.path, exe.file_type);
@@ -93,6 +94,20 @@ pub fn scan_path_executables() -> io::Result<Vec<FileInfo>> {
     Ok(executables)
 }
 
+/// Directories the scan looks for executables in, `PATH` first
+pub fn executable_dirs() -> Vec<PathBuf> {
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    std::env::split_paths(&path)
+        .chain(get_additional_paths())
+        .filter(|dir| dir.is_dir())
+        .collect()
+}
+
+/// Details of one executable, `None` for other files
+pub fn executable_info(path: &Path) -> Option<FileInfo> {
+    get_executable_info(&path.to_path_buf()).ok().flatten()
+}
+
 /// Gets a list of additional directories to scan, including user-specific paths
 fn get_additional_paths() -> Vec<PathBuf> {
     ADDITIONAL_UNIX_PATHS
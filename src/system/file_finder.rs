@@ -0,0 +1,168 @@
+//! Finds files by name for the file search handler
+//!
+//! Queries an external indexer (`fd`, `locate` or `plocate`) or builds a
+//! built-in index by walking the configured root directories.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
+
+use anyhow::{anyhow, Result};
+use log::info;
+
+/// Upper bound for the built-in index, to keep memory use in check
+const MAX_INDEXED_FILES: usize = 200_000;
+
+/// Paths read from an external indexer before they're filtered, so a short
+/// query doesn't list the whole disk
+const MAX_TOOL_RESULTS: usize = 1000;
+
+/// Directories that are never worth indexing
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "__pycache__"];
+
+/// Where file search results come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileSearchBackend {
+    Fd,
+    Locate,
+    Plocate,
+    /// Built-in index of the configured roots
+    Index,
+}
+
+impl FileSearchBackend {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "fd" => Ok(Self::Fd),
+            "locate" => Ok(Self::Locate),
+            "plocate" => Ok(Self::Plocate),
+            "index" => Ok(Self::Index),
+            _ => Err(anyhow!("Unknown file search backend: {}", name)),
+        }
+    }
+}
+
+/// Ask an external indexer for files whose path matches all terms of the query
+pub fn search_with_tool(
+    backend: FileSearchBackend,
+    roots: &[PathBuf],
+    query: &str,
+    limit: usize,
+) -> Result<Vec<PathBuf>> {
+    let terms: Vec<&str> = query.split_whitespace().collect();
+    let Some(first_term) = terms.first() else {
+        return Ok(Vec::new());
+    };
+    // Typed text must never become an option, like `--exec` of fd
+    if let Some(term) = terms.iter().find(|term| term.starts_with('-')) {
+        return Err(anyhow!("Search terms can't start with '-': {}", term));
+    }
+
+    let mut command = match backend {
+        FileSearchBackend::Fd => {
+            let mut command = Command::new("fd");
+            command
+                .args(["--type", "f", "--ignore-case", "--fixed-strings"])
+                .arg(format!("--max-results={}", MAX_TOOL_RESULTS))
+                .arg("--")
+                .arg(first_term)
+                .args(roots);
+            command
+        }
+        FileSearchBackend::Locate | FileSearchBackend::Plocate => {
+            let program = if backend == FileSearchBackend::Plocate {
+                "plocate"
+            } else {
+                "locate"
+            };
+            let mut command = Command::new(program);
+            command
+                .arg("--ignore-case")
+                .arg(format!("--limit={}", MAX_TOOL_RESULTS))
+                .arg("--")
+                .args(&terms);
+            command
+        }
+        FileSearchBackend::Index => return Err(anyhow!("Not an external indexer")),
+    };
+
+    let output = command.output()?;
+    if !output.status.success() && output.stdout.is_empty() {
+        return Err(anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let files = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(PathBuf::from)
+        .filter(|path| matches_terms(path, &terms))
+        .filter(|path| roots.iter().any(|root| path.starts_with(root)))
+        .take(limit)
+        .collect();
+
+    Ok(files)
+}
+
+/// Walk the roots breadth-first and collect all regular files, skipping
+/// hidden entries and symlinks
+pub fn scan_files(roots: &[PathBuf]) -> Vec<PathBuf> {
+    let start = Instant::now();
+    let mut files = Vec::new();
+    let mut queue: VecDeque<PathBuf> = roots.iter().cloned().collect();
+
+    while let Some(dir) = queue.pop_front() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.') {
+                continue;
+            }
+
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => {
+                    if !SKIPPED_DIRS.contains(&name.as_ref()) {
+                        queue.push_back(entry.path());
+                    }
+                }
+                Ok(file_type) if file_type.is_file() => files.push(entry.path()),
+                _ => {}
+            }
+
+            if files.len() >= MAX_INDEXED_FILES {
+                info!("File index limit of {} reached", MAX_INDEXED_FILES);
+                return files;
+            }
+        }
+    }
+
+    info!("Indexed {} files in {:?}", files.len(), start.elapsed());
+    files
+}
+
+/// Case-insensitive check that the path contains every term, with the last
+/// term matching the file name
+pub fn matches_terms(path: &Path, terms: &[&str]) -> bool {
+    let path_str = path.to_string_lossy().to_lowercase();
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match terms.split_last() {
+        Some((last, rest)) => {
+            file_name.contains(&last.to_lowercase())
+                && rest
+                    .iter()
+                    .all(|term| path_str.contains(&term.to_lowercase()))
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,100 @@
+//! Flathub search through its web API, using `curl`, and installed Flatpaks
+//! through the `flatpak` CLI.
+
+use std::collections::HashSet;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context, Result};
+
+const SEARCH_URL: &str = "https://flathub.org/api/v2/search";
+
+const RESULTS_PER_SEARCH: usize = 8;
+
+#[derive(Debug, Clone)]
+pub struct FlathubApp {
+    /// e.g. `org.mozilla.firefox`
+    pub id: String,
+    pub name: String,
+    pub summary: String,
+}
+
+/// Apps on Flathub matching the terms, most relevant first
+pub fn search(terms: &str) -> Result<Vec<FlathubApp>> {
+    let body = serde_json::json!({ "query": terms, "filters": [] }).to_string();
+
+    // The body is read from stdin
+    let mut child = Command::new("curl")
+        .args(["-s", "--fail", "--max-time", "10", "-A", "crowbar"])
+        .args(["-H", "Content-Type: application/json"])
+        .args(["--data-binary", "@-"])
+        .arg(SEARCH_URL)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .context("Failed to start curl")?;
+
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("Failed to open curl stdin"))?;
+    stdin.write_all(body.as_bytes())?;
+    drop(stdin);
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(anyhow!("Flathub is unreachable"));
+    }
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let hits = json["hits"]
+        .as_array()
+        .ok_or_else(|| anyhow!("Unexpected response from Flathub"))?;
+
+    Ok(hits
+        .iter()
+        .filter_map(|hit| {
+            Some(FlathubApp {
+                id: hit["app_id"].as_str()?.to_string(),
+                name: hit["name"].as_str()?.to_string(),
+                summary: hit["summary"].as_str().unwrap_or_default().to_string(),
+            })
+        })
+        .take(RESULTS_PER_SEARCH)
+        .collect())
+}
+
+/// IDs of the installed Flatpak apps, empty if Flatpak isn't installed
+pub fn installed_apps() -> HashSet<String> {
+    let Ok(output) = Command::new("flatpak")
+        .args(["list", "--app", "--columns=application"])
+        .output()
+    else {
+        return HashSet::new();
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// `flatpak install` for the app, asking for confirmation in the terminal
+pub fn install_args(id: &str) -> Vec<String> {
+    ["flatpak", "install", "flathub", id]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect()
+}
+
+pub fn run(id: &str) -> Result<()> {
+    Command::new("flatpak")
+        .args(["run", id])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("Failed to start flatpak")?;
+    Ok(())
+}
@@ -0,0 +1,122 @@
+//! Finds git repositories under the configured roots for the repository
+//! handler.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use log::info;
+
+/// Repositories nested deeper below a root aren't found, which keeps scans
+/// of the home directory short
+const MAX_DEPTH: usize = 4;
+
+/// Directories that never contain repositories worth jumping to
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor", "__pycache__"];
+
+#[derive(Debug, Clone)]
+pub struct GitRepo {
+    pub path: PathBuf,
+    /// Name of the repository's directory
+    pub name: String,
+    /// URL of the `origin` remote, as configured
+    pub remote_url: Option<String>,
+}
+
+/// Walk the roots for directories containing `.git`. Repositories inside
+/// repositories, like submodules, are skipped.
+pub fn scan(roots: &[PathBuf]) -> Vec<GitRepo> {
+    let start = Instant::now();
+    let mut repos = Vec::new();
+    let mut queue: VecDeque<(PathBuf, usize)> =
+        roots.iter().map(|root| (root.clone(), 0)).collect();
+
+    while let Some((dir, depth)) = queue.pop_front() {
+        if dir.join(".git").exists() {
+            repos.push(GitRepo {
+                name: dir
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| dir.to_string_lossy().to_string()),
+                remote_url: origin_url(&dir),
+                path: dir,
+            });
+            continue;
+        }
+        if depth >= MAX_DEPTH {
+            continue;
+        }
+
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()) {
+                continue;
+            }
+            if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                queue.push_back((entry.path(), depth + 1));
+            }
+        }
+    }
+
+    info!(
+        "Found {} git repositories in {:?}",
+        repos.len(),
+        start.elapsed()
+    );
+    repos
+}
+
+/// `url` of `[remote "origin"]` in the repository's config
+fn origin_url(repo: &Path) -> Option<String> {
+    let config = fs::read_to_string(repo.join(".git").join("config")).ok()?;
+    let mut in_origin = false;
+
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_origin = line == "[remote \"origin\"]";
+            continue;
+        }
+        if !in_origin {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            if key.trim() == "url" {
+                return Some(value.trim().to_string());
+            }
+        }
+    }
+    None
+}
+
+/// Web page of a remote, e.g. `https://github.com/owner/repo` for
+/// `git@github.com:owner/repo.git`
+pub fn web_url(remote_url: &str) -> Option<String> {
+    let remote_url = remote_url.trim_end_matches(".git");
+
+    if let Some(rest) = remote_url
+        .strip_prefix("https://")
+        .or_else(|| remote_url.strip_prefix("http://"))
+    {
+        // Drop credentials, e.g. https://user@host/repo
+        let rest = rest.split_once('@').map_or(rest, |(_, host)| host);
+        return Some(format!("https://{}", rest));
+    }
+
+    let rest = remote_url.strip_prefix("ssh://").unwrap_or(remote_url);
+    let rest = rest.split_once('@').map_or(rest, |(_, host)| host);
+    let (host, path) = rest.split_once(':').or_else(|| rest.split_once('/'))?;
+    // ssh://git@host:22/owner/repo has a port before the path
+    let path = path
+        .split_once('/')
+        .filter(|(port, _)| !port.is_empty() && port.chars().all(|c| c.is_ascii_digit()))
+        .map_or(path, |(_, path)| path);
+    if host.is_empty() || path.is_empty() || host.contains('/') || path.starts_with('/') {
+        return None;
+    }
+    Some(format!("https://{}/{}", host, path))
+}
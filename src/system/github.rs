@@ -0,0 +1,133 @@
+//! GitHub search through the REST API, using `curl`.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context, Result};
+
+const API_URL: &str = "https://api.github.com";
+
+const RESULTS_PER_SEARCH: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchKind {
+    Repositories,
+    /// Issues and pull requests
+    Issues,
+}
+
+impl SearchKind {
+    /// Name used in the API path and the result cache
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Repositories => "repositories",
+            Self::Issues => "issues",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GithubResult {
+    pub kind: SearchKind,
+    /// `owner/name` of repositories, `#number title` of issues
+    pub title: String,
+    pub detail: String,
+    pub url: String,
+}
+
+/// Search repositories or issues. Unauthenticated searches work, too, but are
+/// rate limited much more.
+pub fn search(kind: SearchKind, terms: &str, token: Option<&str>) -> Result<Vec<GithubResult>> {
+    let url = format!(
+        "{}/search/{}?q={}&per_page={}",
+        API_URL,
+        kind.name(),
+        urlencoding::encode(terms),
+        RESULTS_PER_SEARCH
+    );
+    let json = get(&url, token)?;
+
+    let items = json["items"].as_array().ok_or_else(|| {
+        anyhow!(
+            "{}",
+            json["message"].as_str().unwrap_or("unexpected response")
+        )
+    })?;
+
+    Ok(items
+        .iter()
+        .filter_map(|item| match kind {
+            SearchKind::Repositories => parse_repository(item),
+            SearchKind::Issues => parse_issue(item),
+        })
+        .collect())
+}
+
+fn parse_repository(item: &serde_json::Value) -> Option<GithubResult> {
+    let stars = item["stargazers_count"].as_u64().unwrap_or_default();
+    let detail = match item["description"].as_str() {
+        Some(description) => format!("★ {} · {}", stars, description),
+        None => format!("★ {}", stars),
+    };
+
+    Some(GithubResult {
+        kind: SearchKind::Repositories,
+        title: item["full_name"].as_str()?.to_string(),
+        detail,
+        url: item["html_url"].as_str()?.to_string(),
+    })
+}
+
+fn parse_issue(item: &serde_json::Value) -> Option<GithubResult> {
+    // e.g. https://api.github.com/repos/owner/name
+    let repository = item["repository_url"]
+        .as_str()?
+        .trim_start_matches(API_URL)
+        .trim_start_matches("/repos/");
+    let kind = if item["pull_request"].is_object() {
+        "pull request"
+    } else {
+        "issue"
+    };
+
+    Some(GithubResult {
+        kind: SearchKind::Issues,
+        title: format!("#{} {}", item["number"].as_u64()?, item["title"].as_str()?),
+        detail: format!(
+            "{} · {} {}",
+            repository,
+            item["state"].as_str().unwrap_or_default(),
+            kind
+        ),
+        url: item["html_url"].as_str()?.to_string(),
+    })
+}
+
+fn get(url: &str, token: Option<&str>) -> Result<serde_json::Value> {
+    // Headers are read from stdin, so the token doesn't show up in the
+    // process list
+    let mut child = Command::new("curl")
+        .args(["-s", "--max-time", "10", "-H", "@-"])
+        .args(["-H", "Accept: application/vnd.github+json"])
+        .args(["-A", "crowbar"])
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .context("Failed to start curl")?;
+
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("Failed to open curl stdin"))?;
+    if let Some(token) = token {
+        writeln!(stdin, "Authorization: Bearer {}", token)?;
+    }
+    drop(stdin);
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(anyhow!("GitHub is unreachable"));
+    }
+    Ok(serde_json::from_slice(&output.stdout)?)
+}
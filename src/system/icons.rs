@@ -0,0 +1,62 @@
+//! Icon files of desktop entries, looked up by their `Icon` name in the
+//! hicolor theme, which every icon theme falls back to, and in `pixmaps`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::common::expand_tilde;
+
+const ICON_DIRS: &[&str] = &[
+    "~/.local/share/icons/hicolor",
+    "/usr/local/share/icons/hicolor",
+    "/usr/share/icons/hicolor",
+    "/var/lib/flatpak/exports/share/icons/hicolor",
+];
+
+/// Sizes closest to the tiles of the grid view first
+const SIZES: &[&str] = &["scalable", "64x64", "48x48", "128x128", "256x256", "32x32"];
+
+const EXTENSIONS: &[&str] = &["svg", "png"];
+
+const PIXMAPS_DIR: &str = "/usr/share/pixmaps";
+
+lazy_static::lazy_static! {
+    static ref ICONS: Mutex<HashMap<String, Option<PathBuf>>> = Mutex::new(HashMap::new());
+}
+
+/// File of the icon, `Icon` may also be an absolute path
+pub fn lookup(name: &str) -> Option<PathBuf> {
+    if name.is_empty() {
+        return None;
+    }
+    if let Some(path) = ICONS.lock().unwrap().get(name) {
+        return path.clone();
+    }
+
+    let path = find(name);
+    ICONS.lock().unwrap().insert(name.to_string(), path.clone());
+    path
+}
+
+fn find(name: &str) -> Option<PathBuf> {
+    let path = Path::new(name);
+    if path.is_absolute() {
+        return path.exists().then(|| path.to_path_buf());
+    }
+
+    let themed = ICON_DIRS.iter().flat_map(|dir| {
+        let dir = expand_tilde(dir);
+        SIZES.iter().flat_map(move |size| {
+            let apps = dir.join(size).join("apps");
+            EXTENSIONS
+                .iter()
+                .map(move |extension| apps.join(format!("{}.{}", name, extension)))
+        })
+    });
+    let pixmaps = EXTENSIONS
+        .iter()
+        .map(|extension| Path::new(PIXMAPS_DIR).join(format!("{}.{}", name, extension)));
+
+    themed.chain(pixmaps).find(|path| path.exists())
+}
@@ -0,0 +1,136 @@
+//! Audio file tags read with `ffprobe`, and queueing files in MPRIS media
+//! players through `dbus-send`.
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{anyhow, Result};
+
+const AUDIO_EXTENSIONS: &[&str] = &[
+    "mp3", "flac", "ogg", "oga", "opus", "m4a", "aac", "wav", "wma", "aiff", "ape", "wv",
+];
+
+const MPRIS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+
+/// Tags of an audio file. ID3, Vorbis comments and MP4 tags are all read.
+#[derive(Debug, Clone, Default)]
+pub struct AudioTags {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    /// Length in seconds
+    pub duration: Option<u64>,
+}
+
+impl AudioTags {
+    /// e.g. "Artist · 3:25"
+    pub fn summary(&self) -> String {
+        let duration = self
+            .duration
+            .map(|seconds| format!("{}:{:02}", seconds / 60, seconds % 60));
+        [self.artist.clone(), duration]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+pub fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| AUDIO_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+}
+
+pub fn read_tags(path: &Path) -> Result<AudioTags> {
+    let output = Command::new("ffprobe")
+        .args(["-v", "quiet", "-print_format", "json", "-show_format"])
+        .arg(path)
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow!("ffprobe failed to read {:?}", path));
+    }
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let format = &json["format"];
+    // Tag names differ in case between formats, e.g. TITLE in Vorbis comments
+    let tag = |name: &str| {
+        format["tags"].as_object().and_then(|tags| {
+            tags.iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .and_then(|(_, value)| value.as_str())
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        })
+    };
+
+    Ok(AudioTags {
+        title: tag("title"),
+        artist: tag("artist"),
+        duration: format["duration"]
+            .as_str()
+            .and_then(|duration| duration.parse::<f64>().ok())
+            .map(|duration| duration.round() as u64),
+    })
+}
+
+/// Bus name of a running player that supports adding tracks to its queue
+pub fn queue_player() -> Option<String> {
+    let output = Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus.ListNames",
+        ])
+        .output()
+        .ok()?;
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("string \""))
+        .map(|name| name.trim_end_matches('"'))
+        .filter(|name| name.starts_with(MPRIS_PREFIX))
+        .find(|name| has_track_list(name))
+        .map(str::to_string)
+}
+
+fn has_track_list(player: &str) -> bool {
+    Command::new("dbus-send")
+        .args(["--session", "--print-reply"])
+        .arg(format!("--dest={}", player))
+        .args([
+            "/org/mpris/MediaPlayer2",
+            "org.freedesktop.DBus.Properties.Get",
+            "string:org.mpris.MediaPlayer2",
+            "string:HasTrackList",
+        ])
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains("boolean true"))
+}
+
+/// Append a file to the end of the player's track list
+pub fn add_to_queue_command(player: &str, uri: &str) -> Command {
+    let mut command = Command::new("dbus-send");
+    command
+        .args(["--session", "--type=method_call"])
+        .arg(format!("--dest={}", player))
+        .args([
+            "/org/mpris/MediaPlayer2",
+            "org.mpris.MediaPlayer2.TrackList.AddTrack",
+        ])
+        .arg(format!("string:{}", uri))
+        .args([
+            "objpath:/org/mpris/MediaPlayer2/TrackList/NoTrack",
+            "boolean:false",
+        ]);
+    command
+}
+
+/// Short name of a player, e.g. "vlc" for `org.mpris.MediaPlayer2.vlc`
+pub fn player_name(player: &str) -> &str {
+    player
+        .strip_prefix(MPRIS_PREFIX)
+        .and_then(|name| name.split('.').next())
+        .unwrap_or(player)
+}
@@ -0,0 +1,37 @@
+//! File types and default applications from the shared MIME database.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Type of the file, e.g. `image/png`, as `xdg-mime` or `file` detect it
+pub fn file_type(path: &Path) -> Option<String> {
+    let output = Command::new("xdg-mime")
+        .args(["query", "filetype"])
+        .arg(path)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .or_else(|| {
+            Command::new("file")
+                .args(["--brief", "--mime-type"])
+                .arg(path)
+                .output()
+                .ok()
+        })?;
+
+    // `xdg-mime` may append parameters, e.g. `text/plain; charset=us-ascii`
+    let mime_type = String::from_utf8_lossy(&output.stdout);
+    let mime_type = mime_type.split(';').next().unwrap_or_default().trim();
+    (output.status.success() && mime_type.contains('/')).then(|| mime_type.to_string())
+}
+
+/// File name of the desktop entry opening the type by default, e.g.
+/// `org.gnome.eog.desktop`
+pub fn default_app(mime_type: &str) -> Option<String> {
+    let output = Command::new("xdg-mime")
+        .args(["query", "default", mime_type])
+        .output()
+        .ok()?;
+    let filename = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !filename.is_empty()).then_some(filename)
+}
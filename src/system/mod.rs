@@ -1,8 +1,35 @@
 pub mod executable_finder;
 pub mod app_finder;
+pub mod appimage;
+pub mod audio;
+pub mod bitwarden;
+pub mod browser;
+pub mod calendar;
 pub mod desktop_entry_categories;
+pub mod clipboard;
+pub mod devices;
+pub mod displays;
+pub mod docker;
+pub mod docsets;
+pub mod file_finder;
+pub mod flathub;
+pub mod git_repos;
+pub mod icons;
+pub mod github;
+pub mod media;
+pub mod mime;
+pub mod nix;
+pub mod notifications;
+pub mod resources;
+pub mod terminal;
+pub mod wifi;
+pub mod workspaces;
+pub mod zoxide;
 
 // Re-export commonly used items for convenience
-pub use app_finder::{DesktopEntry, scan_desktopentries};
+pub use app_finder::{CachedDesktopEntry, DesktopEntry, scan_desktopentries};
+pub use calendar::Calendar;
 pub use executable_finder::{FileInfo, FileType, scan_path_executables};
-pub use desktop_entry_categories::Category; 
\ No newline at end of file
+pub use desktop_entry_categories::Category;
+pub use clipboard::{copy_to_clipboard, read_clipboard};
+pub use devices::{device_batteries, DeviceBattery}; 
\ No newline at end of file
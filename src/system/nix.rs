@@ -0,0 +1,88 @@
+//! nixpkgs search through the `nix` CLI.
+
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context, Result};
+
+/// The flake searched and run from
+pub const NIXPKGS: &str = "nixpkgs";
+
+const RESULTS_PER_SEARCH: usize = 5;
+
+#[derive(Debug, Clone)]
+pub struct NixPackage {
+    /// Attribute path within nixpkgs, e.g. `python3Packages.requests`
+    pub attr: String,
+    pub version: String,
+    pub description: String,
+}
+
+impl NixPackage {
+    /// e.g. `nixpkgs#hello`
+    pub fn flake_ref(&self) -> String {
+        format!("{}#{}", NIXPKGS, self.attr)
+    }
+}
+
+/// Packages in nixpkgs matching all terms, exact attribute names and shorter
+/// ones first
+pub fn search(terms: &str) -> Result<Vec<NixPackage>> {
+    let output = Command::new("nix")
+        .args(["--extra-experimental-features", "nix-command flakes"])
+        .args(["search", NIXPKGS, "--json"])
+        // Every term is a regex for nix, match them literally
+        .args(terms.split_whitespace().map(escape_regex))
+        .stdin(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .context("Failed to start nix")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let error = stderr.lines().last().unwrap_or("nix search failed");
+        return Err(anyhow!(error.trim().to_string()));
+    }
+
+    // Keys are `legacyPackages.<system>.<attr>`
+    let json: serde_json::Map<String, serde_json::Value> = serde_json::from_slice(&output.stdout)?;
+    let mut packages: Vec<NixPackage> = json
+        .into_iter()
+        .filter_map(|(key, package)| {
+            let attr = key.splitn(3, '.').nth(2)?.to_string();
+            Some(NixPackage {
+                attr,
+                version: package["version"].as_str().unwrap_or_default().to_string(),
+                description: package["description"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+            })
+        })
+        .collect();
+
+    let query = terms.trim().to_lowercase();
+    packages.sort_by_key(|package| {
+        (
+            package.attr.to_lowercase() != query,
+            package.attr.contains('.'),
+            package.attr.len(),
+        )
+    });
+    packages.truncate(RESULTS_PER_SEARCH);
+    Ok(packages)
+}
+
+/// `nix run` for the package, its output shown in the terminal
+pub fn run_args(package: &NixPackage) -> Vec<String> {
+    vec!["nix".to_string(), "run".to_string(), package.flake_ref()]
+}
+
+fn escape_regex(term: &str) -> String {
+    let mut escaped = String::new();
+    for c in term.chars() {
+        if "\\.+*?()|[]{}^$".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -0,0 +1,44 @@
+//! Desktop notifications through `notify-send`, which talks to the
+//! freedesktop notification daemon.
+
+use std::process::Command;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+const APP_NAME: &str = "--app-name=Crowbar";
+
+/// Runs the command in `$1` and notifies when it took at least `$2` seconds
+const NOTIFY_WHEN_DONE: &str = r#"start=$(date +%s)
+sh -c "$1"
+status=$?
+if [ $(($(date +%s) - start)) -ge "$2" ]; then
+    if [ $status -eq 0 ]; then
+        notify-send --app-name=Crowbar "Finished" "$1"
+    else
+        notify-send --app-name=Crowbar --urgency=critical "Failed with exit code $status" "$1"
+    fi
+fi"#;
+
+pub fn send(summary: &str, body: &str) -> Result<()> {
+    Command::new("notify-send")
+        .arg(APP_NAME)
+        .arg(summary)
+        .arg(body)
+        .spawn()
+        .context("Failed to run notify-send")?;
+    Ok(())
+}
+
+/// `sh -c` running the shell command, followed by a notification if it ran
+/// for at least `after`. Crowbar has quit long before, so the shell waits.
+pub fn notify_when_done(command: &str, after: Duration) -> Command {
+    let mut shell = Command::new("sh");
+    shell
+        .arg("-c")
+        .arg(NOTIFY_WHEN_DONE)
+        .arg("sh")
+        .arg(command)
+        .arg(after.as_secs().to_string());
+    shell
+}
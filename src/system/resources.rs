@@ -0,0 +1,184 @@
+//! CPU, memory, disk, network and battery usage from `/proc` and `/sys`.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Instant;
+
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+/// Idle and total CPU time of the previous sample
+static CPU_SAMPLE: Mutex<Option<(u64, u64)>> = Mutex::new(None);
+
+/// Time and bytes received and sent of the previous sample
+static NETWORK_SAMPLE: Mutex<Option<(Instant, u64, u64)>> = Mutex::new(None);
+
+/// Used and total amount of something, e.g. bytes of memory
+#[derive(Debug, Clone, Copy)]
+pub struct Usage {
+    pub used: u64,
+    pub total: u64,
+}
+
+impl Usage {
+    pub fn percent(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.used as f64 / self.total as f64 * 100.0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Battery {
+    pub name: String,
+    pub percentage: u8,
+    /// e.g. `Charging`, `Discharging` or `Full`
+    pub status: String,
+}
+
+/// CPU usage in percent since the previous call, or since boot on the
+/// first one
+pub fn cpu_usage() -> Option<f64> {
+    let stat = fs::read_to_string("/proc/stat").ok()?;
+    let times: Vec<u64> = stat
+        .lines()
+        .next()?
+        .split_whitespace()
+        .skip(1)
+        .filter_map(|time| time.parse().ok())
+        .collect();
+    // idle and iowait
+    let idle = times.get(3)? + times.get(4).unwrap_or(&0);
+    let total: u64 = times.iter().sum();
+
+    let previous = CPU_SAMPLE.lock().unwrap().replace((idle, total));
+    let (idle, total) = match previous {
+        Some((previous_idle, previous_total)) if total > previous_total => {
+            (idle - previous_idle.min(idle), total - previous_total)
+        }
+        _ => (idle, total),
+    };
+    if total == 0 {
+        return None;
+    }
+    Some((1.0 - idle as f64 / total as f64) * 100.0)
+}
+
+/// Number of CPUs and the load averages of 1, 5 and 15 minutes
+pub fn cpu_load() -> Option<(usize, [f64; 3])> {
+    let loadavg = fs::read_to_string("/proc/loadavg").ok()?;
+    let mut averages = loadavg
+        .split_whitespace()
+        .filter_map(|average| average.parse().ok());
+    let load = [averages.next()?, averages.next()?, averages.next()?];
+    let cpus = std::thread::available_parallelism().map_or(1, |cpus| cpus.get());
+    Some((cpus, load))
+}
+
+/// Memory in use, not counting caches the kernel can drop, in bytes
+pub fn memory() -> Option<Usage> {
+    let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+    let field = |name: &str| -> Option<u64> {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(name))?
+            .trim_start_matches(':')
+            .split_whitespace()
+            .next()?
+            .parse::<u64>()
+            .ok()
+            .map(|kib| kib * 1024)
+    };
+    let total = field("MemTotal")?;
+    let available = field("MemAvailable")?;
+    Some(Usage {
+        used: total.saturating_sub(available),
+        total,
+    })
+}
+
+/// Bytes received and sent per second by all interfaces but loopback since
+/// the previous call, `None` on the first one
+pub fn network_rates() -> Option<(f64, f64)> {
+    let dev = fs::read_to_string("/proc/net/dev").ok()?;
+    let (received, sent) = dev
+        .lines()
+        // Two header lines, then `lo: 1234 ...`
+        .skip(2)
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(interface, _)| interface.trim() != "lo")
+        .filter_map(|(_, counters)| {
+            let counters: Vec<u64> = counters
+                .split_whitespace()
+                .filter_map(|counter| counter.parse().ok())
+                .collect();
+            Some((*counters.first()?, *counters.get(8)?))
+        })
+        .fold((0, 0), |(received, sent), (rx, tx)| {
+            (received + rx, sent + tx)
+        });
+
+    let now = Instant::now();
+    let previous = NETWORK_SAMPLE
+        .lock()
+        .unwrap()
+        .replace((now, received, sent));
+    let (then, previous_received, previous_sent) = previous?;
+    let seconds = now.duration_since(then).as_secs_f64();
+    if seconds == 0.0 {
+        return None;
+    }
+    // Counters start over when an interface goes away
+    Some((
+        received.saturating_sub(previous_received) as f64 / seconds,
+        sent.saturating_sub(previous_sent) as f64 / seconds,
+    ))
+}
+
+/// Space used on the file system holding `path`, in bytes
+pub fn disk(path: &Path) -> Option<Usage> {
+    let output = Command::new("df").arg("-Pk").arg(path).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Filesystem 1024-blocks Used Available Capacity Mounted-on
+    let fields: Vec<&str> = stdout.lines().nth(1)?.split_whitespace().collect();
+    let used: u64 = fields.get(2)?.parse().ok()?;
+    let available: u64 = fields.get(3)?.parse().ok()?;
+    Some(Usage {
+        used: used * 1024,
+        total: (used + available) * 1024,
+    })
+}
+
+/// Batteries of the machine itself, not of connected devices
+pub fn batteries() -> Vec<Battery> {
+    let Ok(entries) = fs::read_dir(POWER_SUPPLY_DIR) else {
+        return Vec::new();
+    };
+    let read = |dir: &Path, file: &str| {
+        fs::read_to_string(dir.join(file))
+            .ok()
+            .map(|value| value.trim().to_string())
+    };
+
+    let mut batteries: Vec<Battery> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|dir| read(dir, "type").as_deref() == Some("Battery"))
+        // Peripherals report their batteries here too
+        .filter(|dir| read(dir, "scope").as_deref() != Some("Device"))
+        .filter_map(|dir| {
+            Some(Battery {
+                name: dir.file_name()?.to_string_lossy().to_string(),
+                percentage: read(&dir, "capacity")?.parse().ok()?,
+                status: read(&dir, "status").unwrap_or_default(),
+            })
+        })
+        .collect();
+    batteries.sort_by(|a, b| a.name.cmp(&b.name));
+    batteries
+}
@@ -0,0 +1,30 @@
+//! Running programs in a terminal emulator.
+
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+/// Used when neither the `terminal` option nor `$TERMINAL` is set
+const FALLBACK_TERMINAL: &str = "x-terminal-emulator";
+
+/// Run `args` in the configured terminal, `$TERMINAL` or the system's default
+/// terminal, passing them after the `-e` flag most terminals understand
+pub fn command(terminal: Option<&str>, args: &[String]) -> Command {
+    let mut command = Command::new(program(terminal));
+    command.arg("-e").args(args);
+    command
+}
+
+/// Open the terminal with a shell in `dir`
+pub fn shell_in(terminal: Option<&str>, dir: &Path) -> Command {
+    let mut command = Command::new(program(terminal));
+    command.current_dir(dir);
+    command
+}
+
+fn program(terminal: Option<&str>) -> String {
+    terminal
+        .map(str::to_string)
+        .or_else(|| env::var("TERMINAL").ok())
+        .unwrap_or_else(|| FALLBACK_TERMINAL.to_string())
+}
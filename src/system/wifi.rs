@@ -0,0 +1,127 @@
+//! Wi-Fi networks through NetworkManager's `nmcli`.
+
+use std::process::Command;
+
+use anyhow::{anyhow, Result};
+
+/// A network seen by the last scan
+#[derive(Debug, Clone)]
+pub struct WifiNetwork {
+    pub ssid: String,
+    /// Signal strength in percent
+    pub signal: u8,
+    /// e.g. `WPA2`, empty for open networks
+    pub security: String,
+    pub in_use: bool,
+}
+
+/// Visible networks, strongest first. Uses NetworkManager's cached scan
+/// results, so it returns quickly but may miss networks that just appeared.
+pub fn networks() -> Result<Vec<WifiNetwork>> {
+    let output = nmcli(&[
+        "--terse",
+        "--fields",
+        "IN-USE,SSID,SIGNAL,SECURITY",
+        "device",
+        "wifi",
+        "list",
+        "--rescan",
+        "no",
+    ])?;
+
+    let mut networks: Vec<WifiNetwork> = Vec::new();
+    for line in output.lines() {
+        let fields = split_terse(line);
+        let [in_use, ssid, signal, security] = fields.as_slice() else {
+            continue;
+        };
+        // Hidden networks have no SSID to connect to
+        if ssid.is_empty() {
+            continue;
+        }
+
+        let network = WifiNetwork {
+            ssid: ssid.clone(),
+            signal: signal.parse().unwrap_or(0),
+            security: security.clone(),
+            in_use: in_use == "*",
+        };
+
+        // Access points of the same network show up once each
+        match networks.iter_mut().find(|known| known.ssid == network.ssid) {
+            Some(known) => {
+                known.signal = known.signal.max(network.signal);
+                known.in_use |= network.in_use;
+            }
+            None => networks.push(network),
+        }
+    }
+
+    networks.sort_by(|a, b| b.in_use.cmp(&a.in_use).then(b.signal.cmp(&a.signal)));
+    Ok(networks)
+}
+
+pub fn is_enabled() -> Result<bool> {
+    Ok(nmcli(&["radio", "wifi"])?.trim() == "enabled")
+}
+
+/// Connect to a known network, or ask NetworkManager's secret agent for the
+/// password of a new one
+pub fn connect_command(ssid: &str) -> Command {
+    let mut command = Command::new("nmcli");
+    command.args(["device", "wifi", "connect", ssid]);
+    command
+}
+
+pub fn disconnect_command() -> Result<Command> {
+    let device = wifi_device()?;
+    let mut command = Command::new("nmcli");
+    command.args(["device", "disconnect", &device]);
+    Ok(command)
+}
+
+pub fn set_enabled_command(enabled: bool) -> Command {
+    let mut command = Command::new("nmcli");
+    command.args(["radio", "wifi", if enabled { "on" } else { "off" }]);
+    command
+}
+
+/// Name of the first Wi-Fi interface, e.g. `wlan0`
+fn wifi_device() -> Result<String> {
+    nmcli(&["--terse", "--fields", "DEVICE,TYPE", "device"])?
+        .lines()
+        .map(split_terse)
+        .find(|fields| fields.get(1).is_some_and(|kind| kind == "wifi"))
+        .and_then(|fields| fields.into_iter().next())
+        .ok_or_else(|| anyhow!("No Wi-Fi device found"))
+}
+
+fn nmcli(args: &[&str]) -> Result<String> {
+    let output = Command::new("nmcli").args(args).output()?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Split a line of `nmcli --terse` output, where colons inside values are
+/// escaped with a backslash
+fn split_terse(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    fields.last_mut().unwrap().push(escaped);
+                }
+            }
+            ':' => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
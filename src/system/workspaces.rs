@@ -0,0 +1,244 @@
+//! Workspaces of i3, sway and Hyprland, through their IPC sockets.
+
+use std::env;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+/// i3 and sway frame every message with this
+const I3_MAGIC: &[u8] = b"i3-ipc";
+const I3_RUN_COMMAND: u32 = 0;
+const I3_GET_WORKSPACES: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowManager {
+    /// i3 and sway share the protocol
+    I3(&'static str),
+    Hyprland,
+}
+
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub name: String,
+    pub output: String,
+    pub focused: bool,
+}
+
+impl WindowManager {
+    /// The running window manager, from the variables it sets
+    pub fn detect() -> Option<Self> {
+        if env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+            Some(Self::Hyprland)
+        } else if env::var_os("SWAYSOCK").is_some() {
+            Some(Self::I3("SWAYSOCK"))
+        } else if env::var_os("I3SOCK").is_some() {
+            Some(Self::I3("I3SOCK"))
+        } else {
+            None
+        }
+    }
+
+    pub fn workspaces(&self) -> Result<Vec<Workspace>> {
+        match self {
+            Self::I3(variable) => {
+                #[derive(Deserialize)]
+                struct I3Workspace {
+                    name: String,
+                    output: String,
+                    focused: bool,
+                }
+                let reply = i3_message(variable, I3_GET_WORKSPACES, "")?;
+                let workspaces: Vec<I3Workspace> = serde_json::from_slice(&reply)?;
+                Ok(workspaces
+                    .into_iter()
+                    .map(|workspace| Workspace {
+                        name: workspace.name,
+                        output: workspace.output,
+                        focused: workspace.focused,
+                    })
+                    .collect())
+            }
+            Self::Hyprland => {
+                #[derive(Deserialize)]
+                struct HyprWorkspace {
+                    id: i64,
+                    name: String,
+                    monitor: String,
+                }
+                #[derive(Deserialize)]
+                struct HyprActiveWorkspace {
+                    id: i64,
+                }
+                let workspaces: Vec<HyprWorkspace> =
+                    serde_json::from_slice(&hyprland_message("j/workspaces")?)?;
+                let active: HyprActiveWorkspace =
+                    serde_json::from_slice(&hyprland_message("j/activeworkspace")?)?;
+
+                let mut workspaces: Vec<Workspace> = workspaces
+                    .into_iter()
+                    // Negative ids are special workspaces, e.g. the scratchpad
+                    .filter(|workspace| workspace.id > 0)
+                    .map(|workspace| Workspace {
+                        focused: workspace.id == active.id,
+                        name: workspace.name,
+                        output: workspace.monitor,
+                    })
+                    .collect();
+                workspaces.sort_by_key(|workspace| workspace.name.parse::<i64>().ok());
+                Ok(workspaces)
+            }
+        }
+    }
+
+    /// Top left corner of the monitor with the focused workspace, in the
+    /// layout coordinates of the window manager
+    pub fn focused_output_origin(&self) -> Result<Option<(f32, f32)>> {
+        match self {
+            Self::I3(variable) => {
+                #[derive(Deserialize)]
+                struct Rect {
+                    x: f32,
+                    y: f32,
+                }
+                #[derive(Deserialize)]
+                struct I3Workspace {
+                    focused: bool,
+                    rect: Rect,
+                }
+                let reply = i3_message(variable, I3_GET_WORKSPACES, "")?;
+                let workspaces: Vec<I3Workspace> = serde_json::from_slice(&reply)?;
+                // The workspace area leaves out bars, but it lies on the monitor all the same
+                Ok(workspaces
+                    .into_iter()
+                    .find(|workspace| workspace.focused)
+                    .map(|workspace| (workspace.rect.x, workspace.rect.y)))
+            }
+            Self::Hyprland => {
+                #[derive(Deserialize)]
+                struct HyprMonitor {
+                    x: f32,
+                    y: f32,
+                    focused: bool,
+                }
+                let monitors: Vec<HyprMonitor> =
+                    serde_json::from_slice(&hyprland_message("j/monitors")?)?;
+                Ok(monitors
+                    .into_iter()
+                    .find(|monitor| monitor.focused)
+                    .map(|monitor| (monitor.x, monitor.y)))
+            }
+        }
+    }
+
+    /// Switch to the workspace, creating it if needed
+    pub fn switch_to(&self, workspace: &str) -> Result<()> {
+        match self {
+            Self::I3(variable) => i3_command(variable, &format!("workspace {}", quote(workspace))),
+            Self::Hyprland => hyprland_dispatch("workspace", workspace),
+        }
+    }
+
+    /// Move the focused window to the workspace, staying on the current one
+    pub fn move_window_to(&self, workspace: &str) -> Result<()> {
+        match self {
+            Self::I3(variable) => i3_command(
+                variable,
+                &format!("move container to workspace {}", quote(workspace)),
+            ),
+            Self::Hyprland => hyprland_dispatch("movetoworkspacesilent", workspace),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::I3("SWAYSOCK") => "sway",
+            Self::I3(_) => "i3",
+            Self::Hyprland => "Hyprland",
+        }
+    }
+}
+
+/// Named workspaces are referred to by `name:<name>` in Hyprland, numbered
+/// ones by their number
+fn hyprland_dispatch(dispatcher: &str, workspace: &str) -> Result<()> {
+    let target = if workspace.parse::<i64>().is_ok() {
+        workspace.to_string()
+    } else {
+        format!("name:{}", workspace)
+    };
+    let reply = hyprland_message(&format!("dispatch {} {}", dispatcher, target))?;
+    match String::from_utf8_lossy(&reply).trim() {
+        "ok" => Ok(()),
+        error => Err(anyhow!("Hyprland: {}", error)),
+    }
+}
+
+fn hyprland_message(message: &str) -> Result<Vec<u8>> {
+    let signature = env::var("HYPRLAND_INSTANCE_SIGNATURE")?;
+    // Hyprland moved its sockets to the runtime directory in 0.40
+    let runtime_dir = env::var("XDG_RUNTIME_DIR").unwrap_or_default();
+    let path = [
+        PathBuf::from(runtime_dir).join("hypr"),
+        PathBuf::from("/tmp/hypr"),
+    ]
+    .into_iter()
+    .map(|dir| dir.join(&signature).join(".socket.sock"))
+    .find(|path| path.exists())
+    .ok_or_else(|| anyhow!("Hyprland socket not found"))?;
+
+    let mut stream =
+        UnixStream::connect(&path).with_context(|| format!("Failed to connect to {:?}", path))?;
+    stream.write_all(message.as_bytes())?;
+    let mut reply = Vec::new();
+    stream.read_to_end(&mut reply)?;
+    Ok(reply)
+}
+
+fn i3_command(variable: &str, command: &str) -> Result<()> {
+    #[derive(Deserialize)]
+    struct CommandReply {
+        success: bool,
+        error: Option<String>,
+    }
+    let reply = i3_message(variable, I3_RUN_COMMAND, command)?;
+    let replies: Vec<CommandReply> = serde_json::from_slice(&reply)?;
+    match replies.into_iter().find(|reply| !reply.success) {
+        Some(failed) => Err(anyhow!(failed.error.unwrap_or_default())),
+        None => Ok(()),
+    }
+}
+
+/// Send one message and read its reply: the magic string, the payload length
+/// and the message type, both native-endian, then the payload
+fn i3_message(variable: &str, message_type: u32, payload: &str) -> Result<Vec<u8>> {
+    let path = env::var(variable)?;
+    let mut stream =
+        UnixStream::connect(&path).with_context(|| format!("Failed to connect to {}", path))?;
+
+    let mut message = I3_MAGIC.to_vec();
+    message.extend((payload.len() as u32).to_ne_bytes());
+    message.extend(message_type.to_ne_bytes());
+    message.extend(payload.as_bytes());
+    stream.write_all(&message)?;
+
+    let mut header = [0; 14];
+    stream.read_exact(&mut header)?;
+    if &header[..6] != I3_MAGIC {
+        return Err(anyhow!("Unexpected reply from {}", path));
+    }
+    let length = u32::from_ne_bytes([header[6], header[7], header[8], header[9]]);
+    let mut reply = vec![0; length as usize];
+    stream.read_exact(&mut reply)?;
+    Ok(reply)
+}
+
+/// Workspace names may contain spaces
+fn quote(workspace: &str) -> String {
+    format!(
+        "\"{}\"",
+        workspace.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
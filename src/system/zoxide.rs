@@ -0,0 +1,29 @@
+//! Frecent directories remembered by zoxide.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+
+/// Directories matching all keywords, highest zoxide score first
+pub fn query(keywords: &[&str]) -> Result<Vec<PathBuf>> {
+    let output = Command::new("zoxide")
+        .args(["query", "--list", "--"])
+        .args(keywords)
+        .output()
+        .context("Failed to run zoxide")?;
+
+    // zoxide exits with 1 when nothing matches
+    if !output.status.success() && !output.stderr.is_empty() {
+        return Err(anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
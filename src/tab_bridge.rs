@@ -0,0 +1,252 @@
+//! Bridge to a companion browser extension that shares the open tabs.
+//!
+//! When `browser_bridge` is enabled, Crowbar listens for HTTP requests on
+//! `127.0.0.1:<browser_bridge_port>`. Every request must carry the
+//! configured token as `Authorization: Bearer <token>`. The extension:
+//!
+//! - `POST /tabs` with all open tabs whenever they change, answered with 204:
+//!
+//!   ```text
+//!   [{"id":12,"window_id":3,"title":"Crowbar","url":"https://github.com/mxschll/crowbar"}]
+//!   ```
+//!
+//! - `GET /commands` in a loop. The request is held open until Crowbar has a
+//!   command for the browser, or answered with 204 after `LONG_POLL_TIMEOUT`:
+//!
+//!   ```text
+//!   {"command":"focus","tab_id":12,"window_id":3}
+//!   ```
+//!
+//! Crowbar only runs while it's open, so the extension keeps retrying both
+//! requests and sends its tabs again once it reaches Crowbar.
+//!
+//! Unlike other background work the bridge keeps running while the window is
+//! hidden. It only waits for the extension's requests, and tabs sent in the
+//! meantime are current once the window is shown.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context, Result};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+/// `GET /commands` is answered after this long without a command
+const LONG_POLL_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Focusing a tab gives up if the extension doesn't pick up the command
+/// within this time
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Clients that don't send their request within this time are dropped
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Larger requests are rejected
+const MAX_BODY_SIZE: usize = 4 * 1024 * 1024;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static TABS: Mutex<Vec<Tab>> = Mutex::new(Vec::new());
+static COMMANDS: Mutex<Vec<Command>> = Mutex::new(Vec::new());
+static COMMANDS_CHANGED: Condvar = Condvar::new();
+
+/// An open browser tab
+#[derive(Debug, Clone, Deserialize)]
+pub struct Tab {
+    pub id: u64,
+    pub window_id: u64,
+    pub title: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "command", rename_all = "lowercase")]
+enum Command {
+    Focus { tab_id: u64, window_id: u64 },
+}
+
+/// Start listening if the bridge is enabled
+pub fn init(config: &Config) {
+    if !config.browser_bridge {
+        return;
+    }
+
+    let Some(token) = config
+        .browser_bridge_token
+        .clone()
+        .filter(|t| !t.is_empty())
+    else {
+        warn!("browser_bridge is enabled but browser_bridge_token is not set, not starting");
+        return;
+    };
+
+    match TcpListener::bind((Ipv4Addr::LOCALHOST, config.browser_bridge_port)) {
+        Ok(listener) => {
+            info!(
+                "Waiting for the browser extension on port {}",
+                config.browser_bridge_port
+            );
+            ENABLED.store(true, Ordering::Relaxed);
+            thread::spawn(move || accept_clients(listener, token));
+        }
+        Err(e) => warn!("Failed to start browser bridge: {}", e),
+    }
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Tabs the extension sent last
+pub fn tabs() -> Vec<Tab> {
+    TABS.lock().unwrap().clone()
+}
+
+/// Ask the extension to focus a tab and its window. Waits until the
+/// extension picked up the request, as Crowbar quits after running an action.
+pub fn focus(tab: &Tab) -> Result<()> {
+    let mut commands = COMMANDS.lock().unwrap();
+    commands.push(Command::Focus {
+        tab_id: tab.id,
+        window_id: tab.window_id,
+    });
+    COMMANDS_CHANGED.notify_all();
+
+    let (mut commands, timeout) = COMMANDS_CHANGED
+        .wait_timeout_while(commands, DELIVERY_TIMEOUT, |commands| !commands.is_empty())
+        .unwrap();
+    if timeout.timed_out() {
+        commands.clear();
+        return Err(anyhow!("The browser extension didn't respond"));
+    }
+    Ok(())
+}
+
+fn accept_clients(listener: TcpListener, token: String) {
+    for stream in listener.incoming().filter_map(|stream| stream.ok()) {
+        let token = token.clone();
+        thread::spawn(move || {
+            if let Err(e) = handle_client(stream, &token) {
+                info!("Browser bridge request failed: {:#}", e);
+            }
+        });
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+fn handle_client(mut stream: TcpStream, token: &str) -> Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let request = read_request(&stream)?;
+
+    if request.authorization.as_deref() != Some(format!("Bearer {}", token).as_str()) {
+        return respond(&mut stream, "401 Unauthorized", None);
+    }
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/tabs") => {
+            let tabs: Vec<Tab> =
+                serde_json::from_slice(&request.body).context("Invalid tab list")?;
+            *TABS.lock().unwrap() = tabs;
+            respond(&mut stream, "204 No Content", None)
+        }
+        ("GET", "/commands") => match next_command() {
+            Some(command) => {
+                let body = serde_json::to_string(&command)?;
+                respond(&mut stream, "200 OK", Some(&body))
+            }
+            None => respond(&mut stream, "204 No Content", None),
+        },
+        _ => respond(&mut stream, "404 Not Found", None),
+    }
+}
+
+/// Wait for the next command, removing it from the queue
+fn next_command() -> Option<Command> {
+    let deadline = Instant::now() + LONG_POLL_TIMEOUT;
+    let mut commands = COMMANDS.lock().unwrap();
+    while commands.is_empty() {
+        let remaining = deadline.checked_duration_since(Instant::now())?;
+        commands = COMMANDS_CHANGED
+            .wait_timeout(commands, remaining)
+            .unwrap()
+            .0;
+    }
+
+    let command = commands.remove(0);
+    // Wake up `focus`, which waits for the queue to drain
+    COMMANDS_CHANGED.notify_all();
+    Some(command)
+}
+
+fn read_request(stream: &TcpStream) -> Result<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Err(anyhow!("Invalid request line"));
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+
+    let mut authorization = None;
+    let mut content_length = 0;
+    loop {
+        line.clear();
+        reader.read_line(&mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("authorization") {
+            authorization = Some(value.to_string());
+        } else if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse().context("Invalid Content-Length")?;
+        }
+    }
+
+    if content_length > MAX_BODY_SIZE {
+        return Err(anyhow!("Request body too large"));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    Ok(Request {
+        method,
+        path,
+        authorization,
+        body,
+    })
+}
+
+fn respond(stream: &mut TcpStream, status: &str, json: Option<&str>) -> Result<()> {
+    let body = json.unwrap_or_default();
+    let content_type = if json.is_some() {
+        "Content-Type: application/json\r\n"
+    } else {
+        ""
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    Ok(())
+}
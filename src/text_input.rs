@@ -1,21 +1,29 @@
 use std::ops::Range;
 
 use gpui::{
-    div, fill, hsla, point, px, rgba, size, App, Bounds, ClipboardItem, Context, CursorStyle,
-    Element, ElementId, ElementInputHandler, Entity, EntityInputHandler, EventEmitter, FocusHandle,
-    Focusable, GlobalElementId, InteractiveElement, IntoElement, LayoutId, MouseButton,
-    MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, ParentElement, Pixels, Point, Render,
-    ShapedLine, SharedString, Styled, TextRun, UTF16Selection, UnderlineStyle, Window,
+    div, fill, hsla, point, prelude::FluentBuilder, px, relative, rgba, size, App, Bounds,
+    ClipboardItem, Context, CursorStyle, Element, ElementId, ElementInputHandler, Entity,
+    EntityInputHandler, EventEmitter, FocusHandle, Focusable, GlobalElementId, InteractiveElement,
+    IntoElement, LayoutId, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad,
+    ParentElement, Pixels, Point, Render, ShapedLine, SharedString, Styled, TextRun,
+    UTF16Selection, UnderlineStyle, Window, WrappedLine,
 };
 
 use log::debug;
 use unicode_segmentation::*;
 
 use crate::{
-    config::Config, Backspace, Copy, Cut, Delete, End, Home, Left, Paste, Right, SelectAll,
-    SelectLeft, SelectRight,
+    config::Config, Backspace, ClearLine, Copy, Cut, Delete, DeleteToWhitespace, DeleteWordLeft,
+    DeleteWordRight, Down, End, Home, Left, Newline, Paste, Right, SelectAll, SelectLeft,
+    SelectRight, Up, WordLeft, WordRight,
 };
 
+/// Height of a single line of text in the input
+const LINE_HEIGHT: f32 = 30.;
+
+/// Height of the single-line input, including its padding
+pub const INPUT_HEIGHT: f32 = LINE_HEIGHT + 8. * 2.;
+
 pub struct TextInput {
     pub focus_handle: FocusHandle,
     pub content: SharedString,
@@ -26,6 +34,12 @@ pub struct TextInput {
     pub last_layout: Option<ShapedLine>,
     pub last_bounds: Option<Bounds<Pixels>>,
     pub is_selecting: bool,
+    /// Allows newlines, wrapping and moving between lines with Up/Down
+    pub multiline: bool,
+    /// Wrapped lines with their byte offset, only set in multi-line mode
+    pub last_lines: Option<Vec<(usize, WrappedLine)>>,
+    /// Content after this byte offset is rendered as asterisks, e.g. for passphrases
+    pub mask_from: Option<usize>,
 }
 
 impl TextInput {
@@ -38,13 +52,24 @@ impl TextInput {
     }
 
     fn right(&mut self, _: &Right, _window: &mut Window, cx: &mut Context<Self>) {
-        if self.selected_range.is_empty() {
+        // At the end of the query Right opens the secondary actions
+        if self.selected_range.is_empty() && self.cursor_offset() == self.content.len() {
+            cx.propagate();
+        } else if self.selected_range.is_empty() {
             self.move_to(self.next_boundary(self.selected_range.end), cx);
         } else {
             self.move_to(self.selected_range.end, cx)
         }
     }
 
+    fn word_left(&mut self, _: &WordLeft, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(self.previous_word_start(is_word_separator), cx);
+    }
+
+    fn word_right(&mut self, _: &WordRight, _window: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(self.next_word_end(is_word_separator), cx);
+    }
+
     fn select_left(&mut self, _: &SelectLeft, _window: &mut Window, cx: &mut Context<Self>) {
         self.select_to(self.previous_boundary(self.cursor_offset()), cx);
     }
@@ -66,6 +91,28 @@ impl TextInput {
         self.move_to(self.content.len(), cx);
     }
 
+    fn up(&mut self, _: &Up, _window: &mut Window, cx: &mut Context<Self>) {
+        match self.vertical_offset(self.cursor_offset(), true) {
+            Some(offset) if self.multiline => self.move_to(offset, cx),
+            _ => cx.propagate(),
+        }
+    }
+
+    fn down(&mut self, _: &Down, _window: &mut Window, cx: &mut Context<Self>) {
+        match self.vertical_offset(self.cursor_offset(), false) {
+            Some(offset) if self.multiline => self.move_to(offset, cx),
+            _ => cx.propagate(),
+        }
+    }
+
+    fn newline(&mut self, _: &Newline, window: &mut Window, cx: &mut Context<Self>) {
+        if self.multiline {
+            self.replace_text_in_range(None, "\n", window, cx);
+        } else {
+            cx.propagate();
+        }
+    }
+
     fn backspace(&mut self, _: &Backspace, window: &mut Window, cx: &mut Context<Self>) {
         if self.selected_range.is_empty() {
             self.select_to(self.previous_boundary(self.cursor_offset()), cx)
@@ -80,6 +127,52 @@ impl TextInput {
         self.replace_text_in_range(None, "", window, cx)
     }
 
+    fn delete_word_left(
+        &mut self,
+        _: &DeleteWordLeft,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.selected_range.is_empty() {
+            self.select_to(self.previous_word_start(is_word_separator), cx)
+        }
+        self.replace_text_in_range(None, "", window, cx)
+    }
+
+    fn delete_word_right(
+        &mut self,
+        _: &DeleteWordRight,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.selected_range.is_empty() {
+            self.select_to(self.next_word_end(is_word_separator), cx)
+        }
+        self.replace_text_in_range(None, "", window, cx)
+    }
+
+    /// Ctrl+W of readline, words are separated by whitespace only
+    fn delete_to_whitespace(
+        &mut self,
+        _: &DeleteToWhitespace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.selected_range.is_empty() {
+            self.select_to(self.previous_word_start(char::is_whitespace), cx)
+        }
+        self.replace_text_in_range(None, "", window, cx)
+    }
+
+    /// Ctrl+U of readline, keeps the text after the cursor
+    fn clear_line(&mut self, _: &ClearLine, window: &mut Window, cx: &mut Context<Self>) {
+        let offset = self.cursor_offset();
+        let line_start = self.content[..offset].rfind('\n').map_or(0, |i| i + 1);
+        self.selected_range = line_start..offset;
+        self.selection_reversed = false;
+        self.replace_text_in_range(None, "", window, cx)
+    }
+
     fn on_mouse_down(
         &mut self,
         event: &MouseDownEvent,
@@ -116,7 +209,12 @@ impl TextInput {
 
     fn paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
         if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
-            self.replace_text_in_range(None, &text.replace("\n", " "), window, cx);
+            let text = if self.multiline {
+                text
+            } else {
+                text.replace("\n", " ")
+            };
+            self.replace_text_in_range(None, &text, window, cx);
         }
     }
 
@@ -155,6 +253,15 @@ impl TextInput {
             return 0;
         }
 
+        if let (Some(bounds), Some(lines)) = (self.last_bounds.as_ref(), self.last_lines.as_ref()) {
+            return multiline_index_for_position(
+                lines,
+                position - bounds.origin,
+                px(LINE_HEIGHT),
+                self.content.len(),
+            );
+        }
+
         let (Some(bounds), Some(line)) = (self.last_bounds.as_ref(), self.last_layout.as_ref())
         else {
             return 0;
@@ -234,6 +341,72 @@ impl TextInput {
             .unwrap_or(self.content.len())
     }
 
+    /// Start of the word before the cursor, skipping separators in between
+    fn previous_word_start(&self, is_separator: fn(char) -> bool) -> usize {
+        self.content[..self.cursor_offset()]
+            .trim_end_matches(is_separator)
+            .trim_end_matches(|c: char| !is_separator(c))
+            .len()
+    }
+
+    /// End of the word after the cursor, skipping separators in between
+    fn next_word_end(&self, is_separator: fn(char) -> bool) -> usize {
+        let rest = self.content[self.cursor_offset()..]
+            .trim_start_matches(is_separator)
+            .trim_start_matches(|c: char| !is_separator(c));
+        self.content.len() - rest.len()
+    }
+
+    /// Offset on the previous or next line at the same column, if there is one
+    fn vertical_offset(&self, offset: usize, up: bool) -> Option<usize> {
+        let line_start = self.content[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let column = self.content[line_start..offset].chars().count();
+
+        let (target_start, target_end) = if up {
+            if line_start == 0 {
+                return None;
+            }
+            let previous_end = line_start - 1;
+            let previous_start = self.content[..previous_end]
+                .rfind('\n')
+                .map_or(0, |i| i + 1);
+            (previous_start, previous_end)
+        } else {
+            let next_start = offset + self.content[offset..].find('\n')? + 1;
+            let next_end = self.content[next_start..]
+                .find('\n')
+                .map_or(self.content.len(), |i| next_start + i);
+            (next_start, next_end)
+        };
+
+        Some(
+            self.content[target_start..target_end]
+                .char_indices()
+                .nth(column)
+                .map_or(target_end, |(i, _)| target_start + i),
+        )
+    }
+
+    pub fn set_multiline(&mut self, multiline: bool, cx: &mut Context<Self>) {
+        if self.multiline != multiline {
+            self.multiline = multiline;
+            self.last_lines = None;
+            cx.notify();
+        }
+    }
+
+    /// Replace the content, moving the cursor to the end
+    pub fn set_content(&mut self, content: &str, cx: &mut Context<Self>) {
+        self.content = content.to_string().into();
+        self.selected_range = content.len()..content.len();
+        self.selection_reversed = false;
+        self.marked_range = None;
+        cx.emit(TextInputChange {
+            content: self.content.clone(),
+        });
+        cx.notify();
+    }
+
     pub fn reset(&mut self) {
         debug!("Resetting text input state");
         self.content = "".into();
@@ -243,6 +416,21 @@ impl TextInput {
         self.last_layout = None;
         self.last_bounds = None;
         self.is_selecting = false;
+        self.multiline = false;
+        self.last_lines = None;
+        self.mask_from = None;
+    }
+
+    /// Content as shown on screen. Masked characters are replaced with one
+    /// asterisk per byte, so offsets into the content stay valid.
+    fn display_content(&self) -> SharedString {
+        match self.mask_from {
+            Some(offset) if offset <= self.content.len() => {
+                let masked = "*".repeat(self.content.len() - offset);
+                (self.content[..offset].to_owned() + &masked).into()
+            }
+            _ => self.content.clone(),
+        }
     }
 }
 
@@ -367,8 +555,18 @@ impl EntityInputHandler for TextInput {
         _window: &mut Window,
         _cx: &mut Context<Self>,
     ) -> Option<Bounds<Pixels>> {
-        let last_layout = self.last_layout.as_ref()?;
         let range = self.range_from_utf16(&range_utf16);
+        if let Some(lines) = self.last_lines.as_ref() {
+            let line_height = px(LINE_HEIGHT);
+            let start = multiline_position_for_index(lines, range.start, line_height);
+            let end = multiline_position_for_index(lines, range.end, line_height);
+            return Some(Bounds::from_corners(
+                bounds.origin + start,
+                bounds.origin + point(end.x, end.y + line_height),
+            ));
+        }
+
+        let last_layout = self.last_layout.as_ref()?;
         Some(Bounds::from_corners(
             point(
                 bounds.left() + last_layout.x_for_index(range.start),
@@ -388,8 +586,93 @@ struct TextElement {
 
 struct PrepaintState {
     line: Option<ShapedLine>,
+    lines: Option<Vec<(usize, WrappedLine)>>,
     cursor: Option<PaintQuad>,
-    selection: Option<PaintQuad>,
+    selection: Vec<PaintQuad>,
+}
+
+/// Shape text for multi-line mode, returning each logical line with its byte offset
+/// Word movement and deletion stop at anything but letters and digits
+fn is_word_separator(c: char) -> bool {
+    !c.is_alphanumeric()
+}
+
+fn shape_lines(
+    window: &mut Window,
+    text: SharedString,
+    color: gpui::Hsla,
+    wrap_width: Option<Pixels>,
+) -> Vec<(usize, WrappedLine)> {
+    let style = window.text_style();
+    let run = TextRun {
+        len: text.len(),
+        font: style.font(),
+        color,
+        background_color: None,
+        underline: None,
+        strikethrough: None,
+    };
+
+    let offsets = text
+        .split('\n')
+        .scan(0, |offset, line| {
+            let start = *offset;
+            *offset += line.len() + 1;
+            Some(start)
+        })
+        .collect::<Vec<_>>();
+
+    let font_size = style.font_size.to_pixels(window.rem_size());
+    let lines = window
+        .text_system()
+        .shape_text(text, font_size, &[run], wrap_width)
+        .map(|lines| lines.into_vec())
+        .unwrap_or_default();
+
+    offsets.into_iter().zip(lines).collect()
+}
+
+fn multiline_position_for_index(
+    lines: &[(usize, WrappedLine)],
+    index: usize,
+    line_height: Pixels,
+) -> Point<Pixels> {
+    let mut y = px(0.);
+    for (position, (start, line)) in lines.iter().enumerate() {
+        let is_last = position + 1 == lines.len();
+        if is_last || index < lines[position + 1].0 {
+            let local = line
+                .position_for_index(index - start, line_height)
+                .unwrap_or_default();
+            return point(local.x, y + local.y);
+        }
+        y += line.size(line_height).height;
+    }
+    point(px(0.), y)
+}
+
+fn multiline_index_for_position(
+    lines: &[(usize, WrappedLine)],
+    position: Point<Pixels>,
+    line_height: Pixels,
+    content_len: usize,
+) -> usize {
+    if position.y < px(0.) {
+        return 0;
+    }
+
+    let mut y = px(0.);
+    for (start, line) in lines {
+        let height = line.size(line_height).height;
+        if position.y < y + height {
+            let local = point(position.x, position.y - y);
+            return match line.index_for_position(local, line_height) {
+                Ok(index) | Err(index) => start + index,
+            };
+        }
+        y += height;
+    }
+    content_len
 }
 
 impl IntoElement for TextElement {
@@ -419,9 +702,26 @@ impl Element for TextElement {
         let content = if input.content.is_empty() {
             input.placeholder.clone()
         } else {
-            input.content.clone()
+            input.display_content()
         };
 
+        if input.multiline {
+            // Wrap at the width of the previous frame to estimate the height
+            let wrap_width = input.last_bounds.map(|bounds| bounds.size.width);
+            let color = window.text_style().color;
+            let line_height = window.line_height();
+            let height = shape_lines(window, content, color, wrap_width)
+                .iter()
+                .fold(px(0.), |height, (_, line)| {
+                    height + line.size(line_height).height
+                });
+
+            let mut style = gpui::Style::default();
+            style.size.width = relative(1.).into();
+            style.size.height = height.max(line_height).into();
+            return (window.request_layout(style, [], cx), ());
+        }
+
         let style = window.text_style();
 
         let run = TextRun {
@@ -462,9 +762,20 @@ impl Element for TextElement {
         let (display_text, text_color) = if content.is_empty() {
             (input.placeholder.clone(), hsla(1., 1., 1., 0.3))
         } else {
-            (content.clone(), style.color)
+            (input.display_content(), style.color)
         };
 
+        if input.multiline {
+            return prepaint_multiline(
+                window,
+                bounds,
+                display_text,
+                text_color,
+                selected_range,
+                cursor,
+            );
+        }
+
         let run = TextRun {
             len: display_text.len(),
             font: style.font(),
@@ -509,7 +820,7 @@ impl Element for TextElement {
         let cursor_pos = line.x_for_index(cursor);
         let (selection, cursor) = if selected_range.is_empty() {
             (
-                None,
+                Vec::new(),
                 Some(fill(
                     Bounds::new(
                         point(bounds.left() + cursor_pos, bounds.top()),
@@ -520,7 +831,7 @@ impl Element for TextElement {
             )
         } else {
             (
-                Some(fill(
+                vec![fill(
                     Bounds::from_corners(
                         point(
                             bounds.left() + line.x_for_index(selected_range.start),
@@ -532,12 +843,13 @@ impl Element for TextElement {
                         ),
                     ),
                     rgba(0x3311ff30),
-                )),
+                )],
                 None,
             )
         };
         PrepaintState {
             line: Some(line),
+            lines: None,
             cursor,
             selection,
         }
@@ -558,9 +870,32 @@ impl Element for TextElement {
             ElementInputHandler::new(bounds, self.input.clone()),
             cx,
         );
-        if let Some(selection) = prepaint.selection.take() {
+        for selection in prepaint.selection.drain(..) {
             window.paint_quad(selection)
         }
+
+        if let Some(lines) = prepaint.lines.take() {
+            let line_height = window.line_height();
+            let mut origin = bounds.origin;
+            for (_, line) in &lines {
+                line.paint(origin, line_height, window, cx).unwrap();
+                origin.y += line.size(line_height).height;
+            }
+
+            if focus_handle.is_focused(window) {
+                if let Some(cursor) = prepaint.cursor.take() {
+                    window.paint_quad(cursor);
+                }
+            }
+
+            self.input.update(cx, |input, _cx| {
+                input.last_layout = None;
+                input.last_lines = Some(lines);
+                input.last_bounds = Some(bounds);
+            });
+            return;
+        }
+
         let line = prepaint.line.take().unwrap();
         line.paint(bounds.origin, window.line_height(), window, cx)
             .unwrap();
@@ -573,11 +908,64 @@ impl Element for TextElement {
 
         self.input.update(cx, |input, _cx| {
             input.last_layout = Some(line);
+            input.last_lines = None;
             input.last_bounds = Some(bounds);
         });
     }
 }
 
+fn prepaint_multiline(
+    window: &mut Window,
+    bounds: Bounds<Pixels>,
+    text: SharedString,
+    color: gpui::Hsla,
+    selected_range: Range<usize>,
+    cursor: usize,
+) -> PrepaintState {
+    let line_height = window.line_height();
+    let lines = shape_lines(window, text, color, Some(bounds.size.width));
+
+    let (selection, cursor) = if selected_range.is_empty() {
+        let position = multiline_position_for_index(&lines, cursor, line_height);
+        let cursor = fill(
+            Bounds::new(bounds.origin + position, size(px(2.), line_height)),
+            gpui::white(),
+        );
+        (Vec::new(), Some(cursor))
+    } else {
+        let start = multiline_position_for_index(&lines, selected_range.start, line_height);
+        let end = multiline_position_for_index(&lines, selected_range.end, line_height);
+
+        // One rectangle per visual row covered by the selection
+        let mut selection = Vec::new();
+        let mut row_y = start.y;
+        while row_y <= end.y {
+            let left = if row_y == start.y { start.x } else { px(0.) };
+            let right = if row_y == end.y {
+                end.x
+            } else {
+                bounds.size.width
+            };
+            selection.push(fill(
+                Bounds::from_corners(
+                    bounds.origin + point(left, row_y),
+                    bounds.origin + point(right, row_y + line_height),
+                ),
+                rgba(0x3311ff30),
+            ));
+            row_y += line_height;
+        }
+        (selection, None)
+    };
+
+    PrepaintState {
+        line: None,
+        lines: Some(lines),
+        cursor,
+        selection,
+    }
+}
+
 impl Render for TextInput {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let config = cx.global::<Config>();
@@ -589,8 +977,14 @@ impl Render for TextInput {
             .cursor(CursorStyle::IBeam)
             .on_action(cx.listener(Self::backspace))
             .on_action(cx.listener(Self::delete))
+            .on_action(cx.listener(Self::delete_word_left))
+            .on_action(cx.listener(Self::delete_word_right))
+            .on_action(cx.listener(Self::delete_to_whitespace))
+            .on_action(cx.listener(Self::clear_line))
             .on_action(cx.listener(Self::left))
             .on_action(cx.listener(Self::right))
+            .on_action(cx.listener(Self::word_left))
+            .on_action(cx.listener(Self::word_right))
             .on_action(cx.listener(Self::select_left))
             .on_action(cx.listener(Self::select_right))
             .on_action(cx.listener(Self::select_all))
@@ -599,15 +993,21 @@ impl Render for TextInput {
             .on_action(cx.listener(Self::paste))
             .on_action(cx.listener(Self::cut))
             .on_action(cx.listener(Self::copy))
+            .on_action(cx.listener(Self::up))
+            .on_action(cx.listener(Self::down))
+            .on_action(cx.listener(Self::newline))
             .on_mouse_down(MouseButton::Left, cx.listener(Self::on_mouse_down))
             .on_mouse_up(MouseButton::Left, cx.listener(Self::on_mouse_up))
             .on_mouse_up_out(MouseButton::Left, cx.listener(Self::on_mouse_up))
             .on_mouse_move(cx.listener(Self::on_mouse_move))
-            .line_height(px(30.))
+            .w_full()
+            .line_height(px(LINE_HEIGHT))
             .child(
                 div()
-                    .h(px(30. + 8. * 2.))
-                    .px_4()
+                    .w_full()
+                    .when(self.multiline, |d| d.min_h(px(INPUT_HEIGHT)))
+                    .when(!self.multiline, |d| d.h(px(INPUT_HEIGHT)))
+                    .px(px(config.padding))
                     .py_2()
                     .text_color(config.text_primary_color)
                     .child(TextElement {
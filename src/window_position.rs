@@ -0,0 +1,165 @@
+//! Window position: the monitor and anchor of the config, moved by the
+//! configured offsets and by an offset chosen with the keyboard, for
+//! compositors where dragging an undecorated window is awkward. Each monitor
+//! remembers its own keyboard offset.
+
+use std::rc::Rc;
+
+use gpui::{point, px, App, Bounds, Pixels, PlatformDisplay, Size};
+use log::warn;
+
+use crate::config::{Config, Monitor, MonitorName, WindowAnchor};
+use crate::database::Database;
+use crate::system::workspaces::WindowManager;
+
+/// Share of the monitor's width or height the window moves per key press
+const STEP_SHARE: f32 = 0.05;
+
+/// Direction of a single move
+#[derive(Debug, Clone, Copy)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Display of the `monitor` config, the primary one if it isn't connected
+pub fn display(monitor: Monitor, cx: &App) -> Option<Rc<dyn PlatformDisplay>> {
+    let chosen = match monitor {
+        Monitor::Named(MonitorName::Primary) => None,
+        Monitor::Index(index) => {
+            let display = cx.displays().get(index).cloned();
+            if display.is_none() {
+                warn!("There is no monitor {}, using the primary one", index);
+            }
+            display
+        }
+        Monitor::Named(MonitorName::Focused) => focused_display(cx),
+    };
+    chosen.or_else(|| cx.primary_display())
+}
+
+/// The display containing the monitor of the focused workspace
+fn focused_display(cx: &App) -> Option<Rc<dyn PlatformDisplay>> {
+    let Some(window_manager) = WindowManager::detect() else {
+        warn!("The focused monitor is only known in i3, sway and Hyprland");
+        return None;
+    };
+    let (x, y) = window_manager.focused_output_origin().unwrap_or_else(|e| {
+        warn!("Failed to find the focused monitor: {:#}", e);
+        None
+    })?;
+    let corner = point(px(x), px(y));
+    cx.displays()
+        .into_iter()
+        .find(|display| display.bounds().contains(&corner))
+}
+
+/// Bounds of a window of `size` on `display`, at the remembered offset
+pub fn bounds(
+    display: Option<Rc<dyn PlatformDisplay>>,
+    size: Size<Pixels>,
+    config: &Config,
+) -> Bounds<Pixels> {
+    let Some(display) = display else {
+        return Bounds {
+            origin: point(px(0.), px(0.)),
+            size,
+        };
+    };
+
+    let offset = Database::new()
+        .and_then(|db| db.get_window_offset(&display_key(display.as_ref())))
+        .unwrap_or_else(|e| {
+            warn!("Failed to read window position: {:#}", e);
+            None
+        })
+        .unwrap_or_default();
+    place(display.bounds(), size, config, offset)
+}
+
+/// Move the window one step and remember the new offset for the monitor.
+/// Returns the new bounds.
+pub fn step(
+    display: Rc<dyn PlatformDisplay>,
+    size: Size<Pixels>,
+    direction: Direction,
+    config: &Config,
+) -> Bounds<Pixels> {
+    let key = display_key(display.as_ref());
+    let display_size = display.bounds().size;
+    let (step_x, step_y) = (
+        f32::from(display_size.width) * STEP_SHARE,
+        f32::from(display_size.height) * STEP_SHARE,
+    );
+
+    let db = Database::new();
+    let (x, y) = db
+        .as_ref()
+        .ok()
+        .and_then(|db| db.get_window_offset(&key).ok().flatten())
+        .unwrap_or_default();
+    let offset = match direction {
+        Direction::Left => (x - step_x, y),
+        Direction::Right => (x + step_x, y),
+        Direction::Up => (x, y - step_y),
+        Direction::Down => (x, y + step_y),
+    };
+
+    // Remember where the window ended up, not where it would be off-screen
+    let bounds = place(display.bounds(), size, config, offset);
+    let (anchor_x, anchor_y) = anchor(display.bounds(), size, config);
+    let offset = (
+        f32::from(bounds.left()) - anchor_x,
+        f32::from(bounds.top()) - anchor_y,
+    );
+    if let Err(e) = db.and_then(|db| db.set_window_offset(&key, offset)) {
+        warn!("Failed to remember window position: {:#}", e);
+    }
+    bounds
+}
+
+/// At the anchor, moved by the remembered offset and kept on screen
+fn place(
+    area: Bounds<Pixels>,
+    size: Size<Pixels>,
+    config: &Config,
+    (x, y): (f32, f32),
+) -> Bounds<Pixels> {
+    let (anchor_x, anchor_y) = anchor(area, size, config);
+    let max_x = f32::from(area.right() - size.width).max(f32::from(area.left()));
+    let max_y = f32::from(area.bottom() - size.height).max(f32::from(area.top()));
+
+    Bounds {
+        origin: point(
+            px((anchor_x + x).clamp(f32::from(area.left()), max_x)),
+            px((anchor_y + y).clamp(f32::from(area.top()), max_y)),
+        ),
+        size,
+    }
+}
+
+/// Top left corner of the window at the anchor, with the configured offsets
+fn anchor(area: Bounds<Pixels>, size: Size<Pixels>, config: &Config) -> (f32, f32) {
+    let free_width = f32::from(area.size.width - size.width);
+    let free_height = f32::from(area.size.height - size.height);
+    let y = match config.window_anchor {
+        WindowAnchor::Center => free_height / 2.,
+        // The window's middle a third down from the top
+        WindowAnchor::TopThird => f32::from(area.size.height) / 3. - f32::from(size.height) / 2.,
+    };
+
+    (
+        f32::from(area.left()) + free_width / 2. + config.window_offset_x,
+        f32::from(area.top()) + y.max(0.) + config.window_offset_y,
+    )
+}
+
+/// Monitors are told apart by their UUID where the platform has one
+fn display_key(display: &dyn PlatformDisplay) -> String {
+    display
+        .uuid()
+        .map(|uuid| uuid.to_string())
+        .unwrap_or_else(|_| format!("{:?}", display.id()))
+}
@@ -0,0 +1,75 @@
+//! Window height fitted to the number of results, so a handful of results
+//! doesn't sit in a mostly empty window.
+
+use gpui::{px, Pixels};
+
+use crate::config::Config;
+use crate::text_input::INPUT_HEIGHT;
+
+/// gpui's default line height relative to the font size
+const LINE_HEIGHT_RATIO: f32 = 1.618;
+
+/// Font size of the status bar (`text_sm`) at the default rem size
+const STATUS_BAR_FONT_SIZE: f32 = 14.;
+
+/// Vertical padding of the status bar (`py_1`)
+const STATUS_BAR_PADDING: f32 = 8.;
+
+/// Window border plus the border between the results and the input
+const BORDERS: f32 = 3.;
+
+/// Border between the status bar and the results
+const STATUS_BAR_BORDER: f32 = 1.;
+
+/// The window never grows beyond this share of the monitor's height
+const MAX_DISPLAY_SHARE: f32 = 0.8;
+
+/// Height of a result row with the configured font
+pub fn row_height(config: &Config) -> f32 {
+    config.font_size * LINE_HEIGHT_RATIO + config.row_padding * 2.
+}
+
+/// Height of everything but the result rows
+fn chrome_height(config: &Config) -> f32 {
+    let status_bar = if config.show_status_bar {
+        STATUS_BAR_FONT_SIZE * LINE_HEIGHT_RATIO + STATUS_BAR_PADDING + STATUS_BAR_BORDER
+    } else {
+        0.
+    };
+    status_bar + INPUT_HEIGHT + BORDERS + config.window_padding * 2.
+}
+
+/// Whole result rows visible in a window of `window_height`
+pub fn visible_rows(config: &Config, window_height: Pixels) -> usize {
+    ((f32::from(window_height) - chrome_height(config)) / row_height(config))
+        .floor()
+        .max(1.) as usize
+}
+
+/// Window height showing `rows` result rows, or `window_height` if the view
+/// isn't a list of results. Never taller than `window_height` or the
+/// monitor, and rounded to whole device pixels of the monitor the window is
+/// on, so rows don't blur on fractional scale factors.
+pub fn fitted_height(
+    config: &Config,
+    rows: Option<usize>,
+    display_height: Option<Pixels>,
+    scale_factor: f32,
+) -> Pixels {
+    let mut max_height = config.window_height;
+    if let Some(display_height) = display_height {
+        max_height = max_height.min(f32::from(display_height) * MAX_DISPLAY_SHARE);
+    }
+
+    let height = match rows {
+        Some(rows) => {
+            let chrome = chrome_height(config);
+            // Only whole rows fit, the list scrolls beyond them
+            let max_rows = ((max_height - chrome) / row_height(config)).floor().max(1.);
+            chrome + (rows as f32).clamp(1., max_rows) * row_height(config)
+        }
+        None => max_height,
+    };
+
+    px((height * scale_factor).round() / scale_factor)
+}
@@ -0,0 +1,49 @@
+use crowbar::actions::handlers::notes_handler::NotesHandlerFactory;
+use crowbar::actions::handlers::timestamp_handler::TimestampHandlerFactory;
+use crowbar::handler_test::HandlerHarness;
+
+#[gpui::test]
+fn converts_unix_seconds(cx: &mut gpui::TestAppContext) {
+    let harness = HandlerHarness::new(cx);
+    let result = harness.query(&TimestampHandlerFactory, "1700000000", cx);
+
+    result.assert_contains("2023-11-14T22:13:20Z");
+    result.assert_contains("2023-11-14 22:13:20 UTC");
+    result.assert_ranked_before("1700000000", "1700000000000");
+    // The run is logged before the value is copied, which fails without
+    // a clipboard tool
+    let _ = result.execute("1700000000");
+    assert_eq!(harness.execution_count(result.item("1700000000")), 1);
+}
+
+#[gpui::test]
+fn converts_unix_milliseconds(cx: &mut gpui::TestAppContext) {
+    let harness = HandlerHarness::new(cx);
+    let result = harness.query(&TimestampHandlerFactory, "1700000000000", cx);
+
+    result.assert_contains("2023-11-14T22:13:20Z");
+    result.assert_ranked_before("1700000000", "1700000000000");
+}
+
+#[gpui::test]
+fn ignores_short_numbers(cx: &mut gpui::TestAppContext) {
+    let harness = HandlerHarness::new(cx);
+
+    harness
+        .query(&TimestampHandlerFactory, "2024", cx)
+        .assert_empty();
+}
+
+#[gpui::test]
+fn appends_and_finds_notes(cx: &mut gpui::TestAppContext) {
+    let harness = HandlerHarness::new(cx);
+    let result = harness.query(&NotesHandlerFactory, "note buy milk", cx);
+
+    result.assert_contains("Append Note");
+    result.execute("Append Note").unwrap();
+    assert_eq!(harness.db().search_notes("mil", 1).unwrap().len(), 1);
+
+    harness
+        .query(&NotesHandlerFactory, "note milk", cx)
+        .assert_contains("buy milk");
+}